@@ -0,0 +1,79 @@
+//! Range query latency of the sorted set as it grows: `cargo bench --bench zset_range`.
+
+use std::hint::black_box;
+
+use bytes::Bytes;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+// The tests of the module do not run here
+#[allow(dead_code, unused_imports)]
+#[path = "../src/zset.rs"]
+mod zset;
+
+use zset::{ScoreRange, SortedSet};
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+
+/// A set of `size` members whose scores are spread over `0..size` in a scrambled order.
+fn build(size: usize) -> SortedSet {
+    (0..size)
+        .map(|i| {
+            let score = (i * 7_919) % size;
+            (Bytes::from(format!("member:{i}")), score as f64)
+        })
+        .collect()
+}
+
+fn range_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("zset_range");
+    for size in SIZES {
+        let zset = build(size);
+        let middle = size / 2;
+
+        // ZRANGE key middle middle+9
+        group.bench_with_input(BenchmarkId::new("by_rank", size), &zset, |b, zset| {
+            b.iter(|| {
+                zset.range(black_box(middle..middle + 10))
+                    .map(|(_, score)| score)
+                    .sum::<f64>()
+            })
+        });
+
+        // ZRANGE key middle +inf BYSCORE LIMIT 0 10
+        let range = ScoreRange {
+            min: middle as f64,
+            max: f64::INFINITY,
+            min_exclusive: false,
+            max_exclusive: false,
+        };
+        group.bench_with_input(BenchmarkId::new("by_score", size), &zset, |b, zset| {
+            b.iter(|| {
+                let ranks = zset.score_range(black_box(&range));
+                zset.range(ranks.start..ranks.start + 10)
+                    .map(|(_, score)| score)
+                    .sum::<f64>()
+            })
+        });
+
+        // ZCOUNT key 0 middle
+        let range = ScoreRange {
+            min: 0.0,
+            max: middle as f64,
+            min_exclusive: false,
+            max_exclusive: false,
+        };
+        group.bench_with_input(BenchmarkId::new("count", size), &zset, |b, zset| {
+            b.iter(|| zset.score_range(black_box(&range)).len())
+        });
+
+        // ZRANK key member
+        let member = format!("member:{middle}");
+        group.bench_with_input(BenchmarkId::new("rank", size), &zset, |b, zset| {
+            b.iter(|| zset.rank(black_box(member.as_bytes())))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, range_queries);
+criterion_main!(benches);
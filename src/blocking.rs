@@ -0,0 +1,174 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use tokio::sync::{Mutex, oneshot};
+
+use crate::{
+    command::ExecError,
+    db::{Db, Key},
+    resp::RespData,
+    server::{Connection, Server, Stats},
+};
+
+pub type Reply = Result<RespData, ExecError>;
+
+/// Try to serve a blocked client from a key that became ready. Returns `None` when the key can
+/// not serve it yet, which keeps the client blocked. Keys receiving elements as a side effect,
+/// such as the destination of `BLMOVE`, are pushed onto the ready keys.
+pub type Serve = Box<dyn FnMut(&mut Db, &mut Stats, &Key, &mut Vec<Key>) -> Option<Reply> + Send>;
+
+struct BlockedClient {
+    keys: Vec<Key>,
+    serve: Serve,
+    sender: oneshot::Sender<Reply>,
+}
+
+/// Clients parked by blocking commands, waiting for keys to become ready.
+///
+/// Every key keeps its blocked clients in FIFO order, and a client blocked on several keys is
+/// served by whichever key becomes ready first.
+#[derive(Default)]
+pub struct BlockingClients {
+    next_id: u64,
+    clients: HashMap<u64, BlockedClient>,
+    keys: HashMap<Key, VecDeque<u64>>,
+    ready_keys: Vec<Key>,
+}
+
+impl BlockingClients {
+    pub fn block(&mut self, keys: Vec<Key>, serve: Serve) -> (u64, oneshot::Receiver<Reply>) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        for key in &keys {
+            self.keys.entry(key.clone()).or_default().push_back(id);
+        }
+        let (sender, receiver) = oneshot::channel();
+        self.clients.insert(
+            id,
+            BlockedClient {
+                keys,
+                serve,
+                sender,
+            },
+        );
+
+        (id, receiver)
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    fn remove(&mut self, id: u64) -> Option<BlockedClient> {
+        let client = self.clients.remove(&id)?;
+        for key in &client.keys {
+            if let Some(queue) = self.keys.get_mut(key) {
+                queue.retain(|blocked_id| *blocked_id != id);
+                if queue.is_empty() {
+                    self.keys.remove(key);
+                }
+            }
+        }
+        Some(client)
+    }
+
+    pub fn unblock(&mut self, id: u64) {
+        self.remove(id);
+    }
+
+    /// Mark `key` as possibly able to serve blocked clients, after elements were added to it.
+    pub fn signal_key_as_ready(&mut self, key: &Key) {
+        if self.keys.contains_key(key) {
+            self.ready_keys.push(key.clone());
+        }
+    }
+
+    /// Serve the clients blocked on every ready key, in the order they blocked, until no key is
+    /// ready anymore.
+    pub fn serve_ready_keys(&mut self, db: &mut Db, stats: &mut Stats) {
+        while !self.ready_keys.is_empty() {
+            for key in std::mem::take(&mut self.ready_keys) {
+                self.serve_key(db, stats, &key);
+            }
+        }
+    }
+
+    fn serve_key(&mut self, db: &mut Db, stats: &mut Stats, key: &Key) {
+        let Some(queue) = self.keys.get(key) else {
+            return;
+        };
+
+        for id in queue.clone() {
+            if !db.contains_key(key) {
+                break;
+            }
+
+            // The id is in the queue, so the client is blocked
+            let client = self.clients.get_mut(&id).unwrap();
+            // The blocked command was dropped, nobody waits for this reply
+            if client.sender.is_closed() {
+                self.unblock(id);
+                continue;
+            }
+            let Some(reply) = (client.serve)(db, stats, key, &mut self.ready_keys) else {
+                continue;
+            };
+
+            let client = self.remove(id).unwrap();
+            let _ = client.sender.send(reply);
+        }
+    }
+}
+
+/// Wait for the blocked client `id` to be served, for `timeout` to expire, or for the client
+/// of `conn` to disconnect. `None` waits forever. Replies `None` on timeout, and on disconnect
+/// as nobody reads the reply then.
+///
+/// The server lock is only taken again on timeout or disconnect, to unregister the client so
+/// that later pushes are not sent to it.
+pub async fn wait_until_served(
+    server: Arc<Mutex<Server>>,
+    conn: &mut Connection,
+    id: u64,
+    mut receiver: oneshot::Receiver<Reply>,
+    timeout: Option<Duration>,
+) -> Result<Option<RespData>, ExecError> {
+    let expired = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    let disconnected = async {
+        let mut byte = [0; 1];
+        match conn.stream.peek(&mut byte).await {
+            Ok(0) | Err(_) => {}
+            // Commands pipelined after the blocking one wait in the socket, and hide whether the
+            // client leaves until it is served or times out
+            Ok(_) => std::future::pending().await,
+        }
+    };
+    let reply = tokio::select! {
+        reply = &mut receiver => reply.ok(),
+        _ = expired => None,
+        _ = disconnected => None,
+    };
+    if let Some(reply) = reply {
+        return reply.map(Some);
+    }
+
+    server.lock().await.blocking.unblock(id);
+    // The client may have been served between the timeout and taking the lock
+    match receiver.try_recv() {
+        Ok(reply) => reply.map(Some),
+        Err(_) => Ok(None),
+    }
+}
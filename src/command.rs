@@ -1,32 +1,265 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use tokio::sync::Mutex;
 
 use crate::{
     command::{
+        append::Append,
+        bitcount::BitCount,
+        bitfield::BitField,
+        bitop::BitOp,
+        bitpos::BitPos,
+        blmove::BLMove,
+        blmpop::BLMPop,
+        bpop::BPop,
+        bzmpop::BZMPop,
+        bzpop::BZPop,
         client::Client,
         config::Config,
+        copy::Copy,
+        dbsize::DbSize,
+        del::Del,
         echo::Echo,
         error::{ExecResult, ParseResult},
+        exists::Exists,
+        expire::Expire,
+        geoadd::GeoAdd,
+        geodist::GeoDist,
+        geohash::GeoHash,
+        geopos::GeoPos,
+        geosearch::GeoSearch,
         get::Get,
+        getbit::GetBit,
+        getdel::GetDel,
+        getex::GetEx,
+        getrange::GetRange,
+        getset::GetSet,
+        hdel::HDel,
+        hello::Hello,
+        hexists::HExists,
+        hexpire::HExpire,
+        hget::HGet,
+        hgetall::HGetAll,
+        hgetex::HGetEx,
+        hincrby::HIncrBy,
+        hincrbyfloat::HIncrByFloat,
+        hkeys::HKeys,
+        hlen::HLen,
+        hmget::HMGet,
+        hpersist::HPersist,
+        hrandfield::HRandField,
+        hscan::HScan,
+        hset::HSet,
+        hsetex::HSetEx,
+        hsetnx::HSetNx,
+        hstrlen::HStrLen,
+        httl::HTtl,
+        hvals::HVals,
+        incr::Incr,
+        incrbyfloat::IncrByFloat,
+        info::Info,
+        keytype::Type,
+        lcs::Lcs,
+        lindex::LIndex,
+        linsert::LInsert,
+        llen::LLen,
+        lmove::LMove,
+        lmpop::LMPop,
+        lpos::LPos,
+        lrange::LRange,
+        lrem::LRem,
+        lset::LSet,
+        ltrim::LTrim,
+        mget::MGet,
+        mset::MSet,
+        persist::Persist,
+        pfadd::PfAdd,
+        pfcount::PfCount,
+        pfmerge::PfMerge,
         ping::Ping,
+        pop::Pop,
+        push::Push,
+        randomkey::RandomKey,
+        rename::Rename,
+        sadd::SAdd,
+        save::Save,
+        scard::SCard,
         set::Set,
+        setbit::SetBit,
+        setex::SetEx,
+        setnx::SetNx,
+        setop::{SetOp, SetOperator},
+        setrange::SetRange,
+        sismember::SIsMember,
+        smembers::SMembers,
+        smismember::SMIsMember,
+        smove::SMove,
+        spop::SPop,
+        srandmember::SRandMember,
+        srem::SRem,
+        strlen::StrLen,
+        ttl::Ttl,
         unknown::Unknown,
+        xack::XAck,
+        xadd::XAdd,
+        xautoclaim::XAutoClaim,
+        xclaim::XClaim,
+        xdel::XDel,
+        xgroup::XGroup,
+        xinfo::XInfo,
+        xlen::XLen,
+        xpending::XPending,
+        xrange::XRange,
+        xread::XRead,
+        xreadgroup::XReadGroup,
+        xtrim::XTrim,
+        zadd::ZAdd,
+        zcard::ZCard,
+        zcount::ZCount,
+        zincrby::ZIncrBy,
+        zintercard::ZInterCard,
+        zmpop::ZMPop,
+        zpop::ZPop,
+        zrange::{RangeKind, ZRange},
+        zrank::ZRank,
+        zrem::ZRem,
+        zscore::ZScore,
+        zsetop::ZSetOp,
     },
-    resp::{ClientRequest, RespData},
-    server::{Connection, Server},
-    utils::BytesInStr,
+    db::{
+        self, Db, Expiration, ExpireCondition, Hash, Key, List, ListEnd, STRING_MAX_LEN, Value,
+        ZSetEnd, lookup_key,
+    },
+    hyperloglog::HyperLogLog,
+    resp::{ClientRequest, RespData, RespProtocol},
+    server::{Connection, Server, Stats},
+    stream::{Stream, StreamEntry, StreamId},
+    utils::{BytesInStr, format_float},
+    zset::SortedSet,
 };
 
+mod append;
+mod bitcount;
+mod bitfield;
+mod bitop;
+mod bitpos;
+mod blmove;
+mod blmpop;
+mod bpop;
+mod bzmpop;
+mod bzpop;
 mod client;
 mod config;
+mod copy;
+mod dbsize;
+mod del;
 mod echo;
 mod error;
+mod exists;
+mod expire;
+mod geoadd;
+mod geodist;
+mod geohash;
+mod geopos;
+mod geosearch;
 mod get;
+mod getbit;
+mod getdel;
+mod getex;
+mod getrange;
+mod getset;
+mod hdel;
+mod hello;
+mod hexists;
+mod hexpire;
+mod hget;
+mod hgetall;
+mod hgetex;
+mod hincrby;
+mod hincrbyfloat;
+mod hkeys;
+mod hlen;
+mod hmget;
+mod hpersist;
+mod hrandfield;
+mod hscan;
+mod hset;
+mod hsetex;
+mod hsetnx;
+mod hstrlen;
+mod httl;
+mod hvals;
+mod incr;
+mod incrbyfloat;
+mod info;
+mod keytype;
+mod lcs;
+mod lindex;
+mod linsert;
+mod llen;
+mod lmove;
+mod lmpop;
+mod lpos;
+mod lrange;
+mod lrem;
+mod lset;
+mod ltrim;
+mod mget;
+mod mset;
+mod persist;
+mod pfadd;
+mod pfcount;
+mod pfmerge;
 mod ping;
+mod pop;
+mod push;
+mod randomkey;
+mod rename;
+mod sadd;
+mod save;
+mod scard;
 mod set;
+mod setbit;
+mod setex;
+mod setnx;
+mod setop;
+mod setrange;
+mod sismember;
+mod smembers;
+mod smismember;
+mod smove;
+mod spop;
+mod srandmember;
+mod srem;
+mod strlen;
+mod ttl;
 mod unknown;
+mod xack;
+mod xadd;
+mod xautoclaim;
+mod xclaim;
+mod xdel;
+mod xgroup;
+mod xinfo;
+mod xlen;
+mod xpending;
+mod xrange;
+mod xread;
+mod xreadgroup;
+mod xtrim;
+mod zadd;
+mod zcard;
+mod zcount;
+mod zincrby;
+mod zintercard;
+mod zmpop;
+mod zpop;
+mod zrange;
+mod zrank;
+mod zrem;
+mod zscore;
+mod zsetop;
 
 pub use error::{ExecError, ParseError};
 
@@ -36,8 +269,121 @@ pub enum Command {
     Echo(Echo),
     Get(Get),
     Set(Set),
+    Incr(Incr),
+    IncrByFloat(IncrByFloat),
+    Append(Append),
+    StrLen(StrLen),
+    GetRange(GetRange),
+    SetRange(SetRange),
+    GetDel(GetDel),
+    GetEx(GetEx),
+    GetSet(GetSet),
+    MSet(MSet),
+    MGet(MGet),
+    SetNx(SetNx),
+    SetEx(SetEx),
+    Lcs(Lcs),
+    SetBit(SetBit),
+    GetBit(GetBit),
+    BitCount(BitCount),
+    BitPos(BitPos),
+    BitOp(BitOp),
+    BitField(BitField),
+    PfAdd(PfAdd),
+    PfCount(PfCount),
+    PfMerge(PfMerge),
+    GeoAdd(GeoAdd),
+    GeoPos(GeoPos),
+    GeoDist(GeoDist),
+    GeoHash(GeoHash),
+    GeoSearch(GeoSearch),
+    Del(Del),
+    Exists(Exists),
+    Type(Type),
+    Rename(Rename),
+    Copy(Copy),
+    RandomKey(RandomKey),
+    DbSize(DbSize),
+    Expire(Expire),
+    Ttl(Ttl),
+    Persist(Persist),
+    Info(Info),
     Client(Client),
     Config(Config),
+    Hello(Hello),
+    Push(Push),
+    Pop(Pop),
+    LRange(LRange),
+    LLen(LLen),
+    LIndex(LIndex),
+    LSet(LSet),
+    LRem(LRem),
+    LTrim(LTrim),
+    LInsert(LInsert),
+    LPos(LPos),
+    LMove(LMove),
+    LMPop(LMPop),
+    BPop(BPop),
+    BLMove(BLMove),
+    BLMPop(BLMPop),
+    HSet(HSet),
+    HGet(HGet),
+    HMGet(HMGet),
+    HDel(HDel),
+    HGetAll(HGetAll),
+    HKeys(HKeys),
+    HVals(HVals),
+    HLen(HLen),
+    HExists(HExists),
+    HIncrBy(HIncrBy),
+    HIncrByFloat(HIncrByFloat),
+    HSetNx(HSetNx),
+    HStrLen(HStrLen),
+    HRandField(HRandField),
+    HScan(HScan),
+    HExpire(HExpire),
+    HTtl(HTtl),
+    HPersist(HPersist),
+    HGetEx(HGetEx),
+    HSetEx(HSetEx),
+    Save(Save),
+    SAdd(SAdd),
+    SRem(SRem),
+    SMembers(SMembers),
+    SIsMember(SIsMember),
+    SMIsMember(SMIsMember),
+    SCard(SCard),
+    SPop(SPop),
+    SRandMember(SRandMember),
+    SMove(SMove),
+    SetOp(SetOp),
+    ZAdd(ZAdd),
+    ZIncrBy(ZIncrBy),
+    ZRange(ZRange),
+    ZRank(ZRank),
+    ZScore(ZScore),
+    ZRem(ZRem),
+    ZCount(ZCount),
+    ZCard(ZCard),
+    ZPop(ZPop),
+    ZSetOp(ZSetOp),
+    ZInterCard(ZInterCard),
+    ZMPop(ZMPop),
+    BZPop(BZPop),
+    BZMPop(BZMPop),
+    XAdd(XAdd),
+    XRange(XRange),
+    XLen(XLen),
+    XDel(XDel),
+    XRead(XRead),
+    XTrim(XTrim),
+    XGroup(XGroup),
+    XReadGroup(XReadGroup),
+    XAck(XAck),
+    XPending(XPending),
+    XClaim(XClaim),
+    XAutoClaim(XAutoClaim),
+    XInfo(XInfo),
     Unknown(Unknown),
 }
 
@@ -72,14 +418,314 @@ fn check_length_ge(args: &[Bytes], length: usize) -> ParseResult<()> {
     Ok(())
 }
 
+/// Check that `args` holds `field value` pairs after its first `skip` arguments.
+#[inline]
+fn check_pairs(args: &[Bytes], skip: usize) -> ParseResult<()> {
+    check_length_ge(args, skip + 2)?;
+    if !(args.len() - skip).is_multiple_of(2) {
+        return Err(ParseError::ExpectPairs(args.to_vec()));
+    }
+    Ok(())
+}
+
+#[inline]
+fn parse_list_end(arg: &Bytes) -> ParseResult<ListEnd> {
+    let argument = str::from_utf8(arg)?;
+    match argument.to_uppercase().as_str() {
+        "LEFT" => Ok(ListEnd::Left),
+        "RIGHT" => Ok(ListEnd::Right),
+        _ => Err(ParseError::InvalidArgument(argument.to_string())),
+    }
+}
+
+#[inline]
+fn parse_zset_end(arg: &Bytes) -> ParseResult<ZSetEnd> {
+    let argument = str::from_utf8(arg)?;
+    match argument.to_uppercase().as_str() {
+        "MIN" => Ok(ZSetEnd::Min),
+        "MAX" => Ok(ZSetEnd::Max),
+        _ => Err(ParseError::InvalidArgument(argument.to_string())),
+    }
+}
+
+/// Parse a blocking timeout in seconds, which may be fractional. Zero blocks forever.
+#[inline]
+fn parse_timeout(arg: &Bytes) -> ParseResult<Option<Duration>> {
+    let seconds: f64 = lexical_core::parse(arg)?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err(ParseError::InvalidArgument(
+            str::from_utf8(arg)?.to_string(),
+        ));
+    }
+    Ok((seconds > 0.0).then(|| Duration::from_secs_f64(seconds)))
+}
+
+/// Parse the `EX seconds | PX milliseconds | EXAT unix-time-seconds | PXAT unix-time-milliseconds`
+/// option named `option`, which must be uppercase. The time must be positive.
+#[inline]
+fn parse_expiration(option: &str, arg: &Bytes) -> ParseResult<Expiration> {
+    let time: i64 = lexical_core::parse(arg)?;
+    let ms = match option {
+        "EX" | "EXAT" => time.checked_mul(1000),
+        _ => Some(time),
+    }
+    .filter(|ms| *ms > 0)
+    .ok_or_else(|| ParseError::InvalidArgument(format!("{option} {time}")))?;
+    Ok(if option.ends_with("AT") {
+        Expiration::At(ms as u64)
+    } else {
+        Expiration::In(ms as u64)
+    })
+}
+
+/// Parse the `NX | XX | GT | LT` condition of the expire commands, `None` for other arguments.
+#[inline]
+fn parse_expire_condition(argument: &str) -> Option<ExpireCondition> {
+    match argument.to_uppercase().as_str() {
+        "NX" => Some(ExpireCondition::Nx),
+        "XX" => Some(ExpireCondition::Xx),
+        "GT" => Some(ExpireCondition::Gt),
+        "LT" => Some(ExpireCondition::Lt),
+        _ => None,
+    }
+}
+
+/// Parse the `FIELDS numfields field [field ...]` block closing the hash field expiration
+/// commands, where every field spans `width` arguments. Returns the arguments of the fields.
+#[inline]
+fn parse_fields(args: &[Bytes], width: usize) -> ParseResult<&[Bytes]> {
+    check_length_ge(args, 2 + width)?;
+    let argument = str::from_utf8(&args[0])?;
+    if !argument.eq_ignore_ascii_case("FIELDS") {
+        return Err(ParseError::InvalidArgument(argument.to_string()));
+    }
+    let numfields: usize = lexical_core::parse(&args[1])?;
+    if numfields.checked_mul(width) != Some(args.len() - 2) {
+        return Err(ParseError::InvalidArgument(format!(
+            "{argument} {numfields}"
+        )));
+    }
+    Ok(&args[2..])
+}
+
+/// Parse the `numkeys key [key ...]` block opening `args`. Returns the keys and the arguments
+/// following them.
+#[inline]
+fn parse_numkeys(args: &[Bytes]) -> ParseResult<(&[Bytes], &[Bytes])> {
+    check_length_ge(args, 2)?;
+    let numkeys: usize = lexical_core::parse(&args[0])?;
+    if numkeys == 0 {
+        return Err(ParseError::InvalidArgument("numkeys 0".to_string()));
+    }
+    check_length_ge(args, numkeys.saturating_add(1))?;
+    Ok(args[1..].split_at(numkeys))
+}
+
+/// Parse the count of the random sampling commands. Like Redis, reject counts beyond half of
+/// `i64::MAX` either way, whose replies could never be built.
+#[inline]
+fn parse_random_count(arg: &Bytes) -> ParseResult<i64> {
+    let count: i64 = lexical_core::parse(arg)?;
+    if count.unsigned_abs() > (i64::MAX / 2) as u64 {
+        Err(ExecError::ValueOutOfRange)?;
+    }
+    Ok(count)
+}
+
+/// Parse an offset into the bits of a string, which must fit in the largest string.
+#[inline]
+fn parse_bit_offset(arg: &Bytes) -> ParseResult<usize> {
+    lexical_core::parse::<usize>(arg)
+        .ok()
+        .filter(|offset| *offset < STRING_MAX_LEN * 8)
+        .ok_or_else(|| ParseError::InvalidArgument(String::from_utf8_lossy(arg).into_owned()))
+}
+
+/// Parse a stream ID given as `<ms>-<seq>`, or as `<ms>` alone which takes `default_seq`.
+#[inline]
+fn parse_stream_id(arg: &Bytes, default_seq: u64) -> ParseResult<StreamId> {
+    StreamId::parse(arg, default_seq)
+        .ok_or_else(|| ParseError::InvalidArgument(String::from_utf8_lossy(arg).into_owned()))
+}
+
 pub fn parse_command(request: &ClientRequest) -> ParseResult<Command> {
     let command = match request.command.to_uppercase().as_str() {
         "PING" => Command::Ping(Ping::parse(&request.args)?),
         "ECHO" => Command::Echo(Echo::parse(&request.args)?),
         "GET" => Command::Get(Get::parse(&request.args)?),
         "SET" => Command::Set(Set::parse(&request.args)?),
+        "INCR" => Command::Incr(Incr::parse(&request.args, false, false)?),
+        "DECR" => Command::Incr(Incr::parse(&request.args, false, true)?),
+        "INCRBY" => Command::Incr(Incr::parse(&request.args, true, false)?),
+        "DECRBY" => Command::Incr(Incr::parse(&request.args, true, true)?),
+        "INCRBYFLOAT" => Command::IncrByFloat(IncrByFloat::parse(&request.args)?),
+        "APPEND" => Command::Append(Append::parse(&request.args)?),
+        "STRLEN" => Command::StrLen(StrLen::parse(&request.args)?),
+        "GETRANGE" => Command::GetRange(GetRange::parse(&request.args)?),
+        "SETRANGE" => Command::SetRange(SetRange::parse(&request.args)?),
+        "GETDEL" => Command::GetDel(GetDel::parse(&request.args)?),
+        "GETEX" => Command::GetEx(GetEx::parse(&request.args)?),
+        "GETSET" => Command::GetSet(GetSet::parse(&request.args)?),
+        "MSET" => Command::MSet(MSet::parse(&request.args, false)?),
+        "MSETNX" => Command::MSet(MSet::parse(&request.args, true)?),
+        "MGET" => Command::MGet(MGet::parse(&request.args)?),
+        "SETNX" => Command::SetNx(SetNx::parse(&request.args)?),
+        "SETEX" => Command::SetEx(SetEx::parse(&request.args, false)?),
+        "PSETEX" => Command::SetEx(SetEx::parse(&request.args, true)?),
+        "LCS" => Command::Lcs(Lcs::parse(&request.args)?),
+        "SETBIT" => Command::SetBit(SetBit::parse(&request.args)?),
+        "GETBIT" => Command::GetBit(GetBit::parse(&request.args)?),
+        "BITCOUNT" => Command::BitCount(BitCount::parse(&request.args)?),
+        "BITPOS" => Command::BitPos(BitPos::parse(&request.args)?),
+        "BITOP" => Command::BitOp(BitOp::parse(&request.args)?),
+        "BITFIELD" => Command::BitField(BitField::parse(&request.args, false)?),
+        "BITFIELD_RO" => Command::BitField(BitField::parse(&request.args, true)?),
+        "PFADD" => Command::PfAdd(PfAdd::parse(&request.args)?),
+        "PFCOUNT" => Command::PfCount(PfCount::parse(&request.args)?),
+        "PFMERGE" => Command::PfMerge(PfMerge::parse(&request.args)?),
+        "GEOADD" => Command::GeoAdd(GeoAdd::parse(&request.args)?),
+        "GEOPOS" => Command::GeoPos(GeoPos::parse(&request.args)?),
+        "GEODIST" => Command::GeoDist(GeoDist::parse(&request.args)?),
+        "GEOHASH" => Command::GeoHash(GeoHash::parse(&request.args)?),
+        "GEOSEARCH" => Command::GeoSearch(GeoSearch::parse(&request.args, false)?),
+        "GEOSEARCHSTORE" => Command::GeoSearch(GeoSearch::parse(&request.args, true)?),
+        "DEL" => Command::Del(Del::parse(&request.args, false)?),
+        "UNLINK" => Command::Del(Del::parse(&request.args, true)?),
+        "EXISTS" => Command::Exists(Exists::parse(&request.args)?),
+        "TOUCH" => Command::Exists(Exists::parse(&request.args)?),
+        "TYPE" => Command::Type(Type::parse(&request.args)?),
+        "RENAME" => Command::Rename(Rename::parse(&request.args, false)?),
+        "RENAMENX" => Command::Rename(Rename::parse(&request.args, true)?),
+        "COPY" => Command::Copy(Copy::parse(&request.args)?),
+        "RANDOMKEY" => Command::RandomKey(RandomKey::parse(&request.args)?),
+        "DBSIZE" => Command::DbSize(DbSize::parse(&request.args)?),
+        "EXPIRE" => Command::Expire(Expire::parse(&request.args, false, false)?),
+        "PEXPIRE" => Command::Expire(Expire::parse(&request.args, true, false)?),
+        "EXPIREAT" => Command::Expire(Expire::parse(&request.args, false, true)?),
+        "PEXPIREAT" => Command::Expire(Expire::parse(&request.args, true, true)?),
+        "TTL" => Command::Ttl(Ttl::parse(&request.args, false, false)?),
+        "PTTL" => Command::Ttl(Ttl::parse(&request.args, true, false)?),
+        "EXPIRETIME" => Command::Ttl(Ttl::parse(&request.args, false, true)?),
+        "PEXPIRETIME" => Command::Ttl(Ttl::parse(&request.args, true, true)?),
+        "PERSIST" => Command::Persist(Persist::parse(&request.args)?),
+        "INFO" => Command::Info(Info::parse(&request.args)?),
         "CLIENT" => Command::Client(Client::parse(&request.args)?),
         "CONFIG" => Command::Config(Config::parse(&request.args)?),
+        "HELLO" => Command::Hello(Hello::parse(&request.args)?),
+        "LPUSH" => Command::Push(Push::parse(&request.args, ListEnd::Left, false)?),
+        "RPUSH" => Command::Push(Push::parse(&request.args, ListEnd::Right, false)?),
+        "LPUSHX" => Command::Push(Push::parse(&request.args, ListEnd::Left, true)?),
+        "RPUSHX" => Command::Push(Push::parse(&request.args, ListEnd::Right, true)?),
+        "LPOP" => Command::Pop(Pop::parse(&request.args, ListEnd::Left)?),
+        "RPOP" => Command::Pop(Pop::parse(&request.args, ListEnd::Right)?),
+        "LRANGE" => Command::LRange(LRange::parse(&request.args)?),
+        "LLEN" => Command::LLen(LLen::parse(&request.args)?),
+        "LINDEX" => Command::LIndex(LIndex::parse(&request.args)?),
+        "LSET" => Command::LSet(LSet::parse(&request.args)?),
+        "LREM" => Command::LRem(LRem::parse(&request.args)?),
+        "LTRIM" => Command::LTrim(LTrim::parse(&request.args)?),
+        "LINSERT" => Command::LInsert(LInsert::parse(&request.args)?),
+        "LPOS" => Command::LPos(LPos::parse(&request.args)?),
+        "LMOVE" => Command::LMove(LMove::parse(&request.args)?),
+        "LMPOP" => Command::LMPop(LMPop::parse(&request.args)?),
+        "BLPOP" => Command::BPop(BPop::parse(&request.args, ListEnd::Left)?),
+        "BRPOP" => Command::BPop(BPop::parse(&request.args, ListEnd::Right)?),
+        "BLMOVE" => Command::BLMove(BLMove::parse(&request.args)?),
+        "BLMPOP" => Command::BLMPop(BLMPop::parse(&request.args)?),
+        "HSET" => Command::HSet(HSet::parse(&request.args)?),
+        "HGET" => Command::HGet(HGet::parse(&request.args)?),
+        "HMGET" => Command::HMGet(HMGet::parse(&request.args)?),
+        "HDEL" => Command::HDel(HDel::parse(&request.args)?),
+        "HGETALL" => Command::HGetAll(HGetAll::parse(&request.args)?),
+        "HKEYS" => Command::HKeys(HKeys::parse(&request.args)?),
+        "HVALS" => Command::HVals(HVals::parse(&request.args)?),
+        "HLEN" => Command::HLen(HLen::parse(&request.args)?),
+        "HEXISTS" => Command::HExists(HExists::parse(&request.args)?),
+        "HINCRBY" => Command::HIncrBy(HIncrBy::parse(&request.args)?),
+        "HINCRBYFLOAT" => Command::HIncrByFloat(HIncrByFloat::parse(&request.args)?),
+        "HSETNX" => Command::HSetNx(HSetNx::parse(&request.args)?),
+        "HSTRLEN" => Command::HStrLen(HStrLen::parse(&request.args)?),
+        "HRANDFIELD" => Command::HRandField(HRandField::parse(&request.args)?),
+        "HSCAN" => Command::HScan(HScan::parse(&request.args)?),
+        "HEXPIRE" => Command::HExpire(HExpire::parse(&request.args, false, false)?),
+        "HPEXPIRE" => Command::HExpire(HExpire::parse(&request.args, true, false)?),
+        "HEXPIREAT" => Command::HExpire(HExpire::parse(&request.args, false, true)?),
+        "HPEXPIREAT" => Command::HExpire(HExpire::parse(&request.args, true, true)?),
+        "HTTL" => Command::HTtl(HTtl::parse(&request.args, false, false)?),
+        "HPTTL" => Command::HTtl(HTtl::parse(&request.args, true, false)?),
+        "HEXPIRETIME" => Command::HTtl(HTtl::parse(&request.args, false, true)?),
+        "HPEXPIRETIME" => Command::HTtl(HTtl::parse(&request.args, true, true)?),
+        "HPERSIST" => Command::HPersist(HPersist::parse(&request.args)?),
+        "HGETEX" => Command::HGetEx(HGetEx::parse(&request.args)?),
+        "HSETEX" => Command::HSetEx(HSetEx::parse(&request.args)?),
+        "SAVE" => Command::Save(Save::parse(&request.args)?),
+        "SADD" => Command::SAdd(SAdd::parse(&request.args)?),
+        "SREM" => Command::SRem(SRem::parse(&request.args)?),
+        "SMEMBERS" => Command::SMembers(SMembers::parse(&request.args)?),
+        "SISMEMBER" => Command::SIsMember(SIsMember::parse(&request.args)?),
+        "SMISMEMBER" => Command::SMIsMember(SMIsMember::parse(&request.args)?),
+        "SCARD" => Command::SCard(SCard::parse(&request.args)?),
+        "SPOP" => Command::SPop(SPop::parse(&request.args)?),
+        "SRANDMEMBER" => Command::SRandMember(SRandMember::parse(&request.args)?),
+        "SMOVE" => Command::SMove(SMove::parse(&request.args)?),
+        "SUNION" => Command::SetOp(SetOp::parse(&request.args, SetOperator::Union, false)?),
+        "SINTER" => Command::SetOp(SetOp::parse(&request.args, SetOperator::Inter, false)?),
+        "SDIFF" => Command::SetOp(SetOp::parse(&request.args, SetOperator::Diff, false)?),
+        "SUNIONSTORE" => Command::SetOp(SetOp::parse(&request.args, SetOperator::Union, true)?),
+        "SINTERSTORE" => Command::SetOp(SetOp::parse(&request.args, SetOperator::Inter, true)?),
+        "SDIFFSTORE" => Command::SetOp(SetOp::parse(&request.args, SetOperator::Diff, true)?),
+        "ZADD" => Command::ZAdd(ZAdd::parse(&request.args)?),
+        "ZINCRBY" => Command::ZIncrBy(ZIncrBy::parse(&request.args)?),
+        "ZRANGE" => Command::ZRange(ZRange::parse(&request.args, false)?),
+        "ZRANGESTORE" => Command::ZRange(ZRange::parse(&request.args, true)?),
+        "ZREVRANGE" => Command::ZRange(ZRange::parse_legacy(&request.args, RangeKind::Rank, true)?),
+        "ZRANGEBYSCORE" => Command::ZRange(ZRange::parse_legacy(
+            &request.args,
+            RangeKind::Score,
+            false,
+        )?),
+        "ZREVRANGEBYSCORE" => {
+            Command::ZRange(ZRange::parse_legacy(&request.args, RangeKind::Score, true)?)
+        }
+        "ZRANGEBYLEX" => {
+            Command::ZRange(ZRange::parse_legacy(&request.args, RangeKind::Lex, false)?)
+        }
+        "ZREVRANGEBYLEX" => {
+            Command::ZRange(ZRange::parse_legacy(&request.args, RangeKind::Lex, true)?)
+        }
+        "ZRANK" => Command::ZRank(ZRank::parse(&request.args, false)?),
+        "ZREVRANK" => Command::ZRank(ZRank::parse(&request.args, true)?),
+        "ZSCORE" => Command::ZScore(ZScore::parse(&request.args)?),
+        "ZREM" => Command::ZRem(ZRem::parse(&request.args)?),
+        "ZCOUNT" => Command::ZCount(ZCount::parse(&request.args)?),
+        "ZCARD" => Command::ZCard(ZCard::parse(&request.args)?),
+        "ZPOPMIN" => Command::ZPop(ZPop::parse(&request.args, ZSetEnd::Min)?),
+        "ZPOPMAX" => Command::ZPop(ZPop::parse(&request.args, ZSetEnd::Max)?),
+        "ZUNION" => Command::ZSetOp(ZSetOp::parse(&request.args, SetOperator::Union, false)?),
+        "ZINTER" => Command::ZSetOp(ZSetOp::parse(&request.args, SetOperator::Inter, false)?),
+        "ZDIFF" => Command::ZSetOp(ZSetOp::parse(&request.args, SetOperator::Diff, false)?),
+        "ZUNIONSTORE" => Command::ZSetOp(ZSetOp::parse(&request.args, SetOperator::Union, true)?),
+        "ZINTERSTORE" => Command::ZSetOp(ZSetOp::parse(&request.args, SetOperator::Inter, true)?),
+        "ZDIFFSTORE" => Command::ZSetOp(ZSetOp::parse(&request.args, SetOperator::Diff, true)?),
+        "ZINTERCARD" => Command::ZInterCard(ZInterCard::parse(&request.args)?),
+        "ZMPOP" => Command::ZMPop(ZMPop::parse(&request.args)?),
+        "BZPOPMIN" => Command::BZPop(BZPop::parse(&request.args, ZSetEnd::Min)?),
+        "BZPOPMAX" => Command::BZPop(BZPop::parse(&request.args, ZSetEnd::Max)?),
+        "BZMPOP" => Command::BZMPop(BZMPop::parse(&request.args)?),
+        "XADD" => Command::XAdd(XAdd::parse(&request.args)?),
+        "XRANGE" => Command::XRange(XRange::parse(&request.args, false)?),
+        "XREVRANGE" => Command::XRange(XRange::parse(&request.args, true)?),
+        "XLEN" => Command::XLen(XLen::parse(&request.args)?),
+        "XDEL" => Command::XDel(XDel::parse(&request.args)?),
+        "XREAD" => Command::XRead(XRead::parse(&request.args)?),
+        "XTRIM" => Command::XTrim(XTrim::parse(&request.args)?),
+        "XGROUP" => Command::XGroup(XGroup::parse(&request.args)?),
+        "XREADGROUP" => Command::XReadGroup(XReadGroup::parse(&request.args)?),
+        "XACK" => Command::XAck(XAck::parse(&request.args)?),
+        "XPENDING" => Command::XPending(XPending::parse(&request.args)?),
+        "XCLAIM" => Command::XClaim(XClaim::parse(&request.args)?),
+        "XAUTOCLAIM" => Command::XAutoClaim(XAutoClaim::parse(&request.args)?),
+        "XINFO" => Command::XInfo(XInfo::parse(&request.args)?),
         command => {
             tracing::debug!(
                 "Unknown command: `{}`, args: `{:?}`",
@@ -92,6 +738,263 @@ pub fn parse_command(request: &ClientRequest) -> ParseResult<Command> {
     Ok(command)
 }
 
+// ======================================== Keyspace ========================================
+fn get_string<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &[u8],
+) -> ExecResult<Option<&'a mut Bytes>> {
+    match lookup_key(db, stats, key) {
+        None => Ok(None),
+        Some((Value::String(string), _)) => Ok(Some(string)),
+        Some(_) => Err(ExecError::WrongType),
+    }
+}
+
+fn get_or_insert_string<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &Key,
+) -> ExecResult<&'a mut Bytes> {
+    if lookup_key(db, stats, key).is_none() {
+        db.insert(key.clone(), (Value::String(Bytes::new()), None));
+    }
+    // The key exists, as it was just inserted if missing
+    Ok(get_string(db, stats, key)?.unwrap())
+}
+
+/// Update a string value through a `BytesMut`, which takes over its buffer unless it is shared,
+/// so that large values are not copied on every update.
+fn update_string<T>(string: &mut Bytes, update: impl FnOnce(&mut BytesMut) -> T) -> T {
+    let mut buffer = BytesMut::from(std::mem::take(string));
+    let result = update(&mut buffer);
+    *string = buffer.freeze();
+    result
+}
+
+/// The HyperLogLog held by a key, decoded from its string value.
+fn get_hyperloglog(db: &mut Db, stats: &mut Stats, key: &[u8]) -> ExecResult<Option<HyperLogLog>> {
+    get_string(db, stats, key)?
+        .map(|string| Ok(HyperLogLog::from_bytes(string)?))
+        .transpose()
+}
+
+fn get_list<'a>(db: &'a mut Db, stats: &mut Stats, key: &[u8]) -> ExecResult<Option<&'a mut List>> {
+    match lookup_key(db, stats, key) {
+        None => Ok(None),
+        Some((Value::List(list), _)) => Ok(Some(list)),
+        Some(_) => Err(ExecError::WrongType),
+    }
+}
+
+fn get_or_insert_list<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &Key,
+) -> ExecResult<&'a mut List> {
+    if lookup_key(db, stats, key).is_none() {
+        db.insert(key.clone(), (Value::List(List::new()), None));
+    }
+    // The key exists, as it was just inserted if missing
+    Ok(get_list(db, stats, key)?.unwrap())
+}
+
+fn get_hash<'a>(db: &'a mut Db, stats: &mut Stats, key: &[u8]) -> ExecResult<Option<&'a mut Hash>> {
+    match lookup_key(db, stats, key) {
+        None => Ok(None),
+        Some((Value::Hash(hash), _)) => Ok(Some(hash)),
+        Some(_) => Err(ExecError::WrongType),
+    }
+}
+
+fn get_or_insert_hash<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &Key,
+) -> ExecResult<&'a mut Hash> {
+    if lookup_key(db, stats, key).is_none() {
+        db.insert(key.clone(), (Value::Hash(Hash::new()), None));
+    }
+    // The key exists, as it was just inserted if missing
+    Ok(get_hash(db, stats, key)?.unwrap())
+}
+
+fn get_set<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &[u8],
+) -> ExecResult<Option<&'a mut db::Set>> {
+    match lookup_key(db, stats, key) {
+        None => Ok(None),
+        Some((Value::Set(set), _)) => Ok(Some(set)),
+        Some(_) => Err(ExecError::WrongType),
+    }
+}
+
+fn get_or_insert_set<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &Key,
+) -> ExecResult<&'a mut db::Set> {
+    if lookup_key(db, stats, key).is_none() {
+        db.insert(key.clone(), (Value::Set(db::Set::new()), None));
+    }
+    // The key exists, as it was just inserted if missing
+    Ok(get_set(db, stats, key)?.unwrap())
+}
+
+fn get_zset<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &[u8],
+) -> ExecResult<Option<&'a mut SortedSet>> {
+    match lookup_key(db, stats, key) {
+        None => Ok(None),
+        Some((Value::ZSet(zset), _)) => Ok(Some(zset)),
+        Some(_) => Err(ExecError::WrongType),
+    }
+}
+
+fn get_or_insert_zset<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &Key,
+) -> ExecResult<&'a mut SortedSet> {
+    if lookup_key(db, stats, key).is_none() {
+        db.insert(key.clone(), (Value::ZSet(SortedSet::new()), None));
+    }
+    // The key exists, as it was just inserted if missing
+    Ok(get_zset(db, stats, key)?.unwrap())
+}
+
+fn get_stream<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &[u8],
+) -> ExecResult<Option<&'a mut Stream>> {
+    match lookup_key(db, stats, key) {
+        None => Ok(None),
+        Some((Value::Stream(stream), _)) => Ok(Some(stream)),
+        Some(_) => Err(ExecError::WrongType),
+    }
+}
+
+fn get_or_insert_stream<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &Key,
+) -> ExecResult<&'a mut Stream> {
+    if lookup_key(db, stats, key).is_none() {
+        db.insert(key.clone(), (Value::Stream(Stream::new()), None));
+    }
+    // The key exists, as it was just inserted if missing
+    Ok(get_stream(db, stats, key)?.unwrap())
+}
+
+/// The stream at `key` holding the consumer group `group`, or the error `no_group` makes of the
+/// key and group names when either is missing.
+fn get_stream_with_group<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    key: &[u8],
+    group: &[u8],
+    no_group: fn(String, String) -> ExecError,
+) -> ExecResult<&'a mut Stream> {
+    match get_stream(db, stats, key)? {
+        Some(stream) if stream.group(group).is_some() => Ok(stream),
+        _ => Err(no_group(
+            String::from_utf8_lossy(key).into_owned(),
+            String::from_utf8_lossy(group).into_owned(),
+        )),
+    }
+}
+
+// ======================================== Reply ========================================
+/// Reply a score as a double, which RESP2 lacks and gets as a bulk string formatted like Redis.
+fn score_reply(score: f64, protocol: RespProtocol) -> RespData {
+    match protocol {
+        RespProtocol::Resp2 => RespData::BulkString(Some(Bytes::from(format_float(score)))),
+        RespProtocol::Resp3 => RespData::Double(score),
+    }
+}
+
+/// Reply a position as `[longitude, latitude]`. RESP2 gives the coordinates with up to 17
+/// decimals, as Redis does.
+fn coordinates_reply((longitude, latitude): (f64, f64), protocol: RespProtocol) -> RespData {
+    let coordinate = |value: f64| match protocol {
+        RespProtocol::Resp2 => {
+            let value = format!("{value:.17}");
+            let value = value.trim_end_matches('0').trim_end_matches('.');
+            RespData::BulkString(Some(Bytes::from(value.to_string())))
+        }
+        RespProtocol::Resp3 => RespData::Double(value),
+    };
+    RespData::Array(vec![coordinate(longitude), coordinate(latitude)])
+}
+
+/// Reply a distance with 4 decimals, as a bulk string whatever the protocol.
+fn distance_reply(distance: f64) -> RespData {
+    RespData::BulkString(Some(Bytes::from(format!("{distance:.4}"))))
+}
+
+/// Reply sorted set members, along with their scores if `with_scores`: as `[member, score]`
+/// pairs on RESP3, and flattened on RESP2.
+fn scored_members_reply(
+    members: Vec<(Bytes, f64)>,
+    with_scores: bool,
+    protocol: RespProtocol,
+) -> RespData {
+    let reply = if !with_scores {
+        members
+            .into_iter()
+            .map(|(member, _)| RespData::BulkString(Some(member)))
+            .collect()
+    } else if protocol == RespProtocol::Resp3 {
+        members
+            .into_iter()
+            .map(|(member, score)| {
+                RespData::Array(vec![
+                    RespData::BulkString(Some(member)),
+                    score_reply(score, protocol),
+                ])
+            })
+            .collect()
+    } else {
+        members
+            .into_iter()
+            .flat_map(|(member, score)| {
+                [
+                    RespData::BulkString(Some(member)),
+                    score_reply(score, protocol),
+                ]
+            })
+            .collect()
+    };
+    RespData::Array(reply)
+}
+
+/// Reply a stream entry as `[id, [field, value, ...]]`.
+fn stream_entry_reply((id, fields): StreamEntry) -> RespData {
+    RespData::Array(vec![
+        RespData::BulkString(Some(Bytes::from(id.to_string()))),
+        RespData::Array(
+            fields
+                .into_iter()
+                .flat_map(|(field, value)| {
+                    [
+                        RespData::BulkString(Some(field)),
+                        RespData::BulkString(Some(value)),
+                    ]
+                })
+                .collect(),
+        ),
+    ])
+}
+
+fn stream_entries_reply(entries: impl IntoIterator<Item = StreamEntry>) -> RespData {
+    RespData::Array(entries.into_iter().map(stream_entry_reply).collect())
+}
+
 // ======================================== Execute ========================================
 pub trait ExecuteCommand {
     async fn execute(
@@ -112,8 +1015,121 @@ impl ExecuteCommand for Command {
             Command::Echo(echo) => echo.execute(server, conn).await,
             Command::Get(get) => get.execute(server, conn).await,
             Command::Set(set) => set.execute(server, conn).await,
+            Command::Incr(incr) => incr.execute(server, conn).await,
+            Command::IncrByFloat(incr) => incr.execute(server, conn).await,
+            Command::Append(append) => append.execute(server, conn).await,
+            Command::StrLen(strlen) => strlen.execute(server, conn).await,
+            Command::GetRange(getrange) => getrange.execute(server, conn).await,
+            Command::SetRange(setrange) => setrange.execute(server, conn).await,
+            Command::GetDel(getdel) => getdel.execute(server, conn).await,
+            Command::GetEx(getex) => getex.execute(server, conn).await,
+            Command::GetSet(getset) => getset.execute(server, conn).await,
+            Command::MSet(mset) => mset.execute(server, conn).await,
+            Command::MGet(mget) => mget.execute(server, conn).await,
+            Command::SetNx(setnx) => setnx.execute(server, conn).await,
+            Command::SetEx(setex) => setex.execute(server, conn).await,
+            Command::Lcs(lcs) => lcs.execute(server, conn).await,
+            Command::SetBit(setbit) => setbit.execute(server, conn).await,
+            Command::GetBit(getbit) => getbit.execute(server, conn).await,
+            Command::BitCount(bitcount) => bitcount.execute(server, conn).await,
+            Command::BitPos(bitpos) => bitpos.execute(server, conn).await,
+            Command::BitOp(bitop) => bitop.execute(server, conn).await,
+            Command::BitField(bitfield) => bitfield.execute(server, conn).await,
+            Command::PfAdd(pfadd) => pfadd.execute(server, conn).await,
+            Command::PfCount(pfcount) => pfcount.execute(server, conn).await,
+            Command::PfMerge(pfmerge) => pfmerge.execute(server, conn).await,
+            Command::GeoAdd(geoadd) => geoadd.execute(server, conn).await,
+            Command::GeoPos(geopos) => geopos.execute(server, conn).await,
+            Command::GeoDist(geodist) => geodist.execute(server, conn).await,
+            Command::GeoHash(geohash) => geohash.execute(server, conn).await,
+            Command::GeoSearch(geosearch) => geosearch.execute(server, conn).await,
+            Command::Del(del) => del.execute(server, conn).await,
+            Command::Exists(exists) => exists.execute(server, conn).await,
+            Command::Type(keytype) => keytype.execute(server, conn).await,
+            Command::Rename(rename) => rename.execute(server, conn).await,
+            Command::Copy(copy) => copy.execute(server, conn).await,
+            Command::RandomKey(randomkey) => randomkey.execute(server, conn).await,
+            Command::DbSize(dbsize) => dbsize.execute(server, conn).await,
+            Command::Expire(expire) => expire.execute(server, conn).await,
+            Command::Ttl(ttl) => ttl.execute(server, conn).await,
+            Command::Persist(persist) => persist.execute(server, conn).await,
+            Command::Info(info) => info.execute(server, conn).await,
             Command::Client(client) => client.execute(server, conn).await,
             Command::Config(config) => config.execute(server, conn).await,
+            Command::Hello(hello) => hello.execute(server, conn).await,
+            Command::Push(push) => push.execute(server, conn).await,
+            Command::Pop(pop) => pop.execute(server, conn).await,
+            Command::LRange(lrange) => lrange.execute(server, conn).await,
+            Command::LLen(llen) => llen.execute(server, conn).await,
+            Command::LIndex(lindex) => lindex.execute(server, conn).await,
+            Command::LSet(lset) => lset.execute(server, conn).await,
+            Command::LRem(lrem) => lrem.execute(server, conn).await,
+            Command::LTrim(ltrim) => ltrim.execute(server, conn).await,
+            Command::LInsert(linsert) => linsert.execute(server, conn).await,
+            Command::LPos(lpos) => lpos.execute(server, conn).await,
+            Command::LMove(lmove) => lmove.execute(server, conn).await,
+            Command::LMPop(lmpop) => lmpop.execute(server, conn).await,
+            Command::BPop(bpop) => bpop.execute(server, conn).await,
+            Command::BLMove(blmove) => blmove.execute(server, conn).await,
+            Command::BLMPop(blmpop) => blmpop.execute(server, conn).await,
+            Command::HSet(hset) => hset.execute(server, conn).await,
+            Command::HGet(hget) => hget.execute(server, conn).await,
+            Command::HMGet(hmget) => hmget.execute(server, conn).await,
+            Command::HDel(hdel) => hdel.execute(server, conn).await,
+            Command::HGetAll(hgetall) => hgetall.execute(server, conn).await,
+            Command::HKeys(hkeys) => hkeys.execute(server, conn).await,
+            Command::HVals(hvals) => hvals.execute(server, conn).await,
+            Command::HLen(hlen) => hlen.execute(server, conn).await,
+            Command::HExists(hexists) => hexists.execute(server, conn).await,
+            Command::HIncrBy(hincrby) => hincrby.execute(server, conn).await,
+            Command::HIncrByFloat(hincrbyfloat) => hincrbyfloat.execute(server, conn).await,
+            Command::HSetNx(hsetnx) => hsetnx.execute(server, conn).await,
+            Command::HStrLen(hstrlen) => hstrlen.execute(server, conn).await,
+            Command::HRandField(hrandfield) => hrandfield.execute(server, conn).await,
+            Command::HScan(hscan) => hscan.execute(server, conn).await,
+            Command::HExpire(hexpire) => hexpire.execute(server, conn).await,
+            Command::HTtl(httl) => httl.execute(server, conn).await,
+            Command::HPersist(hpersist) => hpersist.execute(server, conn).await,
+            Command::HGetEx(hgetex) => hgetex.execute(server, conn).await,
+            Command::HSetEx(hsetex) => hsetex.execute(server, conn).await,
+            Command::Save(save) => save.execute(server, conn).await,
+            Command::SAdd(sadd) => sadd.execute(server, conn).await,
+            Command::SRem(srem) => srem.execute(server, conn).await,
+            Command::SMembers(smembers) => smembers.execute(server, conn).await,
+            Command::SIsMember(sismember) => sismember.execute(server, conn).await,
+            Command::SMIsMember(smismember) => smismember.execute(server, conn).await,
+            Command::SCard(scard) => scard.execute(server, conn).await,
+            Command::SPop(spop) => spop.execute(server, conn).await,
+            Command::SRandMember(srandmember) => srandmember.execute(server, conn).await,
+            Command::SMove(smove) => smove.execute(server, conn).await,
+            Command::SetOp(setop) => setop.execute(server, conn).await,
+            Command::ZAdd(zadd) => zadd.execute(server, conn).await,
+            Command::ZIncrBy(zincrby) => zincrby.execute(server, conn).await,
+            Command::ZRange(zrange) => zrange.execute(server, conn).await,
+            Command::ZRank(zrank) => zrank.execute(server, conn).await,
+            Command::ZScore(zscore) => zscore.execute(server, conn).await,
+            Command::ZRem(zrem) => zrem.execute(server, conn).await,
+            Command::ZCount(zcount) => zcount.execute(server, conn).await,
+            Command::ZCard(zcard) => zcard.execute(server, conn).await,
+            Command::ZPop(zpop) => zpop.execute(server, conn).await,
+            Command::ZSetOp(zsetop) => zsetop.execute(server, conn).await,
+            Command::ZInterCard(zintercard) => zintercard.execute(server, conn).await,
+            Command::ZMPop(zmpop) => zmpop.execute(server, conn).await,
+            Command::BZPop(bzpop) => bzpop.execute(server, conn).await,
+            Command::BZMPop(bzmpop) => bzmpop.execute(server, conn).await,
+            Command::XAdd(xadd) => xadd.execute(server, conn).await,
+            Command::XRange(xrange) => xrange.execute(server, conn).await,
+            Command::XLen(xlen) => xlen.execute(server, conn).await,
+            Command::XDel(xdel) => xdel.execute(server, conn).await,
+            Command::XRead(xread) => xread.execute(server, conn).await,
+            Command::XTrim(xtrim) => xtrim.execute(server, conn).await,
+            Command::XGroup(xgroup) => xgroup.execute(server, conn).await,
+            Command::XReadGroup(xreadgroup) => xreadgroup.execute(server, conn).await,
+            Command::XAck(xack) => xack.execute(server, conn).await,
+            Command::XPending(xpending) => xpending.execute(server, conn).await,
+            Command::XClaim(xclaim) => xclaim.execute(server, conn).await,
+            Command::XAutoClaim(xautoclaim) => xautoclaim.execute(server, conn).await,
+            Command::XInfo(xinfo) => xinfo.execute(server, conn).await,
             Command::Unknown(unknown) => unknown.execute(server, conn).await,
         }
     }
@@ -121,7 +1137,7 @@ impl ExecuteCommand for Command {
 
 #[cfg(test)]
 pub(super) mod test {
-    use std::{path::PathBuf, sync::Arc};
+    use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
     use bytes::Bytes;
     use tokio::{
@@ -144,7 +1160,18 @@ pub(super) mod test {
         }
     }
 
-    pub async fn build_server_connection() -> (Arc<Mutex<Server>>, Connection) {
+    /// A connection whose client stays connected until the end of the test.
+    pub async fn build_connection(id: u64) -> (SocketAddr, Connection) {
+        let (server_addr, conn, client_stream) = build_connection_with_client(id).await;
+        tokio::spawn(async move {
+            let _client_stream = client_stream;
+            std::future::pending::<()>().await
+        });
+        (server_addr, conn)
+    }
+
+    /// A connection along with its client side, which a test drops to disconnect the client.
+    pub async fn build_connection_with_client(id: u64) -> (SocketAddr, Connection, TcpStream) {
         let listener = TcpListener::bind("127.0.0.1:0")
             .await
             .expect("bind local listener");
@@ -156,14 +1183,23 @@ pub(super) mod test {
         });
         let (server_stream, client_addr) =
             listener.accept().await.expect("accept local connection");
-        let _client_stream = connect.await.expect("join connect task");
+        let client_stream = connect.await.expect("join connect task");
 
+        (
+            server_addr,
+            Connection::new(id, client_addr, server_stream),
+            client_stream,
+        )
+    }
+
+    pub async fn build_server_connection() -> (Arc<Mutex<Server>>, Connection) {
+        let (server_addr, conn) = build_connection(1).await;
         (
             Arc::new(Mutex::new(Server::new(
                 server_addr,
                 PathBuf::from("/tmp/dump.rdb"),
             ))),
-            Connection::new(1, client_addr, server_stream),
+            conn,
         )
     }
 }
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult,
+        get_or_insert_string, update_string,
+    },
+    db::STRING_MAX_LEN,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Append {
+    key: Bytes,
+    value: Bytes,
+}
+
+impl Parse for Append {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(Append {
+            key: args[0].clone(),
+            value: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for Append {
+    /// Replies the length of the string after the append.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let string = get_or_insert_string(db, stats, &self.key)?;
+        if string.len() + self.value.len() > STRING_MAX_LEN {
+            return Err(ExecError::StringTooLong);
+        }
+        update_string(string, |buffer| buffer.extend_from_slice(&self.value));
+
+        Ok(RespData::Integer(string.len() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::Append;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_append_should_read_value() {
+        let cmd = parse_command(&build_request("APPEND", &["k", "v"])).expect("parse append");
+        assert_eq!(
+            cmd,
+            Command::Append(Append {
+                key: Bytes::from_owner("k"),
+                value: Bytes::from_owner("v"),
+            })
+        );
+        assert!(parse_command(&build_request("APPEND", &["k"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_append_should_extend_value_and_keep_ttl() {
+        let (server, mut conn) = build_server_connection().await;
+        let expire_time = Some(unix_time_ms() + 60_000);
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("Hello")), expire_time),
+        );
+
+        for (args, expected) in [
+            (&["k", " World"][..], 11),
+            (&["k", ""], 11),
+            (&["new", "abc"], 3),
+        ] {
+            let cmd = parse_command(&build_request("APPEND", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute append");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+        assert_eq!(
+            server.lock().await.db.get(b"k".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("Hello World")), expire_time))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_append_should_reject_non_string_value() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+        );
+
+        let cmd = parse_command(&build_request("APPEND", &["list", "v"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("append to a list");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_string,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// The `start [end [BYTE | BIT]]` range of `BITCOUNT` and `BITPOS`.
+#[derive(Debug, PartialEq)]
+pub(super) struct BitRange {
+    pub start: i64,
+    pub end: Option<i64>,
+    /// Whether the range is in bits rather than bytes.
+    pub bit: bool,
+}
+
+impl BitRange {
+    /// Parse the range from the arguments left after the key, if any.
+    pub fn parse(args: &[Bytes]) -> ParseResult<Option<Self>> {
+        let Some(start) = args.first() else {
+            return Ok(None);
+        };
+        let mut range = BitRange {
+            start: lexical_core::parse(start)?,
+            end: args
+                .get(1)
+                .map(|end| lexical_core::parse(end))
+                .transpose()?,
+            bit: false,
+        };
+        match args.get(2..) {
+            None | Some([]) => {}
+            Some([unit]) => {
+                let unit = str::from_utf8(unit)?;
+                match unit.to_uppercase().as_str() {
+                    "BYTE" => {}
+                    "BIT" => range.bit = true,
+                    _ => return Err(ParseError::InvalidArgument(unit.to_string())),
+                }
+            }
+            Some(_) => {
+                return Err(ParseError::InvalidArgument(
+                    String::from_utf8_lossy(&args[3]).into_owned(),
+                ));
+            }
+        }
+        Ok(Some(range))
+    }
+
+    /// Resolve the range against a string of `len` bytes the way Redis does: negative offsets
+    /// count from the end, and both ends are clamped into the string. Returns the first and
+    /// last bit of the range, `None` when it is empty.
+    pub fn resolve(&self, len: usize) -> Option<(usize, usize)> {
+        let total = if self.bit { len * 8 } else { len } as i64;
+        let convert = |offset: i64| {
+            if offset < 0 {
+                (total + offset).max(0)
+            } else {
+                offset
+            }
+        };
+        let start = convert(self.start);
+        let end = convert(self.end.unwrap_or(-1)).min(total - 1);
+        if start > end {
+            return None;
+        }
+        let (start, end) = (start as usize, end as usize);
+        Some(if self.bit {
+            (start, end)
+        } else {
+            (start * 8, end * 8 + 7)
+        })
+    }
+}
+
+/// Count the bits set in `bytes`, a word at a time.
+fn popcount(bytes: &[u8]) -> u64 {
+    let mut words = bytes.chunks_exact(8);
+    let count: u64 = words
+        .by_ref()
+        // Chunks of 8 bytes always convert
+        .map(|word| u64::from_ne_bytes(word.try_into().unwrap()).count_ones() as u64)
+        .sum();
+    count
+        + words
+            .remainder()
+            .iter()
+            .map(|byte| byte.count_ones() as u64)
+            .sum::<u64>()
+}
+
+/// Count the bits set from bit `start` to bit `end` included.
+fn count_bits(bytes: &[u8], start: usize, end: usize) -> u64 {
+    let (first, last) = (start / 8, end / 8);
+    // Count whole bytes, then remove the bits of the first and last bytes outside the range
+    let outside = (bytes[first] & !(0xff >> (start % 8))).count_ones()
+        + (bytes[last] & !(0xff << (7 - end % 8))).count_ones();
+    popcount(&bytes[first..=last]) - outside as u64
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BitCount {
+    key: Bytes,
+    range: Option<BitRange>,
+}
+
+impl Parse for BitCount {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        let range = BitRange::parse(&args[1..])?;
+        // The end is required along with the start
+        if range.as_ref().is_some_and(|range| range.end.is_none()) {
+            return Err(ParseError::InvalidArgument(
+                String::from_utf8_lossy(&args[1]).into_owned(),
+            ));
+        }
+        Ok(BitCount {
+            key: args[0].clone(),
+            range,
+        })
+    }
+}
+
+impl ExecuteCommand for BitCount {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let Some(string) = get_string(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(0));
+        };
+        let count = match &self.range {
+            None => popcount(string),
+            // Redis replies 0 for such a range before clamping it
+            Some(BitRange {
+                start,
+                end: Some(end),
+                ..
+            }) if *start < 0 && *end < 0 && start > end => 0,
+            Some(range) => range
+                .resolve(string.len())
+                .map_or(0, |(start, end)| count_bits(string, start, end)),
+        };
+        Ok(RespData::Integer(count as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{BitCount, BitRange, count_bits, popcount};
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_bitcount_should_read_range() {
+        let cmd = parse_command(&build_request("BITCOUNT", &["k", "1", "-1", "bit"]))
+            .expect("parse bitcount");
+        assert_eq!(
+            cmd,
+            Command::BitCount(BitCount {
+                key: Bytes::from_owner("k"),
+                range: Some(BitRange {
+                    start: 1,
+                    end: Some(-1),
+                    bit: true,
+                }),
+            })
+        );
+
+        for args in [
+            &[][..],
+            &["k", "1"],
+            &["k", "1", "2", "WORD"],
+            &["k", "1", "2", "BIT", "x"],
+        ] {
+            assert!(
+                parse_command(&build_request("BITCOUNT", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn bit_range_should_resolve_like_redis() {
+        let range = |start, end, bit| BitRange {
+            start,
+            end: Some(end),
+            bit,
+        };
+        assert_eq!(range(0, -1, false).resolve(3), Some((0, 23)));
+        assert_eq!(range(1, 100, false).resolve(3), Some((8, 23)));
+        assert_eq!(range(0, -100, false).resolve(3), Some((0, 7)));
+        assert_eq!(range(5, 10, true).resolve(3), Some((5, 10)));
+        assert_eq!(range(-3, -1, true).resolve(3), Some((21, 23)));
+        assert_eq!(range(2, 1, false).resolve(3), None);
+        assert_eq!(range(0, -1, false).resolve(0), None);
+    }
+
+    #[test]
+    fn count_bits_should_mask_partial_bytes() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(popcount(&bytes), 1024);
+        assert_eq!(count_bits(&bytes, 0, 255 * 8 + 7), 1024);
+        // 0b0000_0001, 0b0000_0010 and 0b0000_0011
+        assert_eq!(count_bits(&bytes, 15, 31), 4);
+        assert_eq!(count_bits(&bytes, 8, 14), 0);
+        assert_eq!(count_bits(&bytes, 30, 30), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_bitcount_should_count_ranges() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("foobar")), None),
+        );
+
+        for (args, expected) in [
+            (&["k"][..], 26),
+            (&["k", "0", "0"], 4),
+            (&["k", "1", "1"], 6),
+            (&["k", "1", "1", "BYTE"], 6),
+            (&["k", "5", "30", "BIT"], 17),
+            (&["k", "-1", "-5"], 0),
+            (&["missing"], 0),
+        ] {
+            let cmd = parse_command(&build_request("BITCOUNT", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute bitcount");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,536 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecError, ExecResult, ParseError},
+        get_or_insert_string, get_string, update_string,
+    },
+    db::STRING_MAX_LEN,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// What `SET` and `INCRBY` do with a value that does not fit in the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Overflow {
+    /// Keep the lowest bits, wrapping around like integer arithmetic does.
+    Wrap,
+    /// Saturate to the smallest or largest value of the field.
+    Sat,
+    /// Leave the field as it is, and reply nil.
+    Fail,
+}
+
+/// A signed integer of 1 to 64 bits, or an unsigned one of 1 to 63 bits, as `i8` or `u4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Encoding {
+    signed: bool,
+    bits: u32,
+}
+
+impl Encoding {
+    fn parse(arg: &Bytes) -> ParseResult<Self> {
+        let invalid = || ParseError::InvalidArgument(String::from_utf8_lossy(arg).into_owned());
+        let (signed, max_bits) = match arg.first() {
+            Some(b'i' | b'I') => (true, 64),
+            Some(b'u' | b'U') => (false, 63),
+            _ => return Err(invalid()),
+        };
+        let bits = lexical_core::parse(&arg[1..])
+            .ok()
+            .filter(|bits| (1..=max_bits).contains(bits))
+            .ok_or_else(invalid)?;
+        Ok(Encoding { signed, bits })
+    }
+
+    fn min(&self) -> i128 {
+        if self.signed {
+            -(1 << (self.bits - 1))
+        } else {
+            0
+        }
+    }
+
+    fn max(&self) -> i128 {
+        if self.signed {
+            (1 << (self.bits - 1)) - 1
+        } else {
+            (1 << self.bits) - 1
+        }
+    }
+
+    /// Fit `value` in the field, `None` if it overflows with [`Overflow::Fail`].
+    fn fit(&self, value: i128, overflow: Overflow) -> Option<i64> {
+        if (self.min()..=self.max()).contains(&value) {
+            return Some(value as i64);
+        }
+        match overflow {
+            Overflow::Wrap => {
+                let wrapped = value.rem_euclid(1 << self.bits);
+                Some(if wrapped > self.max() {
+                    wrapped - (1 << self.bits)
+                } else {
+                    wrapped
+                } as i64)
+            }
+            Overflow::Sat => Some(if value > self.max() {
+                self.max()
+            } else {
+                self.min()
+            } as i64),
+            Overflow::Fail => None,
+        }
+    }
+
+    /// Read the field at bit `offset`, the bits past the end of `bytes` being 0.
+    fn read(&self, bytes: &[u8], offset: usize) -> i64 {
+        let mut value = 0u64;
+        for position in offset..offset + self.bits as usize {
+            let byte = bytes.get(position / 8).copied().unwrap_or(0);
+            value = (value << 1) | ((byte >> (7 - position % 8)) & 1) as u64;
+        }
+        // Extend the sign bit to the higher bits
+        if self.signed && self.bits < 64 && value >> (self.bits - 1) != 0 {
+            value |= u64::MAX << self.bits;
+        }
+        value as i64
+    }
+
+    /// Write the lowest bits of `value` to the field at bit `offset`, which `bytes` must cover.
+    fn write(&self, bytes: &mut [u8], offset: usize, value: i64) {
+        for (index, position) in (offset..offset + self.bits as usize).enumerate() {
+            let bit = (value as u64 >> (self.bits as usize - 1 - index)) & 1;
+            let mask = 0x80 >> (position % 8);
+            if bit == 1 {
+                bytes[position / 8] |= mask;
+            } else {
+                bytes[position / 8] &= !mask;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldOp {
+    Get,
+    Set(i64),
+    IncrBy(i64),
+}
+
+#[derive(Debug, PartialEq)]
+struct Field {
+    op: FieldOp,
+    encoding: Encoding,
+    /// In bits.
+    offset: usize,
+    /// The mode of the last `OVERFLOW` before the operation.
+    overflow: Overflow,
+}
+
+/// BITFIELD and BITFIELD_RO.
+#[derive(Debug, PartialEq)]
+pub struct BitField {
+    key: Bytes,
+    fields: Vec<Field>,
+}
+
+/// Parse a bit offset, which is multiplied by the width of the field when prefixed by `#`. The
+/// whole field must fit in the largest string, even when only read.
+fn parse_field_offset(arg: &Bytes, encoding: Encoding) -> ParseResult<usize> {
+    let bits = encoding.bits as usize;
+    let (offset, width) = match arg.strip_prefix(b"#") {
+        Some(offset) => (offset, bits),
+        None => (&arg[..], 1),
+    };
+    let offset = lexical_core::parse::<usize>(offset)
+        .ok()
+        .and_then(|offset| offset.checked_mul(width))
+        .filter(|offset| {
+            offset
+                .checked_add(bits)
+                .is_some_and(|end| end <= STRING_MAX_LEN * 8)
+        })
+        .ok_or(ExecError::BitOffsetOutOfRange)?;
+    Ok(offset)
+}
+
+impl BitField {
+    /// `read_only` is for BITFIELD_RO, which only accepts `GET`.
+    pub fn parse(args: &[Bytes], read_only: bool) -> ParseResult<Self> {
+        check_length_ge(args, 1)?;
+        let mut fields = Vec::new();
+        let mut overflow = Overflow::Wrap;
+
+        let mut index = 1;
+        while index < args.len() {
+            let subcommand = str::from_utf8(&args[index])?.to_uppercase();
+            let width = match subcommand.as_str() {
+                "GET" => 3,
+                "SET" | "INCRBY" if !read_only => 4,
+                "OVERFLOW" => 2,
+                _ => return Err(ParseError::InvalidArgument(subcommand)),
+            };
+            check_length_ge(args, index + width)?;
+            let args = &args[index..index + width];
+            index += width;
+
+            if subcommand == "OVERFLOW" {
+                let mode = str::from_utf8(&args[1])?;
+                overflow = match mode.to_uppercase().as_str() {
+                    "WRAP" => Overflow::Wrap,
+                    "SAT" => Overflow::Sat,
+                    "FAIL" => Overflow::Fail,
+                    _ => return Err(ParseError::InvalidArgument(mode.to_string())),
+                };
+                continue;
+            }
+            let encoding = Encoding::parse(&args[1])?;
+            let op = match subcommand.as_str() {
+                "GET" => FieldOp::Get,
+                "SET" => FieldOp::Set(lexical_core::parse(&args[3])?),
+                _ => FieldOp::IncrBy(lexical_core::parse(&args[3])?),
+            };
+            fields.push(Field {
+                op,
+                encoding,
+                offset: parse_field_offset(&args[2], encoding)?,
+                overflow,
+            });
+        }
+
+        Ok(BitField {
+            key: args[0].clone(),
+            fields,
+        })
+    }
+
+    /// Run the `GET` operations of a request without writes on `bytes`.
+    fn read(&self, bytes: &[u8]) -> Vec<RespData> {
+        self.fields
+            .iter()
+            .map(|field| {
+                debug_assert_eq!(field.op, FieldOp::Get);
+                RespData::Integer(field.encoding.read(bytes, field.offset))
+            })
+            .collect()
+    }
+
+    /// Run the operations in order on `bytes`, which covers every field written.
+    fn apply(&self, bytes: &mut [u8]) -> Vec<RespData> {
+        self.fields
+            .iter()
+            .map(|field| {
+                let Field {
+                    op,
+                    encoding,
+                    offset,
+                    overflow,
+                } = *field;
+                let old = encoding.read(bytes, offset);
+                let (new, reply) = match op {
+                    FieldOp::Get => return RespData::Integer(old),
+                    FieldOp::Set(value) => {
+                        // Unsigned fields take the value as unsigned, as Redis does
+                        let value = if encoding.signed {
+                            value as i128
+                        } else {
+                            value as u64 as i128
+                        };
+                        let new = encoding.fit(value, overflow);
+                        (new, old)
+                    }
+                    FieldOp::IncrBy(increment) => {
+                        let new = encoding.fit(old as i128 + increment as i128, overflow);
+                        (new, new.unwrap_or_default())
+                    }
+                };
+                match new {
+                    Some(new) => {
+                        encoding.write(bytes, offset, new);
+                        RespData::Integer(reply)
+                    }
+                    None => RespData::Null,
+                }
+            })
+            .collect()
+    }
+}
+
+impl ExecuteCommand for BitField {
+    /// Replies the result of each operation: the value for `GET`, the previous value for `SET`
+    /// and the new value for `INCRBY`, or nil when an overflow failed it.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        // The bytes needed by the fields written, which the string grows to before any write
+        let len = self
+            .fields
+            .iter()
+            .filter(|field| field.op != FieldOp::Get)
+            .map(|field| (field.offset + field.encoding.bits as usize).div_ceil(8))
+            .max();
+
+        let replies = match len {
+            // Reads only, served from the stored bytes without a copy
+            None => self.read(get_string(db, stats, &self.key)?.map_or(&[], |string| string)),
+            Some(len) => {
+                let string = get_or_insert_string(db, stats, &self.key)?;
+                update_string(string, |buffer| {
+                    if buffer.len() < len {
+                        buffer.resize(len, 0);
+                    }
+                    self.apply(buffer)
+                })
+            }
+        };
+        Ok(RespData::Array(replies))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::{BitField, Encoding, Field, FieldOp, Overflow};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    fn integers(values: &[i64]) -> RespData {
+        RespData::Array(values.iter().map(|v| RespData::Integer(*v)).collect())
+    }
+
+    #[test]
+    fn parse_bitfield_should_read_operations() {
+        let cmd = parse_command(&build_request(
+            "BITFIELD",
+            &[
+                "k", "GET", "u8", "#2", "overflow", "sat", "SET", "i5", "3", "-4", "INCRBY", "i64",
+                "0", "1",
+            ],
+        ))
+        .expect("parse bitfield");
+        let encoding = |signed, bits| Encoding { signed, bits };
+        assert_eq!(
+            cmd,
+            Command::BitField(BitField {
+                key: Bytes::from_owner("k"),
+                fields: vec![
+                    Field {
+                        op: FieldOp::Get,
+                        encoding: encoding(false, 8),
+                        offset: 16,
+                        overflow: Overflow::Wrap,
+                    },
+                    Field {
+                        op: FieldOp::Set(-4),
+                        encoding: encoding(true, 5),
+                        offset: 3,
+                        overflow: Overflow::Sat,
+                    },
+                    Field {
+                        op: FieldOp::IncrBy(1),
+                        encoding: encoding(true, 64),
+                        offset: 0,
+                        overflow: Overflow::Sat,
+                    },
+                ],
+            })
+        );
+
+        for (command, args) in [
+            ("BITFIELD", &["k", "GET", "u64", "0"][..]),
+            ("BITFIELD", &["k", "GET", "i65", "0"]),
+            ("BITFIELD", &["k", "GET", "i0", "0"]),
+            ("BITFIELD", &["k", "GET", "x8", "0"]),
+            ("BITFIELD", &["k", "GET", "u8", "-1"]),
+            ("BITFIELD", &["k", "GET", "u8", "#536870912"]),
+            ("BITFIELD", &["k", "SET", "u8", "0"]),
+            ("BITFIELD", &["k", "OVERFLOW", "NONE"]),
+            ("BITFIELD_RO", &["k", "SET", "u8", "0", "1"]),
+            ("BITFIELD_RO", &["k", "INCRBY", "u8", "0", "1"]),
+        ] {
+            assert!(
+                parse_command(&build_request(command, args)).is_err(),
+                "{command} {args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_bitfield_should_fit_field_in_largest_string() {
+        // The last byte of a 512MB string starts at bit 4294967288
+        for (args, offset) in [
+            (&["k", "GET", "u8", "4294967288"][..], 4294967288),
+            (&["k", "GET", "i64", "#67108863"], 4294967232),
+            (&["k", "SET", "u8", "#536870911", "1"], 4294967288),
+        ] {
+            let Command::BitField(BitField { fields, .. }) =
+                parse_command(&build_request("BITFIELD", args)).expect("parse bitfield")
+            else {
+                panic!("expected BITFIELD");
+            };
+            assert_eq!(fields[0].offset, offset, "{args:?}");
+        }
+
+        for args in [
+            &["k", "GET", "u8", "4294967289"][..],
+            &["k", "GET", "i64", "#67108864"],
+            &["k", "GET", "u2", "4294967295"],
+            &["k", "INCRBY", "u8", "4294967296", "1"],
+            &["k", "GET", "u8", "18446744073709551615"],
+            &["k", "GET", "u8", "abc"],
+        ] {
+            let err = parse_command(&build_request("BITFIELD", args))
+                .expect_err("field past the largest string");
+            assert_eq!(
+                err,
+                ParseError::Rejected(ExecError::BitOffsetOutOfRange),
+                "{args:?}"
+            );
+            assert_eq!(
+                err.to_string(),
+                "ERR bit offset is not an integer or out of range"
+            );
+        }
+    }
+
+    #[test]
+    fn encoding_should_fit_values_by_overflow() {
+        let i8 = Encoding {
+            signed: true,
+            bits: 8,
+        };
+        let u2 = Encoding {
+            signed: false,
+            bits: 2,
+        };
+        assert_eq!(i8.fit(127, Overflow::Fail), Some(127));
+        assert_eq!(i8.fit(128, Overflow::Wrap), Some(-128));
+        assert_eq!(i8.fit(-129, Overflow::Wrap), Some(127));
+        assert_eq!(i8.fit(300, Overflow::Sat), Some(127));
+        assert_eq!(i8.fit(-300, Overflow::Sat), Some(-128));
+        assert_eq!(i8.fit(128, Overflow::Fail), None);
+        assert_eq!(u2.fit(5, Overflow::Wrap), Some(1));
+        assert_eq!(u2.fit(-1, Overflow::Wrap), Some(3));
+        assert_eq!(u2.fit(-1, Overflow::Sat), Some(0));
+    }
+
+    #[test]
+    fn encoding_should_read_and_write_unaligned_fields() {
+        let mut bytes = [0u8; 3];
+        let i5 = Encoding {
+            signed: true,
+            bits: 5,
+        };
+        i5.write(&mut bytes, 6, -3);
+        assert_eq!(bytes, [0b0000_0011, 0b1010_0000, 0]);
+        assert_eq!(i5.read(&bytes, 6), -3);
+        let u63 = Encoding {
+            signed: false,
+            bits: 63,
+        };
+        assert_eq!(u63.read(&bytes, 0), 0b11_1010_0000 << 47);
+        let i64 = Encoding {
+            signed: true,
+            bits: 64,
+        };
+        assert_eq!(i64.read(&[0xff; 8], 0), -1);
+    }
+
+    #[tokio::test]
+    async fn execute_bitfield_should_run_operations_in_order() {
+        let (server, mut conn) = build_server_connection().await;
+
+        for (args, expected) in [
+            (
+                &[
+                    "k", "SET", "u8", "#1", "255", "GET", "u4", "8", "INCRBY", "i8", "8", "1",
+                ][..],
+                integers(&[0, 15, 0]),
+            ),
+            (
+                &[
+                    "k", "INCRBY", "u2", "100", "1", "OVERFLOW", "SAT", "INCRBY", "u2", "102", "5",
+                ],
+                integers(&[1, 3]),
+            ),
+            (
+                &[
+                    "k", "OVERFLOW", "FAIL", "INCRBY", "u2", "102", "1", "GET", "u2", "102",
+                ],
+                RespData::Array(vec![RespData::Null, RespData::Integer(3)]),
+            ),
+            (
+                &[
+                    "k", "SET", "u8", "0", "-1", "OVERFLOW", "SAT", "SET", "u8", "8", "-1",
+                ],
+                integers(&[0, 0]),
+            ),
+        ] {
+            let cmd = parse_command(&build_request("BITFIELD", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute bitfield");
+            assert_eq!(resp, expected, "{args:?}");
+        }
+
+        let cmd = parse_command(&build_request(
+            "BITFIELD_RO",
+            &["k", "GET", "u8", "0", "GET", "u8", "8", "GET", "u8", "1000"],
+        ))
+        .unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute bitfield_ro");
+        assert_eq!(resp, integers(&[255, 255, 0]));
+
+        let mut expected = vec![0; 13];
+        expected[..2].fill(255);
+        expected[12] = 0b0000_0111;
+        assert_eq!(
+            server.lock().await.db.get(b"k".as_ref()),
+            Some(&(Value::String(Bytes::from(expected)), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_bitfield_should_not_create_key_for_reads() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+        );
+
+        let cmd = parse_command(&build_request("BITFIELD", &["k", "GET", "i8", "0"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute bitfield");
+        assert_eq!(resp, integers(&[0]));
+        assert!(!server.lock().await.db.contains_key(b"k".as_ref()));
+
+        let cmd = parse_command(&build_request("BITFIELD", &["list", "GET", "i8", "0"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
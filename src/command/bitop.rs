@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_string,
+    },
+    db::Value,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BitOperator {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BitOp {
+    operator: BitOperator,
+    destination: Bytes,
+    keys: Vec<Bytes>,
+}
+
+impl Parse for BitOp {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 3)?;
+        let operator = str::from_utf8(&args[0])?;
+        let operator = match operator.to_uppercase().as_str() {
+            "AND" => BitOperator::And,
+            "OR" => BitOperator::Or,
+            "XOR" => BitOperator::Xor,
+            // NOT takes a single source key
+            "NOT" if args.len() == 3 => BitOperator::Not,
+            _ => return Err(ParseError::InvalidArgument(operator.to_string())),
+        };
+        Ok(BitOp {
+            operator,
+            destination: args[1].clone(),
+            keys: args[2..].to_vec(),
+        })
+    }
+}
+
+impl BitOp {
+    /// Combine the sources, the shorter ones counting as padded with zero bytes.
+    fn apply(&self, sources: &[Bytes]) -> Vec<u8> {
+        let len = sources.iter().map(Bytes::len).max().unwrap_or(0);
+        let mut result = vec![0; len];
+        let Some((first, rest)) = sources.split_first() else {
+            return result;
+        };
+        result[..first.len()].copy_from_slice(first);
+        for source in rest {
+            let combined = result.iter_mut().zip(source.iter());
+            match self.operator {
+                BitOperator::And => {
+                    combined.for_each(|(byte, source)| *byte &= source);
+                    result[source.len()..].fill(0);
+                }
+                BitOperator::Or => combined.for_each(|(byte, source)| *byte |= source),
+                BitOperator::Xor => combined.for_each(|(byte, source)| *byte ^= source),
+                BitOperator::Not => unreachable!("NOT takes a single source"),
+            }
+        }
+        if self.operator == BitOperator::Not {
+            result.iter_mut().for_each(|byte| *byte = !*byte);
+        }
+        result
+    }
+}
+
+impl ExecuteCommand for BitOp {
+    /// Store the result in the destination, which is deleted when the result is empty. Replies
+    /// the length of the result.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let mut sources = Vec::with_capacity(self.keys.len());
+        for key in &self.keys {
+            sources.push(get_string(db, stats, key)?.cloned().unwrap_or_default());
+        }
+
+        let result = self.apply(&sources);
+        let len = result.len();
+        if result.is_empty() {
+            db.remove(&self.destination);
+        } else {
+            db.insert(
+                self.destination.clone(),
+                (Value::String(Bytes::from(result)), None),
+            );
+        }
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::{BitOp, BitOperator};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_bitop_should_read_operator_and_keys() {
+        let cmd = parse_command(&build_request("BITOP", &["xor", "dest", "a", "b"]))
+            .expect("parse bitop");
+        assert_eq!(
+            cmd,
+            Command::BitOp(BitOp {
+                operator: BitOperator::Xor,
+                destination: Bytes::from_owner("dest"),
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+
+        for args in [
+            &["AND", "dest"][..],
+            &["NOT", "dest", "a", "b"],
+            &["NAND", "dest", "a"],
+        ] {
+            assert!(
+                parse_command(&build_request("BITOP", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_bitop_should_combine_padded_values() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("a"),
+                (Value::String(Bytes::from_static(&[0b1100, 0xff])), None),
+            );
+            db.insert(
+                Bytes::from_owner("b"),
+                (Value::String(Bytes::from_static(&[0b1010])), None),
+            );
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("x")])), None),
+            );
+        }
+
+        for (args, expected) in [
+            (&["AND", "dest", "a", "b"][..], &[0b1000, 0][..]),
+            (&["OR", "dest", "a", "b", "missing"], &[0b1110, 0xff]),
+            (&["XOR", "dest", "a", "b"], &[0b0110, 0xff]),
+            (&["NOT", "dest", "b"], &[!0b1010]),
+        ] {
+            let cmd = parse_command(&build_request("BITOP", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute bitop");
+            assert_eq!(resp, RespData::Integer(expected.len() as i64), "{args:?}");
+            assert_eq!(
+                server.lock().await.db.get(b"dest".as_ref()),
+                Some(&(Value::String(Bytes::copy_from_slice(expected)), None)),
+                "{args:?}"
+            );
+        }
+
+        let cmd = parse_command(&build_request("BITOP", &["AND", "dest", "missing"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute bitop");
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(!server.lock().await.db.contains_key(b"dest".as_ref()));
+
+        let cmd = parse_command(&build_request("BITOP", &["OR", "dest", "a", "list"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult,
+        bitcount::BitRange,
+        check_length_ge,
+        error::{ExecResult, ParseError},
+        get_string,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct BitPos {
+    key: Bytes,
+    bit: bool,
+    range: Option<BitRange>,
+}
+
+impl Parse for BitPos {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        let bit = match args[1].as_ref() {
+            b"0" => false,
+            b"1" => true,
+            _ => {
+                return Err(ParseError::InvalidArgument(
+                    String::from_utf8_lossy(&args[1]).into_owned(),
+                ));
+            }
+        };
+        Ok(BitPos {
+            key: args[0].clone(),
+            bit,
+            range: BitRange::parse(&args[2..])?,
+        })
+    }
+}
+
+/// Find the first bit equal to `bit` from bit `start` to bit `end` included, skipping a word at
+/// a time the words that can not hold it.
+fn find_bit(bytes: &[u8], bit: bool, start: usize, end: usize) -> Option<usize> {
+    let skipped = if bit { 0 } else { u64::MAX };
+    let (first, last) = (start / 8, end / 8);
+    let mut index = first;
+    while index <= last {
+        if index + 8 <= last
+            // Slices of 8 bytes always convert
+            && u64::from_ne_bytes(bytes[index..index + 8].try_into().unwrap()) == skipped
+        {
+            index += 8;
+            continue;
+        }
+        // Look for a set bit, in the complement when looking for a clear one
+        let mut byte = if bit { bytes[index] } else { !bytes[index] };
+        if index == first {
+            byte &= 0xff >> (start % 8);
+        }
+        if index == last {
+            byte &= 0xff << (7 - end % 8);
+        }
+        if byte != 0 {
+            return Some(index * 8 + byte.leading_zeros() as usize);
+        }
+        index += 1;
+    }
+    None
+}
+
+impl ExecuteCommand for BitPos {
+    /// Replies the position of the first bit found, or -1. Without an end to the range, the
+    /// string counts as padded with clear bits on the right, so a clear bit is always found.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let Some(string) = get_string(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(if self.bit { -1 } else { 0 }));
+        };
+        let whole = BitRange {
+            start: 0,
+            end: None,
+            bit: false,
+        };
+        let range = self.range.as_ref().unwrap_or(&whole);
+        let Some((start, end)) = range.resolve(string.len()) else {
+            return Ok(RespData::Integer(-1));
+        };
+        let position = match find_bit(string, self.bit, start, end) {
+            Some(position) => position as i64,
+            None if !self.bit && range.end.is_none() => end as i64 + 1,
+            None => -1,
+        };
+        Ok(RespData::Integer(position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{BitPos, find_bit};
+    use crate::{
+        command::{
+            Command, ExecuteCommand,
+            bitcount::BitRange,
+            parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_bitpos_should_read_bit_and_range() {
+        let cmd = parse_command(&build_request("BITPOS", &["k", "0", "2"])).expect("parse bitpos");
+        assert_eq!(
+            cmd,
+            Command::BitPos(BitPos {
+                key: Bytes::from_owner("k"),
+                bit: false,
+                range: Some(BitRange {
+                    start: 2,
+                    end: None,
+                    bit: false,
+                }),
+            })
+        );
+
+        for args in [&["k"][..], &["k", "2"], &["k", "1", "0", "1", "WORD"]] {
+            assert!(
+                parse_command(&build_request("BITPOS", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn find_bit_should_skip_words_and_mask_ends() {
+        let mut bytes = vec![0u8; 100];
+        bytes[90] = 0b0001_0000;
+        assert_eq!(find_bit(&bytes, true, 0, 799), Some(723));
+        assert_eq!(find_bit(&bytes, true, 724, 799), None);
+        assert_eq!(find_bit(&bytes, true, 0, 722), None);
+        assert_eq!(find_bit(&bytes, false, 3, 799), Some(3));
+
+        let mut bytes = vec![0xff; 100];
+        bytes[50] = 0b1111_1110;
+        assert_eq!(find_bit(&bytes, false, 0, 799), Some(407));
+        assert_eq!(find_bit(&bytes, false, 408, 799), None);
+    }
+
+    #[tokio::test]
+    async fn execute_bitpos_should_find_bits() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("k"),
+                (Value::String(Bytes::from_static(&[0xff, 0xf0, 0x00])), None),
+            );
+            db.insert(
+                Bytes::from_owner("empty"),
+                (Value::String(Bytes::new()), None),
+            );
+            db.insert(
+                Bytes::from_owner("ones"),
+                (Value::String(Bytes::from_static(&[0xff, 0xff, 0xff])), None),
+            );
+        }
+
+        for (args, expected) in [
+            (&["k", "0"][..], 12),
+            (&["k", "1", "2"], -1),
+            (&["k", "1", "1"], 8),
+            (&["k", "1", "7", "15", "BIT"], 7),
+            (&["k", "0", "2", "-1", "BYTE"], 16),
+            (&["ones", "0"], 24),
+            (&["ones", "0", "0", "-1"], -1),
+            (&["ones", "1", "5", "1"], -1),
+            (&["empty", "0"], -1),
+            (&["missing", "0"], 0),
+            (&["missing", "1"], -1),
+        ] {
+            let cmd = parse_command(&build_request("BITPOS", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute bitpos");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    blocking::wait_until_served,
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult,
+        lmove::move_element, parse_list_end, parse_timeout,
+    },
+    db::ListEnd,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct BLMove {
+    source: Bytes,
+    destination: Bytes,
+    from: ListEnd,
+    to: ListEnd,
+    /// `None` blocks forever.
+    timeout: Option<Duration>,
+}
+
+impl Parse for BLMove {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 5)?;
+        Ok(BLMove {
+            source: args[0].clone(),
+            destination: args[1].clone(),
+            from: parse_list_end(&args[2])?,
+            to: parse_list_end(&args[3])?,
+            timeout: parse_timeout(&args[4])?,
+        })
+    }
+}
+
+impl ExecuteCommand for BLMove {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let (id, receiver) = {
+            let server = &mut *server.lock().await;
+
+            let element = move_element(
+                &mut server.db,
+                &mut server.stats,
+                &self.source,
+                &self.destination,
+                self.from,
+                self.to,
+            )?;
+            if element.is_some() {
+                server.serve_blocked_clients(&self.destination);
+                return Ok(RespData::BulkString(element));
+            }
+
+            let (destination, from, to) = (self.destination.clone(), self.from, self.to);
+            server.blocking.block(
+                vec![self.source.clone()],
+                Box::new(move |db, stats, source, ready_keys| {
+                    match move_element(db, stats, source, &destination, from, to) {
+                        Ok(None) => None,
+                        Ok(Some(element)) => {
+                            ready_keys.push(destination.clone());
+                            Some(Ok(RespData::BulkString(Some(element))))
+                        }
+                        Err(err) => Some(Err(err)),
+                    }
+                }),
+            )
+        };
+
+        let resp = wait_until_served(server, conn, id, receiver, self.timeout).await?;
+        Ok(resp.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+
+    use super::BLMove;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_connection, build_request, build_server_connection},
+        },
+        db::{ListEnd, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_blmove_should_read_directions_and_timeout() {
+        let cmd = parse_command(&build_request(
+            "BLMOVE",
+            &["src", "dst", "RIGHT", "left", "1"],
+        ))
+        .expect("parse blmove");
+        assert_eq!(
+            cmd,
+            Command::BLMove(BLMove {
+                source: Bytes::from_owner("src"),
+                destination: Bytes::from_owner("dst"),
+                from: ListEnd::Right,
+                to: ListEnd::Left,
+                timeout: Some(Duration::from_secs(1)),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_blmove_should_return_null_on_timeout() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request(
+            "BLMOVE",
+            &["src", "dst", "LEFT", "LEFT", "0.01"],
+        ))
+        .unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute blmove");
+        assert_eq!(resp, RespData::NullArray);
+        assert!(server.lock().await.blocking.is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_blmove_should_hand_off_to_clients_blocked_on_destination() {
+        let (server, _) = build_server_connection().await;
+
+        // A chain: `dst` waits on `src`, and `out` waits on `dst`.
+        let mut blocked = Vec::new();
+        for (id, args) in [
+            (1, ["dst", "out", "LEFT", "LEFT", "0"]),
+            (2, ["src", "dst", "LEFT", "RIGHT", "0"]),
+        ] {
+            let (_, mut conn) = build_connection(id).await;
+            let blocked_server = server.clone();
+            blocked.push(tokio::spawn(async move {
+                let cmd = parse_command(&build_request("BLMOVE", &args)).unwrap();
+                cmd.execute(blocked_server, &mut conn).await
+            }));
+            while server.lock().await.blocking.len() < id as usize {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        let (_, mut pusher) = build_connection(3).await;
+        let lpush = parse_command(&build_request("LPUSH", &["src", "x"])).unwrap();
+        lpush.execute(server.clone(), &mut pusher).await.unwrap();
+
+        for handle in blocked {
+            let resp = handle.await.unwrap();
+            assert_eq!(resp, Ok(RespData::BulkString(Some(Bytes::from_owner("x")))));
+        }
+        let db = &server.lock().await.db;
+        assert_eq!(db.len(), 1);
+        assert_eq!(
+            db.get(b"out".as_ref()),
+            Some(&(Value::List([Bytes::from_owner("x")].into()), None))
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    blocking::wait_until_served,
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::ExecResult,
+        get_list,
+        lmpop::{LMPop, pop_reply},
+        parse_timeout,
+    },
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct BLMPop {
+    /// `None` blocks forever.
+    timeout: Option<Duration>,
+    lmpop: LMPop,
+}
+
+impl Parse for BLMPop {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(BLMPop {
+            timeout: parse_timeout(&args[0])?,
+            lmpop: LMPop::parse(&args[1..])?,
+        })
+    }
+}
+
+impl ExecuteCommand for BLMPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let (id, receiver) = {
+            let server = &mut *server.lock().await;
+            if let Some(resp) = self.lmpop.pop(&mut server.db, &mut server.stats)? {
+                return Ok(resp);
+            }
+
+            let (end, count) = (self.lmpop.end, self.lmpop.count);
+            server.blocking.block(
+                self.lmpop.keys.clone(),
+                Box::new(move |db, stats, key, _| {
+                    let list = get_list(db, stats, key).ok()??;
+                    let resp = pop_reply(list, key, end, count);
+                    remove_if_empty(db, key);
+                    Some(Ok(resp))
+                }),
+            )
+        };
+
+        let resp = wait_until_served(server, conn, id, receiver, self.timeout).await?;
+        Ok(resp.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+
+    use super::BLMPop;
+    use crate::{
+        command::{
+            Command, ExecuteCommand,
+            lmpop::LMPop,
+            parse_command,
+            test::{build_connection, build_request, build_server_connection},
+        },
+        db::ListEnd,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_blmpop_should_read_timeout_before_lmpop_arguments() {
+        let cmd = parse_command(&build_request("BLMPOP", &["0.1", "1", "a", "LEFT"]))
+            .expect("parse blmpop");
+        assert_eq!(
+            cmd,
+            Command::BLMPop(BLMPop {
+                timeout: Some(Duration::from_millis(100)),
+                lmpop: LMPop {
+                    keys: vec![Bytes::from_owner("a")],
+                    end: ListEnd::Left,
+                    count: 1,
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_blmpop_should_return_null_on_timeout() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("BLMPOP", &["0.01", "1", "a", "LEFT"])).unwrap();
+        let resp = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect("execute blmpop");
+        assert_eq!(resp, RespData::NullArray);
+    }
+
+    #[tokio::test]
+    async fn execute_blmpop_should_be_served_by_any_watched_key() {
+        let (server, _) = build_server_connection().await;
+        let (_, mut conn) = build_connection(1).await;
+        let blocked = {
+            let server = server.clone();
+            tokio::spawn(async move {
+                let cmd = parse_command(&build_request(
+                    "BLMPOP",
+                    &["0", "2", "a", "b", "RIGHT", "COUNT", "2"],
+                ))
+                .unwrap();
+                cmd.execute(server, &mut conn).await
+            })
+        };
+        while server.lock().await.blocking.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let (_, mut pusher) = build_connection(2).await;
+        let rpush = parse_command(&build_request("RPUSH", &["b", "x", "y", "z"])).unwrap();
+        rpush.execute(server.clone(), &mut pusher).await.unwrap();
+
+        assert_eq!(
+            blocked.await.unwrap(),
+            Ok(RespData::Array(vec![
+                RespData::BulkString(Some(Bytes::from_owner("b"))),
+                RespData::Array(vec![
+                    RespData::BulkString(Some(Bytes::from_owner("z"))),
+                    RespData::BulkString(Some(Bytes::from_owner("y"))),
+                ]),
+            ]))
+        );
+        assert!(server.lock().await.blocking.is_empty());
+    }
+}
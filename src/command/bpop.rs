@@ -0,0 +1,243 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    blocking::wait_until_served,
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge, error::ExecResult, get_list, parse_timeout,
+    },
+    db::{Key, ListEnd, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// BLPOP and BRPOP.
+#[derive(Debug, PartialEq)]
+pub struct BPop {
+    keys: Vec<Bytes>,
+    end: ListEnd,
+    /// `None` blocks forever.
+    timeout: Option<Duration>,
+}
+
+impl BPop {
+    pub fn parse(args: &[Bytes], end: ListEnd) -> ParseResult<Self> {
+        check_length_ge(args, 2)?;
+        let (timeout, keys) = args.split_last().unwrap();
+        Ok(BPop {
+            keys: keys.to_vec(),
+            end,
+            timeout: parse_timeout(timeout)?,
+        })
+    }
+}
+
+fn reply(key: &Key, element: Bytes) -> RespData {
+    RespData::Array(vec![
+        RespData::BulkString(Some(key.clone())),
+        RespData::BulkString(Some(element)),
+    ])
+}
+
+impl ExecuteCommand for BPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let (id, receiver) = {
+            let server = &mut *server.lock().await;
+            let (db, stats) = (&mut server.db, &mut server.stats);
+
+            for key in &self.keys {
+                if let Some(list) = get_list(db, stats, key)? {
+                    // Lists are never kept empty
+                    let element = self.end.pop(list).unwrap();
+                    remove_if_empty(db, key);
+                    return Ok(reply(key, element));
+                }
+            }
+
+            let end = self.end;
+            server.blocking.block(
+                self.keys.clone(),
+                Box::new(move |db, stats, key, _| {
+                    let element = end.pop(get_list(db, stats, key).ok()??)?;
+                    remove_if_empty(db, key);
+                    Some(Ok(reply(key, element)))
+                }),
+            )
+        };
+
+        let resp = wait_until_served(server, conn, id, receiver, self.timeout).await?;
+        Ok(resp.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+
+    use super::BPop;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{
+                build_connection, build_connection_with_client, build_request,
+                build_server_connection,
+            },
+        },
+        db::{ListEnd, Value},
+        resp::RespData,
+    };
+
+    fn pair(key: &'static str, element: &'static str) -> RespData {
+        RespData::Array(vec![
+            RespData::BulkString(Some(Bytes::from_owner(key))),
+            RespData::BulkString(Some(Bytes::from_owner(element))),
+        ])
+    }
+
+    #[test]
+    fn parse_bpop_should_read_keys_and_timeout() {
+        let cmd = parse_command(&build_request("BRPOP", &["a", "b", "0.5"])).expect("parse brpop");
+        assert_eq!(
+            cmd,
+            Command::BPop(BPop {
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                end: ListEnd::Right,
+                timeout: Some(Duration::from_millis(500)),
+            })
+        );
+
+        let cmd = parse_command(&build_request("BLPOP", &["a", "0"])).expect("parse blpop");
+        assert_eq!(
+            cmd,
+            Command::BPop(BPop {
+                keys: vec![Bytes::from_owner("a")],
+                end: ListEnd::Left,
+                timeout: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_bpop_should_reject_negative_timeout() {
+        let err =
+            parse_command(&build_request("BLPOP", &["a", "-1"])).expect_err("negative timeout");
+        assert_eq!(err, ParseError::InvalidArgument("-1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn execute_bpop_should_pop_first_non_empty_list_immediately() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("b"),
+            (
+                Value::List([Bytes::from_owner("x"), Bytes::from_owner("y")].into()),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("BRPOP", &["a", "b", "0"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute brpop");
+        assert_eq!(resp, pair("b", "y"));
+    }
+
+    #[tokio::test]
+    async fn execute_bpop_should_return_null_on_timeout() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("BLPOP", &["a", "0.01"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute blpop");
+        assert_eq!(resp, RespData::NullArray);
+
+        // The timed out client does not steal later pushes
+        let (_, mut pusher) = build_connection(2).await;
+        let rpush = parse_command(&build_request("RPUSH", &["a", "x"])).unwrap();
+        rpush.execute(server.clone(), &mut pusher).await.unwrap();
+        assert!(server.lock().await.db.contains_key(b"a".as_ref()));
+    }
+
+    #[tokio::test]
+    async fn execute_bpop_should_unblock_disconnected_client() {
+        let (server, _) = build_server_connection().await;
+        let (_, mut conn, client) = build_connection_with_client(2).await;
+        let blocked_server = server.clone();
+        let blocked = tokio::spawn(async move {
+            let cmd = parse_command(&build_request("BLPOP", &["a", "0"])).unwrap();
+            cmd.execute(blocked_server, &mut conn).await
+        });
+        while server.lock().await.blocking.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        drop(client);
+        assert_eq!(blocked.await.unwrap(), Ok(RespData::NullArray));
+        assert!(server.lock().await.blocking.is_empty());
+
+        // The element pushed afterwards is kept for a live client
+        let (_, mut pusher) = build_connection(3).await;
+        let rpush = parse_command(&build_request("RPUSH", &["a", "x"])).unwrap();
+        rpush.execute(server.clone(), &mut pusher).await.unwrap();
+        assert_eq!(
+            server.lock().await.db.get(b"a".as_ref()),
+            Some(&(Value::List([Bytes::from_owner("x")].into()), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_bpop_should_wake_blocked_clients_in_fifo_order() {
+        let (server, _) = build_server_connection().await;
+
+        let mut blocked = Vec::new();
+        for id in 1..=2 {
+            let (_, mut conn) = build_connection(id).await;
+            let blocked_server = server.clone();
+            blocked.push(tokio::spawn(async move {
+                let cmd = parse_command(&build_request("BLPOP", &["a", "b", "0"])).unwrap();
+                cmd.execute(blocked_server, &mut conn).await
+            }));
+            // Let the client block before the next one
+            while server.lock().await.blocking.len() < id as usize {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        let (_, mut pusher) = build_connection(3).await;
+        let rpush = parse_command(&build_request("RPUSH", &["b", "x", "y", "z"])).unwrap();
+        let resp = rpush.execute(server.clone(), &mut pusher).await.unwrap();
+        assert_eq!(resp, RespData::Integer(3));
+
+        let second = blocked.pop().unwrap().await.unwrap();
+        let first = blocked.pop().unwrap().await.unwrap();
+        assert_eq!(first, Ok(pair("b", "x")));
+        assert_eq!(second, Ok(pair("b", "y")));
+        assert!(server.lock().await.blocking.is_empty());
+        assert_eq!(
+            server.lock().await.db.get(b"b".as_ref()),
+            Some(&(Value::List([Bytes::from_owner("z")].into()), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_bpop_should_report_wrong_type() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("a"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        let cmd = parse_command(&build_request("BLPOP", &["a", "0"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,153 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    blocking::wait_until_served,
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::ExecResult,
+        get_zset, parse_timeout,
+        zmpop::{ZMPop, pop_reply},
+    },
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct BZMPop {
+    /// `None` blocks forever.
+    timeout: Option<Duration>,
+    zmpop: ZMPop,
+}
+
+impl Parse for BZMPop {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(BZMPop {
+            timeout: parse_timeout(&args[0])?,
+            zmpop: ZMPop::parse(&args[1..])?,
+        })
+    }
+}
+
+impl ExecuteCommand for BZMPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let (id, receiver) = {
+            let server = &mut *server.lock().await;
+            if let Some(resp) = self
+                .zmpop
+                .pop(&mut server.db, &mut server.stats, conn.protocol)?
+            {
+                return Ok(resp);
+            }
+
+            let (end, count, protocol) = (self.zmpop.end, self.zmpop.count, conn.protocol);
+            server.blocking.block(
+                self.zmpop.keys.clone(),
+                Box::new(move |db, stats, key, _| {
+                    let zset = get_zset(db, stats, key).ok()??;
+                    let resp = pop_reply(zset, key, end, count, protocol);
+                    remove_if_empty(db, key);
+                    Some(Ok(resp))
+                }),
+            )
+        };
+
+        let resp = wait_until_served(server, conn, id, receiver, self.timeout).await?;
+        Ok(resp.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+
+    use super::BZMPop;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_connection, build_request, build_server_connection},
+            zmpop::ZMPop,
+        },
+        db::ZSetEnd,
+        resp::RespData,
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    #[test]
+    fn parse_bzmpop_should_read_timeout_then_zmpop() {
+        let cmd = parse_command(&build_request("BZMPOP", &["1.5", "1", "a", "MAX"]))
+            .expect("parse bzmpop");
+        assert_eq!(
+            cmd,
+            Command::BZMPop(BZMPop {
+                timeout: Some(Duration::from_millis(1500)),
+                zmpop: ZMPop {
+                    keys: vec![Bytes::from_owner("a")],
+                    end: ZSetEnd::Max,
+                    count: 1,
+                },
+            })
+        );
+        assert!(parse_command(&build_request("BZMPOP", &["-1", "1", "a", "MAX"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_bzmpop_should_wait_for_zadd() {
+        let (server, _) = build_server_connection().await;
+
+        let (_, mut conn) = build_connection(1).await;
+        let blocked_server = server.clone();
+        let blocked = tokio::spawn(async move {
+            let cmd = parse_command(&build_request(
+                "BZMPOP",
+                &["0", "2", "a", "b", "MAX", "COUNT", "2"],
+            ))
+            .unwrap();
+            cmd.execute(blocked_server, &mut conn).await
+        });
+        while server.lock().await.blocking.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let (_, mut adder) = build_connection(2).await;
+        let zadd =
+            parse_command(&build_request("ZADD", &["b", "1", "x", "2", "y", "3", "z"])).unwrap();
+        zadd.execute(server.clone(), &mut adder).await.unwrap();
+
+        assert_eq!(
+            blocked.await.unwrap(),
+            Ok(RespData::Array(vec![
+                bulk("b"),
+                RespData::Array(vec![
+                    RespData::Array(vec![bulk("z"), bulk("3")]),
+                    RespData::Array(vec![bulk("y"), bulk("2")]),
+                ]),
+            ]))
+        );
+        assert!(server.lock().await.blocking.is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_bzmpop_should_return_null_on_timeout() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("BZMPOP", &["0.01", "1", "a", "MIN"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("bzmpop");
+        assert_eq!(resp, RespData::NullArray);
+    }
+}
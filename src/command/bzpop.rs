@@ -0,0 +1,212 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    blocking::wait_until_served,
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge, error::ExecResult, get_zset, parse_timeout,
+        score_reply,
+    },
+    db::{Key, ZSetEnd, remove_if_empty},
+    resp::{RespData, RespProtocol},
+    server::{Connection, Server},
+};
+
+/// BZPOPMIN and BZPOPMAX.
+#[derive(Debug, PartialEq)]
+pub struct BZPop {
+    keys: Vec<Bytes>,
+    end: ZSetEnd,
+    /// `None` blocks forever.
+    timeout: Option<Duration>,
+}
+
+impl BZPop {
+    pub fn parse(args: &[Bytes], end: ZSetEnd) -> ParseResult<Self> {
+        check_length_ge(args, 2)?;
+        let (timeout, keys) = args.split_last().unwrap();
+        Ok(BZPop {
+            keys: keys.to_vec(),
+            end,
+            timeout: parse_timeout(timeout)?,
+        })
+    }
+}
+
+fn reply(key: &Key, (member, score): (Bytes, f64), protocol: RespProtocol) -> RespData {
+    RespData::Array(vec![
+        RespData::BulkString(Some(key.clone())),
+        RespData::BulkString(Some(member)),
+        score_reply(score, protocol),
+    ])
+}
+
+impl ExecuteCommand for BZPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let protocol = conn.protocol;
+        let (id, receiver) = {
+            let server = &mut *server.lock().await;
+            let (db, stats) = (&mut server.db, &mut server.stats);
+
+            for key in &self.keys {
+                if let Some(zset) = get_zset(db, stats, key)? {
+                    // Sorted sets are never kept empty
+                    let popped = self.end.pop(zset).unwrap();
+                    remove_if_empty(db, key);
+                    return Ok(reply(key, popped, protocol));
+                }
+            }
+
+            let end = self.end;
+            server.blocking.block(
+                self.keys.clone(),
+                Box::new(move |db, stats, key, _| {
+                    let popped = end.pop(get_zset(db, stats, key).ok()??)?;
+                    remove_if_empty(db, key);
+                    Some(Ok(reply(key, popped, protocol)))
+                }),
+            )
+        };
+
+        let resp = wait_until_served(server, conn, id, receiver, self.timeout).await?;
+        Ok(resp.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::BZPop;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_connection, build_request, build_server_connection},
+        },
+        db::{Value, ZSetEnd},
+        resp::{RespData, RespProtocol},
+        zset::SortedSet,
+    };
+
+    fn triple(key: &str, member: &str, score: RespData) -> RespData {
+        RespData::Array(vec![
+            RespData::BulkString(Some(Bytes::from(key.to_string()))),
+            RespData::BulkString(Some(Bytes::from(member.to_string()))),
+            score,
+        ])
+    }
+
+    #[test]
+    fn parse_bzpop_should_read_keys_and_timeout() {
+        let cmd =
+            parse_command(&build_request("BZPOPMIN", &["a", "b", "0"])).expect("parse bzpopmin");
+        assert_eq!(
+            cmd,
+            Command::BZPop(BZPop {
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                end: ZSetEnd::Min,
+                timeout: None,
+            })
+        );
+        assert!(parse_command(&build_request("BZPOPMAX", &["a"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_bzpop_should_pop_immediately_when_possible() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("b"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("x"), 1.0),
+                    (Bytes::from_owner("y"), 2.0),
+                ])),
+                None,
+            ),
+        );
+
+        conn.protocol = RespProtocol::Resp3;
+        let cmd = parse_command(&build_request("BZPOPMAX", &["a", "b", "0"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("bzpopmax");
+        assert_eq!(resp, triple("b", "y", RespData::Double(2.0)));
+    }
+
+    #[tokio::test]
+    async fn execute_bzpop_should_share_fifo_order_with_list_clients() {
+        let (server, _) = build_server_connection().await;
+
+        let mut blocked = Vec::new();
+        for (id, command) in [(1, "BZPOPMIN"), (2, "BLPOP"), (3, "BZPOPMIN")] {
+            let (_, mut conn) = build_connection(id).await;
+            let blocked_server = server.clone();
+            blocked.push(tokio::spawn(async move {
+                let cmd = parse_command(&build_request(command, &["k", "0"])).unwrap();
+                cmd.execute(blocked_server, &mut conn).await
+            }));
+            // Let the client block before the next one
+            while server.lock().await.blocking.len() < id as usize {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        // The list client is skipped, and the sorted set clients are served in order
+        let (_, mut adder) = build_connection(4).await;
+        let zadd = parse_command(&build_request("ZADD", &["k", "1", "x", "2", "y"])).unwrap();
+        zadd.execute(server.clone(), &mut adder).await.unwrap();
+
+        let third = blocked.pop().unwrap().await.unwrap();
+        assert_eq!(
+            third,
+            Ok(triple(
+                "k",
+                "y",
+                RespData::BulkString(Some(Bytes::from_owner("2")))
+            ))
+        );
+        let first = blocked.remove(0).await.unwrap();
+        assert_eq!(
+            first,
+            Ok(triple(
+                "k",
+                "x",
+                RespData::BulkString(Some(Bytes::from_owner("1")))
+            ))
+        );
+        assert_eq!(server.lock().await.blocking.len(), 1);
+        assert!(!server.lock().await.db.contains_key(b"k".as_ref()));
+
+        let (_, mut pusher) = build_connection(5).await;
+        let rpush = parse_command(&build_request("RPUSH", &["k", "e"])).unwrap();
+        rpush.execute(server.clone(), &mut pusher).await.unwrap();
+        assert!(blocked.pop().unwrap().await.unwrap().is_ok());
+        assert!(server.lock().await.blocking.is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_bzpop_should_time_out_and_report_wrong_type() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("BZPOPMIN", &["z", "0.01"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("bzpopmin");
+        assert_eq!(resp, RespData::NullArray);
+
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        let cmd = parse_command(&build_request("BZPOPMIN", &["s", "0"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -78,14 +78,15 @@ impl ExecuteCommand for Client {
 fd=24 name={name} age=0 idle=0 flags=N db=0 sub=0 psub=0 ssub=0 multi=-1 \
 watch=0 qbuf=26 qbuf-free=20448 argv-mem=10 multi-mem=0 rbs=16384 \
 rbp=16384 obl=0 oll=0 omem=0 tot-mem=37786 events=r \
-cmd=client|info user=default redir=-1 resp=2 \
+cmd=client|info user=default redir=-1 resp={resp} \
 lib-name={lib_name} lib-ver={lib_ver} io-thread=0\n",
                     id = conn.id,
                     conn_addr = conn.addr,
                     server_addr = server.addr,
                     name = conn.name,
                     lib_name = conn.lib_name,
-                    lib_ver = conn.lib_ver
+                    lib_ver = conn.lib_ver,
+                    resp = conn.protocol.version()
                 );
                 Ok(RespData::BulkString(Some(Bytes::from_owner(info_content))))
             }
@@ -65,6 +65,12 @@ impl ExecuteCommand for Config {
                                 ))),
                             ]);
                         }
+                        "HZ" => {
+                            response.extend([
+                                RespData::BulkString(Some(Bytes::from(param.to_string()))),
+                                RespData::BulkString(Some(Bytes::from(server.hz.to_string()))),
+                            ]);
+                        }
                         _ => {}
                     }
                 }
@@ -118,9 +124,13 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn execute_config_get_should_return_dir_and_dbfilename() {
+    async fn execute_config_get_should_return_dir_dbfilename_and_hz() {
         let (server, mut conn) = build_server_connection().await;
-        let cmd = Config::Get(vec!["dir".to_string(), "dbfilename".to_string()]);
+        let cmd = Config::Get(vec![
+            "dir".to_string(),
+            "dbfilename".to_string(),
+            "hz".to_string(),
+        ]);
         let resp = cmd
             .execute(server, &mut conn)
             .await
@@ -132,6 +142,8 @@ mod tests {
                 RespData::BulkString(Some(Bytes::from_owner("/tmp"))),
                 RespData::BulkString(Some(Bytes::from_owner("dbfilename"))),
                 RespData::BulkString(Some(Bytes::from_owner("dump.rdb"))),
+                RespData::BulkString(Some(Bytes::from_owner("hz"))),
+                RespData::BulkString(Some(Bytes::from_owner("10"))),
             ])
         );
     }
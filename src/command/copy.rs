@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+    },
+    db::{Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Copy {
+    source: Bytes,
+    destination: Bytes,
+    /// Overwrite an existing destination.
+    replace: bool,
+}
+
+impl Parse for Copy {
+    /// Parse `COPY source destination [DB destination-db] [REPLACE]`, where the only database is
+    /// 0.
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        let mut replace = false;
+        let mut index = 2;
+        while let Some(arg) = args.get(index) {
+            let argument = str::from_utf8(arg)?;
+            match argument.to_uppercase().as_str() {
+                "REPLACE" => replace = true,
+                "DB" if index + 1 < args.len() => {
+                    let db: i64 = lexical_core::parse(&args[index + 1])?;
+                    if db != 0 {
+                        return Err(ParseError::InvalidArgument(
+                            "DB index is out of range".to_string(),
+                        ));
+                    }
+                    index += 1;
+                }
+                _ => return Err(ParseError::InvalidArgument(argument.to_string())),
+            }
+            index += 1;
+        }
+        Ok(Copy {
+            source: args[0].clone(),
+            destination: args[1].clone(),
+            replace,
+        })
+    }
+}
+
+impl ExecuteCommand for Copy {
+    /// Replies 1 if the value was copied along with its expire time, or 0 if the source is
+    /// missing or the destination exists without `REPLACE`.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        if self.source == self.destination {
+            return Err(ExecError::SameObject);
+        }
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let Some(item) = lookup_key(db, stats, &self.source).cloned() else {
+            return Ok(RespData::Integer(0));
+        };
+        if !self.replace && lookup_key(db, stats, &self.destination).is_some() {
+            return Ok(RespData::Integer(0));
+        }
+
+        if let (Value::Hash(hash), _) = &item
+            && hash.has_volatile_fields()
+        {
+            server.volatile_hashes.insert(self.destination.clone());
+        }
+        if item.1.is_some() {
+            server.volatile_keys.insert(self.destination.clone());
+        }
+        db.insert(self.destination.clone(), item);
+        server.serve_blocked_clients(&self.destination);
+
+        Ok(RespData::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::Copy;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_copy_should_read_options() {
+        let cmd = parse_command(&build_request("COPY", &["a", "b", "db", "0", "REPLACE"]))
+            .expect("parse copy");
+        assert_eq!(
+            cmd,
+            Command::Copy(Copy {
+                source: Bytes::from_owner("a"),
+                destination: Bytes::from_owner("b"),
+                replace: true,
+            })
+        );
+
+        for args in [
+            &["a"][..],
+            &["a", "b", "DB", "1"],
+            &["a", "b", "DB"],
+            &["a", "b", "NX"],
+        ] {
+            assert!(
+                parse_command(&build_request("COPY", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_copy_should_duplicate_value_and_ttl() {
+        let (server, mut conn) = build_server_connection().await;
+        let expire_time = Some(unix_time_ms() + 60_000);
+        let list = Value::List(VecDeque::from([Bytes::from_owner("x")]));
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(Bytes::from_owner("list"), (list.clone(), expire_time));
+            db.insert(
+                Bytes::from_owner("string"),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+        }
+
+        for (args, expected) in [
+            (&["list", "copy"][..], 1),
+            (&["string", "copy"], 0),
+            (&["missing", "copy"], 0),
+            (&["list", "string", "REPLACE"], 1),
+        ] {
+            let cmd = parse_command(&build_request("COPY", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute copy");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        for key in ["list", "copy", "string"] {
+            assert_eq!(
+                db.get(key.as_bytes()),
+                Some(&(list.clone(), expire_time)),
+                "{key}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_copy_should_track_volatile_hashes() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let mut hash = Hash::new();
+            let field = Bytes::from_owner("f");
+            hash.insert(field.clone(), Bytes::from_owner("v"));
+            hash.set_expire_time(&field, unix_time_ms() + 60_000);
+            server
+                .lock()
+                .await
+                .db
+                .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+        }
+
+        let cmd = parse_command(&build_request("COPY", &["h", "copy"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(1));
+        assert!(
+            server
+                .lock()
+                .await
+                .volatile_hashes
+                .contains(b"copy".as_ref())
+        );
+
+        let cmd = parse_command(&build_request("COPY", &["h", "h"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("copy should fail");
+        assert_eq!(err, ExecError::SameObject);
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct DbSize;
+
+impl Parse for DbSize {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 0)?;
+        Ok(DbSize)
+    }
+}
+
+impl ExecuteCommand for DbSize {
+    /// Replies the number of keys, counting the expired keys not removed yet as Redis does.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let db = &server.lock().await.db;
+        Ok(RespData::Integer(db.len() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::DbSize;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[tokio::test]
+    async fn execute_dbsize_should_count_keys() {
+        assert_eq!(
+            parse_command(&build_request("DBSIZE", &[])).expect("parse dbsize"),
+            Command::DbSize(DbSize)
+        );
+        assert!(parse_command(&build_request("DBSIZE", &["k"])).is_err());
+
+        let (server, mut conn) = build_server_connection().await;
+        for key in ["a", "b"] {
+            server.lock().await.db.insert(
+                Bytes::from_owner(key),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+        }
+        let cmd = parse_command(&build_request("DBSIZE", &[])).unwrap();
+        let resp = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect("execute dbsize");
+        assert_eq!(resp, RespData::Integer(2));
+    }
+}
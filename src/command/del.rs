@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, ParseResult, check_length_ge, error::ExecResult},
+    db::{Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// Values holding more elements than this are freed on a background task by `UNLINK`, as in
+/// Redis.
+const LAZYFREE_THRESHOLD: usize = 64;
+
+/// DEL and UNLINK.
+#[derive(Debug, PartialEq)]
+pub struct Del {
+    keys: Vec<Bytes>,
+    /// UNLINK, which frees the large values off the request path.
+    unlink: bool,
+}
+
+impl Del {
+    pub fn parse(args: &[Bytes], unlink: bool) -> ParseResult<Self> {
+        check_length_ge(args, 1)?;
+        Ok(Del {
+            keys: args.to_vec(),
+            unlink,
+        })
+    }
+}
+
+/// How much work freeing a value takes, roughly its number of allocations.
+fn free_effort(value: &Value) -> usize {
+    match value {
+        Value::String(_) => 1,
+        Value::List(list) => list.len(),
+        Value::Hash(hash) => hash.len(),
+        Value::Set(set) => set.len(),
+        Value::ZSet(zset) => zset.len(),
+        Value::Stream(stream) => stream.len(),
+    }
+}
+
+impl ExecuteCommand for Del {
+    /// Replies the number of keys removed.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let mut removed = 0;
+        let mut lazy_free = vec![];
+        for key in &self.keys {
+            if lookup_key(db, stats, key).is_none() {
+                continue;
+            }
+            // The key exists, as it was just looked up
+            let (value, _) = db.remove(key).unwrap();
+            removed += 1;
+            if self.unlink && free_effort(&value) > LAZYFREE_THRESHOLD {
+                lazy_free.push(value);
+            }
+        }
+        if !lazy_free.is_empty() {
+            tokio::task::spawn_blocking(move || drop(lazy_free));
+        }
+
+        Ok(RespData::Integer(removed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::Del;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_del_should_read_keys() {
+        for (command, unlink) in [("DEL", false), ("UNLINK", true)] {
+            let cmd = parse_command(&build_request(command, &["a", "b"])).expect("parse del");
+            assert_eq!(
+                cmd,
+                Command::Del(Del {
+                    keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                    unlink,
+                })
+            );
+            assert!(parse_command(&build_request(command, &[])).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_del_should_count_removed_keys() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("string"),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+            db.insert(
+                Bytes::from_owner("expired"),
+                (
+                    Value::String(Bytes::from_owner("v")),
+                    Some(unix_time_ms() - 1000),
+                ),
+            );
+            let list = (0..1000).map(|i| Bytes::from(i.to_string())).collect();
+            db.insert(Bytes::from_owner("large"), (Value::List(list), None));
+            db.insert(
+                Bytes::from_owner("small"),
+                (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+            );
+        }
+
+        for (command, args, expected) in [
+            ("DEL", &["string", "string", "expired", "missing"][..], 1),
+            ("UNLINK", &["large", "small", "large"], 2),
+            ("DEL", &["large"], 0),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute del");
+            assert_eq!(resp, RespData::Integer(expected), "{command} {args:?}");
+        }
+        assert!(server.lock().await.db.is_empty());
+    }
+}
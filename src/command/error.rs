@@ -1,6 +1,8 @@
 use bytes::Bytes;
 use thiserror::Error;
 
+use crate::hyperloglog;
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ParseError {
     #[error("Not a utf8 str: {}",  .0)]
@@ -31,14 +33,137 @@ pub enum ParseError {
 
     #[error("Expected length greater : {}, but got length: {}, the content is \"{:?}\".", .0, .1, .2)]
     ExpectLengthGe(usize, usize, Vec<Bytes>),
+
+    #[error("Expected arguments in pairs, the content is \"{:?}\".", .0)]
+    ExpectPairs(Vec<Bytes>),
+
+    /// An argument Redis rejects with an error reply of its own, which is replied as is.
+    #[error("{}", .0)]
+    Rejected(#[from] ExecError),
 }
 
 pub(super) type ParseResult<T> = std::result::Result<T, ParseError>;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, PartialEq)]
 pub enum ExecError {
     // #[error("Failed to obtain database.")]
     // ObtainDbFailed,
+    #[error("WRONGTYPE Operation against a key holding the wrong kind of value")]
+    WrongType,
+
+    #[error("ERR no such key")]
+    NoSuchKey,
+
+    #[error("ERR index out of range")]
+    IndexOutOfRange,
+
+    #[error("ERR bit offset is not an integer or out of range")]
+    BitOffsetOutOfRange,
+
+    #[error("ERR value is out of range")]
+    ValueOutOfRange,
+
+    #[error("ERR value is not an integer or out of range")]
+    NotInteger,
+
+    #[error("ERR value is not a valid float")]
+    NotFloat,
+
+    #[error("ERR string exceeds maximum allowed size (proto-max-bulk-len)")]
+    StringTooLong,
+
+    #[error("ERR The specified keys must contain string values")]
+    LcsNotStrings,
+
+    #[error("ERR Insufficient memory, transient memory for LCS exceeds proto-max-bulk-len")]
+    LcsTooLarge,
+
+    #[error("ERR hash value is not an integer")]
+    HashValueNotInteger,
+
+    #[error("ERR hash value is not a float")]
+    HashValueNotFloat,
+
+    #[error("ERR increment or decrement would overflow")]
+    Overflow,
+
+    #[error("ERR decrement would overflow")]
+    DecrementOverflow,
+
+    #[error("ERR increment would produce NaN or Infinity")]
+    NanOrInfinity,
+
+    #[error("ERR resulting score is not a number (NaN)")]
+    ScoreNaN,
+
+    #[error("ERR The ID specified in XADD is equal or smaller than the target stream top item")]
+    StreamIdTooSmall,
+
+    #[error("ERR The ID specified in XADD must be greater than 0-0")]
+    StreamIdZero,
+
+    #[error("ERR The stream has exhausted the last possible ID, unable to add more items")]
+    StreamIdExhausted,
+
+    #[error("BUSYGROUP Consumer Group name already exists")]
+    BusyGroup,
+
+    #[error("NOGROUP No such consumer group '{}' for key name '{}'", .1, .0)]
+    NoGroup(String, String),
+
+    #[error("NOGROUP No such key '{}' or consumer group '{}'", .0, .1)]
+    NoKeyOrGroup(String, String),
+
+    #[error("NOGROUP No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option", .0, .1)]
+    NoKeyOrGroupInXReadGroup(String, String),
+
+    #[error("NOGROUP the consumer group this client was blocked on no longer exists")]
+    BlockedGroupDestroyed,
+
+    #[error(
+        "ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want \
+         to use the MKSTREAM option to create an empty stream automatically."
+    )]
+    XGroupNoKey,
+
+    #[error("ERR Failed to save the RDB file: {}", .0)]
+    SaveFailed(String),
+
+    #[error("ERR invalid expire time in '{}' command", .0)]
+    InvalidExpireTime(&'static str),
+
+    #[error("ERR invalid longitude,latitude pair {:.6},{:.6}", .0, .1)]
+    InvalidLonLat(f64, f64),
+
+    #[error("ERR could not decode requested zset member")]
+    GeoMemberNotFound,
+
+    #[error("ERR source and destination objects are the same")]
+    SameObject,
+
+    #[error("WRONGTYPE Key is not a valid HyperLogLog string value.")]
+    NotHyperLogLog,
+
+    #[error("INVALIDOBJ Corrupted HLL object detected")]
+    CorruptedHyperLogLog,
+
+    #[error("NOPROTO unsupported protocol version")]
+    NoProto,
+
+    #[error("ERR Protocol version is not an integer or out of range")]
+    ProtocolVersionNotInteger,
+
+    #[error("WRONGPASS invalid username-password pair or user is disabled.")]
+    WrongPass,
+}
+
+impl From<hyperloglog::Error> for ExecError {
+    fn from(err: hyperloglog::Error) -> Self {
+        match err {
+            hyperloglog::Error::Invalid => ExecError::NotHyperLogLog,
+            hyperloglog::Error::Corrupted => ExecError::CorruptedHyperLogLog,
+        }
+    }
 }
 
 pub(super) type ExecResult<T> = std::result::Result<T, ExecError>;
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult},
+    db::lookup_key,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// EXISTS and TOUCH, which only differ in what they mean: TOUCH marks the keys as accessed,
+/// which nothing tracks here.
+#[derive(Debug, PartialEq)]
+pub struct Exists {
+    keys: Vec<Bytes>,
+}
+
+impl Parse for Exists {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(Exists {
+            keys: args.to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for Exists {
+    /// Replies the number of existing keys, a key given several times being counted as many
+    /// times.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let count = self
+            .keys
+            .iter()
+            .filter(|key| lookup_key(db, stats, key).is_some())
+            .count();
+        Ok(RespData::Integer(count as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::Exists;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_exists_should_read_keys() {
+        for command in ["EXISTS", "TOUCH"] {
+            let cmd = parse_command(&build_request(command, &["a", "a"])).expect("parse exists");
+            assert_eq!(
+                cmd,
+                Command::Exists(Exists {
+                    keys: vec![Bytes::from_owner("a"), Bytes::from_owner("a")],
+                })
+            );
+            assert!(parse_command(&build_request(command, &[])).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_exists_should_count_duplicates() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("a"),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+            db.insert(
+                Bytes::from_owner("expired"),
+                (
+                    Value::String(Bytes::from_owner("v")),
+                    Some(unix_time_ms() - 1000),
+                ),
+            );
+        }
+
+        for (command, args, expected) in [
+            ("EXISTS", &["a", "a", "missing"][..], 2),
+            ("EXISTS", &["expired"], 0),
+            ("TOUCH", &["a", "missing", "a"], 2),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute exists");
+            assert_eq!(resp, RespData::Integer(expected), "{command} {args:?}");
+        }
+    }
+}
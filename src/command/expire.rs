@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecError, ExecResult, ParseError},
+        parse_expire_condition,
+    },
+    db::{ExpireCondition, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+/// `EXPIRE`, `PEXPIRE`, `EXPIREAT` and `PEXPIREAT`.
+#[derive(Debug, PartialEq)]
+pub struct Expire {
+    key: Bytes,
+    /// A time to live, or a Unix time when `absolute`, in seconds unless `milliseconds`. It may
+    /// be negative, which deletes the key.
+    time: i64,
+    milliseconds: bool,
+    absolute: bool,
+    condition: Option<ExpireCondition>,
+}
+
+impl Expire {
+    pub fn parse(args: &[Bytes], milliseconds: bool, absolute: bool) -> ParseResult<Self> {
+        check_length_ge(args, 2)?;
+        let condition = match &args[2..] {
+            [] => None,
+            [option] => {
+                let option = str::from_utf8(option)?;
+                Some(
+                    parse_expire_condition(option)
+                        .ok_or_else(|| ParseError::InvalidArgument(option.to_string()))?,
+                )
+            }
+            [_, option, ..] => {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(option)?.to_string(),
+                ));
+            }
+        };
+
+        Ok(Expire {
+            key: args[0].clone(),
+            time: lexical_core::parse(&args[1])?,
+            milliseconds,
+            absolute,
+            condition,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        match (self.milliseconds, self.absolute) {
+            (false, false) => "expire",
+            (true, false) => "pexpire",
+            (false, true) => "expireat",
+            (true, true) => "pexpireat",
+        }
+    }
+}
+
+impl ExecuteCommand for Expire {
+    /// Replies 1 if the expire time was set, or the key deleted as the time is already past, and
+    /// 0 if the key does not exist or the condition is not met.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let now = unix_time_ms();
+        let ms = if self.milliseconds {
+            Some(self.time)
+        } else {
+            self.time.checked_mul(1000)
+        };
+        let when = if self.absolute {
+            ms
+        } else {
+            ms.and_then(|ms| ms.checked_add(now as i64))
+        }
+        .ok_or(ExecError::InvalidExpireTime(self.name()))?
+        .max(0) as u64;
+
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let Some((_, expire_time)) = lookup_key(db, stats, &self.key) else {
+            return Ok(RespData::Integer(0));
+        };
+        if self
+            .condition
+            .is_some_and(|condition| !condition.allows(*expire_time, when))
+        {
+            return Ok(RespData::Integer(0));
+        }
+
+        if when <= now {
+            db.remove(&self.key);
+        } else {
+            *expire_time = Some(when);
+            server.volatile_keys.insert(self.key.clone());
+        }
+        Ok(RespData::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::Expire;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{ExpireCondition, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_expire_should_read_condition() {
+        let cmd = parse_command(&build_request("PEXPIREAT", &["k", "-10", "gt"]))
+            .expect("parse pexpireat");
+        assert_eq!(
+            cmd,
+            Command::Expire(Expire {
+                key: Bytes::from_owner("k"),
+                time: -10,
+                milliseconds: true,
+                absolute: true,
+                condition: Some(ExpireCondition::Gt),
+            })
+        );
+
+        for args in [
+            &["k"][..],
+            &["k", "ten"],
+            &["k", "10", "KEEPTTL"],
+            &["k", "10", "NX", "XX"],
+        ] {
+            assert!(
+                parse_command(&build_request("EXPIRE", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_expire_should_follow_condition() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        let later = (unix_time_ms() + 100_000).to_string();
+
+        for (command, args, expected) in [
+            ("EXPIRE", &["missing", "10"][..], 0),
+            ("EXPIRE", &["k", "10", "XX"], 0),
+            ("EXPIRE", &["k", "10", "GT"], 0),
+            ("EXPIRE", &["k", "10", "NX"], 1),
+            ("EXPIRE", &["k", "20", "NX"], 0),
+            ("PEXPIRE", &["k", "5000", "GT"], 0),
+            ("PEXPIRE", &["k", "5000", "LT"], 1),
+            ("PEXPIREAT", &["k", &later, "XX"], 1),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute expire");
+            assert_eq!(resp, RespData::Integer(expected), "{command} {args:?}");
+        }
+        let server = server.lock().await;
+        assert_eq!(server.db.get(b"k".as_ref()).unwrap().1, later.parse().ok());
+        assert_eq!(server.volatile_keys.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_expire_should_delete_key_with_past_time() {
+        let (server, mut conn) = build_server_connection().await;
+        for key in ["a", "b"] {
+            server.lock().await.db.insert(
+                Bytes::from_owner(key),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+        }
+
+        for (command, args) in [("EXPIRE", &["a", "-1"][..]), ("EXPIREAT", &["b", "1"])] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+            assert_eq!(resp, RespData::Integer(1), "{command} {args:?}");
+        }
+        assert!(server.lock().await.db.is_empty());
+
+        let cmd = parse_command(&build_request("EXPIRE", &["a", &i64::MAX.to_string()])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("expire should overflow");
+        assert_eq!(err, ExecError::InvalidExpireTime("expire"));
+    }
+}
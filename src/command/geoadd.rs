@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_or_insert_zset, get_zset,
+    },
+    geo,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GeoAdd {
+    key: Bytes,
+    /// Only add new members.
+    nx: bool,
+    /// Only update existing members.
+    xx: bool,
+    /// Count the changed members rather than the added ones.
+    ch: bool,
+    /// The longitude, latitude and member of each position.
+    positions: Vec<(f64, f64, Bytes)>,
+}
+
+impl Parse for GeoAdd {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 4)?;
+        let mut geoadd = GeoAdd {
+            key: args[0].clone(),
+            nx: false,
+            xx: false,
+            ch: false,
+            positions: vec![],
+        };
+
+        let mut index = 1;
+        while let Some(arg) = args.get(index) {
+            let flag = match str::from_utf8(arg).map(|arg| arg.to_uppercase()).as_deref() {
+                Ok("NX") => &mut geoadd.nx,
+                Ok("XX") => &mut geoadd.xx,
+                Ok("CH") => &mut geoadd.ch,
+                _ => break,
+            };
+            *flag = true;
+            index += 1;
+        }
+
+        let triples = &args[index..];
+        if triples.is_empty() || !triples.len().is_multiple_of(3) {
+            return Err(ParseError::InvalidArgument(
+                "syntax error. Try GEOADD key [x1] [y1] [name1] [x2] [y2] [name2] ... ".to_string(),
+            ));
+        }
+        if geoadd.nx && geoadd.xx {
+            return Err(ParseError::InvalidArgument(
+                "XX and NX options at the same time are not compatible".to_string(),
+            ));
+        }
+        for triple in triples.chunks_exact(3) {
+            geoadd.positions.push((
+                lexical_core::parse(&triple[0])?,
+                lexical_core::parse(&triple[1])?,
+                triple[2].clone(),
+            ));
+        }
+        Ok(geoadd)
+    }
+}
+
+impl ExecuteCommand for GeoAdd {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        // Nothing is added unless every position is valid
+        if let Some((longitude, latitude, _)) = self
+            .positions
+            .iter()
+            .find(|(longitude, latitude, _)| !geo::is_valid(*longitude, *latitude))
+        {
+            return Err(ExecError::InvalidLonLat(*longitude, *latitude));
+        }
+
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        // XX never creates the key
+        if self.xx && get_zset(db, stats, &self.key)?.is_none() {
+            return Ok(RespData::Integer(0));
+        }
+
+        let zset = get_or_insert_zset(db, stats, &self.key)?;
+        let mut added = 0;
+        let mut changed = 0;
+        for (longitude, latitude, member) in &self.positions {
+            let score = geo::encode(*longitude, *latitude) as f64;
+            match zset.score(member) {
+                None if !self.xx => {
+                    zset.insert(member.clone(), score);
+                    added += 1;
+                }
+                Some(current) if !self.nx && current != score => {
+                    zset.insert(member.clone(), score);
+                    changed += 1;
+                }
+                _ => {}
+            }
+        }
+        server.serve_blocked_clients(&self.key);
+
+        Ok(RespData::Integer(if self.ch {
+            added + changed
+        } else {
+            added
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::GeoAdd;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        geo,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_geoadd_should_read_flags_and_positions() {
+        let cmd = parse_command(&build_request(
+            "GEOADD",
+            &["Sicily", "xx", "CH", "13.361389", "38.115556", "Palermo"],
+        ))
+        .expect("parse geoadd");
+        assert_eq!(
+            cmd,
+            Command::GeoAdd(GeoAdd {
+                key: Bytes::from_owner("Sicily"),
+                nx: false,
+                xx: true,
+                ch: true,
+                positions: vec![(13.361389, 38.115556, Bytes::from_owner("Palermo"))],
+            })
+        );
+
+        for args in [
+            &["Sicily", "13.361389", "38.115556"][..],
+            &["Sicily", "NX", "XX", "13.361389", "38.115556", "Palermo"],
+            &["Sicily", "13.361389", "38.115556", "Palermo", "15"],
+            &["Sicily", "east", "38.115556", "Palermo"],
+        ] {
+            assert!(
+                parse_command(&build_request("GEOADD", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_geoadd_should_store_geohash_scores() {
+        let (server, mut conn) = build_server_connection().await;
+
+        for (args, expected) in [
+            (
+                &[
+                    "Sicily",
+                    "13.361389",
+                    "38.115556",
+                    "Palermo",
+                    "15.087269",
+                    "37.502669",
+                    "Catania",
+                ][..],
+                2,
+            ),
+            (&["Sicily", "NX", "0", "0", "Palermo"], 0),
+            (&["Sicily", "XX", "0", "0", "Ragusa"], 0),
+            (&["missing", "XX", "0", "0", "Ragusa"], 0),
+            (&["Sicily", "CH", "15.087269", "37.502669", "Catania"], 0),
+            (
+                &["Sicily", "CH", "0", "0", "Catania", "1", "1", "Ragusa"],
+                2,
+            ),
+        ] {
+            let cmd = parse_command(&build_request("GEOADD", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute geoadd");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        assert!(!db.contains_key(b"missing".as_ref()));
+        let Some((Value::ZSet(zset), _)) = db.get(b"Sicily".as_ref()) else {
+            panic!("Sicily should be a sorted set");
+        };
+        assert_eq!(zset.score(b"Palermo"), Some(3479099956230698.0));
+        assert_eq!(zset.score(b"Catania"), Some(geo::encode(0.0, 0.0) as f64));
+        assert_eq!(zset.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn execute_geoadd_should_reject_invalid_positions() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request(
+            "GEOADD",
+            &["Sicily", "13", "38", "Palermo", "181", "90", "Nowhere"],
+        ))
+        .unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("geoadd should fail");
+        assert_eq!(err, ExecError::InvalidLonLat(181.0, 90.0));
+        assert_eq!(
+            err.to_string(),
+            "ERR invalid longitude,latitude pair 181.000000,90.000000"
+        );
+        assert!(server.lock().await.db.is_empty());
+    }
+}
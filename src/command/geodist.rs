@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, distance_reply,
+        error::{ExecResult, ParseError},
+        get_zset,
+    },
+    geo,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GeoDist {
+    key: Bytes,
+    member1: Bytes,
+    member2: Bytes,
+    /// The number of meters in the unit of the reply.
+    unit: f64,
+}
+
+impl Parse for GeoDist {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 3)?;
+        let unit = match args {
+            [_, _, _] => 1.0,
+            [_, _, _, unit] => parse_unit(unit)?,
+            _ => return Err(ParseError::InvalidArgument("syntax error".to_string())),
+        };
+        Ok(GeoDist {
+            key: args[0].clone(),
+            member1: args[1].clone(),
+            member2: args[2].clone(),
+            unit,
+        })
+    }
+}
+
+/// Parse a distance unit as the number of meters in it.
+pub(super) fn parse_unit(unit: &Bytes) -> ParseResult<f64> {
+    geo::unit_in_meters(unit).ok_or_else(|| {
+        ParseError::InvalidArgument(
+            "unsupported unit provided. please use M, KM, FT, MI".to_string(),
+        )
+    })
+}
+
+impl ExecuteCommand for GeoDist {
+    /// Replies nil if a member is missing.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let Some(zset) = get_zset(db, stats, &self.key)? else {
+            return Ok(RespData::BulkString(None));
+        };
+        let (Some(score1), Some(score2)) = (zset.score(&self.member1), zset.score(&self.member2))
+        else {
+            return Ok(RespData::BulkString(None));
+        };
+        let distance = geo::distance(geo::decode(score1 as u64), geo::decode(score2 as u64));
+        Ok(distance_reply(distance / self.unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::GeoDist;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_geodist_should_read_unit() {
+        let cmd = parse_command(&build_request("GEODIST", &["Sicily", "a", "b", "KM"]))
+            .expect("parse geodist");
+        assert_eq!(
+            cmd,
+            Command::GeoDist(GeoDist {
+                key: Bytes::from_owner("Sicily"),
+                member1: Bytes::from_owner("a"),
+                member2: Bytes::from_owner("b"),
+                unit: 1000.0,
+            })
+        );
+
+        for args in [
+            &["Sicily", "a"][..],
+            &["Sicily", "a", "b", "yd"],
+            &["Sicily", "a", "b", "m", "m"],
+        ] {
+            assert!(
+                parse_command(&build_request("GEODIST", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_geodist_should_convert_units() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request(
+            "GEOADD",
+            &[
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+            ],
+        ))
+        .unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+
+        for (args, expected) in [
+            (&["Sicily", "Palermo", "Catania"][..], Some("166274.1516")),
+            (&["Sicily", "Palermo", "Catania", "km"], Some("166.2742")),
+            (&["Sicily", "Palermo", "Catania", "MI"], Some("103.3182")),
+            (&["Sicily", "Palermo", "Palermo", "ft"], Some("0.0000")),
+            (&["Sicily", "Palermo", "Agrigento"], None),
+            (&["missing", "Palermo", "Catania"], None),
+        ] {
+            let cmd = parse_command(&build_request("GEODIST", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute geodist");
+            assert_eq!(
+                resp,
+                RespData::BulkString(expected.map(Bytes::from_owner)),
+                "{args:?}"
+            );
+        }
+    }
+}
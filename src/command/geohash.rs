@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_zset},
+    geo,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GeoHash {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl Parse for GeoHash {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(GeoHash {
+            key: args[0].clone(),
+            members: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for GeoHash {
+    /// Replies the standard geohash string of each member, or nil for the missing ones.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let zset = get_zset(db, stats, &self.key)?;
+        let hashes = self
+            .members
+            .iter()
+            .map(|member| {
+                let score = zset.as_ref().and_then(|zset| zset.score(member));
+                RespData::BulkString(
+                    score.map(|score| Bytes::from(geo::to_geohash_string(score as u64))),
+                )
+            })
+            .collect();
+        Ok(RespData::Array(hashes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::GeoHash;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_geohash_should_read_members() {
+        let cmd = parse_command(&build_request("GEOHASH", &["Sicily", "a", "b"])).expect("parse");
+        assert_eq!(
+            cmd,
+            Command::GeoHash(GeoHash {
+                key: Bytes::from_owner("Sicily"),
+                members: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("GEOHASH", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_geohash_should_reply_standard_geohashes() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request(
+            "GEOADD",
+            &[
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+            ],
+        ))
+        .unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+
+        for (args, expected) in [
+            (
+                &["Sicily", "Palermo", "Catania", "Agrigento"][..],
+                vec![Some("sqc8b49rny0"), Some("sqdtr74hyu0"), None],
+            ),
+            (&["missing", "Palermo"], vec![None]),
+        ] {
+            let cmd = parse_command(&build_request("GEOHASH", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute geohash");
+            assert_eq!(
+                resp,
+                RespData::Array(
+                    expected
+                        .into_iter()
+                        .map(|hash| RespData::BulkString(hash.map(Bytes::from_owner)))
+                        .collect()
+                ),
+                "{args:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, coordinates_reply, error::ExecResult,
+        get_zset,
+    },
+    geo,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GeoPos {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl Parse for GeoPos {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(GeoPos {
+            key: args[0].clone(),
+            members: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for GeoPos {
+    /// Replies the position of each member, or nil for the missing ones.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let zset = get_zset(db, stats, &self.key)?;
+        let positions = self
+            .members
+            .iter()
+            .map(|member| {
+                let score = zset.as_ref().and_then(|zset| zset.score(member));
+                score.map_or(RespData::NullArray, |score| {
+                    coordinates_reply(geo::decode(score as u64), conn.protocol)
+                })
+            })
+            .collect();
+        Ok(RespData::Array(positions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::GeoPos;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        resp::{RespData, RespProtocol},
+    };
+
+    #[test]
+    fn parse_geopos_should_read_members() {
+        let cmd = parse_command(&build_request("GEOPOS", &["Sicily", "Palermo"])).expect("parse");
+        assert_eq!(
+            cmd,
+            Command::GeoPos(GeoPos {
+                key: Bytes::from_owner("Sicily"),
+                members: vec![Bytes::from_owner("Palermo")],
+            })
+        );
+        assert!(parse_command(&build_request("GEOPOS", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_geopos_should_reply_decoded_positions() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request(
+            "GEOADD",
+            &["Sicily", "13.361389", "38.115556", "Palermo"],
+        ))
+        .unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+
+        let cmd = parse_command(&build_request(
+            "GEOPOS",
+            &["Sicily", "Palermo", "NonExisting"],
+        ))
+        .unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute geopos");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::Array(vec![
+                    RespData::BulkString(Some(Bytes::from_owner("13.36138933897018433"))),
+                    RespData::BulkString(Some(Bytes::from_owner("38.11555639549629859"))),
+                ]),
+                RespData::NullArray,
+            ])
+        );
+
+        conn.protocol = RespProtocol::Resp3;
+        let cmd = parse_command(&build_request("GEOPOS", &["Sicily", "Palermo"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(
+            resp,
+            RespData::Array(vec![RespData::Array(vec![
+                RespData::Double(13.361389338970184),
+                RespData::Double(38.1155563954963),
+            ])])
+        );
+
+        let cmd = parse_command(&build_request("GEOPOS", &["missing", "a"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Array(vec![RespData::NullArray]));
+    }
+}
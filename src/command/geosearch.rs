@@ -0,0 +1,742 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, ParseResult, check_length_ge, coordinates_reply, distance_reply,
+        error::{ExecResult, ParseError},
+        geodist::parse_unit,
+        get_zset,
+    },
+    db::Value,
+    geo::{self, Shape},
+    resp::RespData,
+    server::{Connection, Server},
+    zset::SortedSet,
+};
+
+/// The point a search is centered at.
+#[derive(Debug, PartialEq)]
+enum Origin {
+    /// `FROMMEMBER`, the position of a member of the searched key.
+    Member(Bytes),
+    /// `FROMLONLAT`, a longitude and a latitude.
+    Position(f64, f64),
+}
+
+/// The order of the results by distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Asc,
+    Desc,
+}
+
+/// A position found by a search.
+struct Found {
+    member: Bytes,
+    score: f64,
+    /// The distance from the center, in meters.
+    distance: f64,
+}
+
+/// `GEOSEARCH` and `GEOSEARCHSTORE`.
+#[derive(Debug, PartialEq)]
+pub struct GeoSearch {
+    /// Store the results there and reply their count, instead of replying them.
+    destination: Option<Bytes>,
+    key: Bytes,
+    origin: Origin,
+    /// The searched area, in meters.
+    shape: Shape,
+    /// The number of meters in the unit the shape was given in, which distances are replied in.
+    unit: f64,
+    order: Option<Order>,
+    /// The maximum number of results, and whether any matching results will do, rather than
+    /// the nearest ones.
+    count: Option<(usize, bool)>,
+    with_coord: bool,
+    with_dist: bool,
+    with_hash: bool,
+    /// Store the distances as scores rather than the geohashes.
+    store_dist: bool,
+}
+
+impl GeoSearch {
+    /// Parse `GEOSEARCH key FROMMEMBER member | FROMLONLAT longitude latitude BYRADIUS radius
+    /// unit | BYBOX width height unit [ASC | DESC] [COUNT count [ANY]] [WITHCOORD] [WITHDIST]
+    /// [WITHHASH]`, or `GEOSEARCHSTORE` which takes a destination first, `STOREDIST` and no
+    /// `WITH` options.
+    pub fn parse(args: &[Bytes], store: bool) -> ParseResult<Self> {
+        let (destination, args) = if store {
+            check_length_ge(args, 2)?;
+            (Some(args[0].clone()), &args[1..])
+        } else {
+            check_length_ge(args, 1)?;
+            (None, args)
+        };
+
+        let mut origin = None;
+        let mut shape = None;
+        let mut order = None;
+        let mut count = None;
+        let mut any = false;
+        let (mut with_coord, mut with_dist, mut with_hash, mut store_dist) =
+            (false, false, false, false);
+        let mut index = 1;
+        while let Some(arg) = args.get(index) {
+            let argument = str::from_utf8(arg)?;
+            let values = &args[index + 1..];
+            match argument.to_uppercase().as_str() {
+                "FROMMEMBER" if origin.is_none() && !values.is_empty() => {
+                    origin = Some(Origin::Member(values[0].clone()));
+                    index += 1;
+                }
+                "FROMLONLAT" if origin.is_none() && values.len() >= 2 => {
+                    origin = Some(Origin::Position(
+                        lexical_core::parse(&values[0])?,
+                        lexical_core::parse(&values[1])?,
+                    ));
+                    index += 2;
+                }
+                "BYRADIUS" if shape.is_none() && values.len() >= 2 => {
+                    let radius: f64 = lexical_core::parse(&values[0])?;
+                    if radius < 0.0 {
+                        return Err(ParseError::InvalidArgument(
+                            "radius cannot be negative".to_string(),
+                        ));
+                    }
+                    let unit = parse_unit(&values[1])?;
+                    shape = Some((Shape::Radius(radius * unit), unit));
+                    index += 2;
+                }
+                "BYBOX" if shape.is_none() && values.len() >= 3 => {
+                    let width: f64 = lexical_core::parse(&values[0])?;
+                    let height: f64 = lexical_core::parse(&values[1])?;
+                    if width < 0.0 || height < 0.0 {
+                        return Err(ParseError::InvalidArgument(
+                            "height or width cannot be negative".to_string(),
+                        ));
+                    }
+                    let unit = parse_unit(&values[2])?;
+                    shape = Some((
+                        Shape::Box {
+                            width: width * unit,
+                            height: height * unit,
+                        },
+                        unit,
+                    ));
+                    index += 3;
+                }
+                "ASC" => order = Some(Order::Asc),
+                "DESC" => order = Some(Order::Desc),
+                "COUNT" if !values.is_empty() => {
+                    let value: i64 = lexical_core::parse(&values[0])?;
+                    if value <= 0 {
+                        return Err(ParseError::InvalidArgument("COUNT must be > 0".to_string()));
+                    }
+                    count = Some(value as usize);
+                    index += 1;
+                }
+                "ANY" => any = true,
+                "WITHCOORD" if !store => with_coord = true,
+                "WITHDIST" if !store => with_dist = true,
+                "WITHHASH" if !store => with_hash = true,
+                "STOREDIST" if store => store_dist = true,
+                _ => return Err(ParseError::InvalidArgument(argument.to_string())),
+            }
+            index += 1;
+        }
+
+        let command = if store { "GEOSEARCHSTORE" } else { "GEOSEARCH" };
+        let origin = origin.ok_or_else(|| {
+            ParseError::InvalidArgument(format!(
+                "exactly one of FROMMEMBER or FROMLONLAT can be specified for {command}"
+            ))
+        })?;
+        let (shape, unit) = shape.ok_or_else(|| {
+            ParseError::InvalidArgument(format!(
+                "exactly one of BYRADIUS and BYBOX can be specified for {command}"
+            ))
+        })?;
+        if any && count.is_none() {
+            return Err(ParseError::InvalidArgument(
+                "the ANY argument requires COUNT argument".to_string(),
+            ));
+        }
+
+        Ok(GeoSearch {
+            destination,
+            key: args[0].clone(),
+            origin,
+            shape,
+            unit,
+            order,
+            count: count.map(|count| (count, any)),
+            with_coord,
+            with_dist,
+            with_hash,
+            store_dist,
+        })
+    }
+
+    /// The positions within the shape centered at `center`, in the order of the reply.
+    fn search(&self, zset: &SortedSet, center: (f64, f64)) -> Vec<Found> {
+        // With ANY, the search stops at the first matching positions
+        let limit = match self.count {
+            Some((count, true)) => count,
+            _ => usize::MAX,
+        };
+        let mut found = vec![];
+        'ranges: for range in geo::search_ranges(center, &self.shape) {
+            for (member, score) in zset.range(zset.score_range(&range)) {
+                if found.len() >= limit {
+                    break 'ranges;
+                }
+                let position = geo::decode(score as u64);
+                if let Some(distance) = self.shape.distance_within(center, position) {
+                    found.push(Found {
+                        member: member.clone(),
+                        score,
+                        distance,
+                    });
+                }
+            }
+        }
+
+        // The nearest positions are found by sorting them, unless any will do
+        let order = match (self.order, self.count) {
+            (None, Some((_, false))) => Some(Order::Asc),
+            (order, _) => order,
+        };
+        match order {
+            Some(Order::Asc) => found.sort_by(|a, b| a.distance.total_cmp(&b.distance)),
+            Some(Order::Desc) => found.sort_by(|a, b| b.distance.total_cmp(&a.distance)),
+            None => {}
+        }
+        if let Some((count, _)) = self.count {
+            found.truncate(count);
+        }
+        found
+    }
+}
+
+impl ExecuteCommand for GeoSearch {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        if let Origin::Position(longitude, latitude) = self.origin
+            && !geo::is_valid(longitude, latitude)
+        {
+            return Err(ExecError::InvalidLonLat(longitude, latitude));
+        }
+
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let found = match get_zset(db, stats, &self.key)? {
+            None => vec![],
+            Some(zset) => {
+                let center = match &self.origin {
+                    Origin::Member(member) => {
+                        let score = zset.score(member).ok_or(ExecError::GeoMemberNotFound)?;
+                        geo::decode(score as u64)
+                    }
+                    Origin::Position(longitude, latitude) => (*longitude, *latitude),
+                };
+                self.search(zset, center)
+            }
+        };
+
+        let Some(destination) = &self.destination else {
+            let with_any = self.with_coord || self.with_dist || self.with_hash;
+            let reply = found
+                .into_iter()
+                .map(|found| {
+                    if !with_any {
+                        return RespData::BulkString(Some(found.member));
+                    }
+                    let mut item = vec![RespData::BulkString(Some(found.member))];
+                    if self.with_dist {
+                        item.push(distance_reply(found.distance / self.unit));
+                    }
+                    if self.with_hash {
+                        item.push(RespData::Integer(found.score as i64));
+                    }
+                    if self.with_coord {
+                        item.push(coordinates_reply(
+                            geo::decode(found.score as u64),
+                            conn.protocol,
+                        ));
+                    }
+                    RespData::Array(item)
+                })
+                .collect();
+            return Ok(RespData::Array(reply));
+        };
+
+        let len = found.len();
+        if found.is_empty() {
+            db.remove(destination);
+        } else {
+            let zset = found
+                .into_iter()
+                .map(|found| {
+                    let score = if self.store_dist {
+                        found.distance / self.unit
+                    } else {
+                        found.score
+                    };
+                    (found.member, score)
+                })
+                .collect();
+            db.insert(destination.clone(), (Value::ZSet(zset), None));
+            server.serve_blocked_clients(destination);
+        }
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use tokio::sync::Mutex;
+
+    use super::{GeoSearch, Order, Origin};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        geo::Shape,
+        resp::RespData,
+        server::{Connection, Server},
+    };
+
+    async fn add_sicily(server: &Arc<Mutex<Server>>, conn: &mut Connection) {
+        let cmd = parse_command(&build_request(
+            "GEOADD",
+            &[
+                "Sicily",
+                "13.361389",
+                "38.115556",
+                "Palermo",
+                "15.087269",
+                "37.502669",
+                "Catania",
+                "12.758489",
+                "38.788135",
+                "edge1",
+                "17.241510",
+                "38.788135",
+                "edge2",
+            ],
+        ))
+        .unwrap();
+        cmd.execute(server.clone(), conn).await.unwrap();
+    }
+
+    fn members(names: &[&str]) -> RespData {
+        RespData::Array(
+            names
+                .iter()
+                .map(|name| RespData::BulkString(Some(Bytes::from(name.to_string()))))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parse_geosearch_should_read_options() {
+        let cmd = parse_command(&build_request(
+            "GEOSEARCH",
+            &[
+                "Sicily",
+                "FROMLONLAT",
+                "15",
+                "37",
+                "BYBOX",
+                "400",
+                "200",
+                "km",
+                "desc",
+                "COUNT",
+                "2",
+                "ANY",
+                "WITHDIST",
+                "withcoord",
+            ],
+        ))
+        .expect("parse geosearch");
+        assert_eq!(
+            cmd,
+            Command::GeoSearch(GeoSearch {
+                destination: None,
+                key: Bytes::from_owner("Sicily"),
+                origin: Origin::Position(15.0, 37.0),
+                shape: Shape::Box {
+                    width: 400_000.0,
+                    height: 200_000.0,
+                },
+                unit: 1000.0,
+                order: Some(Order::Desc),
+                count: Some((2, true)),
+                with_coord: true,
+                with_dist: true,
+                with_hash: false,
+                store_dist: false,
+            })
+        );
+
+        let cmd = parse_command(&build_request(
+            "GEOSEARCHSTORE",
+            &[
+                "dest",
+                "Sicily",
+                "FROMMEMBER",
+                "Palermo",
+                "BYRADIUS",
+                "10",
+                "mi",
+                "STOREDIST",
+            ],
+        ))
+        .expect("parse geosearchstore");
+        assert_eq!(
+            cmd,
+            Command::GeoSearch(GeoSearch {
+                destination: Some(Bytes::from_owner("dest")),
+                key: Bytes::from_owner("Sicily"),
+                origin: Origin::Member(Bytes::from_owner("Palermo")),
+                shape: Shape::Radius(16093.4),
+                unit: 1609.34,
+                order: None,
+                count: None,
+                with_coord: false,
+                with_dist: false,
+                with_hash: false,
+                store_dist: true,
+            })
+        );
+
+        for (command, args) in [
+            ("GEOSEARCH", &["Sicily", "BYRADIUS", "1", "m"][..]),
+            ("GEOSEARCH", &["Sicily", "FROMMEMBER", "a"]),
+            (
+                "GEOSEARCH",
+                &[
+                    "Sicily",
+                    "FROMMEMBER",
+                    "a",
+                    "FROMLONLAT",
+                    "1",
+                    "1",
+                    "BYRADIUS",
+                    "1",
+                    "m",
+                ],
+            ),
+            (
+                "GEOSEARCH",
+                &[
+                    "Sicily",
+                    "FROMMEMBER",
+                    "a",
+                    "BYRADIUS",
+                    "1",
+                    "m",
+                    "BYBOX",
+                    "1",
+                    "1",
+                    "m",
+                ],
+            ),
+            (
+                "GEOSEARCH",
+                &["Sicily", "FROMMEMBER", "a", "BYRADIUS", "-1", "m"],
+            ),
+            (
+                "GEOSEARCH",
+                &["Sicily", "FROMMEMBER", "a", "BYBOX", "1", "-1", "m"],
+            ),
+            (
+                "GEOSEARCH",
+                &["Sicily", "FROMMEMBER", "a", "BYRADIUS", "1", "yd"],
+            ),
+            (
+                "GEOSEARCH",
+                &["Sicily", "FROMMEMBER", "a", "BYRADIUS", "1", "m", "ANY"],
+            ),
+            (
+                "GEOSEARCH",
+                &[
+                    "Sicily",
+                    "FROMMEMBER",
+                    "a",
+                    "BYRADIUS",
+                    "1",
+                    "m",
+                    "COUNT",
+                    "0",
+                ],
+            ),
+            (
+                "GEOSEARCH",
+                &[
+                    "Sicily",
+                    "FROMMEMBER",
+                    "a",
+                    "BYRADIUS",
+                    "1",
+                    "m",
+                    "STOREDIST",
+                ],
+            ),
+            (
+                "GEOSEARCHSTORE",
+                &[
+                    "dest",
+                    "Sicily",
+                    "FROMMEMBER",
+                    "a",
+                    "BYRADIUS",
+                    "1",
+                    "m",
+                    "WITHDIST",
+                ],
+            ),
+        ] {
+            assert!(
+                parse_command(&build_request(command, args)).is_err(),
+                "{command} {args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_geosearch_should_reply_positions_in_shape() {
+        let (server, mut conn) = build_server_connection().await;
+        add_sicily(&server, &mut conn).await;
+
+        for (args, expected) in [
+            (
+                &[
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYRADIUS",
+                    "200",
+                    "km",
+                    "ASC",
+                ][..],
+                members(&["Catania", "Palermo"]),
+            ),
+            (
+                &[
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYBOX",
+                    "400",
+                    "400",
+                    "km",
+                    "asc",
+                ],
+                members(&["Catania", "Palermo", "edge2", "edge1"]),
+            ),
+            (
+                &[
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYBOX",
+                    "400",
+                    "400",
+                    "km",
+                    "DESC",
+                ],
+                members(&["edge1", "edge2", "Palermo", "Catania"]),
+            ),
+            (
+                &[
+                    "Sicily",
+                    "FROMMEMBER",
+                    "Palermo",
+                    "BYRADIUS",
+                    "100",
+                    "mi",
+                    "COUNT",
+                    "2",
+                ],
+                members(&["Palermo", "edge1"]),
+            ),
+            (
+                &["missing", "FROMMEMBER", "Palermo", "BYRADIUS", "100", "mi"],
+                members(&[]),
+            ),
+            (
+                &[
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYRADIUS",
+                    "200",
+                    "km",
+                    "ASC",
+                    "WITHCOORD",
+                    "WITHDIST",
+                    "WITHHASH",
+                ],
+                RespData::Array(vec![
+                    RespData::Array(vec![
+                        RespData::BulkString(Some(Bytes::from_owner("Catania"))),
+                        RespData::BulkString(Some(Bytes::from_owner("56.4413"))),
+                        RespData::Integer(3479447370796909),
+                        RespData::Array(vec![
+                            RespData::BulkString(Some(Bytes::from_owner("15.08726745843887329"))),
+                            RespData::BulkString(Some(Bytes::from_owner("37.50266842333162032"))),
+                        ]),
+                    ]),
+                    RespData::Array(vec![
+                        RespData::BulkString(Some(Bytes::from_owner("Palermo"))),
+                        RespData::BulkString(Some(Bytes::from_owner("190.4424"))),
+                        RespData::Integer(3479099956230698),
+                        RespData::Array(vec![
+                            RespData::BulkString(Some(Bytes::from_owner("13.36138933897018433"))),
+                            RespData::BulkString(Some(Bytes::from_owner("38.11555639549629859"))),
+                        ]),
+                    ]),
+                ]),
+            ),
+        ] {
+            let cmd = parse_command(&build_request("GEOSEARCH", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute geosearch");
+            assert_eq!(resp, expected, "{args:?}");
+        }
+
+        // Any matching positions will do, so only their number is known
+        let cmd = parse_command(&build_request(
+            "GEOSEARCH",
+            &[
+                "Sicily",
+                "FROMLONLAT",
+                "15",
+                "37",
+                "BYBOX",
+                "400",
+                "400",
+                "km",
+                "COUNT",
+                "3",
+                "ANY",
+            ],
+        ))
+        .unwrap();
+        let RespData::Array(found) = cmd.execute(server.clone(), &mut conn).await.unwrap() else {
+            panic!("geosearch should reply an array");
+        };
+        assert_eq!(found.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn execute_geosearch_should_reject_invalid_origins() {
+        let (server, mut conn) = build_server_connection().await;
+        add_sicily(&server, &mut conn).await;
+
+        for (args, expected) in [
+            (
+                &["Sicily", "FROMMEMBER", "Agrigento", "BYRADIUS", "1", "km"][..],
+                ExecError::GeoMemberNotFound,
+            ),
+            (
+                &["Sicily", "FROMLONLAT", "15", "86", "BYRADIUS", "1", "km"],
+                ExecError::InvalidLonLat(15.0, 86.0),
+            ),
+        ] {
+            let cmd = parse_command(&build_request("GEOSEARCH", args)).unwrap();
+            let err = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect_err("geosearch should fail");
+            assert_eq!(err, expected, "{args:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_geosearchstore_should_store_scores_or_distances() {
+        let (server, mut conn) = build_server_connection().await;
+        add_sicily(&server, &mut conn).await;
+
+        for (args, expected) in [
+            (
+                &[
+                    "hashes",
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYRADIUS",
+                    "200",
+                    "km",
+                ][..],
+                2,
+            ),
+            (
+                &[
+                    "distances",
+                    "Sicily",
+                    "FROMLONLAT",
+                    "15",
+                    "37",
+                    "BYRADIUS",
+                    "200",
+                    "km",
+                    "COUNT",
+                    "1",
+                    "STOREDIST",
+                ],
+                1,
+            ),
+            (
+                &[
+                    "hashes",
+                    "Sicily",
+                    "FROMLONLAT",
+                    "0",
+                    "0",
+                    "BYRADIUS",
+                    "1",
+                    "km",
+                ],
+                0,
+            ),
+        ] {
+            let cmd = parse_command(&build_request("GEOSEARCHSTORE", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute geosearchstore");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        assert!(!db.contains_key(b"hashes".as_ref()));
+        let Some((Value::ZSet(zset), _)) = db.get(b"distances".as_ref()) else {
+            panic!("distances should be a sorted set");
+        };
+        assert_eq!(zset.len(), 1);
+        let distance = zset.score(b"Catania").unwrap();
+        assert!((distance - 56.4413).abs() < 1e-4, "{distance}");
+    }
+}
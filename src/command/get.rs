@@ -1,13 +1,16 @@
 use std::sync::Arc;
 
 use bytes::Bytes;
-use tokio::{sync::Mutex, time::Instant};
+use tokio::sync::Mutex;
 
 use crate::{
-    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult},
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq,
+        error::{ExecError, ExecResult},
+    },
+    db::{Value, lookup_key},
     resp::RespData,
     server::{Connection, Server},
-    utils::BytesInStr,
 };
 
 #[derive(Debug, PartialEq)]
@@ -33,41 +36,31 @@ impl ExecuteCommand for Get {
         server: Arc<Mutex<Server>>,
         _conn: &mut Connection,
     ) -> ExecResult<RespData> {
-        let db = &mut server.lock().await.db;
+        let Server { db, stats, .. } = &mut *server.lock().await;
 
-        let Some((value, expire_time)) = db.get(&self.key) else {
-            return Ok(RespData::BulkString(None));
-        };
-
-        if expire_time.is_some_and(|t| t <= Instant::now()) {
-            // If key does not exist, the function has returned in the previous else
-            let (value, _) = db.remove(&self.key).unwrap();
-            tracing::info!(
-                "Remove Key: {}, Value: {}",
-                BytesInStr::from_bytes(&self.key),
-                BytesInStr::from_bytes(&value)
-            );
-            return Ok(RespData::BulkString(None));
+        match lookup_key(db, stats, &self.key) {
+            None => Ok(RespData::BulkString(None)),
+            Some((Value::String(value), _)) => Ok(RespData::BulkString(Some(value.clone()))),
+            Some(_) => Err(ExecError::WrongType),
         }
-
-        Ok(RespData::BulkString(Some(value.clone())))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::collections::VecDeque;
 
     use bytes::Bytes;
-    use tokio::time::Instant;
 
     use super::Get;
     use crate::{
         command::{
-            Command, ExecuteCommand, ParseError, parse_command,
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
             test::{build_request, build_server_connection},
         },
+        db::Value,
         resp::RespData,
+        utils::unix_time_ms,
     };
 
     #[test]
@@ -102,7 +95,7 @@ mod tests {
         let (server, mut conn) = build_server_connection().await;
         server.lock().await.db.insert(
             Bytes::from_owner("my-key"),
-            (Bytes::from_owner("my-value"), None),
+            (Value::String(Bytes::from_owner("my-value")), None),
         );
 
         let cmd = Get {
@@ -131,8 +124,8 @@ mod tests {
         server.lock().await.db.insert(
             Bytes::from_owner("my-key"),
             (
-                Bytes::from_owner("my-value"),
-                Some(Instant::now() - Duration::from_millis(1)),
+                Value::String(Bytes::from_owner("my-value")),
+                Some(unix_time_ms() - 1),
             ),
         );
 
@@ -152,4 +145,22 @@ mod tests {
                 .contains_key(&Bytes::from_owner("my-key"))
         );
     }
+
+    #[tokio::test]
+    async fn execute_get_should_reject_non_string_value() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("my-list"),
+            (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+        );
+
+        let cmd = Get {
+            key: Bytes::from_owner("my-list"),
+        };
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("get on a list should fail");
+        assert_eq!(err, ExecError::WrongType);
+    }
 }
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_string,
+        parse_bit_offset,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GetBit {
+    key: Bytes,
+    offset: usize,
+}
+
+impl Parse for GetBit {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(GetBit {
+            key: args[0].clone(),
+            offset: parse_bit_offset(&args[1])?,
+        })
+    }
+}
+
+impl ExecuteCommand for GetBit {
+    /// Bits past the end of the string are 0.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let bit = get_string(db, stats, &self.key)?
+            .and_then(|string| string.get(self.offset / 8))
+            .is_some_and(|byte| byte & (0x80 >> (self.offset % 8)) != 0);
+        Ok(RespData::Integer(bit as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::GetBit;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_getbit_should_read_offset() {
+        let cmd = parse_command(&build_request("GETBIT", &["k", "7"])).expect("parse getbit");
+        assert_eq!(
+            cmd,
+            Command::GetBit(GetBit {
+                key: Bytes::from_owner("k"),
+                offset: 7,
+            })
+        );
+        assert!(parse_command(&build_request("GETBIT", &["k", "-1"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_getbit_should_read_bits_with_zero_padding() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_static(&[0b1000_0001])), None),
+        );
+
+        for (key, offset, expected) in [
+            ("k", "0", 1),
+            ("k", "1", 0),
+            ("k", "7", 1),
+            ("k", "100", 0),
+            ("missing", "0", 0),
+        ] {
+            let cmd = parse_command(&build_request("GETBIT", &[key, offset])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute getbit");
+            assert_eq!(resp, RespData::Integer(expected), "{key} {offset}");
+        }
+    }
+}
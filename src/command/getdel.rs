@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_string},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GetDel {
+    key: Bytes,
+}
+
+impl Parse for GetDel {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(GetDel {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for GetDel {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let value = get_string(db, stats, &self.key)?.cloned();
+        if value.is_some() {
+            db.remove(&self.key);
+        }
+        Ok(RespData::BulkString(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::GetDel;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_getdel_should_read_key() {
+        let cmd = parse_command(&build_request("GETDEL", &["k"])).expect("parse getdel");
+        assert_eq!(
+            cmd,
+            Command::GetDel(GetDel {
+                key: Bytes::from_owner("k"),
+            })
+        );
+        assert!(parse_command(&build_request("GETDEL", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_getdel_should_return_and_remove_string() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("k"),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+            );
+        }
+
+        for expected in [Some(Bytes::from_owner("v")), None] {
+            let cmd = parse_command(&build_request("GETDEL", &["k"])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute getdel");
+            assert_eq!(resp, RespData::BulkString(expected));
+        }
+
+        let cmd = parse_command(&build_request("GETDEL", &["list"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+        assert!(server.lock().await.db.contains_key(b"list".as_ref()));
+    }
+}
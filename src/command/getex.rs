@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult,
+        error::{ExecError, ExecResult, ParseError},
+        get_string, parse_expiration,
+    },
+    db::Expiration,
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GetEx {
+    key: Bytes,
+    expiration: Option<Expiration>,
+    /// Remove the expire time of the key, for the `PERSIST` option.
+    persist: bool,
+}
+
+impl Parse for GetEx {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        let mut getex = GetEx {
+            key: args
+                .first()
+                .ok_or_else(|| ParseError::ExpectLengthGe(1, 0, vec![]))?
+                .clone(),
+            expiration: None,
+            persist: false,
+        };
+
+        let argument = match args.get(1) {
+            Some(arg) => str::from_utf8(arg)?.to_uppercase(),
+            None => return Ok(getex),
+        };
+        match (argument.as_str(), &args[2..]) {
+            ("EX" | "PX" | "EXAT" | "PXAT", [time]) => {
+                getex.expiration = Some(parse_expiration(&argument, time)?)
+            }
+            ("PERSIST", []) => getex.persist = true,
+            _ => return Err(ParseError::InvalidArgument(argument)),
+        }
+        Ok(getex)
+    }
+}
+
+impl ExecuteCommand for GetEx {
+    /// Replies the value like `GET`, then updates the expire time of the key. A key given an
+    /// expire time already past is deleted.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let now = unix_time_ms();
+        let when = self
+            .expiration
+            .map(|expiration| {
+                expiration
+                    .unix_time_ms(now)
+                    .ok_or(ExecError::InvalidExpireTime("getex"))
+            })
+            .transpose()?;
+
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let Some(value) = get_string(db, stats, &self.key)?.cloned() else {
+            return Ok(RespData::BulkString(None));
+        };
+        match when {
+            Some(when) if when <= now => {
+                db.remove(&self.key);
+            }
+            Some(when) => {
+                // The key was just looked up
+                db.get_mut(&self.key).unwrap().1 = Some(when);
+                server.volatile_keys.insert(self.key.clone());
+            }
+            None if self.persist => db.get_mut(&self.key).unwrap().1 = None,
+            None => {}
+        }
+
+        Ok(RespData::BulkString(Some(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::GetEx;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Expiration, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_getex_should_read_options() {
+        for (args, expiration, persist) in [
+            (&["k"][..], None, false),
+            (&["k", "ex", "10"], Some(Expiration::In(10_000)), false),
+            (&["k", "PXAT", "123"], Some(Expiration::At(123)), false),
+            (&["k", "PERSIST"], None, true),
+        ] {
+            let cmd = parse_command(&build_request("GETEX", args)).expect("parse getex");
+            assert_eq!(
+                cmd,
+                Command::GetEx(GetEx {
+                    key: Bytes::from_owner("k"),
+                    expiration,
+                    persist,
+                }),
+                "{args:?}"
+            );
+        }
+
+        for args in [
+            &[][..],
+            &["k", "EX"],
+            &["k", "EX", "0"],
+            &["k", "EX", "10", "PERSIST"],
+            &["k", "PERSIST", "1"],
+            &["k", "KEEPTTL"],
+        ] {
+            assert!(
+                parse_command(&build_request("GETEX", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_getex_should_update_expire_time() {
+        let (server, mut conn) = build_server_connection().await;
+        for key in ["k", "gone"] {
+            server.lock().await.db.insert(
+                Bytes::from_owner(key),
+                (
+                    Value::String(Bytes::from_owner("v")),
+                    Some(unix_time_ms() + 60_000),
+                ),
+            );
+        }
+        let past = (unix_time_ms() - 1000).to_string();
+
+        // The expire time moves past the initial minute, is kept, then removed
+        for (args, volatile) in [
+            (&["k", "PX", "500000"][..], true),
+            (&["k"], true),
+            (&["k", "PERSIST"], false),
+        ] {
+            let cmd = parse_command(&build_request("GETEX", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute getex");
+            assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("v"))));
+            let expire_time = server.lock().await.db.get(b"k".as_ref()).unwrap().1;
+            assert_eq!(
+                expire_time.is_some_and(|t| t > unix_time_ms() + 100_000),
+                volatile,
+                "{args:?}"
+            );
+        }
+
+        let cmd = parse_command(&build_request("GETEX", &["gone", "PXAT", &past])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute getex");
+        assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("v"))));
+        assert!(!server.lock().await.db.contains_key(b"gone".as_ref()));
+
+        let cmd = parse_command(&build_request("GETEX", &["missing", "EX", "10"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute getex");
+        assert_eq!(resp, RespData::BulkString(None));
+    }
+}
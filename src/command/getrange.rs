@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_string},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::normalize_range,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GetRange {
+    key: Bytes,
+    start: i64,
+    end: i64,
+}
+
+impl Parse for GetRange {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(GetRange {
+            key: args[0].clone(),
+            start: lexical_core::parse(&args[1])?,
+            end: lexical_core::parse(&args[2])?,
+        })
+    }
+}
+
+impl ExecuteCommand for GetRange {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let range = get_string(db, stats, &self.key)?.and_then(|string| {
+            normalize_range(self.start, self.end, string.len())
+                .map(|(start, end)| string.slice(start..=end))
+        });
+        Ok(RespData::BulkString(Some(range.unwrap_or_default())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::GetRange;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_getrange_should_read_offsets() {
+        let cmd =
+            parse_command(&build_request("GETRANGE", &["k", "-3", "10"])).expect("parse getrange");
+        assert_eq!(
+            cmd,
+            Command::GetRange(GetRange {
+                key: Bytes::from_owner("k"),
+                start: -3,
+                end: 10,
+            })
+        );
+        assert!(parse_command(&build_request("GETRANGE", &["k", "0"])).is_err());
+        assert!(parse_command(&build_request("GETRANGE", &["k", "a", "1"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_getrange_should_clamp_offsets() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("This is a string")), None),
+        );
+
+        for (key, start, end, expected) in [
+            ("k", "0", "3", "This"),
+            ("k", "-3", "-1", "ing"),
+            ("k", "0", "-1", "This is a string"),
+            ("k", "10", "100", "string"),
+            ("k", "5", "2", ""),
+            ("k", "-1", "-5", ""),
+            ("missing", "0", "-1", ""),
+        ] {
+            let cmd = parse_command(&build_request("GETRANGE", &[key, start, end])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute getrange");
+            assert_eq!(
+                resp,
+                RespData::BulkString(Some(Bytes::from_owner(expected))),
+                "{start} {end}"
+            );
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_string},
+    db::Value,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct GetSet {
+    key: Bytes,
+    value: Bytes,
+}
+
+impl Parse for GetSet {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(GetSet {
+            key: args[0].clone(),
+            value: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for GetSet {
+    /// Set the value like `SET` does, discarding the expire time, and reply the old value.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let old = get_string(db, stats, &self.key)?.cloned();
+        db.insert(self.key.clone(), (Value::String(self.value.clone()), None));
+        Ok(RespData::BulkString(old))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::GetSet;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_getset_should_read_value() {
+        let cmd = parse_command(&build_request("GETSET", &["k", "v"])).expect("parse getset");
+        assert_eq!(
+            cmd,
+            Command::GetSet(GetSet {
+                key: Bytes::from_owner("k"),
+                value: Bytes::from_owner("v"),
+            })
+        );
+        assert!(parse_command(&build_request("GETSET", &["k"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_getset_should_reply_old_value_and_clear_ttl() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("k"),
+                (
+                    Value::String(Bytes::from_owner("old")),
+                    Some(unix_time_ms() + 60_000),
+                ),
+            );
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+            );
+        }
+
+        for (key, expected) in [("k", Some("old")), ("missing", None)] {
+            let cmd = parse_command(&build_request("GETSET", &[key, "new"])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute getset");
+            assert_eq!(resp, RespData::BulkString(expected.map(Bytes::from_owner)));
+        }
+        let cmd = parse_command(&build_request("GETSET", &["list", "new"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+
+        let db = &server.lock().await.db;
+        for key in ["k", "missing"] {
+            assert_eq!(
+                db.get(key.as_bytes()),
+                Some(&(Value::String(Bytes::from_owner("new")), None))
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_hash},
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HDel {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+
+impl Parse for HDel {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        Ok(HDel {
+            key: args[0].clone(),
+            fields: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for HDel {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(hash) = get_hash(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(0));
+        };
+        let removed = self
+            .fields
+            .iter()
+            .filter(|field| hash.remove(field).is_some())
+            .count();
+        remove_if_empty(db, &self.key);
+
+        Ok(RespData::Integer(removed as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HDel;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hdel_should_read_fields() {
+        let cmd = parse_command(&build_request("HDEL", &["h", "a", "b"])).expect("parse hdel");
+        assert_eq!(
+            cmd,
+            Command::HDel(HDel {
+                key: Bytes::from_owner("h"),
+                fields: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hdel_should_count_removed_fields_and_drop_empty_hash() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HDEL", &["h", "a", "x", "a"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hdel");
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = parse_command(&build_request("HDEL", &["h", "b"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hdel");
+        assert_eq!(resp, RespData::Integer(1));
+        assert!(server.lock().await.db.is_empty());
+    }
+}
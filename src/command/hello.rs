@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult,
+        error::{ExecError, ExecResult, ParseError},
+    },
+    resp::{RespData, RespProtocol},
+    server::{Connection, REDIS_VERSION, Server},
+};
+
+const DEFAULT_USER: &str = "default";
+
+#[derive(Debug, PartialEq)]
+pub struct Hello {
+    protover: Option<i64>,
+    auth: Option<(String, String)>,
+    name: Option<String>,
+}
+
+impl Parse for Hello {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        let mut hello = Hello {
+            protover: None,
+            auth: None,
+            name: None,
+        };
+        let Some(protover) = args.first() else {
+            return Ok(hello);
+        };
+        hello.protover =
+            Some(lexical_core::parse(protover).map_err(|_| ExecError::ProtocolVersionNotInteger)?);
+
+        let mut i = 1;
+        while i < args.len() {
+            let argument = str::from_utf8(&args[i])?.to_string();
+            match argument.to_uppercase().as_str() {
+                "AUTH" if i + 2 < args.len() => {
+                    let username = str::from_utf8(&args[i + 1])?.to_string();
+                    let password = str::from_utf8(&args[i + 2])?.to_string();
+                    hello.auth = Some((username, password));
+                    i += 3;
+                }
+                "SETNAME" if i + 1 < args.len() => {
+                    hello.name = Some(str::from_utf8(&args[i + 1])?.to_string());
+                    i += 2;
+                }
+                _ => return Err(ParseError::InvalidArgument(argument)),
+            }
+        }
+
+        Ok(hello)
+    }
+}
+
+impl ExecuteCommand for Hello {
+    async fn execute(
+        &self,
+        _server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let protocol = match self.protover {
+            None => conn.protocol,
+            Some(2) => RespProtocol::Resp2,
+            Some(3) => RespProtocol::Resp3,
+            Some(_) => return Err(ExecError::NoProto),
+        };
+
+        // There is no ACL support, every connection is the passwordless default user.
+        if let Some((username, _)) = &self.auth
+            && username != DEFAULT_USER
+        {
+            return Err(ExecError::WrongPass);
+        }
+
+        if let Some(name) = &self.name {
+            conn.name = name.to_string();
+        }
+        conn.protocol = protocol;
+
+        let bulk = |s: &str| RespData::BulkString(Some(Bytes::copy_from_slice(s.as_bytes())));
+        Ok(RespData::Map(vec![
+            (Bytes::from_static(b"server"), bulk("redis")),
+            (Bytes::from_static(b"version"), bulk(REDIS_VERSION)),
+            (
+                Bytes::from_static(b"proto"),
+                RespData::Integer(protocol.version()),
+            ),
+            (Bytes::from_static(b"id"), RespData::Integer(conn.id as i64)),
+            (Bytes::from_static(b"mode"), bulk("standalone")),
+            (Bytes::from_static(b"role"), bulk("master")),
+            (Bytes::from_static(b"modules"), RespData::Array(vec![])),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::Hello;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        resp::{RespData, RespProtocol},
+    };
+
+    #[test]
+    fn parse_hello_should_accept_no_arguments() {
+        let cmd = parse_command(&build_request("HELLO", &[])).expect("parse hello");
+        assert_eq!(
+            cmd,
+            Command::Hello(Hello {
+                protover: None,
+                auth: None,
+                name: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_hello_should_parse_auth_and_setname() {
+        let cmd = parse_command(&build_request(
+            "HELLO",
+            &["3", "auth", "default", "secret", "SETNAME", "alice"],
+        ))
+        .expect("parse hello");
+        assert_eq!(
+            cmd,
+            Command::Hello(Hello {
+                protover: Some(3),
+                auth: Some(("default".to_string(), "secret".to_string())),
+                name: Some("alice".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_hello_should_reject_incomplete_auth() {
+        let err = parse_command(&build_request("HELLO", &["3", "AUTH", "default"]))
+            .expect_err("auth needs username and password");
+        assert_eq!(err, ParseError::InvalidArgument("AUTH".to_string()));
+    }
+
+    #[test]
+    fn parse_hello_should_reject_non_integer_protover() {
+        let err = parse_command(&build_request("HELLO", &["abc"]))
+            .expect_err("protover must be an integer");
+        assert_eq!(
+            err,
+            ParseError::Rejected(ExecError::ProtocolVersionNotInteger)
+        );
+        assert_eq!(
+            err.to_string(),
+            "ERR Protocol version is not an integer or out of range"
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hello_3_should_switch_protocol_and_return_server_info() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = Hello {
+            protover: Some(3),
+            auth: None,
+            name: Some("alice".to_string()),
+        };
+        let resp = cmd.execute(server, &mut conn).await.expect("execute hello");
+
+        assert_eq!(conn.protocol, RespProtocol::Resp3);
+        assert_eq!(conn.name, "alice");
+        let RespData::Map(info) = resp else {
+            panic!("hello should reply with a map, got {resp:?}");
+        };
+        let fields: Vec<_> = info.iter().map(|(field, _)| field.as_ref()).collect();
+        assert_eq!(
+            fields,
+            [
+                b"server".as_ref(),
+                b"version",
+                b"proto",
+                b"id",
+                b"mode",
+                b"role",
+                b"modules"
+            ]
+        );
+        assert_eq!(info[2].1, RespData::Integer(3));
+        assert_eq!(info[3].1, RespData::Integer(1));
+        assert_eq!(
+            info[0].1,
+            RespData::BulkString(Some(Bytes::from_owner("redis")))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hello_without_protover_should_keep_protocol() {
+        let (server, mut conn) = build_server_connection().await;
+        conn.protocol = RespProtocol::Resp3;
+        let cmd = Hello {
+            protover: None,
+            auth: None,
+            name: None,
+        };
+        cmd.execute(server, &mut conn).await.expect("execute hello");
+        assert_eq!(conn.protocol, RespProtocol::Resp3);
+    }
+
+    #[tokio::test]
+    async fn execute_hello_should_reject_unsupported_protover() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = Hello {
+            protover: Some(4),
+            auth: None,
+            name: None,
+        };
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("hello should fail");
+        assert_eq!(err, ExecError::NoProto);
+        assert_eq!(conn.protocol, RespProtocol::Resp2);
+    }
+
+    #[tokio::test]
+    async fn execute_hello_should_reject_unknown_user() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = Hello {
+            protover: Some(3),
+            auth: Some(("bob".to_string(), "pass".to_string())),
+            name: None,
+        };
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("hello should fail");
+        assert_eq!(err, ExecError::WrongPass);
+        assert_eq!(conn.protocol, RespProtocol::Resp2);
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HExists {
+    key: Bytes,
+    field: Bytes,
+}
+
+impl Parse for HExists {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(HExists {
+            key: args[0].clone(),
+            field: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HExists {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let exists =
+            get_hash(db, stats, &self.key)?.is_some_and(|hash| hash.contains_key(&self.field));
+        Ok(RespData::Integer(exists as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HExists;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hexists_should_read_key_and_field() {
+        let cmd = parse_command(&build_request("HEXISTS", &["h", "f"])).expect("parse hexists");
+        assert_eq!(
+            cmd,
+            Command::HExists(HExists {
+                key: Bytes::from_owner("h"),
+                field: Bytes::from_owner("f"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hexists_should_check_field() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("v"),
+                )])),
+                None,
+            ),
+        );
+
+        for (key, field, expected) in [("h", "f", 1), ("h", "x", 0), ("x", "f", 0)] {
+            let cmd = parse_command(&build_request("HEXISTS", &[key, field])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute hexists");
+            assert_eq!(resp, RespData::Integer(expected));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecError, ExecResult, ParseError},
+        get_hash, parse_expire_condition, parse_fields,
+    },
+    db::{ExpireCondition, FIELD_EXPIRE_TIME_MAX, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+/// `HEXPIRE`, `HPEXPIRE`, `HEXPIREAT` and `HPEXPIREAT`.
+#[derive(Debug, PartialEq)]
+pub struct HExpire {
+    key: Bytes,
+    /// A time to live, or a Unix time when `absolute`, in seconds unless `milliseconds`.
+    time: u64,
+    milliseconds: bool,
+    absolute: bool,
+    condition: Option<ExpireCondition>,
+    fields: Vec<Bytes>,
+}
+
+impl HExpire {
+    pub fn parse(args: &[Bytes], milliseconds: bool, absolute: bool) -> ParseResult<Self> {
+        check_length_ge(args, 5)?;
+
+        let time: i64 = lexical_core::parse(&args[1])?;
+        if time < 0 {
+            return Err(ParseError::InvalidArgument(time.to_string()));
+        }
+        let condition = parse_expire_condition(str::from_utf8(&args[2])?);
+        let fields_start = if condition.is_some() { 3 } else { 2 };
+
+        Ok(HExpire {
+            key: args[0].clone(),
+            time: time as u64,
+            milliseconds,
+            absolute,
+            condition,
+            fields: parse_fields(&args[fields_start..], 1)?.to_vec(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        match (self.milliseconds, self.absolute) {
+            (false, false) => "hexpire",
+            (true, false) => "hpexpire",
+            (false, true) => "hexpireat",
+            (true, true) => "hpexpireat",
+        }
+    }
+}
+
+impl ExecuteCommand for HExpire {
+    /// Replies for every field -2 if it does not exist, 0 if the condition is not met, 1 if the
+    /// expire time was set, and 2 if the field was deleted as the time is already past.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let now = unix_time_ms();
+        let ms = if self.milliseconds {
+            Some(self.time)
+        } else {
+            self.time.checked_mul(1000)
+        };
+        let when = if self.absolute {
+            ms
+        } else {
+            ms.and_then(|ms| now.checked_add(ms))
+        }
+        .filter(|when| *when <= FIELD_EXPIRE_TIME_MAX)
+        .ok_or(ExecError::InvalidExpireTime(self.name()))?;
+
+        let mut server = server.lock().await;
+        let server = &mut *server;
+        let Some(hash) = get_hash(&mut server.db, &mut server.stats, &self.key)? else {
+            return Ok(RespData::Array(
+                self.fields.iter().map(|_| RespData::Integer(-2)).collect(),
+            ));
+        };
+
+        let mut replies = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            let reply = if !hash.contains_key(field) {
+                -2
+            } else if self
+                .condition
+                .is_some_and(|condition| !condition.allows(hash.expire_time(field), when))
+            {
+                0
+            } else if when <= now {
+                hash.remove(field);
+                2
+            } else {
+                hash.set_expire_time(field, when);
+                1
+            };
+            replies.push(RespData::Integer(reply));
+        }
+
+        if hash.has_volatile_fields() {
+            server.volatile_hashes.insert(self.key.clone());
+        }
+        remove_if_empty(&mut server.db, &self.key);
+
+        Ok(RespData::Array(replies))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HExpire;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{ExpireCondition, Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    fn integers(replies: &[i64]) -> RespData {
+        RespData::Array(replies.iter().map(|i| RespData::Integer(*i)).collect())
+    }
+
+    #[test]
+    fn parse_hexpire_should_read_condition_and_fields() {
+        let cmd = parse_command(&build_request(
+            "HPEXPIREAT",
+            &["h", "1000", "gt", "FIELDS", "2", "a", "b"],
+        ))
+        .expect("parse hpexpireat");
+        assert_eq!(
+            cmd,
+            Command::HExpire(HExpire {
+                key: Bytes::from_owner("h"),
+                time: 1000,
+                milliseconds: true,
+                absolute: true,
+                condition: Some(ExpireCondition::Gt),
+                fields: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+
+        for args in [
+            ["h", "10", "FIELDS", "2", "a"].as_slice(),
+            &["h", "10", "FIELDS", "0"],
+            &["h", "-1", "FIELDS", "1", "a"],
+            &["h", "10", "NX", "XX", "FIELDS", "1", "a"],
+        ] {
+            assert!(parse_command(&build_request("HEXPIRE", args)).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_hexpire_should_reply_per_field() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request(
+            "HEXPIRE",
+            &["h", "100", "FIELDS", "2", "a", "x"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, integers(&[1, -2]));
+        assert!(server.lock().await.volatile_hashes.contains(b"h".as_ref()));
+
+        // NX only applies to fields without an expire time
+        let cmd = parse_command(&build_request(
+            "HEXPIRE",
+            &["h", "200", "NX", "FIELDS", "2", "a", "b"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, integers(&[0, 1]));
+
+        // GT never applies to fields without an expire time, LT always does
+        let cmd = parse_command(&build_request(
+            "HEXPIRE",
+            &["h", "150", "GT", "FIELDS", "2", "a", "b"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, integers(&[1, 0]));
+
+        let guard = server.lock().await;
+        let Some((Value::Hash(hash), _)) = guard.db.get(b"h".as_ref()) else {
+            panic!("expected a hash");
+        };
+        let now = unix_time_ms();
+        let ttl_a = hash.expire_time(b"a").unwrap() - now;
+        let ttl_b = hash.expire_time(b"b").unwrap() - now;
+        assert!((149_000..=150_000).contains(&ttl_a), "ttl {ttl_a}");
+        assert!((199_000..=200_000).contains(&ttl_b), "ttl {ttl_b}");
+    }
+
+    #[tokio::test]
+    async fn execute_hexpire_should_delete_fields_in_the_past() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("1"),
+                )])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request(
+            "HEXPIRE",
+            &["missing", "1", "FIELDS", "1", "a"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, integers(&[-2]));
+
+        let cmd = parse_command(&build_request(
+            "HPEXPIREAT",
+            &["h", "1", "FIELDS", "1", "a"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, integers(&[2]));
+        assert!(server.lock().await.db.is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_hexpire_should_reject_time_overflow() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request(
+            "HEXPIRE",
+            &["h", "9223372036854775807", "FIELDS", "1", "a"],
+        ))
+        .unwrap();
+        let err = cmd.execute(server, &mut conn).await.expect_err("overflow");
+        assert_eq!(err, ExecError::InvalidExpireTime("hexpire"));
+        assert_eq!(
+            err.to_string(),
+            "ERR invalid expire time in 'hexpire' command"
+        );
+    }
+}
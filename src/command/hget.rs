@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HGet {
+    key: Bytes,
+    field: Bytes,
+}
+
+impl Parse for HGet {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(HGet {
+            key: args[0].clone(),
+            field: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HGet {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let value = get_hash(db, stats, &self.key)?.and_then(|hash| hash.get(&self.field).cloned());
+        Ok(RespData::BulkString(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HGet;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hget_should_read_key_and_field() {
+        let cmd = parse_command(&build_request("HGET", &["h", "f"])).expect("parse hget");
+        assert_eq!(
+            cmd,
+            Command::HGet(HGet {
+                key: Bytes::from_owner("h"),
+                field: Bytes::from_owner("f"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hget_should_return_value_or_null() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("v"),
+                )])),
+                None,
+            ),
+        );
+
+        for (key, field, expected) in [("h", "f", Some("v")), ("h", "x", None), ("x", "f", None)] {
+            let cmd = HGet {
+                key: Bytes::from_owner(key),
+                field: Bytes::from_owner(field),
+            };
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute hget");
+            assert_eq!(resp, RespData::BulkString(expected.map(Bytes::from_owner)));
+        }
+    }
+}
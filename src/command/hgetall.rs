@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HGetAll {
+    key: Bytes,
+}
+
+impl Parse for HGetAll {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(HGetAll {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HGetAll {
+    /// Replies a map, which RESP2 connections receive flattened into `field value` pairs.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let map = get_hash(db, stats, &self.key)?
+            .map(|hash| {
+                hash.iter()
+                    .map(|(field, value)| {
+                        (field.clone(), RespData::BulkString(Some(value.clone())))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(RespData::Map(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+
+    use super::HGetAll;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hgetall_should_read_key() {
+        let cmd = parse_command(&build_request("HGETALL", &["h"])).expect("parse hgetall");
+        assert_eq!(
+            cmd,
+            Command::HGetAll(HGetAll {
+                key: Bytes::from_owner("h"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hgetall_should_reply_map() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HGETALL", &["h"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hgetall");
+        let RespData::Map(map) = resp else {
+            panic!("hgetall should reply with a map, got {resp:?}");
+        };
+        assert_eq!(
+            map.into_iter().collect::<HashMap<_, _>>(),
+            HashMap::from([
+                (
+                    Bytes::from_owner("a"),
+                    RespData::BulkString(Some(Bytes::from_owner("1")))
+                ),
+                (
+                    Bytes::from_owner("b"),
+                    RespData::BulkString(Some(Bytes::from_owner("2")))
+                ),
+            ])
+        );
+
+        let cmd = parse_command(&build_request("HGETALL", &["missing"])).unwrap();
+        let resp = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect("execute hgetall");
+        assert_eq!(resp, RespData::Map(Vec::new()));
+    }
+}
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecError, ExecResult, ParseError},
+        get_hash, parse_expiration, parse_fields,
+    },
+    db::{Expiration, FIELD_EXPIRE_TIME_MAX, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HGetEx {
+    key: Bytes,
+    expiration: Option<Expiration>,
+    /// Remove the expire time of the fields, for the `PERSIST` option.
+    persist: bool,
+    fields: Vec<Bytes>,
+}
+
+impl Parse for HGetEx {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 4)?;
+
+        let mut hgetex = HGetEx {
+            key: args[0].clone(),
+            expiration: None,
+            persist: false,
+            fields: vec![],
+        };
+
+        let argument = str::from_utf8(&args[1])?.to_uppercase();
+        let fields_start = match argument.as_str() {
+            "EX" | "PX" | "EXAT" | "PXAT" => {
+                hgetex.expiration = Some(parse_expiration(&argument, &args[2])?);
+                3
+            }
+            "PERSIST" => {
+                hgetex.persist = true;
+                2
+            }
+            "FIELDS" => 1,
+            _ => return Err(ParseError::InvalidArgument(argument)),
+        };
+        hgetex.fields = parse_fields(&args[fields_start..], 1)?.to_vec();
+
+        Ok(hgetex)
+    }
+}
+
+impl ExecuteCommand for HGetEx {
+    /// Replies the values of the fields before updating their expire time. Fields given an
+    /// expire time already past are deleted.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let now = unix_time_ms();
+        let when = self
+            .expiration
+            .map(|expiration| {
+                expiration
+                    .unix_time_ms(now)
+                    .filter(|when| *when <= FIELD_EXPIRE_TIME_MAX)
+                    .ok_or(ExecError::InvalidExpireTime("hgetex"))
+            })
+            .transpose()?;
+
+        let mut server = server.lock().await;
+        let server = &mut *server;
+        let Some(hash) = get_hash(&mut server.db, &mut server.stats, &self.key)? else {
+            return Ok(RespData::Array(
+                self.fields
+                    .iter()
+                    .map(|_| RespData::BulkString(None))
+                    .collect(),
+            ));
+        };
+
+        let mut values = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            let value = hash.get(field).cloned();
+            if value.is_some() {
+                match when {
+                    Some(when) if when <= now => {
+                        hash.remove(field);
+                    }
+                    Some(when) => hash.set_expire_time(field, when),
+                    None if self.persist => {
+                        hash.persist(field);
+                    }
+                    None => {}
+                }
+            }
+            values.push(RespData::BulkString(value));
+        }
+
+        if hash.has_volatile_fields() {
+            server.volatile_hashes.insert(self.key.clone());
+        }
+        remove_if_empty(&mut server.db, &self.key);
+
+        Ok(RespData::Array(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HGetEx;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Expiration, Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_hgetex_should_read_expiration() {
+        let cmd = parse_command(&build_request(
+            "HGETEX",
+            &["h", "EX", "10", "FIELDS", "1", "a"],
+        ))
+        .expect("parse hgetex");
+        assert_eq!(
+            cmd,
+            Command::HGetEx(HGetEx {
+                key: Bytes::from_owner("h"),
+                expiration: Some(Expiration::In(10_000)),
+                persist: false,
+                fields: vec![Bytes::from_owner("a")],
+            })
+        );
+
+        for args in [
+            ["h", "EX", "0", "FIELDS", "1", "a"].as_slice(),
+            &["h", "EX", "10", "PERSIST", "FIELDS", "1", "a"],
+            &["h", "KEEPTTL", "FIELDS", "1", "a"],
+        ] {
+            assert!(parse_command(&build_request("HGETEX", args)).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_hgetex_should_return_values_and_update_expire_times() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request(
+            "HGETEX",
+            &["h", "PX", "5000", "FIELDS", "2", "a", "x"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+                RespData::BulkString(None),
+            ])
+        );
+        {
+            let guard = server.lock().await;
+            let Some((Value::Hash(hash), _)) = guard.db.get(b"h".as_ref()) else {
+                panic!("expected a hash");
+            };
+            let ttl = hash.expire_time(b"a").unwrap() - unix_time_ms();
+            assert!((4_000..=5_000).contains(&ttl), "ttl {ttl}");
+            assert!(guard.volatile_hashes.contains(b"h".as_ref()));
+        }
+
+        let cmd = parse_command(&build_request(
+            "HGETEX",
+            &["h", "PERSIST", "FIELDS", "1", "a"],
+        ))
+        .unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+        {
+            let guard = server.lock().await;
+            let Some((Value::Hash(hash), _)) = guard.db.get(b"h".as_ref()) else {
+                panic!("expected a hash");
+            };
+            assert_eq!(hash.expire_time(b"a"), None);
+        }
+
+        // A past Unix time deletes the fields after replying their values
+        let cmd = parse_command(&build_request(
+            "HGETEX",
+            &["h", "PXAT", "1", "FIELDS", "2", "a", "b"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+                RespData::BulkString(Some(Bytes::from_owner("2"))),
+            ])
+        );
+        assert!(server.lock().await.db.is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult,
+        get_hash, get_or_insert_hash,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HIncrBy {
+    key: Bytes,
+    field: Bytes,
+    increment: i64,
+}
+
+impl Parse for HIncrBy {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(HIncrBy {
+            key: args[0].clone(),
+            field: args[1].clone(),
+            increment: lexical_core::parse(&args[2])?,
+        })
+    }
+}
+
+impl ExecuteCommand for HIncrBy {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let current = match get_hash(db, stats, &self.key)?.and_then(|hash| hash.get(&self.field)) {
+            None => 0,
+            Some(value) => {
+                lexical_core::parse::<i64>(value).map_err(|_| ExecError::HashValueNotInteger)?
+            }
+        };
+        let value = current
+            .checked_add(self.increment)
+            .ok_or(ExecError::Overflow)?;
+        get_or_insert_hash(db, stats, &self.key)?
+            .insert_keep_ttl(self.field.clone(), Bytes::from(value.to_string()));
+
+        Ok(RespData::Integer(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HIncrBy;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hincrby_should_read_increment() {
+        let cmd =
+            parse_command(&build_request("HINCRBY", &["h", "f", "-5"])).expect("parse hincrby");
+        assert_eq!(
+            cmd,
+            Command::HIncrBy(HIncrBy {
+                key: Bytes::from_owner("h"),
+                field: Bytes::from_owner("f"),
+                increment: -5,
+            })
+        );
+        assert!(parse_command(&build_request("HINCRBY", &["h", "f", "1.5"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_hincrby_should_create_and_increment_field() {
+        let (server, mut conn) = build_server_connection().await;
+
+        let cmd = parse_command(&build_request("HINCRBY", &["h", "f", "5"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hincrby");
+        assert_eq!(resp, RespData::Integer(5));
+
+        let cmd = parse_command(&build_request("HINCRBY", &["h", "f", "-7"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hincrby");
+        assert_eq!(resp, RespData::Integer(-2));
+        assert_eq!(
+            server.lock().await.db.get(b"h".as_ref()),
+            Some(&(
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("-2")
+                )])),
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hincrby_should_reject_non_integer_and_overflow() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("text"), Bytes::from_owner("abc")),
+                    (Bytes::from_owner("max"), Bytes::from(i64::MAX.to_string())),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HINCRBY", &["h", "text", "1"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("not an integer");
+        assert_eq!(err, ExecError::HashValueNotInteger);
+
+        let cmd = parse_command(&build_request("HINCRBY", &["h", "max", "1"])).unwrap();
+        let err = cmd.execute(server, &mut conn).await.expect_err("overflow");
+        assert_eq!(err, ExecError::Overflow);
+    }
+}
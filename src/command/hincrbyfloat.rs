@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult,
+        get_hash, get_or_insert_hash,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    utils::format_float,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HIncrByFloat {
+    key: Bytes,
+    field: Bytes,
+    increment: f64,
+}
+
+impl Parse for HIncrByFloat {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(HIncrByFloat {
+            key: args[0].clone(),
+            field: args[1].clone(),
+            increment: lexical_core::parse(&args[2])?,
+        })
+    }
+}
+
+impl ExecuteCommand for HIncrByFloat {
+    /// Replies the new value as a bulk string, formatted the same way it is stored.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let current = match get_hash(db, stats, &self.key)?.and_then(|hash| hash.get(&self.field)) {
+            None => 0.0,
+            Some(value) => lexical_core::parse::<f64>(value)
+                .ok()
+                .filter(|value| !value.is_nan())
+                .ok_or(ExecError::HashValueNotFloat)?,
+        };
+        let value = current + self.increment;
+        if !value.is_finite() {
+            return Err(ExecError::NanOrInfinity);
+        }
+        let value = Bytes::from(format_float(value));
+        get_or_insert_hash(db, stats, &self.key)?
+            .insert_keep_ttl(self.field.clone(), value.clone());
+
+        Ok(RespData::BulkString(Some(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HIncrByFloat;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hincrbyfloat_should_read_increment() {
+        let cmd = parse_command(&build_request("HINCRBYFLOAT", &["h", "f", "0.5"]))
+            .expect("parse hincrbyfloat");
+        assert_eq!(
+            cmd,
+            Command::HIncrByFloat(HIncrByFloat {
+                key: Bytes::from_owner("h"),
+                field: Bytes::from_owner("f"),
+                increment: 0.5,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hincrbyfloat_should_format_result() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("10.5"),
+                )])),
+                None,
+            ),
+        );
+
+        for (increment, expected) in [("0.1", "10.6"), ("-5.6", "5"), ("2.0e2", "205")] {
+            let cmd =
+                parse_command(&build_request("HINCRBYFLOAT", &["h", "f", increment])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute hincrbyfloat");
+            assert_eq!(
+                resp,
+                RespData::BulkString(Some(Bytes::from_owner(expected)))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_hincrbyfloat_should_reject_non_float_and_infinity() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("abc"),
+                )])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HINCRBYFLOAT", &["h", "f", "1"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("not a float");
+        assert_eq!(err, ExecError::HashValueNotFloat);
+
+        let cmd = parse_command(&build_request("HINCRBYFLOAT", &["missing", "f", "inf"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("infinity");
+        assert_eq!(err, ExecError::NanOrInfinity);
+        assert!(!server.lock().await.db.contains_key(b"missing".as_ref()));
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HKeys {
+    key: Bytes,
+}
+
+impl Parse for HKeys {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(HKeys {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HKeys {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let fields = get_hash(db, stats, &self.key)?
+            .map(|hash| {
+                hash.keys()
+                    .map(|field| RespData::BulkString(Some(field.clone())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(RespData::Array(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HKeys;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hkeys_should_read_key() {
+        let cmd = parse_command(&build_request("HKEYS", &["h"])).expect("parse hkeys");
+        assert_eq!(
+            cmd,
+            Command::HKeys(HKeys {
+                key: Bytes::from_owner("h"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hkeys_should_return_every_field() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HKEYS", &["h"])).unwrap();
+        let RespData::Array(mut fields) = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hkeys")
+        else {
+            panic!("expected an array");
+        };
+        fields.sort_by_key(|field| format!("{:?}", field));
+        assert_eq!(
+            fields,
+            vec![
+                RespData::BulkString(Some(Bytes::from_owner("a"))),
+                RespData::BulkString(Some(Bytes::from_owner("b"))),
+            ]
+        );
+
+        let cmd = parse_command(&build_request("HKEYS", &["missing"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute hkeys");
+        assert_eq!(resp, RespData::Array(vec![]));
+    }
+}
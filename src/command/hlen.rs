@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HLen {
+    key: Bytes,
+}
+
+impl Parse for HLen {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(HLen {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HLen {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let len = get_hash(db, stats, &self.key)?.map_or(0, |hash| hash.len());
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HLen;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hlen_should_read_key() {
+        let cmd = parse_command(&build_request("HLEN", &["h"])).expect("parse hlen");
+        assert_eq!(
+            cmd,
+            Command::HLen(HLen {
+                key: Bytes::from_owner("h"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hlen_should_count_fields() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        let cmd = parse_command(&build_request("HLEN", &["h"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hlen");
+        assert_eq!(resp, RespData::Integer(2));
+
+        let cmd = parse_command(&build_request("HLEN", &["missing"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hlen");
+        assert_eq!(resp, RespData::Integer(0));
+
+        let cmd = parse_command(&build_request("HLEN", &["s"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HMGet {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+
+impl Parse for HMGet {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        Ok(HMGet {
+            key: args[0].clone(),
+            fields: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for HMGet {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let hash = get_hash(db, stats, &self.key)?;
+        Ok(RespData::Array(
+            self.fields
+                .iter()
+                .map(|field| {
+                    RespData::BulkString(hash.as_ref().and_then(|hash| hash.get(field).cloned()))
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HMGet;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hmget_should_read_fields() {
+        let cmd = parse_command(&build_request("HMGET", &["h", "a", "b"])).expect("parse hmget");
+        assert_eq!(
+            cmd,
+            Command::HMGet(HMGet {
+                key: Bytes::from_owner("h"),
+                fields: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hmget_should_return_null_for_missing_fields() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("1"),
+                )])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HMGET", &["h", "a", "b"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hmget");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+                RespData::BulkString(None),
+            ])
+        );
+
+        let cmd = parse_command(&build_request("HMGET", &["missing", "a"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute hmget");
+        assert_eq!(resp, RespData::Array(vec![RespData::BulkString(None)]));
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_hash,
+        parse_fields,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HPersist {
+    key: Bytes,
+    fields: Vec<Bytes>,
+}
+
+impl Parse for HPersist {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 4)?;
+        Ok(HPersist {
+            key: args[0].clone(),
+            fields: parse_fields(&args[1..], 1)?.to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for HPersist {
+    /// Replies for every field -2 if it does not exist, -1 if it has no expire time, and 1 once
+    /// its expire time is removed.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let mut hash = get_hash(db, stats, &self.key)?;
+
+        Ok(RespData::Array(
+            self.fields
+                .iter()
+                .map(|field| {
+                    let reply = match hash.as_mut() {
+                        Some(hash) if hash.contains_key(field) => {
+                            if hash.persist(field) {
+                                1
+                            } else {
+                                -1
+                            }
+                        }
+                        _ => -2,
+                    };
+                    RespData::Integer(reply)
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HPersist;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_hpersist_should_read_fields() {
+        let cmd = parse_command(&build_request("HPERSIST", &["h", "FIELDS", "2", "a", "b"]))
+            .expect("parse hpersist");
+        assert_eq!(
+            cmd,
+            Command::HPersist(HPersist {
+                key: Bytes::from_owner("h"),
+                fields: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hpersist_should_remove_expire_times() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut hash = Hash::from([
+            (Bytes::from_owner("a"), Bytes::from_owner("1")),
+            (Bytes::from_owner("b"), Bytes::from_owner("2")),
+        ]);
+        hash.set_expire_time(&Bytes::from_owner("a"), unix_time_ms() + 10_000);
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+
+        let cmd = parse_command(&build_request(
+            "HPERSIST",
+            &["h", "FIELDS", "3", "a", "b", "x"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::Integer(1),
+                RespData::Integer(-1),
+                RespData::Integer(-2),
+            ])
+        );
+
+        let guard = server.lock().await;
+        let Some((Value::Hash(hash), _)) = guard.db.get(b"h".as_ref()) else {
+            panic!("expected a hash");
+        };
+        assert!(!hash.has_volatile_fields());
+    }
+}
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rand::seq::{IndexedRandom, IteratorRandom};
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_hash, parse_random_count,
+    },
+    resp::{RespData, RespProtocol},
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HRandField {
+    key: Bytes,
+    /// Reply with an array of up to `count` distinct fields, or exactly `-count` fields which may
+    /// repeat when negative.
+    count: Option<i64>,
+    with_values: bool,
+}
+
+impl Parse for HRandField {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        if args.len() > 3 {
+            return Err(ParseError::ExpectLengthEq(3, args.len(), args.to_vec()));
+        }
+
+        let with_values = match args.get(2) {
+            None => false,
+            Some(arg) => {
+                let argument = str::from_utf8(arg)?;
+                if !argument.eq_ignore_ascii_case("WITHVALUES") {
+                    return Err(ParseError::InvalidArgument(argument.to_string()));
+                }
+                true
+            }
+        };
+
+        Ok(HRandField {
+            key: args[0].clone(),
+            count: args.get(1).map(parse_random_count).transpose()?,
+            with_values,
+        })
+    }
+}
+
+impl ExecuteCommand for HRandField {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let hash = get_hash(db, stats, &self.key)?;
+        let mut rng = rand::rng();
+
+        let Some(count) = self.count else {
+            let field = hash.and_then(|hash| hash.keys().choose(&mut rng).cloned());
+            return Ok(RespData::BulkString(field));
+        };
+        let Some(hash) = hash else {
+            return Ok(RespData::Array(vec![]));
+        };
+
+        let pairs: Vec<(&Bytes, &Bytes)> = if count >= 0 {
+            hash.iter().choose_multiple(&mut rng, count as usize)
+        } else {
+            let entries: Vec<_> = hash.iter().collect();
+            (0..count.unsigned_abs())
+                .filter_map(|_| entries.choose(&mut rng).copied())
+                .collect()
+        };
+
+        let reply = if !self.with_values {
+            pairs
+                .into_iter()
+                .map(|(field, _)| RespData::BulkString(Some(field.clone())))
+                .collect()
+        } else if conn.protocol == RespProtocol::Resp3 {
+            pairs
+                .into_iter()
+                .map(|(field, value)| {
+                    RespData::Array(vec![
+                        RespData::BulkString(Some(field.clone())),
+                        RespData::BulkString(Some(value.clone())),
+                    ])
+                })
+                .collect()
+        } else {
+            pairs
+                .into_iter()
+                .flat_map(|(field, value)| {
+                    [
+                        RespData::BulkString(Some(field.clone())),
+                        RespData::BulkString(Some(value.clone())),
+                    ]
+                })
+                .collect()
+        };
+        Ok(RespData::Array(reply))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HRandField;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::{RespData, RespProtocol},
+    };
+
+    fn field_or_value(resp: &RespData) -> &str {
+        match resp {
+            RespData::BulkString(Some(bytes)) => str::from_utf8(bytes).unwrap(),
+            other => panic!("expected a bulk string, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_hrandfield_should_read_count_and_withvalues() {
+        let cmd = parse_command(&build_request("HRANDFIELD", &["h", "-3", "withvalues"]))
+            .expect("parse hrandfield");
+        assert_eq!(
+            cmd,
+            Command::HRandField(HRandField {
+                key: Bytes::from_owner("h"),
+                count: Some(-3),
+                with_values: true,
+            })
+        );
+
+        let err = parse_command(&build_request("HRANDFIELD", &["h", "1", "values"]))
+            .expect_err("unknown option");
+        assert_eq!(err, ParseError::InvalidArgument("values".to_string()));
+
+        for count in [
+            "-4611686018427387904",
+            "4611686018427387904",
+            "-9223372036854775808",
+        ] {
+            let err = parse_command(&build_request("HRANDFIELD", &["h", count]))
+                .expect_err("count out of range");
+            assert_eq!(
+                err,
+                ParseError::Rejected(ExecError::ValueOutOfRange),
+                "{count}"
+            );
+        }
+        assert!(
+            parse_command(&build_request("HRANDFIELD", &["h", "-4611686018427387903"])).is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hrandfield_should_sample_fields() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                    (Bytes::from_owner("c"), Bytes::from_owner("3")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HRANDFIELD", &["h"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hrandfield");
+        assert!(["a", "b", "c"].contains(&field_or_value(&resp)));
+
+        // A positive count never repeats fields and is capped by the hash size
+        let cmd = parse_command(&build_request("HRANDFIELD", &["h", "5"])).unwrap();
+        let RespData::Array(fields) = cmd.execute(server.clone(), &mut conn).await.unwrap() else {
+            panic!("expected an array");
+        };
+        let mut fields: Vec<_> = fields.iter().map(field_or_value).collect();
+        fields.sort();
+        assert_eq!(fields, vec!["a", "b", "c"]);
+
+        // A negative count replies exactly that many fields
+        let cmd = parse_command(&build_request("HRANDFIELD", &["h", "-10"])).unwrap();
+        let RespData::Array(fields) = cmd.execute(server.clone(), &mut conn).await.unwrap() else {
+            panic!("expected an array");
+        };
+        assert_eq!(fields.len(), 10);
+
+        let cmd = parse_command(&build_request("HRANDFIELD", &["missing"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::BulkString(None));
+
+        let cmd = parse_command(&build_request("HRANDFIELD", &["missing", "2"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Array(vec![]));
+    }
+
+    #[tokio::test]
+    async fn execute_hrandfield_withvalues_should_follow_protocol() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("1"),
+                )])),
+                None,
+            ),
+        );
+        let field = || RespData::BulkString(Some(Bytes::from_owner("a")));
+        let value = || RespData::BulkString(Some(Bytes::from_owner("1")));
+
+        let cmd = parse_command(&build_request("HRANDFIELD", &["h", "1", "WITHVALUES"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Array(vec![field(), value()]));
+
+        conn.protocol = RespProtocol::Resp3;
+        let resp = cmd.execute(server, &mut conn).await.unwrap();
+        assert_eq!(
+            resp,
+            RespData::Array(vec![RespData::Array(vec![field(), value()])])
+        );
+    }
+}
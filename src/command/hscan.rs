@@ -0,0 +1,250 @@
+use std::{
+    hash::{DefaultHasher, Hash as _, Hasher},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_hash,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    utils::glob_match,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HScan {
+    key: Bytes,
+    cursor: u64,
+    pattern: Option<Bytes>,
+    count: usize,
+    no_values: bool,
+}
+
+impl Parse for HScan {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+
+        let mut hscan = HScan {
+            key: args[0].clone(),
+            cursor: lexical_core::parse(&args[1])?,
+            pattern: None,
+            count: 10,
+            no_values: false,
+        };
+
+        let mut i = 2;
+        while i < args.len() {
+            let argument = str::from_utf8(&args[i])?.to_string();
+            match argument.to_uppercase().as_str() {
+                "NOVALUES" => {
+                    hscan.no_values = true;
+                    i += 1;
+                    continue;
+                }
+                "MATCH" | "COUNT" => {}
+                _ => return Err(ParseError::InvalidArgument(argument)),
+            }
+
+            let value = args.get(i + 1).ok_or(ParseError::ExpectLengthGe(
+                i + 2,
+                args.len(),
+                args.to_vec(),
+            ))?;
+            if argument.eq_ignore_ascii_case("MATCH") {
+                hscan.pattern = Some(value.clone());
+            } else {
+                hscan.count = lexical_core::parse(value)?;
+                if hscan.count == 0 {
+                    return Err(ParseError::InvalidArgument(format!("{argument} 0")));
+                }
+            }
+            i += 2;
+        }
+
+        Ok(hscan)
+    }
+}
+
+/// The position of a field in the scan order. Fields are visited by increasing hash, which only
+/// depends on the field itself, so fields present during the whole scan are returned at least
+/// once however the hash is modified between calls.
+fn scan_position(field: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    field.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl ExecuteCommand for HScan {
+    /// The cursor is the position to resume from, and zero once the scan is complete.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let mut candidates: Vec<(u64, &Bytes, &Bytes)> = get_hash(db, stats, &self.key)?
+            .map(|hash| {
+                hash.iter()
+                    .map(|(field, value)| (scan_position(field), field, value))
+                    .filter(|(position, _, _)| *position >= self.cursor)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut cursor = 0;
+        if candidates.len() > self.count {
+            candidates.select_nth_unstable_by_key(self.count, |(position, _, _)| *position);
+            // The smallest remaining position, as the selected ones sort before it
+            cursor = candidates[self.count].0;
+            candidates.truncate(self.count);
+        }
+
+        let mut elements = Vec::new();
+        for (_, field, value) in candidates {
+            if let Some(pattern) = &self.pattern
+                && !glob_match(pattern, field)
+            {
+                continue;
+            }
+            elements.push(RespData::BulkString(Some(field.clone())));
+            if !self.no_values {
+                elements.push(RespData::BulkString(Some(value.clone())));
+            }
+        }
+
+        Ok(RespData::Array(vec![
+            RespData::BulkString(Some(Bytes::from(cursor.to_string()))),
+            RespData::Array(elements),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bytes::Bytes;
+
+    use super::HScan;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    fn split_reply(resp: RespData) -> (String, Vec<String>) {
+        let RespData::Array(mut reply) = resp else {
+            panic!("expected an array");
+        };
+        let (Some(RespData::Array(elements)), Some(RespData::BulkString(Some(cursor)))) =
+            (reply.pop(), reply.pop())
+        else {
+            panic!("expected a cursor and elements");
+        };
+        let elements = elements
+            .into_iter()
+            .map(|element| match element {
+                RespData::BulkString(Some(bytes)) => String::from_utf8(bytes.to_vec()).unwrap(),
+                other => panic!("expected a bulk string, got {other:?}"),
+            })
+            .collect();
+        (String::from_utf8(cursor.to_vec()).unwrap(), elements)
+    }
+
+    #[test]
+    fn parse_hscan_should_read_options() {
+        let cmd = parse_command(&build_request(
+            "HSCAN",
+            &["h", "42", "match", "f*", "COUNT", "3", "NOVALUES"],
+        ))
+        .expect("parse hscan");
+        assert_eq!(
+            cmd,
+            Command::HScan(HScan {
+                key: Bytes::from_owner("h"),
+                cursor: 42,
+                pattern: Some(Bytes::from_owner("f*")),
+                count: 3,
+                no_values: true,
+            })
+        );
+        assert!(parse_command(&build_request("HSCAN", &["h", "0", "COUNT", "0"])).is_err());
+        assert!(parse_command(&build_request("HSCAN", &["h", "-1"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_hscan_should_visit_every_field_once() {
+        let (server, mut conn) = build_server_connection().await;
+        let hash: Hash = (0..100)
+            .map(|i| (Bytes::from(format!("f{i}")), Bytes::from(i.to_string())))
+            .collect();
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+
+        let mut cursor = "0".to_string();
+        let mut seen = HashSet::new();
+        let mut calls = 0;
+        loop {
+            let cmd =
+                parse_command(&build_request("HSCAN", &["h", &cursor, "COUNT", "7"])).unwrap();
+            let (next, elements) =
+                split_reply(cmd.execute(server.clone(), &mut conn).await.unwrap());
+            for pair in elements.chunks_exact(2) {
+                assert_eq!(pair[0], format!("f{}", pair[1]));
+                assert!(seen.insert(pair[0].clone()), "{} returned twice", pair[0]);
+            }
+            calls += 1;
+            if next == "0" {
+                break;
+            }
+            cursor = next;
+        }
+        assert_eq!(seen.len(), 100);
+        assert_eq!(calls, 15);
+    }
+
+    #[tokio::test]
+    async fn execute_hscan_should_filter_by_pattern() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("foo"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("bar"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request(
+            "HSCAN",
+            &["h", "0", "MATCH", "f*", "NOVALUES"],
+        ))
+        .unwrap();
+        let (cursor, elements) = split_reply(cmd.execute(server.clone(), &mut conn).await.unwrap());
+        assert_eq!(cursor, "0");
+        assert_eq!(elements, vec!["foo"]);
+
+        let cmd = parse_command(&build_request("HSCAN", &["missing", "0"])).unwrap();
+        let (cursor, elements) = split_reply(cmd.execute(server, &mut conn).await.unwrap());
+        assert_eq!(cursor, "0");
+        assert!(elements.is_empty());
+    }
+}
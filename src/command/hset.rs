@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_pairs, error::ExecResult, get_or_insert_hash,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HSet {
+    key: Bytes,
+    pairs: Vec<(Bytes, Bytes)>,
+}
+
+impl Parse for HSet {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_pairs(args, 1)?;
+        Ok(HSet {
+            key: args[0].clone(),
+            pairs: args[1..]
+                .chunks_exact(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+        })
+    }
+}
+
+impl ExecuteCommand for HSet {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let hash = get_or_insert_hash(db, stats, &self.key)?;
+
+        let mut added = 0;
+        for (field, value) in &self.pairs {
+            if hash.insert(field.clone(), value.clone()).is_none() {
+                added += 1;
+            }
+        }
+
+        Ok(RespData::Integer(added))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HSet;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hset_should_read_field_value_pairs() {
+        let cmd = parse_command(&build_request("HSET", &["h", "f1", "v1", "f2", "v2"]))
+            .expect("parse hset");
+        assert_eq!(
+            cmd,
+            Command::HSet(HSet {
+                key: Bytes::from_owner("h"),
+                pairs: vec![
+                    (Bytes::from_owner("f1"), Bytes::from_owner("v1")),
+                    (Bytes::from_owner("f2"), Bytes::from_owner("v2")),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_hset_should_reject_dangling_field() {
+        let err = parse_command(&build_request("HSET", &["h", "f1", "v1", "f2"]))
+            .expect_err("field without value");
+        assert!(matches!(err, ParseError::ExpectPairs(_)));
+    }
+
+    #[tokio::test]
+    async fn execute_hset_should_count_only_new_fields() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("HSET", &["h", "f1", "v1", "f2", "v2"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hset");
+        assert_eq!(resp, RespData::Integer(2));
+
+        let cmd = parse_command(&build_request("HSET", &["h", "f1", "new", "f3", "v3"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hset");
+        assert_eq!(resp, RespData::Integer(1));
+
+        assert_eq!(
+            server.lock().await.db.get(b"h".as_ref()),
+            Some(&(
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("f1"), Bytes::from_owner("new")),
+                    (Bytes::from_owner("f2"), Bytes::from_owner("v2")),
+                    (Bytes::from_owner("f3"), Bytes::from_owner("v3")),
+                ])),
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hset_should_reject_list_key() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (Value::List([Bytes::from_owner("a")].into()), None),
+        );
+        let cmd = parse_command(&build_request("HSET", &["h", "f", "v"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,272 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecError, ExecResult, ParseError},
+        get_hash, get_or_insert_hash, parse_expiration, parse_fields,
+    },
+    db::{Expiration, FIELD_EXPIRE_TIME_MAX, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+#[derive(Debug, PartialEq)]
+enum FieldsCondition {
+    /// Only set the fields if none of them exists.
+    Fnx,
+    /// Only set the fields if all of them exist.
+    Fxx,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HSetEx {
+    key: Bytes,
+    condition: Option<FieldsCondition>,
+    expiration: Option<Expiration>,
+    keep_ttl: bool,
+    pairs: Vec<(Bytes, Bytes)>,
+}
+
+impl Parse for HSetEx {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 5)?;
+
+        let mut hsetex = HSetEx {
+            key: args[0].clone(),
+            condition: None,
+            expiration: None,
+            keep_ttl: false,
+            pairs: vec![],
+        };
+
+        let mut i = 1;
+        loop {
+            let argument = str::from_utf8(&args[i])?.to_uppercase();
+            match argument.as_str() {
+                "FNX" | "FXX" if hsetex.condition.is_none() => {
+                    hsetex.condition = Some(if argument == "FNX" {
+                        FieldsCondition::Fnx
+                    } else {
+                        FieldsCondition::Fxx
+                    });
+                    i += 1;
+                }
+                "EX" | "PX" | "EXAT" | "PXAT"
+                    if hsetex.expiration.is_none() && !hsetex.keep_ttl =>
+                {
+                    let value = args.get(i + 1).ok_or(ParseError::ExpectLengthGe(
+                        i + 2,
+                        args.len(),
+                        args.to_vec(),
+                    ))?;
+                    hsetex.expiration = Some(parse_expiration(&argument, value)?);
+                    i += 2;
+                }
+                "KEEPTTL" if hsetex.expiration.is_none() && !hsetex.keep_ttl => {
+                    hsetex.keep_ttl = true;
+                    i += 1;
+                }
+                "FIELDS" => break,
+                _ => return Err(ParseError::InvalidArgument(argument)),
+            }
+            if i >= args.len() {
+                return Err(ParseError::ExpectLengthGe(i + 4, args.len(), args.to_vec()));
+            }
+        }
+
+        hsetex.pairs = parse_fields(&args[i..], 2)?
+            .chunks_exact(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect();
+
+        Ok(hsetex)
+    }
+}
+
+impl ExecuteCommand for HSetEx {
+    /// Replies 1 when the fields were set, and 0 when the `FNX` or `FXX` condition is not met.
+    /// Without `KEEPTTL`, fields lose their previous expire time.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let now = unix_time_ms();
+        let when = self
+            .expiration
+            .map(|expiration| {
+                expiration
+                    .unix_time_ms(now)
+                    .filter(|when| *when <= FIELD_EXPIRE_TIME_MAX)
+                    .ok_or(ExecError::InvalidExpireTime("hsetex"))
+            })
+            .transpose()?;
+
+        let mut server = server.lock().await;
+        let server = &mut *server;
+
+        if let Some(condition) = &self.condition {
+            let hash = get_hash(&mut server.db, &mut server.stats, &self.key)?;
+            let exists = |field: &Bytes| hash.as_ref().is_some_and(|hash| hash.contains_key(field));
+            let met = match condition {
+                FieldsCondition::Fnx => self.pairs.iter().all(|(field, _)| !exists(field)),
+                FieldsCondition::Fxx => self.pairs.iter().all(|(field, _)| exists(field)),
+            };
+            if !met {
+                return Ok(RespData::Integer(0));
+            }
+        }
+
+        let hash = get_or_insert_hash(&mut server.db, &mut server.stats, &self.key)?;
+        for (field, value) in &self.pairs {
+            if self.keep_ttl {
+                hash.insert_keep_ttl(field.clone(), value.clone());
+            } else {
+                hash.insert(field.clone(), value.clone());
+            }
+            match when {
+                Some(when) if when <= now => {
+                    hash.remove(field);
+                }
+                Some(when) => hash.set_expire_time(field, when),
+                None => {}
+            }
+        }
+
+        if hash.has_volatile_fields() {
+            server.volatile_hashes.insert(self.key.clone());
+        }
+        remove_if_empty(&mut server.db, &self.key);
+
+        Ok(RespData::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{FieldsCondition, HSetEx};
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Expiration, Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_hsetex_should_read_condition_expiration_and_pairs() {
+        let cmd = parse_command(&build_request(
+            "HSETEX",
+            &["h", "FNX", "EXAT", "100", "FIELDS", "2", "a", "1", "b", "2"],
+        ))
+        .expect("parse hsetex");
+        assert_eq!(
+            cmd,
+            Command::HSetEx(HSetEx {
+                key: Bytes::from_owner("h"),
+                condition: Some(FieldsCondition::Fnx),
+                expiration: Some(Expiration::At(100_000)),
+                keep_ttl: false,
+                pairs: vec![
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ],
+            })
+        );
+
+        for args in [
+            ["h", "FIELDS", "2", "a", "1"].as_slice(),
+            &["h", "EX", "10", "KEEPTTL", "FIELDS", "1", "a", "1"],
+            &["h", "FNX", "FXX", "FIELDS", "1", "a", "1"],
+            &["h", "FNX", "FNX"],
+        ] {
+            assert!(parse_command(&build_request("HSETEX", args)).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_hsetex_should_check_fields_condition() {
+        let (server, mut conn) = build_server_connection().await;
+
+        let cmd = parse_command(&build_request(
+            "HSETEX",
+            &["h", "FXX", "FIELDS", "1", "a", "1"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(server.lock().await.db.is_empty());
+
+        let cmd = parse_command(&build_request(
+            "HSETEX",
+            &["h", "FNX", "EX", "10", "FIELDS", "1", "a", "1"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = parse_command(&build_request(
+            "HSETEX",
+            &["h", "FNX", "FIELDS", "2", "a", "2", "b", "2"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(0));
+
+        let guard = server.lock().await;
+        let Some((Value::Hash(hash), _)) = guard.db.get(b"h".as_ref()) else {
+            panic!("expected a hash");
+        };
+        assert_eq!(hash.get(b"a"), Some(&Bytes::from_owner("1")));
+        assert!(hash.expire_time(b"a").is_some());
+        assert!(!hash.contains_key(b"b"));
+        assert!(guard.volatile_hashes.contains(b"h".as_ref()));
+    }
+
+    #[tokio::test]
+    async fn execute_hsetex_should_keep_or_discard_expire_times() {
+        let (server, mut conn) = build_server_connection().await;
+        let when = unix_time_ms() + 10_000;
+        let mut hash = Hash::from([
+            (Bytes::from_owner("a"), Bytes::from_owner("1")),
+            (Bytes::from_owner("b"), Bytes::from_owner("2")),
+        ]);
+        hash.set_expire_time(&Bytes::from_owner("a"), when);
+        hash.set_expire_time(&Bytes::from_owner("b"), when);
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+
+        let cmd = parse_command(&build_request(
+            "HSETEX",
+            &["h", "KEEPTTL", "FIELDS", "1", "a", "x"],
+        ))
+        .unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+        let cmd = parse_command(&build_request("HSETEX", &["h", "FIELDS", "1", "b", "y"])).unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+
+        let guard = server.lock().await;
+        let Some((Value::Hash(hash), _)) = guard.db.get(b"h".as_ref()) else {
+            panic!("expected a hash");
+        };
+        assert_eq!(hash.get(b"a"), Some(&Bytes::from_owner("x")));
+        assert_eq!(hash.expire_time(b"a"), Some(when));
+        assert_eq!(hash.get(b"b"), Some(&Bytes::from_owner("y")));
+        assert_eq!(hash.expire_time(b"b"), None);
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_or_insert_hash,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HSetNx {
+    key: Bytes,
+    field: Bytes,
+    value: Bytes,
+}
+
+impl Parse for HSetNx {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(HSetNx {
+            key: args[0].clone(),
+            field: args[1].clone(),
+            value: args[2].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HSetNx {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let hash = get_or_insert_hash(db, stats, &self.key)?;
+
+        if hash.contains_key(&self.field) {
+            return Ok(RespData::Integer(0));
+        }
+        hash.insert(self.field.clone(), self.value.clone());
+        Ok(RespData::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HSetNx;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hsetnx_should_read_field_and_value() {
+        let cmd = parse_command(&build_request("HSETNX", &["h", "f", "v"])).expect("parse hsetnx");
+        assert_eq!(
+            cmd,
+            Command::HSetNx(HSetNx {
+                key: Bytes::from_owner("h"),
+                field: Bytes::from_owner("f"),
+                value: Bytes::from_owner("v"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hsetnx_should_not_overwrite_field() {
+        let (server, mut conn) = build_server_connection().await;
+
+        let cmd = parse_command(&build_request("HSETNX", &["h", "f", "first"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hsetnx");
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = parse_command(&build_request("HSETNX", &["h", "f", "second"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hsetnx");
+        assert_eq!(resp, RespData::Integer(0));
+        assert_eq!(
+            server.lock().await.db.get(b"h".as_ref()),
+            Some(&(
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("first")
+                )])),
+                None
+            ))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HStrLen {
+    key: Bytes,
+    field: Bytes,
+}
+
+impl Parse for HStrLen {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(HStrLen {
+            key: args[0].clone(),
+            field: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HStrLen {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let len = get_hash(db, stats, &self.key)?
+            .and_then(|hash| hash.get(&self.field))
+            .map_or(0, |value| value.len());
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HStrLen;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hstrlen_should_read_key_and_field() {
+        let cmd = parse_command(&build_request("HSTRLEN", &["h", "f"])).expect("parse hstrlen");
+        assert_eq!(
+            cmd,
+            Command::HStrLen(HStrLen {
+                key: Bytes::from_owner("h"),
+                field: Bytes::from_owner("f"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hstrlen_should_return_value_length() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("value"),
+                )])),
+                None,
+            ),
+        );
+
+        for (key, field, expected) in [("h", "f", 5), ("h", "x", 0), ("x", "f", 0)] {
+            let cmd = parse_command(&build_request("HSTRLEN", &[key, field])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute hstrlen");
+            assert_eq!(resp, RespData::Integer(expected));
+        }
+    }
+}
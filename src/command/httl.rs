@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge, error::ExecResult, get_hash, parse_fields,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+/// `HTTL`, `HPTTL`, `HEXPIRETIME` and `HPEXPIRETIME`.
+#[derive(Debug, PartialEq)]
+pub struct HTtl {
+    key: Bytes,
+    /// Reply in milliseconds rather than seconds.
+    milliseconds: bool,
+    /// Reply the expire time as a Unix time rather than the time to live.
+    absolute: bool,
+    fields: Vec<Bytes>,
+}
+
+impl HTtl {
+    pub fn parse(args: &[Bytes], milliseconds: bool, absolute: bool) -> ParseResult<Self> {
+        check_length_ge(args, 4)?;
+        Ok(HTtl {
+            key: args[0].clone(),
+            milliseconds,
+            absolute,
+            fields: parse_fields(&args[1..], 1)?.to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for HTtl {
+    /// Replies for every field -2 if it does not exist, -1 if it has no expire time, and
+    /// otherwise its time to live or expire time. Seconds are rounded up.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let hash = get_hash(db, stats, &self.key)?;
+        let base_time = if self.absolute { 0 } else { unix_time_ms() };
+
+        Ok(RespData::Array(
+            self.fields
+                .iter()
+                .map(|field| {
+                    let reply = match hash.as_ref() {
+                        Some(hash) if hash.contains_key(field) => match hash.expire_time(field) {
+                            None => -1,
+                            Some(when) if self.milliseconds => {
+                                when.saturating_sub(base_time) as i64
+                            }
+                            Some(when) => when.saturating_sub(base_time).div_ceil(1000) as i64,
+                        },
+                        _ => -2,
+                    };
+                    RespData::Integer(reply)
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HTtl;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_httl_should_read_fields() {
+        let cmd = parse_command(&build_request("HPEXPIRETIME", &["h", "FIELDS", "1", "a"]))
+            .expect("parse hpexpiretime");
+        assert_eq!(
+            cmd,
+            Command::HTtl(HTtl {
+                key: Bytes::from_owner("h"),
+                milliseconds: true,
+                absolute: true,
+                fields: vec![Bytes::from_owner("a")],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_httl_should_reply_ttl_per_field() {
+        let (server, mut conn) = build_server_connection().await;
+        let when = unix_time_ms() + 10_000;
+        let mut hash = Hash::from([
+            (Bytes::from_owner("a"), Bytes::from_owner("1")),
+            (Bytes::from_owner("b"), Bytes::from_owner("2")),
+            (Bytes::from_owner("gone"), Bytes::from_owner("3")),
+        ]);
+        hash.set_expire_time(&Bytes::from_owner("a"), when);
+        hash.set_expire_time(&Bytes::from_owner("gone"), 1);
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+
+        // An expired field is lazily removed, as if it never existed
+        let cmd = parse_command(&build_request(
+            "HTTL",
+            &["h", "FIELDS", "4", "a", "b", "gone", "x"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::Integer(10),
+                RespData::Integer(-1),
+                RespData::Integer(-2),
+                RespData::Integer(-2),
+            ])
+        );
+
+        let cmd =
+            parse_command(&build_request("HPEXPIRETIME", &["h", "FIELDS", "1", "a"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Array(vec![RespData::Integer(when as i64)]));
+
+        let cmd = parse_command(&build_request("HTTL", &["missing", "FIELDS", "1", "a"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Array(vec![RespData::Integer(-2)]));
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_hash},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct HVals {
+    key: Bytes,
+}
+
+impl Parse for HVals {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(HVals {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for HVals {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let values = get_hash(db, stats, &self.key)?
+            .map(|hash| {
+                hash.values()
+                    .map(|value| RespData::BulkString(Some(value.clone())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(RespData::Array(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::HVals;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_hvals_should_read_key() {
+        let cmd = parse_command(&build_request("HVALS", &["h"])).expect("parse hvals");
+        assert_eq!(
+            cmd,
+            Command::HVals(HVals {
+                key: Bytes::from_owner("h"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_hvals_should_return_every_value() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("h"),
+            (
+                Value::Hash(Hash::from([
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("HVALS", &["h"])).unwrap();
+        let RespData::Array(mut values) = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute hvals")
+        else {
+            panic!("expected an array");
+        };
+        values.sort_by_key(|value| format!("{:?}", value));
+        assert_eq!(
+            values,
+            vec![
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+                RespData::BulkString(Some(Bytes::from_owner("2"))),
+            ]
+        );
+
+        let cmd = parse_command(&build_request("HVALS", &["missing"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute hvals");
+        assert_eq!(resp, RespData::Array(vec![]));
+    }
+}
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, ParseResult, check_length_eq, error::ExecResult,
+        get_or_insert_string, get_string,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// INCR, DECR, INCRBY and DECRBY.
+#[derive(Debug, PartialEq)]
+pub struct Incr {
+    key: Bytes,
+    increment: i64,
+    /// DECR and DECRBY, which negate the increment.
+    decrement: bool,
+}
+
+impl Incr {
+    /// `by` is whether the increment is given, as for INCRBY and DECRBY.
+    pub fn parse(args: &[Bytes], by: bool, decrement: bool) -> ParseResult<Self> {
+        check_length_eq(args, 1 + by as usize)?;
+        let increment = if by {
+            lexical_core::parse(&args[1]).map_err(|_| ExecError::NotInteger)?
+        } else {
+            1
+        };
+        Ok(Incr {
+            key: args[0].clone(),
+            increment,
+            decrement,
+        })
+    }
+}
+
+/// Parse a string value as an integer the way Redis does: only the canonical form is accepted,
+/// without sign prefix, leading zeros or spaces.
+fn parse_integer_value(value: &[u8]) -> Option<i64> {
+    lexical_core::parse::<i64>(value)
+        .ok()
+        .filter(|integer| integer.to_string().as_bytes() == value)
+}
+
+impl ExecuteCommand for Incr {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let increment = if self.decrement {
+            self.increment
+                .checked_neg()
+                .ok_or(ExecError::DecrementOverflow)?
+        } else {
+            self.increment
+        };
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let current = match get_string(db, stats, &self.key)? {
+            None => 0,
+            Some(value) => parse_integer_value(value).ok_or(ExecError::NotInteger)?,
+        };
+        let value = current.checked_add(increment).ok_or(ExecError::Overflow)?;
+        // Updated in place, so the key keeps its expire time
+        *get_or_insert_string(db, stats, &self.key)? = Bytes::from(value.to_string());
+
+        Ok(RespData::Integer(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::Incr;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_incr_family_should_read_increment() {
+        for (command, args, increment, decrement) in [
+            ("INCR", &["k"][..], 1, false),
+            ("DECR", &["k"], 1, true),
+            ("INCRBY", &["k", "-5"], -5, false),
+            ("DECRBY", &["k", "7"], 7, true),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).expect("parse incr");
+            assert_eq!(
+                cmd,
+                Command::Incr(Incr {
+                    key: Bytes::from_owner("k"),
+                    increment,
+                    decrement,
+                })
+            );
+        }
+
+        for (command, args) in [
+            ("INCR", &["k", "1"][..]),
+            ("INCRBY", &["k"]),
+            ("INCRBY", &["k", "1.5"]),
+            ("DECRBY", &["k", "99999999999999999999"]),
+        ] {
+            assert!(
+                parse_command(&build_request(command, args)).is_err(),
+                "{command} {args:?}"
+            );
+        }
+
+        for (command, args) in [("INCRBY", &["k", "abc"]), ("DECRBY", &["k", "1.5"])] {
+            assert_eq!(
+                parse_command(&build_request(command, args)),
+                Err(ParseError::Rejected(ExecError::NotInteger)),
+                "{command} {args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_incr_should_update_value_and_keep_ttl() {
+        let (server, mut conn) = build_server_connection().await;
+        let expire_time = Some(unix_time_ms() + 60_000);
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("10")), expire_time),
+        );
+
+        for (command, args, expected) in [
+            ("INCR", &["k"][..], 11),
+            ("DECRBY", &["k", "20"], -9),
+            ("INCRBY", &["k", "4"], -5),
+            ("DECR", &["missing"], -1),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute incr");
+            assert_eq!(resp, RespData::Integer(expected), "{command} {args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        assert_eq!(
+            db.get(b"k".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("-5")), expire_time))
+        );
+        assert_eq!(
+            db.get(b"missing".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("-1")), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_incr_should_reject_invalid_values_and_overflow() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            for (key, value) in [
+                ("text", "abc"),
+                ("padded", " 1"),
+                ("plus", "+1"),
+                ("zeros", "01"),
+                ("max", "9223372036854775807"),
+            ] {
+                db.insert(
+                    Bytes::from_owner(key),
+                    (Value::String(Bytes::from_owner(value)), None),
+                );
+            }
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+            );
+        }
+
+        for (command, args, expected) in [
+            ("INCR", &["text"][..], ExecError::NotInteger),
+            ("INCR", &["padded"], ExecError::NotInteger),
+            ("INCR", &["plus"], ExecError::NotInteger),
+            ("INCR", &["zeros"], ExecError::NotInteger),
+            ("INCR", &["max"], ExecError::Overflow),
+            (
+                "DECRBY",
+                &["k", "-9223372036854775808"],
+                ExecError::DecrementOverflow,
+            ),
+            ("INCR", &["list"], ExecError::WrongType),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let err = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect_err("incr should fail");
+            assert_eq!(err, expected, "{command} {args:?}");
+        }
+        assert!(!server.lock().await.db.contains_key(b"k".as_ref()));
+    }
+}
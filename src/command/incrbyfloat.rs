@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult,
+        get_or_insert_string, get_string,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    utils::format_float,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct IncrByFloat {
+    key: Bytes,
+    increment: f64,
+}
+
+impl Parse for IncrByFloat {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(IncrByFloat {
+            key: args[0].clone(),
+            increment: lexical_core::parse(&args[1]).map_err(|_| ExecError::NotFloat)?,
+        })
+    }
+}
+
+impl ExecuteCommand for IncrByFloat {
+    /// Replies the new value as a bulk string, formatted the same way it is stored.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let current = match get_string(db, stats, &self.key)? {
+            None => 0.0,
+            Some(value) => lexical_core::parse::<f64>(value)
+                .ok()
+                .filter(|value| !value.is_nan())
+                .ok_or(ExecError::NotFloat)?,
+        };
+        let value = current + self.increment;
+        if !value.is_finite() {
+            return Err(ExecError::NanOrInfinity);
+        }
+        let value = Bytes::from(format_float(value));
+        // Updated in place, so the key keeps its expire time
+        *get_or_insert_string(db, stats, &self.key)? = value.clone();
+
+        Ok(RespData::BulkString(Some(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::IncrByFloat;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_incrbyfloat_should_read_increment() {
+        let cmd = parse_command(&build_request("INCRBYFLOAT", &["k", "-1.5e3"]))
+            .expect("parse incrbyfloat");
+        assert_eq!(
+            cmd,
+            Command::IncrByFloat(IncrByFloat {
+                key: Bytes::from_owner("k"),
+                increment: -1500.0,
+            })
+        );
+        assert_eq!(
+            parse_command(&build_request("INCRBYFLOAT", &["k", "x"])),
+            Err(ParseError::Rejected(ExecError::NotFloat))
+        );
+        assert!(parse_command(&build_request("INCRBYFLOAT", &["k"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_incrbyfloat_should_format_result_and_keep_ttl() {
+        let (server, mut conn) = build_server_connection().await;
+        let expire_time = Some(unix_time_ms() + 60_000);
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("10.5")), expire_time),
+        );
+
+        for (increment, expected) in [("0.1", "10.6"), ("-5.6", "5"), ("2.0e2", "205")] {
+            let cmd = parse_command(&build_request("INCRBYFLOAT", &["k", increment])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute incrbyfloat");
+            assert_eq!(
+                resp,
+                RespData::BulkString(Some(Bytes::from_owner(expected)))
+            );
+        }
+        assert_eq!(
+            server.lock().await.db.get(b"k".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("205")), expire_time))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_incrbyfloat_should_reject_non_float_and_infinity() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("abc")), None),
+        );
+
+        let cmd = parse_command(&build_request("INCRBYFLOAT", &["k", "1"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("not a float");
+        assert_eq!(err, ExecError::NotFloat);
+
+        let cmd = parse_command(&build_request("INCRBYFLOAT", &["missing", "inf"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("infinity");
+        assert_eq!(err, ExecError::NanOrInfinity);
+        assert!(!server.lock().await.db.contains_key(b"missing".as_ref()));
+    }
+}
@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, error::ExecResult},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// `INFO [section ...]`, of which only the stats section is kept.
+#[derive(Debug, PartialEq)]
+pub struct Info {
+    /// The sections asked for in lowercase, all of them when empty.
+    sections: Vec<String>,
+}
+
+impl Parse for Info {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        Ok(Info {
+            sections: args
+                .iter()
+                .map(|arg| str::from_utf8(arg).map(|section| section.to_lowercase()))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl ExecuteCommand for Info {
+    /// Replies the `field:value` lines of the sections asked for, unknown sections being left
+    /// out.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = server.lock().await;
+        let mut info = String::new();
+        if self.sections.is_empty()
+            || self.sections.iter().any(|section| {
+                matches!(section.as_str(), "stats" | "default" | "all" | "everything")
+            })
+        {
+            info.push_str(&format!(
+                "# Stats\r\nexpired_keys:{}\r\nexpired_stale_perc:{:.2}\r\n",
+                server.stats.expired_keys, server.stats.expired_stale_perc
+            ));
+        }
+        Ok(RespData::VerbatimString(
+            "txt".to_string(),
+            Bytes::from(info),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::Info;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[tokio::test]
+    async fn execute_info_should_reply_stats() {
+        let cmd = parse_command(&build_request("INFO", &["Stats"])).expect("parse info");
+        assert_eq!(
+            cmd,
+            Command::Info(Info {
+                sections: vec!["stats".to_string()]
+            })
+        );
+
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let mut server = server.lock().await;
+            server.stats.expired_keys = 3;
+            server.stats.expired_stale_perc = 12.5;
+        }
+        for (args, expected) in [
+            (
+                &[][..],
+                "# Stats\r\nexpired_keys:3\r\nexpired_stale_perc:12.50\r\n",
+            ),
+            (
+                &["stats"],
+                "# Stats\r\nexpired_keys:3\r\nexpired_stale_perc:12.50\r\n",
+            ),
+            (&["keyspace"], ""),
+        ] {
+            let cmd = parse_command(&build_request("INFO", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute info");
+            assert_eq!(
+                resp,
+                RespData::VerbatimString("txt".to_string(), Bytes::from_owner(expected)),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_info_should_count_keys_expired_on_access() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("k"),
+                (
+                    Value::String(Bytes::from_owner("v")),
+                    Some(unix_time_ms() - 1),
+                ),
+            );
+            // A hash emptied by its expired fields is deleted, not counted as an expired key
+            let field = Bytes::from_owner("f");
+            let mut hash = Hash::from([(field.clone(), Bytes::from_owner("v"))]);
+            hash.set_expire_time(&field, 1);
+            db.insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+        }
+
+        for (command, args, expected) in [
+            ("GET", &["k"][..], RespData::BulkString(None)),
+            ("GET", &["k"], RespData::BulkString(None)),
+            ("HGET", &["h", "f"], RespData::BulkString(None)),
+            (
+                "INFO",
+                &["stats"],
+                RespData::VerbatimString(
+                    "txt".to_string(),
+                    Bytes::from_owner("# Stats\r\nexpired_keys:1\r\nexpired_stale_perc:0.00\r\n"),
+                ),
+            ),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+            assert_eq!(resp, expected, "{command} {args:?}");
+        }
+        assert!(server.lock().await.db.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult},
+    db::lookup_key,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// TYPE, in a module named so as `type` is a keyword.
+#[derive(Debug, PartialEq)]
+pub struct Type {
+    key: Bytes,
+}
+
+impl Parse for Type {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(Type {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for Type {
+    /// Replies `none` for a missing key.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let name = lookup_key(db, stats, &self.key).map_or("none", |(value, _)| value.type_name());
+        Ok(RespData::SimpleString(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashSet, VecDeque};
+
+    use bytes::Bytes;
+
+    use super::Type;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+        stream::Stream,
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_type_should_read_key() {
+        let cmd = parse_command(&build_request("TYPE", &["k"])).expect("parse type");
+        assert_eq!(
+            cmd,
+            Command::Type(Type {
+                key: Bytes::from_owner("k"),
+            })
+        );
+        assert!(parse_command(&build_request("TYPE", &["k", "k"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_type_should_name_value_types() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            for (key, value) in [
+                ("string", Value::String(Bytes::from_owner("v"))),
+                ("list", Value::List(VecDeque::new())),
+                ("hash", Value::Hash(Hash::new())),
+                ("set", Value::Set(HashSet::new())),
+                ("zset", Value::ZSet(SortedSet::new())),
+                ("stream", Value::Stream(Stream::new())),
+            ] {
+                db.insert(Bytes::from_owner(key), (value, None));
+            }
+        }
+
+        for (key, expected) in [
+            ("string", "string"),
+            ("list", "list"),
+            ("hash", "hash"),
+            ("set", "set"),
+            ("zset", "zset"),
+            ("stream", "stream"),
+            ("missing", "none"),
+        ] {
+            let cmd = parse_command(&build_request("TYPE", &[key])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute type");
+            assert_eq!(resp, RespData::SimpleString(expected.to_string()), "{key}");
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+    },
+    db::{STRING_MAX_LEN, Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Lcs {
+    key1: Bytes,
+    key2: Bytes,
+    /// Reply the length of the longest common subsequence only.
+    len: bool,
+    /// Reply the ranges of the matches instead of the subsequence.
+    idx: bool,
+    /// Only reply the matches at least this long.
+    min_match_len: usize,
+    /// Reply the length of each match along with its ranges.
+    with_match_len: bool,
+}
+
+impl Parse for Lcs {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        let mut lcs = Lcs {
+            key1: args[0].clone(),
+            key2: args[1].clone(),
+            len: false,
+            idx: false,
+            min_match_len: 0,
+            with_match_len: false,
+        };
+
+        let mut index = 2;
+        while index < args.len() {
+            let option = str::from_utf8(&args[index])?.to_uppercase();
+            match option.as_str() {
+                "LEN" => lcs.len = true,
+                "IDX" => lcs.idx = true,
+                "WITHMATCHLEN" => lcs.with_match_len = true,
+                "MINMATCHLEN" => {
+                    check_length_ge(args, index + 2)?;
+                    let min_match_len: i64 = lexical_core::parse(&args[index + 1])?;
+                    lcs.min_match_len = min_match_len.max(0) as usize;
+                    index += 1;
+                }
+                _ => return Err(ParseError::InvalidArgument(option)),
+            }
+            index += 1;
+        }
+        // Redis refuses both, the length being part of the IDX reply
+        if lcs.len && lcs.idx {
+            return Err(ParseError::InvalidArgument("LEN IDX".to_string()));
+        }
+        Ok(lcs)
+    }
+}
+
+/// A run of bytes common to both strings, as inclusive ranges of each.
+#[derive(Debug, PartialEq)]
+struct Match {
+    a: (usize, usize),
+    b: (usize, usize),
+}
+
+impl Match {
+    fn len(&self) -> usize {
+        self.a.1 - self.a.0 + 1
+    }
+}
+
+/// The longest common subsequence of `a` and `b`, along with the matches it is made of, from
+/// the end of the strings to their start as Redis replies them.
+fn longest_common_subsequence(a: &[u8], b: &[u8]) -> (Vec<u8>, Vec<Match>) {
+    // table[i * (b.len() + 1) + j] is the length of the LCS of a[..i] and b[..j]
+    let width = b.len() + 1;
+    let mut table = vec![0u32; (a.len() + 1) * width];
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            table[i * width + j] = if a[i - 1] == b[j - 1] {
+                table[(i - 1) * width + j - 1] + 1
+            } else {
+                table[(i - 1) * width + j].max(table[i * width + j - 1])
+            };
+        }
+    }
+
+    let mut subsequence = vec![0; table[a.len() * width + b.len()] as usize];
+    let mut matches = Vec::new();
+    let mut current: Option<Match> = None;
+    let (mut i, mut j, mut k) = (a.len(), b.len(), subsequence.len());
+    while i > 0 && j > 0 {
+        if a[i - 1] == b[j - 1] {
+            k -= 1;
+            subsequence[k] = a[i - 1];
+            i -= 1;
+            j -= 1;
+            // Going backward, a match extends the current one down to its new start
+            match &mut current {
+                Some(run) => {
+                    run.a.0 = i;
+                    run.b.0 = j;
+                }
+                None => {
+                    current = Some(Match {
+                        a: (i, i),
+                        b: (j, j),
+                    })
+                }
+            }
+            if i == 0 || j == 0 {
+                matches.extend(current.take());
+            }
+        } else {
+            if table[(i - 1) * width + j] > table[i * width + j - 1] {
+                i -= 1;
+            } else {
+                j -= 1;
+            }
+            matches.extend(current.take());
+        }
+    }
+    (subsequence, matches)
+}
+
+impl ExecuteCommand for Lcs {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let mut get = |key: &Bytes| match lookup_key(db, stats, key) {
+            None => Ok(Bytes::new()),
+            Some((Value::String(value), _)) => Ok(value.clone()),
+            Some(_) => Err(ExecError::LcsNotStrings),
+        };
+        let (a, b) = (get(&self.key1)?, get(&self.key2)?);
+        // The table takes 4 bytes per cell, and is bounded like any string
+        if (a.len() + 1).saturating_mul(b.len() + 1) > STRING_MAX_LEN / 4 {
+            return Err(ExecError::LcsTooLarge);
+        }
+
+        let (subsequence, matches) = longest_common_subsequence(&a, &b);
+        if self.len {
+            return Ok(RespData::Integer(subsequence.len() as i64));
+        }
+        if !self.idx {
+            return Ok(RespData::BulkString(Some(Bytes::from(subsequence))));
+        }
+
+        let range = |(start, end): (usize, usize)| {
+            RespData::Array(vec![
+                RespData::Integer(start as i64),
+                RespData::Integer(end as i64),
+            ])
+        };
+        let matches = matches
+            .into_iter()
+            .filter(|run| run.len() >= self.min_match_len)
+            .map(|run| {
+                let mut reply = vec![range(run.a), range(run.b)];
+                if self.with_match_len {
+                    reply.push(RespData::Integer(run.len() as i64));
+                }
+                RespData::Array(reply)
+            })
+            .collect();
+        Ok(RespData::Map(
+            [
+                (Bytes::from_static(b"matches"), RespData::Array(matches)),
+                (
+                    Bytes::from_static(b"len"),
+                    RespData::Integer(subsequence.len() as i64),
+                ),
+            ]
+            .into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::{Lcs, Match, longest_common_subsequence};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    fn range(start: i64, end: i64) -> RespData {
+        RespData::Array(vec![RespData::Integer(start), RespData::Integer(end)])
+    }
+
+    #[test]
+    fn parse_lcs_should_read_options() {
+        let cmd = parse_command(&build_request(
+            "LCS",
+            &["a", "b", "idx", "MINMATCHLEN", "4", "WITHMATCHLEN"],
+        ))
+        .expect("parse lcs");
+        assert_eq!(
+            cmd,
+            Command::Lcs(Lcs {
+                key1: Bytes::from_owner("a"),
+                key2: Bytes::from_owner("b"),
+                len: false,
+                idx: true,
+                min_match_len: 4,
+                with_match_len: true,
+            })
+        );
+
+        for args in [
+            &["a"][..],
+            &["a", "b", "LEN", "IDX"],
+            &["a", "b", "MINMATCHLEN"],
+            &["a", "b", "UNKNOWN"],
+        ] {
+            assert!(
+                parse_command(&build_request("LCS", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn longest_common_subsequence_should_find_matches_backward() {
+        let (subsequence, matches) = longest_common_subsequence(b"ohmytext", b"mynewtext");
+        assert_eq!(subsequence, b"mytext");
+        assert_eq!(
+            matches,
+            vec![
+                Match {
+                    a: (4, 7),
+                    b: (5, 8)
+                },
+                Match {
+                    a: (2, 3),
+                    b: (0, 1)
+                },
+            ]
+        );
+        assert_eq!(longest_common_subsequence(b"", b"abc"), (vec![], vec![]));
+    }
+
+    #[tokio::test]
+    async fn execute_lcs_should_reply_subsequence_length_and_matches() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            for (key, value) in [("key1", "ohmytext"), ("key2", "mynewtext")] {
+                db.insert(
+                    Bytes::from_owner(key),
+                    (Value::String(Bytes::from_owner(value)), None),
+                );
+            }
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+            );
+        }
+
+        for (args, expected) in [
+            (
+                &["key1", "key2"][..],
+                RespData::BulkString(Some(Bytes::from_owner("mytext"))),
+            ),
+            (&["key1", "key2", "LEN"], RespData::Integer(6)),
+            (
+                &["key1", "missing"],
+                RespData::BulkString(Some(Bytes::new())),
+            ),
+            (
+                &["key1", "key2", "IDX", "MINMATCHLEN", "4", "WITHMATCHLEN"],
+                RespData::Map(
+                    [
+                        (
+                            Bytes::from_owner("matches"),
+                            RespData::Array(vec![RespData::Array(vec![
+                                range(4, 7),
+                                range(5, 8),
+                                RespData::Integer(4),
+                            ])]),
+                        ),
+                        (Bytes::from_owner("len"), RespData::Integer(6)),
+                    ]
+                    .into(),
+                ),
+            ),
+        ] {
+            let cmd = parse_command(&build_request("LCS", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute lcs");
+            assert_eq!(resp, expected, "{args:?}");
+        }
+
+        let cmd = parse_command(&build_request("LCS", &["key1", "list"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("not a string");
+        assert_eq!(err, ExecError::LcsNotStrings);
+    }
+}
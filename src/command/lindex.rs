@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_list},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::normalize_index,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LIndex {
+    key: Bytes,
+    index: i64,
+}
+
+impl Parse for LIndex {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(LIndex {
+            key: args[0].clone(),
+            index: lexical_core::parse(&args[1])?,
+        })
+    }
+}
+
+impl ExecuteCommand for LIndex {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let element = get_list(db, stats, &self.key)?.and_then(|list| {
+            normalize_index(self.index, list.len()).map(|index| list[index].clone())
+        });
+        Ok(RespData::BulkString(element))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::LIndex;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_lindex_should_read_index() {
+        let cmd = parse_command(&build_request("LINDEX", &["list", "-1"])).expect("parse lindex");
+        assert_eq!(
+            cmd,
+            Command::LIndex(LIndex {
+                key: Bytes::from_owner("list"),
+                index: -1,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_lindex_should_support_negative_index() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (
+                Value::List(VecDeque::from([
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("b"),
+                ])),
+                None,
+            ),
+        );
+
+        for (index, expected) in [(0, Some("a")), (-1, Some("b")), (2, None), (-3, None)] {
+            let cmd = LIndex {
+                key: Bytes::from_owner("list"),
+                index,
+            };
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute lindex");
+            assert_eq!(resp, RespData::BulkString(expected.map(Bytes::from_owner)));
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq,
+        error::{ExecResult, ParseError},
+        get_list,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LInsert {
+    key: Bytes,
+    before: bool,
+    pivot: Bytes,
+    element: Bytes,
+}
+
+impl Parse for LInsert {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 4)?;
+        let position = str::from_utf8(&args[1])?;
+        let before = match position.to_uppercase().as_str() {
+            "BEFORE" => true,
+            "AFTER" => false,
+            _ => return Err(ParseError::InvalidArgument(position.to_string())),
+        };
+        Ok(LInsert {
+            key: args[0].clone(),
+            before,
+            pivot: args[2].clone(),
+            element: args[3].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for LInsert {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(list) = get_list(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(0));
+        };
+        let Some(pivot) = list.iter().position(|element| *element == self.pivot) else {
+            return Ok(RespData::Integer(-1));
+        };
+        let index = if self.before { pivot } else { pivot + 1 };
+        list.insert(index, self.element.clone());
+
+        Ok(RespData::Integer(list.len() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::LInsert;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    fn list(elements: &[&'static str]) -> Value {
+        Value::List(
+            elements
+                .iter()
+                .map(|s| Bytes::from_static(s.as_bytes()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parse_linsert_should_read_position() {
+        let cmd = parse_command(&build_request("LINSERT", &["list", "after", "a", "b"]))
+            .expect("parse linsert");
+        assert_eq!(
+            cmd,
+            Command::LInsert(LInsert {
+                key: Bytes::from_owner("list"),
+                before: false,
+                pivot: Bytes::from_owner("a"),
+                element: Bytes::from_owner("b"),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_linsert_should_reject_invalid_position() {
+        let err = parse_command(&build_request("LINSERT", &["list", "middle", "a", "b"]))
+            .expect_err("invalid position");
+        assert_eq!(err, ParseError::InvalidArgument("middle".to_string()));
+    }
+
+    #[tokio::test]
+    async fn execute_linsert_should_insert_around_pivot() {
+        let (server, mut conn) = build_server_connection().await;
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("list"), (list(&["a", "c"]), None));
+
+        for (before, pivot, element, expected) in [
+            (true, "c", "b", 3),
+            (false, "c", "d", 4),
+            (true, "missing", "x", -1),
+        ] {
+            let cmd = LInsert {
+                key: Bytes::from_owner("list"),
+                before,
+                pivot: Bytes::from_owner(pivot),
+                element: Bytes::from_owner(element),
+            };
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute linsert");
+            assert_eq!(resp, RespData::Integer(expected));
+        }
+        assert_eq!(
+            server.lock().await.db.get(b"list".as_ref()),
+            Some(&(list(&["a", "b", "c", "d"]), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_linsert_should_return_zero_for_missing_key() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = LInsert {
+            key: Bytes::from_owner("list"),
+            before: true,
+            pivot: Bytes::from_owner("a"),
+            element: Bytes::from_owner("b"),
+        };
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute linsert");
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(server.lock().await.db.is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_list},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LLen {
+    key: Bytes,
+}
+
+impl Parse for LLen {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(LLen {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for LLen {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let len = get_list(db, stats, &self.key)?.map_or(0, |list| list.len());
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::LLen;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_llen_should_read_key() {
+        let cmd = parse_command(&build_request("LLEN", &["list"])).expect("parse llen");
+        assert_eq!(
+            cmd,
+            Command::LLen(LLen {
+                key: Bytes::from_owner("list")
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_llen_should_count_elements_or_return_zero() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+        );
+
+        let cmd = LLen {
+            key: Bytes::from_owner("list"),
+        };
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute llen");
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = LLen {
+            key: Bytes::from_owner("missing"),
+        };
+        let resp = cmd.execute(server, &mut conn).await.expect("execute llen");
+        assert_eq!(resp, RespData::Integer(0));
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_list,
+        get_or_insert_list, parse_list_end,
+    },
+    db::{Db, Key, ListEnd, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server, Stats},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LMove {
+    source: Bytes,
+    destination: Bytes,
+    from: ListEnd,
+    to: ListEnd,
+}
+
+impl Parse for LMove {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 4)?;
+        Ok(LMove {
+            source: args[0].clone(),
+            destination: args[1].clone(),
+            from: parse_list_end(&args[2])?,
+            to: parse_list_end(&args[3])?,
+        })
+    }
+}
+
+/// Pop an element from the `from` end of `source` and push it onto the `to` end of
+/// `destination`. Nothing is touched when `destination` holds another type.
+pub(super) fn move_element(
+    db: &mut Db,
+    stats: &mut Stats,
+    source: &Key,
+    destination: &Key,
+    from: ListEnd,
+    to: ListEnd,
+) -> ExecResult<Option<Bytes>> {
+    if get_list(db, stats, source)?.is_none() {
+        return Ok(None);
+    }
+    get_list(db, stats, destination)?;
+
+    // The source exists, as it was just checked, and lists are never kept empty
+    let element = from.pop(get_list(db, stats, source)?.unwrap()).unwrap();
+    remove_if_empty(db, source);
+    to.push(get_or_insert_list(db, stats, destination)?, element.clone());
+
+    Ok(Some(element))
+}
+
+impl ExecuteCommand for LMove {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let element = move_element(
+            &mut server.db,
+            &mut server.stats,
+            &self.source,
+            &self.destination,
+            self.from,
+            self.to,
+        )?;
+        if element.is_some() {
+            server.serve_blocked_clients(&self.destination);
+        }
+        Ok(RespData::BulkString(element))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::LMove;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{ListEnd, Value},
+        resp::RespData,
+    };
+
+    fn list(elements: &[&'static str]) -> Value {
+        Value::List(
+            elements
+                .iter()
+                .map(|s| Bytes::from_static(s.as_bytes()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parse_lmove_should_read_directions() {
+        let cmd = parse_command(&build_request("LMOVE", &["src", "dst", "left", "RIGHT"]))
+            .expect("parse lmove");
+        assert_eq!(
+            cmd,
+            Command::LMove(LMove {
+                source: Bytes::from_owner("src"),
+                destination: Bytes::from_owner("dst"),
+                from: ListEnd::Left,
+                to: ListEnd::Right,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_lmove_should_reject_invalid_direction() {
+        let err = parse_command(&build_request("LMOVE", &["src", "dst", "up", "RIGHT"]))
+            .expect_err("invalid direction");
+        assert_eq!(err, ParseError::InvalidArgument("up".to_string()));
+    }
+
+    #[tokio::test]
+    async fn execute_lmove_should_move_between_lists() {
+        let (server, mut conn) = build_server_connection().await;
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("src"), (list(&["a", "b"]), None));
+
+        let cmd = LMove {
+            source: Bytes::from_owner("src"),
+            destination: Bytes::from_owner("dst"),
+            from: ListEnd::Right,
+            to: ListEnd::Left,
+        };
+        for expected in ["b", "a"] {
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute lmove");
+            assert_eq!(
+                resp,
+                RespData::BulkString(Some(Bytes::from_owner(expected)))
+            );
+        }
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lmove");
+        assert_eq!(resp, RespData::BulkString(None));
+
+        let db = &server.lock().await.db;
+        assert!(!db.contains_key(b"src".as_ref()));
+        assert_eq!(db.get(b"dst".as_ref()), Some(&(list(&["a", "b"]), None)));
+    }
+
+    #[tokio::test]
+    async fn execute_lmove_should_rotate_same_list() {
+        let (server, mut conn) = build_server_connection().await;
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("src"), (list(&["a", "b", "c"]), None));
+
+        let cmd = LMove {
+            source: Bytes::from_owner("src"),
+            destination: Bytes::from_owner("src"),
+            from: ListEnd::Left,
+            to: ListEnd::Right,
+        };
+        cmd.execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lmove");
+        assert_eq!(
+            server.lock().await.db.get(b"src".as_ref()),
+            Some(&(list(&["b", "c", "a"]), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_lmove_should_keep_source_on_wrong_destination_type() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(Bytes::from_owner("src"), (list(&["a"]), None));
+            db.insert(
+                Bytes::from_owner("dst"),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+        }
+
+        let cmd = LMove {
+            source: Bytes::from_owner("src"),
+            destination: Bytes::from_owner("dst"),
+            from: ListEnd::Left,
+            to: ListEnd::Right,
+        };
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+        assert_eq!(
+            server.lock().await.db.get(b"src".as_ref()),
+            Some(&(list(&["a"]), None))
+        );
+    }
+}
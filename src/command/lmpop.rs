@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_list, parse_list_end,
+    },
+    db::{Db, Key, List, ListEnd, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server, Stats},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LMPop {
+    pub(super) keys: Vec<Bytes>,
+    pub(super) end: ListEnd,
+    pub(super) count: usize,
+}
+
+impl Parse for LMPop {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        let numkeys: usize = lexical_core::parse(&args[0])?;
+        if numkeys == 0 {
+            return Err(ParseError::InvalidArgument("numkeys 0".to_string()));
+        }
+        check_length_ge(args, numkeys + 2)?;
+
+        let mut lmpop = LMPop {
+            keys: args[1..=numkeys].to_vec(),
+            end: parse_list_end(&args[numkeys + 1])?,
+            count: 1,
+        };
+
+        match &args[numkeys + 2..] {
+            [] => {}
+            [option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+                lmpop.count = lexical_core::parse(count)?;
+                if lmpop.count == 0 {
+                    return Err(ParseError::InvalidArgument("COUNT 0".to_string()));
+                }
+            }
+            [option, ..] => {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(option)?.to_string(),
+                ));
+            }
+        }
+
+        Ok(lmpop)
+    }
+}
+
+impl LMPop {
+    /// Pop from the first non-empty list among the keys.
+    pub(super) fn pop(&self, db: &mut Db, stats: &mut Stats) -> ExecResult<Option<RespData>> {
+        for key in &self.keys {
+            if let Some(list) = get_list(db, stats, key)? {
+                let resp = pop_reply(list, key, self.end, self.count);
+                remove_if_empty(db, key);
+                return Ok(Some(resp));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Pop up to `count` elements, replying with the key and the popped elements.
+pub(super) fn pop_reply(list: &mut List, key: &Key, end: ListEnd, count: usize) -> RespData {
+    let elements = std::iter::from_fn(|| end.pop(list))
+        .take(count)
+        .map(|element| RespData::BulkString(Some(element)))
+        .collect();
+    RespData::Array(vec![
+        RespData::BulkString(Some(key.clone())),
+        RespData::Array(elements),
+    ])
+}
+
+impl ExecuteCommand for LMPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        Ok(self.pop(db, stats)?.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::LMPop;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{ListEnd, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_lmpop_should_read_keys_direction_and_count() {
+        let cmd = parse_command(&build_request(
+            "LMPOP",
+            &["2", "a", "b", "right", "count", "3"],
+        ))
+        .expect("parse lmpop");
+        assert_eq!(
+            cmd,
+            Command::LMPop(LMPop {
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                end: ListEnd::Right,
+                count: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_lmpop_should_reject_zero_numkeys_and_count() {
+        let err =
+            parse_command(&build_request("LMPOP", &["0", "a", "LEFT"])).expect_err("numkeys 0");
+        assert_eq!(err, ParseError::InvalidArgument("numkeys 0".to_string()));
+
+        let err = parse_command(&build_request("LMPOP", &["1", "a", "LEFT", "COUNT", "0"]))
+            .expect_err("count 0");
+        assert_eq!(err, ParseError::InvalidArgument("COUNT 0".to_string()));
+    }
+
+    #[tokio::test]
+    async fn execute_lmpop_should_pop_from_first_non_empty_list() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("b"),
+            (
+                Value::List([Bytes::from_owner("x"), Bytes::from_owner("y")].into()),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request(
+            "LMPOP",
+            &["2", "a", "b", "LEFT", "COUNT", "5"],
+        ))
+        .unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lmpop");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::BulkString(Some(Bytes::from_owner("b"))),
+                RespData::Array(vec![
+                    RespData::BulkString(Some(Bytes::from_owner("x"))),
+                    RespData::BulkString(Some(Bytes::from_owner("y"))),
+                ]),
+            ])
+        );
+        assert!(server.lock().await.db.is_empty());
+
+        let resp = cmd.execute(server, &mut conn).await.expect("execute lmpop");
+        assert_eq!(resp, RespData::NullArray);
+    }
+}
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_list,
+    },
+    db::List,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LPos {
+    key: Bytes,
+    element: Bytes,
+    /// Skip the first `rank - 1` matches, scanning from the tail when negative.
+    rank: i64,
+    /// Reply with an array of up to `count` positions, zero meaning all of them.
+    count: Option<usize>,
+    /// Compare at most `maxlen` elements, zero meaning the whole list.
+    maxlen: usize,
+}
+
+impl Parse for LPos {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+
+        let mut lpos = LPos {
+            key: args[0].clone(),
+            element: args[1].clone(),
+            rank: 1,
+            count: None,
+            maxlen: 0,
+        };
+
+        let mut i = 2;
+        while i < args.len() {
+            let argument = str::from_utf8(&args[i])?.to_string();
+            let value = args.get(i + 1).ok_or(ParseError::ExpectLengthGe(
+                i + 2,
+                args.len(),
+                args.to_vec(),
+            ))?;
+            match argument.to_uppercase().as_str() {
+                "RANK" => {
+                    lpos.rank = lexical_core::parse(value)?;
+                    // Negating i64::MIN would overflow
+                    if lpos.rank == 0 || lpos.rank == i64::MIN {
+                        return Err(ParseError::InvalidArgument(format!(
+                            "{} {}",
+                            argument, lpos.rank
+                        )));
+                    }
+                }
+                "COUNT" => lpos.count = Some(lexical_core::parse(value)?),
+                "MAXLEN" => lpos.maxlen = lexical_core::parse(value)?,
+                _ => return Err(ParseError::InvalidArgument(argument)),
+            }
+            i += 2;
+        }
+
+        Ok(lpos)
+    }
+}
+
+impl LPos {
+    fn positions(&self, list: &List, indexes: impl Iterator<Item = usize>) -> Vec<usize> {
+        let maxlen = if self.maxlen == 0 {
+            list.len()
+        } else {
+            self.maxlen
+        };
+        let limit = match self.count {
+            None => 1,
+            Some(0) => usize::MAX,
+            Some(count) => count,
+        };
+        indexes
+            .take(maxlen)
+            .filter(|&i| list[i] == self.element)
+            .skip(self.rank.unsigned_abs() as usize - 1)
+            .take(limit)
+            .collect()
+    }
+}
+
+impl ExecuteCommand for LPos {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let positions = match get_list(db, stats, &self.key)? {
+            None => vec![],
+            Some(list) if self.rank > 0 => self.positions(list, 0..list.len()),
+            Some(list) => self.positions(list, (0..list.len()).rev()),
+        };
+
+        Ok(match self.count {
+            None => positions
+                .first()
+                .map_or(RespData::BulkString(None), |&i| RespData::Integer(i as i64)),
+            Some(_) => RespData::Array(
+                positions
+                    .into_iter()
+                    .map(|i| RespData::Integer(i as i64))
+                    .collect(),
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::LPos;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_lpos_should_read_options() {
+        let cmd = parse_command(&build_request(
+            "LPOS",
+            &["list", "a", "rank", "-2", "COUNT", "0", "MAXLEN", "10"],
+        ))
+        .expect("parse lpos");
+        assert_eq!(
+            cmd,
+            Command::LPos(LPos {
+                key: Bytes::from_owner("list"),
+                element: Bytes::from_owner("a"),
+                rank: -2,
+                count: Some(0),
+                maxlen: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_lpos_should_reject_zero_rank() {
+        let err = parse_command(&build_request("LPOS", &["list", "a", "RANK", "0"]))
+            .expect_err("rank can't be zero");
+        assert_eq!(err, ParseError::InvalidArgument("RANK 0".to_string()));
+    }
+
+    #[tokio::test]
+    async fn execute_lpos_should_find_positions() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (
+                Value::List(
+                    ["a", "b", "c", "1", "2", "3", "c", "c"]
+                        .into_iter()
+                        .map(Bytes::from_owner)
+                        .collect(),
+                ),
+                None,
+            ),
+        );
+
+        for (args, expected) in [
+            (vec!["list", "c"], RespData::Integer(2)),
+            (vec!["list", "c", "RANK", "2"], RespData::Integer(6)),
+            (vec!["list", "c", "RANK", "-1"], RespData::Integer(7)),
+            (
+                vec!["list", "c", "COUNT", "2"],
+                RespData::Array(vec![RespData::Integer(2), RespData::Integer(6)]),
+            ),
+            (
+                vec!["list", "c", "RANK", "-1", "COUNT", "0"],
+                RespData::Array(vec![
+                    RespData::Integer(7),
+                    RespData::Integer(6),
+                    RespData::Integer(2),
+                ]),
+            ),
+            (
+                vec!["list", "c", "COUNT", "0", "MAXLEN", "7"],
+                RespData::Array(vec![RespData::Integer(2), RespData::Integer(6)]),
+            ),
+            (vec!["list", "x"], RespData::BulkString(None)),
+            (vec!["missing", "x", "COUNT", "1"], RespData::Array(vec![])),
+        ] {
+            let cmd = parse_command(&build_request("LPOS", &args)).expect("parse lpos");
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute lpos");
+            assert_eq!(resp, expected, "LPOS {args:?}");
+        }
+    }
+}
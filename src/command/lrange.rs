@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_list},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::normalize_range,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LRange {
+    key: Bytes,
+    start: i64,
+    stop: i64,
+}
+
+impl Parse for LRange {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(LRange {
+            key: args[0].clone(),
+            start: lexical_core::parse(&args[1])?,
+            stop: lexical_core::parse(&args[2])?,
+        })
+    }
+}
+
+impl ExecuteCommand for LRange {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(list) = get_list(db, stats, &self.key)? else {
+            return Ok(RespData::Array(vec![]));
+        };
+        let Some((start, stop)) = normalize_range(self.start, self.stop, list.len()) else {
+            return Ok(RespData::Array(vec![]));
+        };
+
+        Ok(RespData::Array(
+            list.range(start..=stop)
+                .map(|element| RespData::BulkString(Some(element.clone())))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::LRange;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_lrange_should_read_negative_indexes() {
+        let cmd =
+            parse_command(&build_request("LRANGE", &["list", "0", "-1"])).expect("parse lrange");
+        assert_eq!(
+            cmd,
+            Command::LRange(LRange {
+                key: Bytes::from_owner("list"),
+                start: 0,
+                stop: -1,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_lrange_should_reject_non_integer_index() {
+        let err =
+            parse_command(&build_request("LRANGE", &["list", "a", "1"])).expect_err("bad index");
+        assert!(matches!(err, ParseError::LexicalCoreError(_)));
+    }
+
+    #[tokio::test]
+    async fn execute_lrange_should_clamp_ranges() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (
+                Value::List(VecDeque::from([
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("b"),
+                    Bytes::from_owner("c"),
+                ])),
+                None,
+            ),
+        );
+
+        for (start, stop, expected) in [
+            (0, -1, vec!["a", "b", "c"]),
+            (-2, 100, vec!["b", "c"]),
+            (-100, 0, vec!["a"]),
+            (2, 1, vec![]),
+            (5, 10, vec![]),
+        ] {
+            let cmd = LRange {
+                key: Bytes::from_owner("list"),
+                start,
+                stop,
+            };
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute lrange");
+            assert_eq!(
+                resp,
+                RespData::Array(
+                    expected
+                        .into_iter()
+                        .map(|s| RespData::BulkString(Some(Bytes::from_owner(s))))
+                        .collect()
+                ),
+                "LRANGE list {start} {stop}"
+            );
+        }
+    }
+}
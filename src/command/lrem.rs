@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_list},
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LRem {
+    key: Bytes,
+    /// Positive counts remove from head to tail, negative ones from tail to head, and zero removes
+    /// every match.
+    count: i64,
+    element: Bytes,
+}
+
+impl Parse for LRem {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(LRem {
+            key: args[0].clone(),
+            count: lexical_core::parse(&args[1])?,
+            element: args[2].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for LRem {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(list) = get_list(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(0));
+        };
+
+        let limit = match self.count {
+            0 => usize::MAX,
+            count => count.unsigned_abs() as usize,
+        };
+        let mut removed = 0;
+        if self.count >= 0 {
+            let mut i = 0;
+            while i < list.len() && removed < limit {
+                if list[i] == self.element {
+                    list.remove(i);
+                    removed += 1;
+                } else {
+                    i += 1;
+                }
+            }
+        } else {
+            let mut i = list.len();
+            while i > 0 && removed < limit {
+                i -= 1;
+                if list[i] == self.element {
+                    list.remove(i);
+                    removed += 1;
+                }
+            }
+        }
+        remove_if_empty(db, &self.key);
+
+        Ok(RespData::Integer(removed as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::LRem;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    fn list(elements: &[&'static str]) -> Value {
+        Value::List(
+            elements
+                .iter()
+                .map(|s| Bytes::from_static(s.as_bytes()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parse_lrem_should_read_count_and_element() {
+        let cmd = parse_command(&build_request("LREM", &["list", "-2", "a"])).expect("parse lrem");
+        assert_eq!(
+            cmd,
+            Command::LRem(LRem {
+                key: Bytes::from_owner("list"),
+                count: -2,
+                element: Bytes::from_owner("a"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_lrem_should_respect_count_direction() {
+        let (server, mut conn) = build_server_connection().await;
+        for (count, removed, remaining) in [
+            (1, 1, vec!["b", "a", "c", "a"]),
+            (-1, 1, vec!["a", "b", "a", "c"]),
+            (0, 3, vec!["b", "c"]),
+        ] {
+            server.lock().await.db.insert(
+                Bytes::from_owner("list"),
+                (list(&["a", "b", "a", "c", "a"]), None),
+            );
+            let cmd = LRem {
+                key: Bytes::from_owner("list"),
+                count,
+                element: Bytes::from_owner("a"),
+            };
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute lrem");
+            assert_eq!(resp, RespData::Integer(removed));
+            assert_eq!(
+                server.lock().await.db.get(b"list".as_ref()),
+                Some(&(list(&remaining), None)),
+                "LREM list {count} a"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_lrem_should_remove_emptied_list() {
+        let (server, mut conn) = build_server_connection().await;
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("list"), (list(&["a", "a"]), None));
+        let cmd = LRem {
+            key: Bytes::from_owner("list"),
+            count: 0,
+            element: Bytes::from_owner("a"),
+        };
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lrem");
+        assert_eq!(resp, RespData::Integer(2));
+        assert!(server.lock().await.db.is_empty());
+    }
+}
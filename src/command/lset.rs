@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq,
+        error::{ExecError, ExecResult},
+        get_list,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    utils::normalize_index,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LSet {
+    key: Bytes,
+    index: i64,
+    element: Bytes,
+}
+
+impl Parse for LSet {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(LSet {
+            key: args[0].clone(),
+            index: lexical_core::parse(&args[1])?,
+            element: args[2].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for LSet {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let list = get_list(db, stats, &self.key)?.ok_or(ExecError::NoSuchKey)?;
+        let index = normalize_index(self.index, list.len()).ok_or(ExecError::IndexOutOfRange)?;
+        list[index] = self.element.clone();
+
+        Ok(RespData::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::LSet;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_lset_should_read_index_and_element() {
+        let cmd = parse_command(&build_request("LSET", &["list", "-1", "x"])).expect("parse lset");
+        assert_eq!(
+            cmd,
+            Command::LSet(LSet {
+                key: Bytes::from_owner("list"),
+                index: -1,
+                element: Bytes::from_owner("x"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_lset_should_replace_element() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (
+                Value::List(VecDeque::from([
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("b"),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = LSet {
+            key: Bytes::from_owner("list"),
+            index: -1,
+            element: Bytes::from_owner("x"),
+        };
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lset");
+        assert_eq!(resp, RespData::SimpleString("OK".to_string()));
+        assert_eq!(
+            server.lock().await.db.get(b"list".as_ref()),
+            Some(&(
+                Value::List(VecDeque::from([
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("x")
+                ])),
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_lset_should_report_missing_key_and_bad_index() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = LSet {
+            key: Bytes::from_owner("list"),
+            index: 0,
+            element: Bytes::from_owner("x"),
+        };
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("no such key");
+        assert_eq!(err, ExecError::NoSuchKey);
+
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+        );
+        let cmd = LSet {
+            key: Bytes::from_owner("list"),
+            index: 1,
+            element: Bytes::from_owner("x"),
+        };
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("out of range");
+        assert_eq!(err, ExecError::IndexOutOfRange);
+    }
+}
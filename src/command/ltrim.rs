@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_list},
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+    utils::normalize_range,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct LTrim {
+    key: Bytes,
+    start: i64,
+    stop: i64,
+}
+
+impl Parse for LTrim {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(LTrim {
+            key: args[0].clone(),
+            start: lexical_core::parse(&args[1])?,
+            stop: lexical_core::parse(&args[2])?,
+        })
+    }
+}
+
+impl ExecuteCommand for LTrim {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        if let Some(list) = get_list(db, stats, &self.key)? {
+            match normalize_range(self.start, self.stop, list.len()) {
+                Some((start, stop)) => {
+                    list.truncate(stop + 1);
+                    list.drain(..start);
+                }
+                None => list.clear(),
+            }
+            remove_if_empty(db, &self.key);
+        }
+
+        Ok(RespData::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::LTrim;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    fn list(elements: &[&'static str]) -> Value {
+        Value::List(
+            elements
+                .iter()
+                .map(|s| Bytes::from_static(s.as_bytes()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parse_ltrim_should_read_range() {
+        let cmd =
+            parse_command(&build_request("LTRIM", &["list", "1", "-1"])).expect("parse ltrim");
+        assert_eq!(
+            cmd,
+            Command::LTrim(LTrim {
+                key: Bytes::from_owner("list"),
+                start: 1,
+                stop: -1,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_ltrim_should_keep_only_range() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (list(&["a", "b", "c", "d"]), None),
+        );
+
+        let cmd = LTrim {
+            key: Bytes::from_owner("list"),
+            start: 1,
+            stop: -2,
+        };
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute ltrim");
+        assert_eq!(resp, RespData::SimpleString("OK".to_string()));
+        assert_eq!(
+            server.lock().await.db.get(b"list".as_ref()),
+            Some(&(list(&["b", "c"]), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_ltrim_should_remove_list_for_empty_range() {
+        let (server, mut conn) = build_server_connection().await;
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("list"), (list(&["a", "b"]), None));
+
+        let cmd = LTrim {
+            key: Bytes::from_owner("list"),
+            start: 5,
+            stop: 10,
+        };
+        cmd.execute(server.clone(), &mut conn)
+            .await
+            .expect("execute ltrim");
+        assert!(server.lock().await.db.is_empty());
+    }
+}
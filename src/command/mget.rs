@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult},
+    db::{Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct MGet {
+    keys: Vec<Bytes>,
+}
+
+impl Parse for MGet {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(MGet {
+            keys: args.to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for MGet {
+    /// Replies nil for the keys missing or not holding a string, instead of an error.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let values = self
+            .keys
+            .iter()
+            .map(|key| match lookup_key(db, stats, key) {
+                Some((Value::String(value), _)) => RespData::BulkString(Some(value.clone())),
+                _ => RespData::BulkString(None),
+            })
+            .collect();
+        Ok(RespData::Array(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::MGet;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_mget_should_read_keys() {
+        let cmd = parse_command(&build_request("MGET", &["a", "b"])).expect("parse mget");
+        assert_eq!(
+            cmd,
+            Command::MGet(MGet {
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("MGET", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_mget_should_reply_nil_for_missing_and_non_string() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("a"),
+                (Value::String(Bytes::from_owner("1")), None),
+            );
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("x")])), None),
+            );
+        }
+
+        let cmd = parse_command(&build_request("MGET", &["a", "missing", "list", "a"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute mget");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+                RespData::BulkString(None),
+                RespData::BulkString(None),
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+            ])
+        );
+    }
+}
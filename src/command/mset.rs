@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, ParseResult, check_pairs, error::ExecResult},
+    db::{Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// MSET and MSETNX.
+#[derive(Debug, PartialEq)]
+pub struct MSet {
+    pairs: Vec<(Bytes, Bytes)>,
+    /// MSETNX, which sets nothing if any key already exists.
+    nx: bool,
+}
+
+impl MSet {
+    pub fn parse(args: &[Bytes], nx: bool) -> ParseResult<Self> {
+        check_pairs(args, 0)?;
+        Ok(MSet {
+            pairs: args
+                .chunks_exact(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+            nx,
+        })
+    }
+}
+
+impl ExecuteCommand for MSet {
+    /// MSET replies OK, MSETNX replies 1 if the keys were set and 0 otherwise.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        if self.nx
+            && self
+                .pairs
+                .iter()
+                .any(|(key, _)| lookup_key(db, stats, key).is_some())
+        {
+            return Ok(RespData::Integer(0));
+        }
+        for (key, value) in &self.pairs {
+            db.insert(key.clone(), (Value::String(value.clone()), None));
+        }
+        Ok(if self.nx {
+            RespData::Integer(1)
+        } else {
+            RespData::SimpleString("OK".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::MSet;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_mset_should_read_pairs() {
+        let cmd =
+            parse_command(&build_request("MSETNX", &["a", "1", "b", "2"])).expect("parse msetnx");
+        assert_eq!(
+            cmd,
+            Command::MSet(MSet {
+                pairs: vec![
+                    (Bytes::from_owner("a"), Bytes::from_owner("1")),
+                    (Bytes::from_owner("b"), Bytes::from_owner("2")),
+                ],
+                nx: true,
+            })
+        );
+        assert!(parse_command(&build_request("MSET", &["a", "1", "b"])).is_err());
+        assert!(parse_command(&build_request("MSET", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_mset_should_overwrite_keys() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("a"),
+            (
+                Value::List(VecDeque::from([Bytes::from_owner("x")])),
+                Some(unix_time_ms() + 60_000),
+            ),
+        );
+
+        let cmd = parse_command(&build_request("MSET", &["a", "1", "b", "2", "a", "3"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute mset");
+        assert_eq!(resp, RespData::SimpleString("OK".to_string()));
+
+        let db = &server.lock().await.db;
+        for (key, value) in [("a", "3"), ("b", "2")] {
+            assert_eq!(
+                db.get(key.as_bytes()),
+                Some(&(Value::String(Bytes::from_owner(value)), None))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_msetnx_should_set_all_or_nothing() {
+        let (server, mut conn) = build_server_connection().await;
+
+        for (args, expected) in [
+            (&["a", "1", "b", "2"][..], 1),
+            (&["c", "3", "b", "4"], 0),
+            (&["c", "3", "d", "4"], 1),
+        ] {
+            let cmd = parse_command(&build_request("MSETNX", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute msetnx");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        for (key, value) in [("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")] {
+            assert_eq!(
+                db.get(key.as_bytes()),
+                Some(&(Value::String(Bytes::from_owner(value)), None))
+            );
+        }
+    }
+}
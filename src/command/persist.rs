@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult},
+    db::lookup_key,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Persist {
+    key: Bytes,
+}
+
+impl Parse for Persist {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(Persist {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for Persist {
+    /// Replies 1 once the expire time of the key is removed, and 0 if the key does not exist or
+    /// has no expire time.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let persisted = lookup_key(db, stats, &self.key)
+            .and_then(|(_, expire_time)| expire_time.take())
+            .is_some();
+        Ok(RespData::Integer(persisted as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::Persist;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[tokio::test]
+    async fn execute_persist_should_remove_expire_time() {
+        assert_eq!(
+            parse_command(&build_request("PERSIST", &["k"])).expect("parse persist"),
+            Command::Persist(Persist {
+                key: Bytes::from_owner("k"),
+            })
+        );
+
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (
+                Value::String(Bytes::from_owner("v")),
+                Some(unix_time_ms() + 60_000),
+            ),
+        );
+
+        for (key, expected) in [("k", 1), ("k", 0), ("missing", 0)] {
+            let cmd = parse_command(&build_request("PERSIST", &[key])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute persist");
+            assert_eq!(resp, RespData::Integer(expected), "{key}");
+        }
+        assert_eq!(server.lock().await.db.get(b"k".as_ref()).unwrap().1, None);
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_hyperloglog,
+        get_or_insert_string,
+    },
+    hyperloglog::HyperLogLog,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct PfAdd {
+    key: Bytes,
+    elements: Vec<Bytes>,
+}
+
+impl Parse for PfAdd {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(PfAdd {
+            key: args[0].clone(),
+            elements: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for PfAdd {
+    /// Replies 1 if the key was created or a register changed, else 0.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let (mut hll, mut updated) = match get_hyperloglog(db, stats, &self.key)? {
+            Some(hll) => (hll, false),
+            None => (HyperLogLog::default(), true),
+        };
+        for element in &self.elements {
+            updated |= hll.add(element);
+        }
+        if updated {
+            // Updated in place, so the key keeps its expire time
+            *get_or_insert_string(db, stats, &self.key)? = hll.to_bytes();
+        }
+
+        Ok(RespData::Integer(updated as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::PfAdd;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        hyperloglog::HyperLogLog,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_pfadd_should_read_key_and_elements() {
+        let cmd = parse_command(&build_request("PFADD", &["hll", "a", "b"])).expect("parse pfadd");
+        assert_eq!(
+            cmd,
+            Command::PfAdd(PfAdd {
+                key: Bytes::from_owner("hll"),
+                elements: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("PFADD", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_pfadd_should_reply_whether_updated() {
+        let (server, mut conn) = build_server_connection().await;
+        let expire_time = Some(unix_time_ms() + 60_000);
+
+        for (args, expected) in [
+            (&["empty"][..], 1),
+            (&["empty"], 0),
+            (&["hll", "a", "b", "c"], 1),
+            (&["hll", "a", "b", "c"], 0),
+            (&["hll", "d"], 1),
+        ] {
+            let cmd = parse_command(&build_request("PFADD", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute pfadd");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let mut guard = server.lock().await;
+        let db = &mut guard.db;
+        assert_eq!(
+            db.get(b"empty".as_ref()),
+            Some(&(Value::String(HyperLogLog::default().to_bytes()), None))
+        );
+        let Some((Value::String(value), ttl)) = db.get_mut(b"hll".as_ref()) else {
+            panic!("hll should be a string");
+        };
+        let hll = HyperLogLog::from_bytes(value).unwrap();
+        assert_eq!(hll.count(), 4);
+        assert_eq!(hll.cached_count(), None);
+        *ttl = expire_time;
+        drop(guard);
+
+        let cmd = parse_command(&build_request("PFADD", &["hll", "e"])).unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(
+            server.lock().await.db.get(b"hll".as_ref()).unwrap().1,
+            expire_time
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_pfadd_should_reject_invalid_values() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("string"),
+                (Value::String(Bytes::from_owner("abc")), None),
+            );
+            db.insert(
+                Bytes::from_owner("corrupted"),
+                (
+                    Value::String(Bytes::from_owner(
+                        b"HYLL\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f\xfe",
+                    )),
+                    None,
+                ),
+            );
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+            );
+        }
+
+        for (key, expected) in [
+            ("string", ExecError::NotHyperLogLog),
+            ("corrupted", ExecError::CorruptedHyperLogLog),
+            ("list", ExecError::WrongType),
+        ] {
+            let cmd = parse_command(&build_request("PFADD", &[key, "a"])).unwrap();
+            let err = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect_err("pfadd should fail");
+            assert_eq!(err, expected, "{key}");
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_hyperloglog,
+        get_string, update_string,
+    },
+    hyperloglog::{self, HyperLogLog},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct PfCount {
+    keys: Vec<Bytes>,
+}
+
+impl Parse for PfCount {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(PfCount {
+            keys: args.to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for PfCount {
+    /// The cardinality of a single key is cached in its value, while that of the union of several
+    /// keys is computed every time.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        if let [key] = &self.keys[..] {
+            let Some(string) = get_string(db, stats, key)? else {
+                return Ok(RespData::Integer(0));
+            };
+            // Only the header is needed while the cache is valid
+            if let Some(count) = hyperloglog::read_cached_count(string)? {
+                return Ok(RespData::Integer(count as i64));
+            }
+            let count = HyperLogLog::from_bytes(string)?.count();
+            update_string(string, |bytes| {
+                hyperloglog::write_cached_count(bytes, count)
+            });
+            return Ok(RespData::Integer(count as i64));
+        }
+
+        let mut union = HyperLogLog::default();
+        for key in &self.keys {
+            if let Some(hll) = get_hyperloglog(db, stats, key)? {
+                union.merge(&hll);
+            }
+        }
+        Ok(RespData::Integer(union.count() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::PfCount;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        hyperloglog::HyperLogLog,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_pfcount_should_read_keys() {
+        let cmd = parse_command(&build_request("PFCOUNT", &["a", "b"])).expect("parse pfcount");
+        assert_eq!(
+            cmd,
+            Command::PfCount(PfCount {
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("PFCOUNT", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_pfcount_should_count_union_and_cache() {
+        let (server, mut conn) = build_server_connection().await;
+        for args in [
+            &["a", "1", "2", "3"][..],
+            &["b", "3", "4", "5", "6"],
+            &["other", "x"],
+        ] {
+            let cmd = parse_command(&build_request("PFADD", args)).unwrap();
+            cmd.execute(server.clone(), &mut conn).await.unwrap();
+        }
+
+        for (args, expected) in [
+            (&["a"][..], 3),
+            (&["b"], 4),
+            (&["missing"], 0),
+            (&["a", "b"], 6),
+            (&["a", "b", "missing", "a"], 6),
+        ] {
+            let cmd = parse_command(&build_request("PFCOUNT", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute pfcount");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        for (key, cached) in [("a", Some(3)), ("b", Some(4)), ("other", None)] {
+            let Some((Value::String(value), _)) = db.get(key.as_bytes()) else {
+                panic!("{key} should be a string");
+            };
+            assert_eq!(
+                HyperLogLog::from_bytes(value).unwrap().cached_count(),
+                cached,
+                "{key}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_pfcount_should_reject_invalid_values() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("string"),
+            (Value::String(Bytes::from_owner("HYLL")), None),
+        );
+
+        for args in [&["string"][..], &["missing", "string"]] {
+            let cmd = parse_command(&build_request("PFCOUNT", args)).unwrap();
+            let err = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect_err("pfcount should fail");
+            assert_eq!(err, ExecError::NotHyperLogLog, "{args:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_pfcount_should_trust_valid_cache_without_decoding() {
+        let (server, mut conn) = build_server_connection().await;
+        // Sparse registers truncated mid opcode, behind a cached cardinality of 7
+        let corrupted = b"HYLL\x01\x00\x00\x00\x07\x00\x00\x00\x00\x00\x00\x00\x7f";
+        server.lock().await.db.insert(
+            Bytes::from_owner("hll"),
+            (Value::String(Bytes::from_static(corrupted)), None),
+        );
+
+        let cmd = parse_command(&build_request("PFCOUNT", &["hll"])).unwrap();
+        let resp = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect("execute pfcount");
+        assert_eq!(resp, RespData::Integer(7));
+    }
+}
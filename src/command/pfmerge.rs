@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_hyperloglog,
+        get_or_insert_string,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct PfMerge {
+    destination: Bytes,
+    sources: Vec<Bytes>,
+}
+
+impl Parse for PfMerge {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        Ok(PfMerge {
+            destination: args[0].clone(),
+            sources: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for PfMerge {
+    /// The destination is part of the union if it exists. The result is dense if any of the
+    /// merged HyperLogLogs was.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let mut merged = get_hyperloglog(db, stats, &self.destination)?.unwrap_or_default();
+        for source in &self.sources {
+            if let Some(hll) = get_hyperloglog(db, stats, source)? {
+                merged.merge(&hll);
+            }
+        }
+        merged.invalidate_cache();
+        // Updated in place, so the key keeps its expire time
+        *get_or_insert_string(db, stats, &self.destination)? = merged.to_bytes();
+
+        Ok(RespData::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::PfMerge;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        hyperloglog::HyperLogLog,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_pfmerge_should_read_destination_and_sources() {
+        let cmd = parse_command(&build_request("PFMERGE", &["dest", "a", "b"])).expect("parse");
+        assert_eq!(
+            cmd,
+            Command::PfMerge(PfMerge {
+                destination: Bytes::from_owner("dest"),
+                sources: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("PFMERGE", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_pfmerge_should_store_union() {
+        let (server, mut conn) = build_server_connection().await;
+        for args in [&["a", "1", "2", "3"][..], &["b", "3", "4"], &["dest", "5"]] {
+            let cmd = parse_command(&build_request("PFADD", args)).unwrap();
+            cmd.execute(server.clone(), &mut conn).await.unwrap();
+        }
+        let dense = large_hll();
+        // A dense source makes the result dense
+        assert_eq!(dense[4], 0);
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("dense"), (Value::String(dense), None));
+
+        for (args, expected) in [
+            (&["dest", "a", "b", "missing"][..], 5),
+            (&["new"], 0),
+            (&["large", "dense", "a"], 4003),
+        ] {
+            let cmd = parse_command(&build_request("PFMERGE", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute pfmerge");
+            assert_eq!(resp, RespData::SimpleString("OK".to_string()), "{args:?}");
+
+            let db = &server.lock().await.db;
+            let Some((Value::String(value), _)) = db.get(args[0].as_bytes()) else {
+                panic!("{args:?} should store a string");
+            };
+            let hll = HyperLogLog::from_bytes(value).unwrap();
+            assert_eq!(hll.cached_count(), None, "{args:?}");
+            let count = hll.count() as f64;
+            assert!(
+                (count - expected as f64).abs() <= expected as f64 * 0.02,
+                "{args:?}: {count}"
+            );
+        }
+
+        let db = &server.lock().await.db;
+        let Some((Value::String(value), _)) = db.get(b"large".as_ref()) else {
+            panic!("large should be a string");
+        };
+        // Dense encoding
+        assert_eq!(value[4], 0);
+    }
+
+    #[tokio::test]
+    async fn execute_pfmerge_should_reject_invalid_values() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("string"),
+            (Value::String(Bytes::from_owner("abc")), None),
+        );
+
+        for args in [&["string", "missing"][..], &["dest", "string"]] {
+            let cmd = parse_command(&build_request("PFMERGE", args)).unwrap();
+            let err = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect_err("pfmerge should fail");
+            assert_eq!(err, ExecError::NotHyperLogLog, "{args:?}");
+        }
+        assert!(!server.lock().await.db.contains_key(b"dest".as_ref()));
+    }
+
+    /// A HyperLogLog of 4000 elements, too many for the sparse encoding.
+    fn large_hll() -> Bytes {
+        let mut hll = HyperLogLog::default();
+        for i in 0..4000 {
+            hll.add(format!("element:{i}").as_bytes());
+        }
+        hll.to_bytes()
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_list,
+    },
+    db::{ListEnd, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// LPOP and RPOP.
+#[derive(Debug, PartialEq)]
+pub struct Pop {
+    key: Bytes,
+    /// Without a count a single element is replied instead of an array.
+    count: Option<usize>,
+    end: ListEnd,
+}
+
+impl Pop {
+    pub fn parse(args: &[Bytes], end: ListEnd) -> ParseResult<Self> {
+        check_length_ge(args, 1)?;
+        let count = match args {
+            [_] => None,
+            [_, count] => Some(lexical_core::parse(count)?),
+            _ => {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(&args[2])?.to_string(),
+                ));
+            }
+        };
+        Ok(Pop {
+            key: args[0].clone(),
+            count,
+            end,
+        })
+    }
+}
+
+impl ExecuteCommand for Pop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(list) = get_list(db, stats, &self.key)? else {
+            return Ok(match self.count {
+                None => RespData::BulkString(None),
+                Some(_) => RespData::NullArray,
+            });
+        };
+
+        let resp = match self.count {
+            None => RespData::BulkString(self.end.pop(list)),
+            Some(count) => RespData::Array(
+                std::iter::from_fn(|| self.end.pop(list))
+                    .take(count)
+                    .map(|element| RespData::BulkString(Some(element)))
+                    .collect(),
+            ),
+        };
+        remove_if_empty(db, &self.key);
+
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::Pop;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{ListEnd, Value},
+        resp::RespData,
+    };
+
+    fn bulk(s: &'static str) -> RespData {
+        RespData::BulkString(Some(Bytes::from_static(s.as_bytes())))
+    }
+
+    #[test]
+    fn parse_pop_should_read_optional_count() {
+        let cmd = parse_command(&build_request("RPOP", &["list", "2"])).expect("parse rpop");
+        assert_eq!(
+            cmd,
+            Command::Pop(Pop {
+                key: Bytes::from_owner("list"),
+                count: Some(2),
+                end: ListEnd::Right,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_pop_should_reject_negative_count() {
+        let err =
+            parse_command(&build_request("LPOP", &["list", "-1"])).expect_err("negative count");
+        assert!(matches!(err, ParseError::LexicalCoreError(_)));
+    }
+
+    #[tokio::test]
+    async fn execute_pop_should_pop_and_remove_empty_list() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (
+                Value::List(VecDeque::from([
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("b"),
+                    Bytes::from_owner("c"),
+                ])),
+                None,
+            ),
+        );
+
+        let lpop = parse_command(&build_request("LPOP", &["list"])).unwrap();
+        let resp = lpop
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lpop");
+        assert_eq!(resp, bulk("a"));
+
+        let rpop = parse_command(&build_request("RPOP", &["list", "5"])).unwrap();
+        let resp = rpop
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute rpop");
+        assert_eq!(resp, RespData::Array(vec![bulk("c"), bulk("b")]));
+        assert!(server.lock().await.db.is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_pop_should_return_null_for_missing_key() {
+        let (server, mut conn) = build_server_connection().await;
+        let lpop = parse_command(&build_request("LPOP", &["list"])).unwrap();
+        let resp = lpop
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lpop");
+        assert_eq!(resp, RespData::BulkString(None));
+
+        let lpop = parse_command(&build_request("LPOP", &["list", "1"])).unwrap();
+        let resp = lpop.execute(server, &mut conn).await.expect("execute lpop");
+        assert_eq!(resp, RespData::NullArray);
+    }
+}
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge, error::ExecResult, get_list,
+        get_or_insert_list,
+    },
+    db::ListEnd,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// LPUSH, RPUSH, LPUSHX and RPUSHX.
+#[derive(Debug, PartialEq)]
+pub struct Push {
+    key: Bytes,
+    elements: Vec<Bytes>,
+    end: ListEnd,
+    /// The X variants only push onto a list that already exists.
+    only_existing: bool,
+}
+
+impl Push {
+    pub fn parse(args: &[Bytes], end: ListEnd, only_existing: bool) -> ParseResult<Self> {
+        check_length_ge(args, 2)?;
+        Ok(Push {
+            key: args[0].clone(),
+            elements: args[1..].to_vec(),
+            end,
+            only_existing,
+        })
+    }
+}
+
+impl ExecuteCommand for Push {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+
+        let list = if self.only_existing {
+            match get_list(db, stats, &self.key)? {
+                Some(list) => list,
+                None => return Ok(RespData::Integer(0)),
+            }
+        } else {
+            get_or_insert_list(db, stats, &self.key)?
+        };
+        for element in &self.elements {
+            self.end.push(list, element.clone());
+        }
+        let len = list.len();
+        server.serve_blocked_clients(&self.key);
+
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::Push;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{ListEnd, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_push_should_read_key_and_elements() {
+        let cmd =
+            parse_command(&build_request("RPUSHX", &["list", "a", "b"])).expect("parse rpushx");
+        assert_eq!(
+            cmd,
+            Command::Push(Push {
+                key: Bytes::from_owner("list"),
+                elements: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                end: ListEnd::Right,
+                only_existing: true,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_push_should_reject_missing_elements() {
+        let err =
+            parse_command(&build_request("LPUSH", &["list"])).expect_err("lpush needs elements");
+        assert_eq!(
+            err,
+            ParseError::ExpectLengthGe(2, 1, vec![Bytes::from_owner("list")])
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_push_should_push_in_order_on_both_ends() {
+        let (server, mut conn) = build_server_connection().await;
+        let lpush = parse_command(&build_request("LPUSH", &["list", "a", "b"])).unwrap();
+        let rpush = parse_command(&build_request("RPUSH", &["list", "c"])).unwrap();
+
+        let resp = lpush
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lpush");
+        assert_eq!(resp, RespData::Integer(2));
+        let resp = rpush
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute rpush");
+        assert_eq!(resp, RespData::Integer(3));
+
+        assert_eq!(
+            server.lock().await.db.get(b"list".as_ref()),
+            Some(&(
+                Value::List(VecDeque::from([
+                    Bytes::from_owner("b"),
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("c"),
+                ])),
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_pushx_should_not_create_list() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("LPUSHX", &["list", "a"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute lpushx");
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(server.lock().await.db.is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_push_should_reject_string_key() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("key"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        let cmd = parse_command(&build_request("RPUSH", &["key", "a"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rand::seq::IteratorRandom;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult},
+    db::lookup_key,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct RandomKey;
+
+impl Parse for RandomKey {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 0)?;
+        Ok(RandomKey)
+    }
+}
+
+impl ExecuteCommand for RandomKey {
+    /// Replies nil if the keyspace is empty. The expired keys drawn are removed, and another key
+    /// is drawn.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let mut rng = rand::rng();
+        while let Some(key) = db.keys().choose(&mut rng).cloned() {
+            if lookup_key(db, stats, &key).is_some() {
+                return Ok(RespData::BulkString(Some(key)));
+            }
+        }
+        Ok(RespData::BulkString(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::RandomKey;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_randomkey_should_take_no_argument() {
+        let cmd = parse_command(&build_request("RANDOMKEY", &[])).expect("parse randomkey");
+        assert_eq!(cmd, Command::RandomKey(RandomKey));
+        assert!(parse_command(&build_request("RANDOMKEY", &["k"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_randomkey_should_skip_expired_keys() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("RANDOMKEY", &[])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::BulkString(None));
+
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("live"),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+            for i in 0..10 {
+                db.insert(
+                    Bytes::from(format!("expired{i}")),
+                    (
+                        Value::String(Bytes::from_owner("v")),
+                        Some(unix_time_ms() - 1000),
+                    ),
+                );
+            }
+        }
+        for _ in 0..3 {
+            let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+            assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("live"))));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecError, ExecuteCommand, ParseResult, check_length_eq, error::ExecResult},
+    db::{Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// RENAME and RENAMENX.
+#[derive(Debug, PartialEq)]
+pub struct Rename {
+    key: Bytes,
+    new_key: Bytes,
+    /// RENAMENX, which leaves an existing `new_key` alone.
+    nx: bool,
+}
+
+impl Rename {
+    pub fn parse(args: &[Bytes], nx: bool) -> ParseResult<Self> {
+        check_length_eq(args, 2)?;
+        Ok(Rename {
+            key: args[0].clone(),
+            new_key: args[1].clone(),
+            nx,
+        })
+    }
+
+    fn reply(&self, renamed: bool) -> RespData {
+        if self.nx {
+            RespData::Integer(renamed as i64)
+        } else {
+            RespData::SimpleString("OK".to_string())
+        }
+    }
+}
+
+impl ExecuteCommand for Rename {
+    /// The value keeps its expire time, and replaces the value of `new_key` unless `nx`.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        if lookup_key(db, stats, &self.key).is_none() {
+            return Err(ExecError::NoSuchKey);
+        }
+        if self.key == self.new_key {
+            return Ok(self.reply(false));
+        }
+        if self.nx && lookup_key(db, stats, &self.new_key).is_some() {
+            return Ok(self.reply(false));
+        }
+
+        // The key exists, as it was just looked up
+        let item = db.remove(&self.key).unwrap();
+        if let (Value::Hash(hash), _) = &item
+            && hash.has_volatile_fields()
+        {
+            server.volatile_hashes.insert(self.new_key.clone());
+        }
+        if item.1.is_some() {
+            server.volatile_keys.insert(self.new_key.clone());
+        }
+        db.insert(self.new_key.clone(), item);
+        server.serve_blocked_clients(&self.new_key);
+
+        Ok(self.reply(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::Rename;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_rename_should_read_keys() {
+        for (command, nx) in [("RENAME", false), ("RENAMENX", true)] {
+            let cmd = parse_command(&build_request(command, &["a", "b"])).expect("parse rename");
+            assert_eq!(
+                cmd,
+                Command::Rename(Rename {
+                    key: Bytes::from_owner("a"),
+                    new_key: Bytes::from_owner("b"),
+                    nx,
+                })
+            );
+            assert!(parse_command(&build_request(command, &["a"])).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_rename_should_move_value_and_ttl() {
+        let (server, mut conn) = build_server_connection().await;
+        let expire_time = Some(unix_time_ms() + 60_000);
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("a"),
+                (Value::String(Bytes::from_owner("1")), expire_time),
+            );
+            db.insert(
+                Bytes::from_owner("b"),
+                (Value::List(VecDeque::from([Bytes::from_owner("x")])), None),
+            );
+        }
+
+        for (command, args, expected) in [
+            ("RENAMENX", &["a", "b"][..], RespData::Integer(0)),
+            ("RENAMENX", &["a", "a"], RespData::Integer(0)),
+            (
+                "RENAME",
+                &["a", "a"],
+                RespData::SimpleString("OK".to_string()),
+            ),
+            (
+                "RENAME",
+                &["a", "b"],
+                RespData::SimpleString("OK".to_string()),
+            ),
+            ("RENAMENX", &["b", "c"], RespData::Integer(1)),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute rename");
+            assert_eq!(resp, expected, "{command} {args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        assert_eq!(db.len(), 1);
+        assert_eq!(
+            db.get(b"c".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("1")), expire_time))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_rename_should_track_volatile_hashes() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let mut hash = Hash::new();
+            let field = Bytes::from_owner("f");
+            hash.insert(field.clone(), Bytes::from_owner("v"));
+            hash.set_expire_time(&field, unix_time_ms() + 60_000);
+            server
+                .lock()
+                .await
+                .db
+                .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+        }
+
+        let cmd = parse_command(&build_request("RENAME", &["h", "renamed"])).unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert!(
+            server
+                .lock()
+                .await
+                .volatile_hashes
+                .contains(b"renamed".as_ref())
+        );
+
+        for command in ["RENAME", "RENAMENX"] {
+            let cmd = parse_command(&build_request(command, &["missing", "b"])).unwrap();
+            let err = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect_err("rename should fail");
+            assert_eq!(err, ExecError::NoSuchKey, "{command}");
+        }
+    }
+}
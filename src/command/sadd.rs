@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_or_insert_set,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SAdd {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl Parse for SAdd {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        Ok(SAdd {
+            key: args[0].clone(),
+            members: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for SAdd {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let set = get_or_insert_set(db, stats, &self.key)?;
+        let added = self
+            .members
+            .iter()
+            .filter(|member| set.insert((*member).clone()))
+            .count();
+        Ok(RespData::Integer(added as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SAdd;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_sadd_should_read_members() {
+        let cmd = parse_command(&build_request("SADD", &["s", "a", "b"])).expect("parse sadd");
+        assert_eq!(
+            cmd,
+            Command::SAdd(SAdd {
+                key: Bytes::from_owner("s"),
+                members: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("SADD", &["s"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_sadd_should_count_new_members() {
+        let (server, mut conn) = build_server_connection().await;
+
+        let cmd = parse_command(&build_request("SADD", &["s", "a", "b", "a"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute sadd");
+        assert_eq!(resp, RespData::Integer(2));
+
+        let cmd = parse_command(&build_request("SADD", &["s", "b", "c"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute sadd");
+        assert_eq!(resp, RespData::Integer(1));
+        assert_eq!(
+            server.lock().await.db.get(b"s".as_ref()),
+            Some(&(
+                Value::Set(Set::from([
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("b"),
+                    Bytes::from_owner("c"),
+                ])),
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_sadd_should_reject_string_key() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        let cmd = parse_command(&build_request("SADD", &["s", "a"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq,
+        error::{ExecError, ExecResult},
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Save;
+
+impl Parse for Save {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 0)?;
+        Ok(Save)
+    }
+}
+
+impl ExecuteCommand for Save {
+    /// Saves synchronously, blocking every other client until the RDB file is written.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = server.lock().await;
+        server.save_rdb().map_err(|err| {
+            tracing::error!("Failed to save {}: {}", server.rdb_file.display(), err);
+            ExecError::SaveFailed(err.to_string())
+        })?;
+        Ok(RespData::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bytes::Bytes;
+
+    use super::Save;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Hash, Value},
+        rdb,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_save_should_accept_no_argument() {
+        let cmd = parse_command(&build_request("SAVE", &[])).expect("parse save");
+        assert_eq!(cmd, Command::Save(Save));
+        assert!(parse_command(&build_request("SAVE", &["now"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_save_should_write_field_expire_times() {
+        let (server, mut conn) = build_server_connection().await;
+        let rdb_file = std::env::temp_dir().join(format!("save-test-{}.rdb", std::process::id()));
+        let mut hash = Hash::from([(Bytes::from_owner("f"), Bytes::from_owner("v"))]);
+        let when = unix_time_ms() + 60_000;
+        hash.set_expire_time(&Bytes::from_owner("f"), when);
+        {
+            let mut server = server.lock().await;
+            server.rdb_file = rdb_file.clone();
+            server
+                .db
+                .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+        }
+
+        let cmd = parse_command(&build_request("SAVE", &[])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute save");
+        assert_eq!(resp, RespData::SimpleString("OK".to_string()));
+
+        let db = rdb::load(&fs::read(&rdb_file).unwrap()).expect("load saved rdb");
+        fs::remove_file(&rdb_file).unwrap();
+        let Some((Value::Hash(hash), _)) = db.get(b"h".as_ref()) else {
+            panic!("expected a hash");
+        };
+        assert_eq!(hash.expire_time(b"f"), Some(when));
+
+        server.lock().await.rdb_file = rdb_file.join("missing-dir").join("dump.rdb");
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("unwritable file");
+        assert!(matches!(&err, ExecError::SaveFailed(_)));
+        assert!(
+            err.to_string()
+                .starts_with("ERR Failed to save the RDB file: No such file or directory"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_set},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SCard {
+    key: Bytes,
+}
+
+impl Parse for SCard {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(SCard {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for SCard {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let len = get_set(db, stats, &self.key)?.map_or(0, |set| set.len());
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SCard;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_scard_should_read_key() {
+        let cmd = parse_command(&build_request("SCARD", &["s"])).expect("parse scard");
+        assert_eq!(
+            cmd,
+            Command::SCard(SCard {
+                key: Bytes::from_owner("s"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_scard_should_count_members() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("set"),
+            (
+                Value::Set(Set::from([Bytes::from_owner("a"), Bytes::from_owner("b")])),
+                None,
+            ),
+        );
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        let cmd = parse_command(&build_request("SCARD", &["set"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute scard");
+        assert_eq!(resp, RespData::Integer(2));
+
+        let cmd = parse_command(&build_request("SCARD", &["missing"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute scard");
+        assert_eq!(resp, RespData::Integer(0));
+
+        let cmd = parse_command(&build_request("SCARD", &["s"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -1,129 +1,205 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
 use bytes::Bytes;
-use tokio::{sync::Mutex, time::Instant};
+use tokio::sync::Mutex;
 
 use crate::{
     command::{
         ExecuteCommand, Parse, ParseResult, check_length_ge,
-        error::{ExecResult, ParseError},
+        error::{ExecError, ExecResult, ParseError},
+        parse_expiration,
     },
+    db::{Expiration, Value, lookup_key},
     resp::RespData,
     server::{Connection, Server},
-    utils::BytesInStr,
+    utils::{BytesInStr, unix_time_ms},
 };
 
+/// The `NX | XX` condition of `SET`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SetCondition {
+    /// Only set the key if it does not exist.
+    Nx,
+    /// Only set the key if it already exists.
+    Xx,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Set {
     key: Bytes,
     value: Bytes,
-    /// represent all of the following parameters in milliseconds
     /// EX seconds -- Set the specified expire time, in seconds (a positive integer).
     /// PX milliseconds -- Set the specified expire time, in milliseconds (a positive integer).
     /// EXAT timestamp-seconds -- Set the specified Unix time at which the key will expire, in seconds (a positive integer).
     /// PXAT timestamp-milliseconds -- Set the specified Unix time at which the key will expire, in milliseconds (a positive integer).
-    expire_time: Option<u64>,
+    expiration: Option<Expiration>,
+    /// KEEPTTL -- Retain the time to live associated with the key.
+    keep_ttl: bool,
+    condition: Option<SetCondition>,
+    /// GET -- Return the old string stored at key, or nil if key did not exist.
+    get: bool,
 }
+
 impl Parse for Set {
     fn parse(args: &[Bytes]) -> ParseResult<Self> {
-        fn parse_i_to_u64(args: &[Bytes], i: usize) -> ParseResult<u64> {
-            let expire_time = lexical_core::parse(
-                args.get(i + 1)
-                    .ok_or(ParseError::ExpectLengthGe(i + 1, i, args.to_vec()))?,
-            )?;
-            Ok(expire_time)
-        }
-
-        fn check_expire_time_is_none(
-            expire_time: &Option<u64>,
-            new_name: String,
-            new_value: String,
-        ) -> ParseResult<()> {
-            if let Some(expire_time) = expire_time {
-                return Err(ParseError::ValueHasBeenSet {
-                    name: "Expire Time",
-                    value: expire_time.to_string(),
-                    new_name,
-                    new_value,
-                });
-            }
-            Ok(())
-        }
-
         check_length_ge(args, 2)?;
 
-        let key = args[0].clone();
-        let value = args[1].clone();
-
-        let mut expire_time: Option<u64> = None;
+        let mut set = Set {
+            key: args[0].clone(),
+            value: args[1].clone(),
+            expiration: None,
+            keep_ttl: false,
+            condition: None,
+            get: false,
+        };
+        // The expire option given first and its argument, as only one is allowed
+        let mut expire_option: Option<(&str, &Bytes)> = None;
 
         // 解析可选参数
         let mut i = 2;
 
         while i < args.len() {
-            let argument = str::from_utf8(&args[i])?.to_string();
-            match argument.to_uppercase().as_str() {
-                "EX" | "EXAT" => {
-                    let seconds = parse_i_to_u64(args, i)?;
-                    check_expire_time_is_none(&expire_time, argument, seconds.to_string())?;
-                    expire_time = Some(seconds * 1000);
-                    i += 2;
+            let argument = str::from_utf8(&args[i])?;
+            let option = argument.to_uppercase();
+            match option.as_str() {
+                // Repeating a condition is fine, but NX and XX exclude each other
+                "NX" | "XX" => {
+                    let condition = if option == "NX" {
+                        SetCondition::Nx
+                    } else {
+                        SetCondition::Xx
+                    };
+                    if set.condition.is_some_and(|set| set != condition) {
+                        return Err(ParseError::InvalidArgument(argument.to_string()));
+                    }
+                    set.condition = Some(condition);
+                    i += 1;
+                }
+                "GET" => {
+                    set.get = true;
+                    i += 1;
+                }
+                "KEEPTTL" => {
+                    if expire_option.is_some() {
+                        return Err(ParseError::InvalidArgument(argument.to_string()));
+                    }
+                    set.keep_ttl = true;
+                    i += 1;
                 }
-                "PX" | "PXAT" => {
-                    let ms = parse_i_to_u64(args, i)?;
-                    check_expire_time_is_none(&expire_time, argument, ms.to_string())?;
-                    expire_time = Some(ms);
+                "EX" | "PX" | "EXAT" | "PXAT" => {
+                    let time = args.get(i + 1).ok_or(ParseError::ExpectLengthGe(
+                        i + 1,
+                        i,
+                        args.to_vec(),
+                    ))?;
+                    if let Some((_, value)) = expire_option {
+                        return Err(ParseError::ValueHasBeenSet {
+                            name: "Expire Time",
+                            value: String::from_utf8_lossy(value).into_owned(),
+                            new_name: argument.to_string(),
+                            new_value: String::from_utf8_lossy(time).into_owned(),
+                        });
+                    }
+                    if set.keep_ttl {
+                        return Err(ParseError::InvalidArgument(argument.to_string()));
+                    }
+                    set.expiration = Some(parse_expiration(&option, time)?);
+                    expire_option = Some((argument, time));
                     i += 2;
                 }
                 _ => {
-                    return Err(ParseError::InvalidArgument(argument));
+                    return Err(ParseError::InvalidArgument(argument.to_string()));
                 }
             }
         }
 
-        Ok(Set {
-            key,
-            value,
-            expire_time,
-        })
+        Ok(set)
     }
 }
 
 impl ExecuteCommand for Set {
+    /// Replies OK, or nil when the condition prevented the set. With GET, replies the old value
+    /// instead, whether the key was set or not.
     async fn execute(
         &self,
         server: Arc<Mutex<Server>>,
         _conn: &mut Connection,
     ) -> ExecResult<RespData> {
-        let expire_time = self
-            .expire_time
-            .map(|ms| Instant::now() + Duration::from_millis(ms));
-        server
-            .lock()
-            .await
-            .db
-            .insert(self.key.clone(), (self.value.clone(), expire_time));
+        let now = unix_time_ms();
+        let when = self
+            .expiration
+            .map(|expiration| {
+                expiration
+                    .unix_time_ms(now)
+                    .ok_or(ExecError::InvalidExpireTime("set"))
+            })
+            .transpose()?;
+
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let (exists, old_value, old_expire_time) = match lookup_key(db, stats, &self.key) {
+            None => (false, None, None),
+            Some((Value::String(value), expire_time)) => (true, Some(value.clone()), *expire_time),
+            // Only GET needs the old value to be a string
+            Some(_) if self.get => return Err(ExecError::WrongType),
+            Some((_, expire_time)) => (true, None, *expire_time),
+        };
+        let reply = if self.get {
+            RespData::BulkString(old_value)
+        } else {
+            RespData::SimpleString("OK".to_string())
+        };
+
+        if let Some(condition) = self.condition
+            && exists != (condition == SetCondition::Xx)
+        {
+            return Ok(if self.get {
+                reply
+            } else {
+                RespData::BulkString(None)
+            });
+        }
+
+        let expire_time = match when {
+            // An expire time already past deletes the key right away
+            Some(when) if when <= now => {
+                db.remove(&self.key);
+                return Ok(reply);
+            }
+            None if self.keep_ttl => old_expire_time,
+            when => when,
+        };
+        if expire_time.is_some() {
+            server.volatile_keys.insert(self.key.clone());
+        }
+        db.insert(
+            self.key.clone(),
+            (Value::String(self.value.clone()), expire_time),
+        );
         tracing::info!(
             "Add Key: {}, Value: {}",
             BytesInStr::from_bytes(&self.key),
             BytesInStr::from_bytes(&self.value)
         );
-        Ok(RespData::SimpleString("OK".to_string()))
+        Ok(reply)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::VecDeque;
+
     use bytes::Bytes;
-    use tokio::time::Instant;
 
-    use super::Set;
+    use super::{Set, SetCondition};
     use crate::{
         command::{
-            Command, ExecuteCommand, ParseError, parse_command,
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
             test::{build_request, build_server_connection},
         },
+        db::{Expiration, Value},
         resp::RespData,
+        utils::unix_time_ms,
     };
 
     #[test]
@@ -134,7 +210,10 @@ mod tests {
             Command::Set(Set {
                 key: Bytes::from_owner("k"),
                 value: Bytes::from_owner("v"),
-                expire_time: None,
+                expiration: None,
+                keep_ttl: false,
+                condition: None,
+                get: false,
             })
         );
     }
@@ -148,7 +227,10 @@ mod tests {
             Command::Set(Set {
                 key: Bytes::from_owner("k"),
                 value: Bytes::from_owner("v"),
-                expire_time: Some(100),
+                expiration: Some(Expiration::In(100)),
+                keep_ttl: false,
+                condition: None,
+                get: false,
             })
         );
     }
@@ -162,7 +244,10 @@ mod tests {
             Command::Set(Set {
                 key: Bytes::from_owner("k"),
                 value: Bytes::from_owner("v"),
-                expire_time: Some(7),
+                expiration: Some(Expiration::In(7)),
+                keep_ttl: false,
+                condition: None,
+                get: false,
             })
         );
     }
@@ -176,7 +261,10 @@ mod tests {
             Command::Set(Set {
                 key: Bytes::from_owner("k"),
                 value: Bytes::from_owner("v"),
-                expire_time: Some(2000),
+                expiration: Some(Expiration::In(2000)),
+                keep_ttl: false,
+                condition: None,
+                get: false,
             })
         );
     }
@@ -190,7 +278,10 @@ mod tests {
             Command::Set(Set {
                 key: Bytes::from_owner("k"),
                 value: Bytes::from_owner("v"),
-                expire_time: Some(123),
+                expiration: Some(Expiration::At(123)),
+                keep_ttl: false,
+                condition: None,
+                get: false,
             })
         );
     }
@@ -204,7 +295,10 @@ mod tests {
             Command::Set(Set {
                 key: Bytes::from_owner("k"),
                 value: Bytes::from_owner("v"),
-                expire_time: Some(3000),
+                expiration: Some(Expiration::At(3000)),
+                keep_ttl: false,
+                condition: None,
+                get: false,
             })
         );
     }
@@ -218,11 +312,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_set_should_parse_condition_get_and_keepttl() {
+        let cmd = parse_command(&build_request(
+            "SET",
+            &["k", "v", "nx", "GET", "KEEPTTL", "NX"],
+        ))
+        .expect("parse set nx get keepttl");
+        assert_eq!(
+            cmd,
+            Command::Set(Set {
+                key: Bytes::from_owner("k"),
+                value: Bytes::from_owner("v"),
+                expiration: None,
+                keep_ttl: true,
+                condition: Some(SetCondition::Nx),
+                get: true,
+            })
+        );
+    }
+
     #[test]
     fn parse_set_should_reject_invalid_option() {
         let err = parse_command(&build_request("SET", &["k", "v", "NX", "1"]))
             .expect_err("unsupported option");
-        assert_eq!(err, ParseError::InvalidArgument("NX".to_string()));
+        assert_eq!(err, ParseError::InvalidArgument("1".to_string()));
+    }
+
+    #[test]
+    fn parse_set_should_reject_exclusive_options() {
+        for (args, argument) in [
+            (&["k", "v", "NX", "XX"][..], "XX"),
+            (&["k", "v", "xx", "nx"], "nx"),
+            (&["k", "v", "KEEPTTL", "PX", "10"], "PX"),
+            (&["k", "v", "EXAT", "10", "KEEPTTL"], "KEEPTTL"),
+        ] {
+            let err = parse_command(&build_request("SET", args)).expect_err("exclusive options");
+            assert_eq!(
+                err,
+                ParseError::InvalidArgument(argument.to_string()),
+                "{args:?}"
+            );
+        }
+        assert!(parse_command(&build_request("SET", &["k", "v", "EX", "0"])).is_err());
     }
 
     #[test]
@@ -264,7 +396,10 @@ mod tests {
         let cmd = Set {
             key: Bytes::from_owner("k"),
             value: Bytes::from_owner("v"),
-            expire_time: None,
+            expiration: None,
+            keep_ttl: false,
+            condition: None,
+            get: false,
         };
 
         let resp = cmd
@@ -276,7 +411,7 @@ mod tests {
         let db = &server.lock().await.db;
         assert_eq!(
             db.get(&Bytes::from_owner("k")),
-            Some(&(Bytes::from_owner("v"), None))
+            Some(&(Value::String(Bytes::from_owner("v")), None))
         );
     }
 
@@ -286,7 +421,10 @@ mod tests {
         let cmd = Set {
             key: Bytes::from_owner("k"),
             value: Bytes::from_owner("v"),
-            expire_time: Some(1000),
+            expiration: Some(Expiration::In(1000)),
+            keep_ttl: false,
+            condition: None,
+            get: false,
         };
 
         let set_resp = cmd
@@ -301,6 +439,93 @@ mod tests {
             .db
             .get(&Bytes::from_owner("k"))
             .and_then(|(_, expire)| *expire);
-        assert!(expire_at.is_some_and(|t| t > Instant::now()));
+        assert!(expire_at.is_some_and(|t| t > unix_time_ms()));
+    }
+
+    #[tokio::test]
+    async fn execute_set_should_follow_condition() {
+        let (server, mut conn) = build_server_connection().await;
+
+        for (args, expected) in [
+            (&["k", "1", "XX"][..], RespData::BulkString(None)),
+            (&["k", "1", "NX"], RespData::SimpleString("OK".to_string())),
+            (&["k", "2", "NX"], RespData::BulkString(None)),
+            (
+                &["k", "2", "NX", "GET"],
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+            ),
+            (
+                &["k", "3", "XX", "GET"],
+                RespData::BulkString(Some(Bytes::from_owner("1"))),
+            ),
+            (&["new", "4", "GET"], RespData::BulkString(None)),
+        ] {
+            let cmd = parse_command(&build_request("SET", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute set");
+            assert_eq!(resp, expected, "{args:?}");
+        }
+
+        let db = &server.lock().await.db;
+        for (key, value) in [("k", "3"), ("new", "4")] {
+            assert_eq!(
+                db.get(key.as_bytes()),
+                Some(&(Value::String(Bytes::from_owner(value)), None))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_set_should_keep_ttl_and_handle_absolute_times() {
+        let (server, mut conn) = build_server_connection().await;
+        let later = (unix_time_ms() + 60_000).to_string();
+        let past = (unix_time_ms() - 1000).to_string();
+
+        let cmd = parse_command(&build_request("SET", &["k", "1", "PXAT", &later])).unwrap();
+        cmd.execute(server.clone(), &mut conn)
+            .await
+            .expect("execute set");
+        let expire_time = server.lock().await.db.get(b"k".as_ref()).unwrap().1;
+        assert!(expire_time.is_some_and(|t| t > unix_time_ms() && t <= unix_time_ms() + 60_000));
+
+        let cmd = parse_command(&build_request("SET", &["k", "2", "KEEPTTL"])).unwrap();
+        cmd.execute(server.clone(), &mut conn)
+            .await
+            .expect("execute set");
+        assert_eq!(
+            server.lock().await.db.get(b"k".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("2")), expire_time))
+        );
+
+        // An expire time already past deletes the key, GET still replying the old value
+        let cmd = parse_command(&build_request("SET", &["k", "3", "PXAT", &past, "GET"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute set");
+        assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("2"))));
+        assert!(!server.lock().await.db.contains_key(b"k".as_ref()));
+    }
+
+    #[tokio::test]
+    async fn execute_set_get_should_reject_non_string_value() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+        );
+
+        let cmd = parse_command(&build_request("SET", &["list", "v", "GET"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+
+        let cmd = parse_command(&build_request("SET", &["list", "v"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute set");
+        assert_eq!(resp, RespData::SimpleString("OK".to_string()));
     }
 }
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq,
+        error::{ExecResult, ParseError},
+        get_or_insert_string, parse_bit_offset, update_string,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SetBit {
+    key: Bytes,
+    offset: usize,
+    value: bool,
+}
+
+impl Parse for SetBit {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        let value = match args[2].as_ref() {
+            b"0" => false,
+            b"1" => true,
+            _ => {
+                return Err(ParseError::InvalidArgument(
+                    String::from_utf8_lossy(&args[2]).into_owned(),
+                ));
+            }
+        };
+        Ok(SetBit {
+            key: args[0].clone(),
+            offset: parse_bit_offset(&args[1])?,
+            value,
+        })
+    }
+}
+
+impl ExecuteCommand for SetBit {
+    /// Set the bit, growing the string with zero bytes if it is too short. Replies the previous
+    /// value of the bit.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let string = get_or_insert_string(db, stats, &self.key)?;
+        let (index, mask) = (self.offset / 8, 0x80 >> (self.offset % 8));
+        let old = update_string(string, |buffer| {
+            if buffer.len() <= index {
+                buffer.resize(index + 1, 0);
+            }
+            let old = buffer[index] & mask != 0;
+            if self.value {
+                buffer[index] |= mask;
+            } else {
+                buffer[index] &= !mask;
+            }
+            old
+        });
+        Ok(RespData::Integer(old as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SetBit;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_setbit_should_read_offset_and_value() {
+        let cmd = parse_command(&build_request("SETBIT", &["k", "7", "1"])).expect("parse setbit");
+        assert_eq!(
+            cmd,
+            Command::SetBit(SetBit {
+                key: Bytes::from_owner("k"),
+                offset: 7,
+                value: true,
+            })
+        );
+
+        for args in [
+            &["k", "7", "2"][..],
+            &["k", "-1", "1"],
+            &["k", "4294967296", "1"],
+            &["k", "7"],
+        ] {
+            assert!(
+                parse_command(&build_request("SETBIT", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_setbit_should_grow_value_and_reply_old_bit() {
+        let (server, mut conn) = build_server_connection().await;
+
+        for (offset, value, expected) in [
+            ("7", "1", 0),
+            ("7", "0", 1),
+            ("1", "1", 0),
+            ("18", "1", 0),
+            ("18", "1", 1),
+        ] {
+            let cmd = parse_command(&build_request("SETBIT", &["k", offset, value])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute setbit");
+            assert_eq!(resp, RespData::Integer(expected), "{offset} {value}");
+        }
+        assert_eq!(
+            server.lock().await.db.get(b"k".as_ref()),
+            Some(&(
+                Value::String(Bytes::from_static(&[0b0100_0000, 0, 0b0010_0000])),
+                None
+            ))
+        );
+    }
+}
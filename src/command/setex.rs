@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_eq,
+        error::{ExecError, ExecResult},
+        parse_expiration,
+    },
+    db::{Expiration, Value},
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+/// SETEX and PSETEX.
+#[derive(Debug, PartialEq)]
+pub struct SetEx {
+    key: Bytes,
+    expiration: Expiration,
+    value: Bytes,
+    /// PSETEX, which takes the expire time in milliseconds.
+    milliseconds: bool,
+}
+
+impl SetEx {
+    pub fn parse(args: &[Bytes], milliseconds: bool) -> ParseResult<Self> {
+        check_length_eq(args, 3)?;
+        let option = if milliseconds { "PX" } else { "EX" };
+        Ok(SetEx {
+            key: args[0].clone(),
+            expiration: parse_expiration(option, &args[1])?,
+            value: args[2].clone(),
+            milliseconds,
+        })
+    }
+}
+
+impl ExecuteCommand for SetEx {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let command = if self.milliseconds { "psetex" } else { "setex" };
+        let expire_time = self
+            .expiration
+            .unix_time_ms(unix_time_ms())
+            .ok_or(ExecError::InvalidExpireTime(command))?;
+        let server = &mut *server.lock().await;
+        server.db.insert(
+            self.key.clone(),
+            (Value::String(self.value.clone()), Some(expire_time)),
+        );
+        server.volatile_keys.insert(self.key.clone());
+        Ok(RespData::SimpleString("OK".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SetEx;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Expiration, Value},
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_setex_should_read_expire_time() {
+        for (command, expiration, milliseconds) in [
+            ("SETEX", Expiration::In(10_000), false),
+            ("PSETEX", Expiration::In(10), true),
+        ] {
+            let cmd = parse_command(&build_request(command, &["k", "10", "v"])).expect("parse");
+            assert_eq!(
+                cmd,
+                Command::SetEx(SetEx {
+                    key: Bytes::from_owner("k"),
+                    expiration,
+                    value: Bytes::from_owner("v"),
+                    milliseconds,
+                })
+            );
+        }
+        assert!(parse_command(&build_request("SETEX", &["k", "0", "v"])).is_err());
+        assert!(parse_command(&build_request("PSETEX", &["k", "-1", "v"])).is_err());
+        assert!(parse_command(&build_request("SETEX", &["k", "10"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_setex_should_store_value_with_expire_time() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd = parse_command(&build_request("PSETEX", &["k", "60000", "v"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute psetex");
+        assert_eq!(resp, RespData::SimpleString("OK".to_string()));
+
+        let (value, expire_time) = server.lock().await.db.get(b"k".as_ref()).cloned().unwrap();
+        assert_eq!(value, Value::String(Bytes::from_owner("v")));
+        assert!(expire_time.is_some_and(|t| t > unix_time_ms() + 50_000));
+
+        let cmd = parse_command(&build_request("SETEX", &["k", "9223372036854775", "v"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("overflow");
+        assert_eq!(err, ExecError::InvalidExpireTime("setex"));
+    }
+}
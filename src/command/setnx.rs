@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult},
+    db::{Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SetNx {
+    key: Bytes,
+    value: Bytes,
+}
+
+impl Parse for SetNx {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(SetNx {
+            key: args[0].clone(),
+            value: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for SetNx {
+    /// Replies 1 if the key was set, 0 if it already existed.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        if lookup_key(db, stats, &self.key).is_some() {
+            return Ok(RespData::Integer(0));
+        }
+        db.insert(self.key.clone(), (Value::String(self.value.clone()), None));
+        Ok(RespData::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::SetNx;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_setnx_should_read_value() {
+        let cmd = parse_command(&build_request("SETNX", &["k", "v"])).expect("parse setnx");
+        assert_eq!(
+            cmd,
+            Command::SetNx(SetNx {
+                key: Bytes::from_owner("k"),
+                value: Bytes::from_owner("v"),
+            })
+        );
+        assert!(parse_command(&build_request("SETNX", &["k", "v", "x"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_setnx_should_only_set_missing_key() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("list"),
+            (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+        );
+
+        for (key, value, expected) in [("k", "1", 1), ("k", "2", 0), ("list", "3", 0)] {
+            let cmd = parse_command(&build_request("SETNX", &[key, value])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute setnx");
+            assert_eq!(resp, RespData::Integer(expected), "{key} {value}");
+        }
+        assert_eq!(
+            server.lock().await.db.get(b"k".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("1")), None))
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, ParseResult, check_length_ge, error::ExecResult, get_set},
+    db::{Db, Set, Value},
+    resp::RespData,
+    server::{Connection, Server, Stats},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOperator {
+    Union,
+    Inter,
+    Diff,
+}
+
+/// `SUNION`, `SINTER`, `SDIFF` and their `STORE` variants.
+#[derive(Debug, PartialEq)]
+pub struct SetOp {
+    operator: SetOperator,
+    /// Store the result there and reply its cardinality, instead of replying it.
+    destination: Option<Bytes>,
+    keys: Vec<Bytes>,
+}
+
+impl SetOp {
+    pub fn parse(args: &[Bytes], operator: SetOperator, store: bool) -> ParseResult<Self> {
+        let (destination, keys) = if store {
+            check_length_ge(args, 2)?;
+            (Some(args[0].clone()), &args[1..])
+        } else {
+            check_length_ge(args, 1)?;
+            (None, args)
+        };
+        Ok(SetOp {
+            operator,
+            destination,
+            keys: keys.to_vec(),
+        })
+    }
+}
+
+/// Look up the sets of `keys`, missing keys being `None`. Fails if any key holds another type.
+fn lookup_sets<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    keys: &[Bytes],
+) -> ExecResult<Vec<Option<&'a Set>>> {
+    for key in keys {
+        get_set(db, stats, key)?;
+    }
+    let db = &*db;
+    Ok(keys
+        .iter()
+        .map(|key| match db.get(key) {
+            Some((Value::Set(set), _)) => Some(set),
+            _ => None,
+        })
+        .collect())
+}
+
+fn combine(operator: SetOperator, sets: &[Option<&Set>]) -> Set {
+    match operator {
+        SetOperator::Union => sets
+            .iter()
+            .flatten()
+            .flat_map(|set| set.iter())
+            .cloned()
+            .collect(),
+        SetOperator::Inter => {
+            let Some(mut sets) = sets.iter().copied().collect::<Option<Vec<_>>>() else {
+                // A missing key is an empty set, which empties the intersection
+                return Set::new();
+            };
+            // Probe the other sets with the members of the smallest one
+            sets.sort_by_key(|set| set.len());
+            sets[0]
+                .iter()
+                .filter(|member| sets[1..].iter().all(|set| set.contains(*member)))
+                .cloned()
+                .collect()
+        }
+        SetOperator::Diff => {
+            let Some(first) = sets[0] else {
+                return Set::new();
+            };
+            first
+                .iter()
+                .filter(|member| !sets[1..].iter().flatten().any(|set| set.contains(*member)))
+                .cloned()
+                .collect()
+        }
+    }
+}
+
+impl ExecuteCommand for SetOp {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let result = combine(self.operator, &lookup_sets(db, stats, &self.keys)?);
+
+        let Some(destination) = &self.destination else {
+            return Ok(RespData::Set(
+                result
+                    .into_iter()
+                    .map(|member| RespData::BulkString(Some(member)))
+                    .collect(),
+            ));
+        };
+
+        let len = result.len();
+        // The destination is overwritten whatever it holds, and removed by an empty result
+        if result.is_empty() {
+            db.remove(destination);
+        } else {
+            db.insert(destination.clone(), (Value::Set(result), None));
+        }
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{SetOp, SetOperator};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+        server::Server,
+    };
+
+    fn set(members: &[&str]) -> Set {
+        members
+            .iter()
+            .map(|member| Bytes::copy_from_slice(member.as_bytes()))
+            .collect()
+    }
+
+    fn insert_sets(server: &mut Server) {
+        for (key, members) in [
+            ("a", ["1", "2", "3"].as_slice()),
+            ("b", &["2", "3", "4"]),
+            ("c", &["3", "5"]),
+        ] {
+            server.db.insert(
+                Bytes::copy_from_slice(key.as_bytes()),
+                (Value::Set(set(members)), None),
+            );
+        }
+    }
+
+    fn reply_set(resp: RespData) -> Set {
+        let RespData::Set(members) = resp else {
+            panic!("expected a set, got {resp:?}");
+        };
+        members
+            .into_iter()
+            .map(|member| match member {
+                RespData::BulkString(Some(member)) => member,
+                other => panic!("expected a bulk string, got {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_setop_should_read_destination_and_keys() {
+        let cmd = parse_command(&build_request("SINTERSTORE", &["dst", "a", "b"]))
+            .expect("parse sinterstore");
+        assert_eq!(
+            cmd,
+            Command::SetOp(SetOp {
+                operator: SetOperator::Inter,
+                destination: Some(Bytes::from_owner("dst")),
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("SUNIONSTORE", &["dst"])).is_err());
+        assert!(parse_command(&build_request("SDIFF", &[])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_setop_should_combine_sets() {
+        let (server, mut conn) = build_server_connection().await;
+        insert_sets(&mut *server.lock().await);
+
+        for (command, keys, expected) in [
+            (
+                "SUNION",
+                ["a", "b", "c"].as_slice(),
+                ["1", "2", "3", "4", "5"].as_slice(),
+            ),
+            ("SUNION", &["missing", "c"], &["3", "5"]),
+            ("SINTER", &["a", "b", "c"], &["3"]),
+            ("SINTER", &["a", "b"], &["2", "3"]),
+            ("SINTER", &["a", "missing"], &[]),
+            ("SDIFF", &["a", "b"], &["1"]),
+            ("SDIFF", &["b", "missing", "c"], &["2", "4"]),
+            ("SDIFF", &["missing", "a"], &[]),
+        ] {
+            let cmd = parse_command(&build_request(command, keys)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+            assert_eq!(reply_set(resp), set(expected), "{command} {keys:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_setop_store_should_overwrite_destination() {
+        let (server, mut conn) = build_server_connection().await;
+        insert_sets(&mut *server.lock().await);
+        server.lock().await.db.insert(
+            Bytes::from_owner("dst"),
+            (Value::String(Bytes::from_owner("old")), None),
+        );
+
+        let cmd = parse_command(&build_request("SINTERSTORE", &["dst", "a", "b"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(2));
+        assert_eq!(
+            server.lock().await.db.get(b"dst".as_ref()),
+            Some(&(Value::Set(set(&["2", "3"])), None))
+        );
+
+        // A store may read its own destination
+        let cmd = parse_command(&build_request("SDIFFSTORE", &["dst", "dst", "c"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = parse_command(&build_request("SDIFFSTORE", &["dst", "c", "a"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = parse_command(&build_request("SINTERSTORE", &["dst", "a", "missing"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(!server.lock().await.db.contains_key(b"dst".as_ref()));
+    }
+
+    #[tokio::test]
+    async fn execute_setop_should_reject_non_set_key() {
+        let (server, mut conn) = build_server_connection().await;
+        insert_sets(&mut *server.lock().await);
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        let cmd = parse_command(&build_request("SUNION", &["a", "s"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
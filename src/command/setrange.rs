@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult,
+        get_or_insert_string, get_string, update_string,
+    },
+    db::STRING_MAX_LEN,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SetRange {
+    key: Bytes,
+    offset: usize,
+    value: Bytes,
+}
+
+impl Parse for SetRange {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(SetRange {
+            key: args[0].clone(),
+            offset: lexical_core::parse(&args[1])?,
+            value: args[2].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for SetRange {
+    /// Overwrite the string from the offset, padding it with zero bytes if it is shorter.
+    /// Replies the length of the string after the update.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        // An empty value changes nothing, and does not create the key
+        if self.value.is_empty() {
+            let len = get_string(db, stats, &self.key)?.map_or(0, |string| string.len());
+            return Ok(RespData::Integer(len as i64));
+        }
+        let end = self
+            .offset
+            .checked_add(self.value.len())
+            .filter(|end| *end <= STRING_MAX_LEN)
+            .ok_or(ExecError::StringTooLong)?;
+
+        let string = get_or_insert_string(db, stats, &self.key)?;
+        update_string(string, |buffer| {
+            if buffer.len() < end {
+                buffer.resize(end, 0);
+            }
+            buffer[self.offset..end].copy_from_slice(&self.value);
+        });
+
+        Ok(RespData::Integer(string.len() as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SetRange;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_setrange_should_read_offset() {
+        let cmd = parse_command(&build_request("SETRANGE", &["k", "6", "Redis"]))
+            .expect("parse setrange");
+        assert_eq!(
+            cmd,
+            Command::SetRange(SetRange {
+                key: Bytes::from_owner("k"),
+                offset: 6,
+                value: Bytes::from_owner("Redis"),
+            })
+        );
+        assert!(parse_command(&build_request("SETRANGE", &["k", "-1", "v"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_setrange_should_overwrite_and_pad() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("k"),
+            (Value::String(Bytes::from_owner("Hello World")), None),
+        );
+
+        for (args, expected) in [
+            (&["k", "6", "Redis"][..], 11),
+            (&["k", "20", ""], 11),
+            (&["padded", "3", "ab"], 5),
+            (&["missing", "3", ""], 0),
+        ] {
+            let cmd = parse_command(&build_request("SETRANGE", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute setrange");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let cmd = parse_command(&build_request("SETRANGE", &["k", "536870911", "ab"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("too long");
+        assert_eq!(err, ExecError::StringTooLong);
+
+        let db = &server.lock().await.db;
+        assert_eq!(
+            db.get(b"k".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("Hello Redis")), None))
+        );
+        assert_eq!(
+            db.get(b"padded".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("\0\0\0ab")), None))
+        );
+        assert!(!db.contains_key(b"missing".as_ref()));
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_set},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SIsMember {
+    key: Bytes,
+    member: Bytes,
+}
+
+impl Parse for SIsMember {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(SIsMember {
+            key: args[0].clone(),
+            member: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for SIsMember {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let is_member =
+            get_set(db, stats, &self.key)?.is_some_and(|set| set.contains(&self.member));
+        Ok(RespData::Integer(is_member as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SIsMember;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_sismember_should_read_member() {
+        let cmd = parse_command(&build_request("SISMEMBER", &["s", "a"])).expect("parse sismember");
+        assert_eq!(
+            cmd,
+            Command::SIsMember(SIsMember {
+                key: Bytes::from_owner("s"),
+                member: Bytes::from_owner("a"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_sismember_should_check_membership() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::Set(Set::from([Bytes::from_owner("a")])), None),
+        );
+
+        for (key, member, expected) in [("s", "a", 1), ("s", "b", 0), ("missing", "a", 0)] {
+            let cmd = parse_command(&build_request("SISMEMBER", &[key, member])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute sismember");
+            assert_eq!(resp, RespData::Integer(expected));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_set},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SMembers {
+    key: Bytes,
+}
+
+impl Parse for SMembers {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(SMembers {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for SMembers {
+    /// Replies a set, which RESP2 connections receive as an array.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let members = get_set(db, stats, &self.key)?
+            .map(|set| {
+                set.iter()
+                    .map(|member| RespData::BulkString(Some(member.clone())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(RespData::Set(members))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SMembers;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_smembers_should_read_key() {
+        let cmd = parse_command(&build_request("SMEMBERS", &["s"])).expect("parse smembers");
+        assert_eq!(
+            cmd,
+            Command::SMembers(SMembers {
+                key: Bytes::from_owner("s"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_smembers_should_reply_set() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::Set(Set::from([Bytes::from_owner("a")])), None),
+        );
+
+        let cmd = parse_command(&build_request("SMEMBERS", &["s"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute smembers");
+        assert_eq!(
+            resp,
+            RespData::Set(vec![RespData::BulkString(Some(Bytes::from_owner("a")))])
+        );
+
+        let cmd = parse_command(&build_request("SMEMBERS", &["missing"])).unwrap();
+        let resp = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect("execute smembers");
+        assert_eq!(resp, RespData::Set(vec![]));
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_set},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SMIsMember {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl Parse for SMIsMember {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        Ok(SMIsMember {
+            key: args[0].clone(),
+            members: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for SMIsMember {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let set = get_set(db, stats, &self.key)?;
+        Ok(RespData::Array(
+            self.members
+                .iter()
+                .map(|member| {
+                    let is_member = set.as_ref().is_some_and(|set| set.contains(member));
+                    RespData::Integer(is_member as i64)
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SMIsMember;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_smismember_should_read_members() {
+        let cmd = parse_command(&build_request("SMISMEMBER", &["s", "a", "b"]))
+            .expect("parse smismember");
+        assert_eq!(
+            cmd,
+            Command::SMIsMember(SMIsMember {
+                key: Bytes::from_owner("s"),
+                members: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_smismember_should_check_every_member() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::Set(Set::from([Bytes::from_owner("a")])), None),
+        );
+
+        let cmd = parse_command(&build_request("SMISMEMBER", &["s", "a", "b"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute smismember");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![RespData::Integer(1), RespData::Integer(0)])
+        );
+
+        let cmd = parse_command(&build_request("SMISMEMBER", &["missing", "a"])).unwrap();
+        let resp = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect("execute smismember");
+        assert_eq!(resp, RespData::Array(vec![RespData::Integer(0)]));
+    }
+}
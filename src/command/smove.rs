@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_or_insert_set,
+        get_set,
+    },
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SMove {
+    source: Bytes,
+    destination: Bytes,
+    member: Bytes,
+}
+
+impl Parse for SMove {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(SMove {
+            source: args[0].clone(),
+            destination: args[1].clone(),
+            member: args[2].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for SMove {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        // Both keys must hold sets, even when the member is not moved
+        get_set(db, stats, &self.destination)?;
+        let Some(source) = get_set(db, stats, &self.source)? else {
+            return Ok(RespData::Integer(0));
+        };
+        if self.source == self.destination {
+            return Ok(RespData::Integer(source.contains(&self.member) as i64));
+        }
+        if !source.remove(&self.member) {
+            return Ok(RespData::Integer(0));
+        }
+        remove_if_empty(db, &self.source);
+
+        get_or_insert_set(db, stats, &self.destination)?.insert(self.member.clone());
+        Ok(RespData::Integer(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SMove;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_smove_should_read_keys_and_member() {
+        let cmd = parse_command(&build_request("SMOVE", &["a", "b", "m"])).expect("parse smove");
+        assert_eq!(
+            cmd,
+            Command::SMove(SMove {
+                source: Bytes::from_owner("a"),
+                destination: Bytes::from_owner("b"),
+                member: Bytes::from_owner("m"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_smove_should_move_member() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("a"),
+            (Value::Set(Set::from([Bytes::from_owner("m")])), None),
+        );
+
+        let cmd = parse_command(&build_request("SMOVE", &["a", "b", "m"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute smove");
+        assert_eq!(resp, RespData::Integer(1));
+        {
+            let db = &server.lock().await.db;
+            assert!(!db.contains_key(b"a".as_ref()));
+            assert_eq!(
+                db.get(b"b".as_ref()),
+                Some(&(Value::Set(Set::from([Bytes::from_owner("m")])), None))
+            );
+        }
+
+        let cmd = parse_command(&build_request("SMOVE", &["a", "b", "m"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute smove");
+        assert_eq!(resp, RespData::Integer(0));
+
+        let cmd = parse_command(&build_request("SMOVE", &["b", "b", "m"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute smove");
+        assert_eq!(resp, RespData::Integer(1));
+    }
+
+    #[tokio::test]
+    async fn execute_smove_should_reject_wrong_destination_type() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("a"),
+            (Value::Set(Set::from([Bytes::from_owner("m")])), None),
+        );
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        let cmd = parse_command(&build_request("SMOVE", &["a", "s", "x"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
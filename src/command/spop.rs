@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rand::seq::IteratorRandom;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_set,
+    },
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SPop {
+    key: Bytes,
+    /// Reply with a set of up to `count` members instead of a single one.
+    count: Option<usize>,
+}
+
+impl Parse for SPop {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        if args.len() > 2 {
+            return Err(ParseError::ExpectLengthEq(2, args.len(), args.to_vec()));
+        }
+        Ok(SPop {
+            key: args[0].clone(),
+            count: args
+                .get(1)
+                .map(|arg| lexical_core::parse(arg))
+                .transpose()?,
+        })
+    }
+}
+
+impl ExecuteCommand for SPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(set) = get_set(db, stats, &self.key)? else {
+            return Ok(match self.count {
+                None => RespData::BulkString(None),
+                Some(_) => RespData::Set(vec![]),
+            });
+        };
+
+        let mut rng = rand::rng();
+        let popped: Vec<Bytes> = set
+            .iter()
+            .cloned()
+            .choose_multiple(&mut rng, self.count.unwrap_or(1));
+        for member in &popped {
+            set.remove(member);
+        }
+        remove_if_empty(db, &self.key);
+
+        let mut popped = popped
+            .into_iter()
+            .map(|member| RespData::BulkString(Some(member)));
+        Ok(match self.count {
+            // The set exists, so it holds at least one member
+            None => popped.next().unwrap(),
+            Some(_) => RespData::Set(popped.collect()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SPop;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_spop_should_read_count() {
+        let cmd = parse_command(&build_request("SPOP", &["s", "3"])).expect("parse spop");
+        assert_eq!(
+            cmd,
+            Command::SPop(SPop {
+                key: Bytes::from_owner("s"),
+                count: Some(3),
+            })
+        );
+        assert!(parse_command(&build_request("SPOP", &["s", "-1"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_spop_should_remove_popped_members() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (
+                Value::Set(Set::from([
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("b"),
+                    Bytes::from_owner("c"),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("SPOP", &["s"])).unwrap();
+        let RespData::BulkString(Some(popped)) =
+            cmd.execute(server.clone(), &mut conn).await.unwrap()
+        else {
+            panic!("expected a bulk string");
+        };
+        {
+            let guard = server.lock().await;
+            let Some((Value::Set(set), _)) = guard.db.get(b"s".as_ref()) else {
+                panic!("expected a set");
+            };
+            assert_eq!(set.len(), 2);
+            assert!(!set.contains(&popped));
+        }
+
+        let cmd = parse_command(&build_request("SPOP", &["s", "5"])).unwrap();
+        let RespData::Set(popped) = cmd.execute(server.clone(), &mut conn).await.unwrap() else {
+            panic!("expected a set");
+        };
+        assert_eq!(popped.len(), 2);
+        assert!(server.lock().await.db.is_empty());
+
+        let cmd = parse_command(&build_request("SPOP", &["s"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::BulkString(None));
+
+        let cmd = parse_command(&build_request("SPOP", &["s", "1"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Set(vec![]));
+    }
+}
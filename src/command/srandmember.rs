@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rand::seq::{IndexedRandom, IteratorRandom};
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_set, parse_random_count,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SRandMember {
+    key: Bytes,
+    /// Reply with an array of up to `count` distinct members, or exactly `-count` members which
+    /// may repeat when negative.
+    count: Option<i64>,
+}
+
+impl Parse for SRandMember {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 1)?;
+        if args.len() > 2 {
+            return Err(ParseError::ExpectLengthEq(2, args.len(), args.to_vec()));
+        }
+        Ok(SRandMember {
+            key: args[0].clone(),
+            count: args.get(1).map(parse_random_count).transpose()?,
+        })
+    }
+}
+
+impl ExecuteCommand for SRandMember {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let set = get_set(db, stats, &self.key)?;
+        let mut rng = rand::rng();
+
+        let Some(count) = self.count else {
+            let member = set.and_then(|set| set.iter().choose(&mut rng).cloned());
+            return Ok(RespData::BulkString(member));
+        };
+        let Some(set) = set else {
+            return Ok(RespData::Array(vec![]));
+        };
+
+        let members: Vec<&Bytes> = if count >= 0 {
+            set.iter().choose_multiple(&mut rng, count as usize)
+        } else {
+            let members: Vec<_> = set.iter().collect();
+            (0..count.unsigned_abs())
+                .filter_map(|_| members.choose(&mut rng).copied())
+                .collect()
+        };
+        Ok(RespData::Array(
+            members
+                .into_iter()
+                .map(|member| RespData::BulkString(Some(member.clone())))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bytes::Bytes;
+
+    use super::SRandMember;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, ParseError, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_srandmember_should_read_count() {
+        let cmd =
+            parse_command(&build_request("SRANDMEMBER", &["s", "-2"])).expect("parse srandmember");
+        assert_eq!(
+            cmd,
+            Command::SRandMember(SRandMember {
+                key: Bytes::from_owner("s"),
+                count: Some(-2),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_srandmember_should_reject_count_out_of_range() {
+        for count in ["-4611686018427387904", "4611686018427387904"] {
+            let err = parse_command(&build_request("SRANDMEMBER", &["s", count]))
+                .expect_err("count out of range");
+            assert_eq!(
+                err,
+                ParseError::Rejected(ExecError::ValueOutOfRange),
+                "{count}"
+            );
+            assert_eq!(err.to_string(), "ERR value is out of range");
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_srandmember_should_sample_without_removing() {
+        let (server, mut conn) = build_server_connection().await;
+        let set = Set::from([Bytes::from_owner("a"), Bytes::from_owner("b")]);
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Set(set.clone()), None));
+
+        let cmd = parse_command(&build_request("SRANDMEMBER", &["s", "5"])).unwrap();
+        let RespData::Array(members) = cmd.execute(server.clone(), &mut conn).await.unwrap() else {
+            panic!("expected an array");
+        };
+        let members: HashSet<_> = members
+            .into_iter()
+            .map(|member| match member {
+                RespData::BulkString(Some(member)) => member,
+                other => panic!("expected a bulk string, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(members, set);
+
+        let cmd = parse_command(&build_request("SRANDMEMBER", &["s", "-5"])).unwrap();
+        let RespData::Array(members) = cmd.execute(server.clone(), &mut conn).await.unwrap() else {
+            panic!("expected an array");
+        };
+        assert_eq!(members.len(), 5);
+
+        assert_eq!(
+            server.lock().await.db.get(b"s".as_ref()),
+            Some(&(Value::Set(set), None))
+        );
+
+        let cmd = parse_command(&build_request("SRANDMEMBER", &["missing"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::BulkString(None));
+    }
+}
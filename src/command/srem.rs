@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_set},
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct SRem {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl Parse for SRem {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        Ok(SRem {
+            key: args[0].clone(),
+            members: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for SRem {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(set) = get_set(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(0));
+        };
+        let removed = self
+            .members
+            .iter()
+            .filter(|member| set.remove(*member))
+            .count();
+        remove_if_empty(db, &self.key);
+
+        Ok(RespData::Integer(removed as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::SRem;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_srem_should_read_members() {
+        let cmd = parse_command(&build_request("SREM", &["s", "a"])).expect("parse srem");
+        assert_eq!(
+            cmd,
+            Command::SRem(SRem {
+                key: Bytes::from_owner("s"),
+                members: vec![Bytes::from_owner("a")],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_srem_should_count_removed_members_and_drop_empty_set() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (
+                Value::Set(Set::from([Bytes::from_owner("a"), Bytes::from_owner("b")])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("SREM", &["s", "a", "x", "a"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute srem");
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = parse_command(&build_request("SREM", &["s", "b"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("execute srem");
+        assert_eq!(resp, RespData::Integer(1));
+        assert!(server.lock().await.db.is_empty());
+
+        let cmd = parse_command(&build_request("SREM", &["s", "b"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("execute srem");
+        assert_eq!(resp, RespData::Integer(0));
+    }
+}
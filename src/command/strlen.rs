@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_string},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct StrLen {
+    key: Bytes,
+}
+
+impl Parse for StrLen {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(StrLen {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for StrLen {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let len = get_string(db, stats, &self.key)?.map_or(0, |string| string.len());
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use bytes::Bytes;
+
+    use super::StrLen;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+    };
+
+    #[test]
+    fn parse_strlen_should_read_key() {
+        let cmd = parse_command(&build_request("STRLEN", &["k"])).expect("parse strlen");
+        assert_eq!(
+            cmd,
+            Command::StrLen(StrLen {
+                key: Bytes::from_owner("k"),
+            })
+        );
+        assert!(parse_command(&build_request("STRLEN", &["k", "x"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_strlen_should_count_bytes() {
+        let (server, mut conn) = build_server_connection().await;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("k"),
+                (Value::String(Bytes::from_owner("héllo")), None),
+            );
+            db.insert(
+                Bytes::from_owner("list"),
+                (Value::List(VecDeque::from([Bytes::from_owner("a")])), None),
+            );
+        }
+
+        for (key, expected) in [("k", 6), ("missing", 0)] {
+            let cmd = parse_command(&build_request("STRLEN", &[key])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute strlen");
+            assert_eq!(resp, RespData::Integer(expected), "{key}");
+        }
+
+        let cmd = parse_command(&build_request("STRLEN", &["list"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, ParseResult, check_length_eq, error::ExecResult},
+    db::lookup_key,
+    resp::RespData,
+    server::{Connection, Server},
+    utils::unix_time_ms,
+};
+
+/// `TTL`, `PTTL`, `EXPIRETIME` and `PEXPIRETIME`.
+#[derive(Debug, PartialEq)]
+pub struct Ttl {
+    key: Bytes,
+    /// Reply in milliseconds rather than seconds.
+    milliseconds: bool,
+    /// Reply the expire time as a Unix time rather than the time to live.
+    absolute: bool,
+}
+
+impl Ttl {
+    pub fn parse(args: &[Bytes], milliseconds: bool, absolute: bool) -> ParseResult<Self> {
+        check_length_eq(args, 1)?;
+        Ok(Ttl {
+            key: args[0].clone(),
+            milliseconds,
+            absolute,
+        })
+    }
+}
+
+impl ExecuteCommand for Ttl {
+    /// Replies -2 if the key does not exist, -1 if it has no expire time, and otherwise its time
+    /// to live or expire time. Seconds are rounded to the nearest, as Redis does.
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let base_time = if self.absolute { 0 } else { unix_time_ms() };
+        let reply = match lookup_key(db, stats, &self.key) {
+            None => -2,
+            Some((_, None)) => -1,
+            Some((_, Some(when))) if self.milliseconds => when.saturating_sub(base_time) as i64,
+            Some((_, Some(when))) => (when.saturating_sub(base_time) + 500) as i64 / 1000,
+        };
+        Ok(RespData::Integer(reply))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::Ttl;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        utils::unix_time_ms,
+    };
+
+    #[test]
+    fn parse_ttl_should_read_key() {
+        let cmd = parse_command(&build_request("PEXPIRETIME", &["k"])).expect("parse pexpiretime");
+        assert_eq!(
+            cmd,
+            Command::Ttl(Ttl {
+                key: Bytes::from_owner("k"),
+                milliseconds: true,
+                absolute: true,
+            })
+        );
+        assert!(parse_command(&build_request("TTL", &["k", "k"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_ttl_should_reply_expire_time() {
+        let (server, mut conn) = build_server_connection().await;
+        let when = unix_time_ms() + 100_000;
+        {
+            let db = &mut server.lock().await.db;
+            db.insert(
+                Bytes::from_owner("volatile"),
+                (Value::String(Bytes::from_owner("v")), Some(when)),
+            );
+            db.insert(
+                Bytes::from_owner("persistent"),
+                (Value::String(Bytes::from_owner("v")), None),
+            );
+        }
+
+        for (command, key, expected) in [
+            ("TTL", "missing", -2),
+            ("PTTL", "persistent", -1),
+            ("EXPIRETIME", "persistent", -1),
+            ("TTL", "volatile", 100),
+            ("EXPIRETIME", "volatile", (when as i64 + 500) / 1000),
+            ("PEXPIRETIME", "volatile", when as i64),
+        ] {
+            let cmd = parse_command(&build_request(command, &[key])).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("execute ttl");
+            assert_eq!(resp, RespData::Integer(expected), "{command} {key}");
+        }
+
+        let cmd = parse_command(&build_request("PTTL", &["volatile"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert!(matches!(resp, RespData::Integer(ttl) if ttl > 99_000 && ttl <= 100_000));
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_stream,
+        parse_stream_id,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::StreamId,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct XAck {
+    key: Bytes,
+    group: Bytes,
+    ids: Vec<StreamId>,
+}
+
+impl Parse for XAck {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 3)?;
+        Ok(XAck {
+            key: args[0].clone(),
+            group: args[1].clone(),
+            ids: args[2..]
+                .iter()
+                .map(|arg| parse_stream_id(arg, 0))
+                .collect::<ParseResult<_>>()?,
+        })
+    }
+}
+
+impl ExecuteCommand for XAck {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let acked = get_stream(db, stats, &self.key)?
+            .and_then(|stream| stream.group_mut(&self.group))
+            .map_or(0, |group| {
+                self.ids.iter().filter(|id| group.ack(**id)).count()
+            });
+        Ok(RespData::Integer(acked as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::XAck;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{ConsumerGroup, Stream, StreamId},
+    };
+
+    #[test]
+    fn parse_xack_should_read_ids() {
+        let cmd =
+            parse_command(&build_request("XACK", &["s", "g", "1-1", "2"])).expect("parse xack");
+        assert_eq!(
+            cmd,
+            Command::XAck(XAck {
+                key: Bytes::from_owner("s"),
+                group: Bytes::from_owner("g"),
+                ids: vec![StreamId::new(1, 1), StreamId::new(2, 0)],
+            })
+        );
+        assert!(parse_command(&build_request("XACK", &["s", "g"])).is_err());
+        assert!(parse_command(&build_request("XACK", &["s", "g", ">"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_xack_should_remove_pending_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for seq in 1..=3 {
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+            );
+        }
+        let (group, consumer) = (Bytes::from_owner("g"), Bytes::from_owner("c"));
+        stream.create_group(group.clone(), ConsumerGroup::new(StreamId::MIN, None));
+        stream.read_group(&group, &consumer, 3, false, 0).unwrap();
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        for (args, expected) in [
+            (&["s", "g", "1-1", "1-1", "1-9"][..], 1),
+            (&["s", "g", "1-2", "1-3"], 2),
+            (&["s", "missing", "1-1"], 0),
+            (&["missing", "g", "1-1"], 0),
+        ] {
+            let cmd = parse_command(&build_request("XACK", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.expect("xack");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let server = server.lock().await;
+        let Some((Value::Stream(stream), _)) = server.db.get(b"s".as_ref()) else {
+            panic!("expected a stream");
+        };
+        let group = stream.group(b"g").unwrap();
+        assert!(group.pending().is_empty());
+        assert!(group.consumer(b"c").unwrap().pending().is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_or_insert_stream, get_stream, parse_stream_id,
+        xtrim::{Trim, TrimOptions},
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::StreamId,
+    utils::unix_time_ms,
+};
+
+/// The ID of the entry to add.
+#[derive(Debug, PartialEq)]
+enum NewId {
+    /// `*`, generated from the current time.
+    Auto,
+    /// `<ms>-*`, with a generated sequence number.
+    AutoSeq(u64),
+    Explicit(StreamId),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct XAdd {
+    key: Bytes,
+    /// Do not create the stream if it is missing.
+    nomkstream: bool,
+    trim: Option<Trim>,
+    id: NewId,
+    fields: Vec<(Bytes, Bytes)>,
+}
+
+impl Parse for XAdd {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 4)?;
+        let mut nomkstream = false;
+        let mut options = TrimOptions::default();
+        let mut index = 1;
+        while index < args.len() {
+            if args[index].eq_ignore_ascii_case(b"NOMKSTREAM") {
+                nomkstream = true;
+                index += 1;
+                continue;
+            }
+            match options.parse_option(&args[index..])? {
+                0 => break,
+                taken => index += taken,
+            }
+        }
+
+        let Some((id, pairs)) = args[index..].split_first() else {
+            return Err(ParseError::ExpectPairs(args.to_vec()));
+        };
+        if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+            return Err(ParseError::ExpectPairs(args.to_vec()));
+        }
+        let id = if id.as_ref() == b"*" {
+            NewId::Auto
+        } else if let Some(ms) = id.strip_suffix(b"-*") {
+            NewId::AutoSeq(lexical_core::parse(ms)?)
+        } else {
+            NewId::Explicit(parse_stream_id(id, 0)?)
+        };
+
+        Ok(XAdd {
+            key: args[0].clone(),
+            nomkstream,
+            trim: options.finish()?,
+            id,
+            fields: pairs
+                .chunks_exact(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+        })
+    }
+}
+
+impl ExecuteCommand for XAdd {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        if self.id == NewId::Explicit(StreamId::MIN) {
+            return Err(ExecError::StreamIdZero);
+        }
+
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let stream = if self.nomkstream {
+            match get_stream(db, stats, &self.key)? {
+                Some(stream) => stream,
+                None => return Ok(RespData::Null),
+            }
+        } else {
+            get_or_insert_stream(db, stats, &self.key)?
+        };
+
+        // A new stream takes any ID but 0-0, so it is never left empty by these errors
+        let id = match self.id {
+            NewId::Auto => stream
+                .next_id(unix_time_ms())
+                .ok_or(ExecError::StreamIdExhausted)?,
+            NewId::AutoSeq(ms) => stream.next_id_at(ms).ok_or(ExecError::StreamIdTooSmall)?,
+            NewId::Explicit(id) if id > stream.last_id() => id,
+            NewId::Explicit(_) => return Err(ExecError::StreamIdTooSmall),
+        };
+        stream.add(id, &self.fields);
+        if let Some(trim) = &self.trim {
+            trim.apply(stream);
+        }
+        server.serve_blocked_clients(&self.key);
+        Ok(RespData::BulkString(Some(Bytes::from(id.to_string()))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{NewId, XAdd};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::StreamId,
+        utils::unix_time_ms,
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    #[test]
+    fn parse_xadd_should_read_options_id_and_fields() {
+        let cmd = parse_command(&build_request(
+            "XADD",
+            &["s", "NOMKSTREAM", "5-*", "f", "v", "g", "w"],
+        ))
+        .expect("parse xadd");
+        assert_eq!(
+            cmd,
+            Command::XAdd(XAdd {
+                key: Bytes::from_owner("s"),
+                nomkstream: true,
+                trim: None,
+                id: NewId::AutoSeq(5),
+                fields: vec![
+                    (Bytes::from_owner("f"), Bytes::from_owner("v")),
+                    (Bytes::from_owner("g"), Bytes::from_owner("w")),
+                ],
+            })
+        );
+
+        let Command::XAdd(xadd) = parse_command(&build_request(
+            "XADD",
+            &["s", "MAXLEN", "~", "10", "LIMIT", "3", "7", "f", "v"],
+        ))
+        .expect("parse xadd") else {
+            panic!("expected xadd");
+        };
+        assert!(xadd.trim.is_some());
+        assert_eq!(xadd.id, NewId::Explicit(StreamId::new(7, 0)));
+
+        for args in [
+            &["s", "*", "f"][..],
+            &["s", "*", "f", "v", "g"],
+            &["s", "MAXLEN", "1", "f", "v"],
+            &["s", "x-1", "f", "v"],
+            &["s", "1-x", "f", "v"],
+            &["s", "LIMIT", "2", "*", "f", "v"],
+        ] {
+            assert!(
+                parse_command(&build_request("XADD", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xadd_should_generate_and_check_ids() {
+        let (server, mut conn) = build_server_connection().await;
+        for (id, expected) in [
+            ("0-*", Ok(bulk("0-1"))),
+            ("0-1", Err(ExecError::StreamIdTooSmall)),
+            ("1", Ok(bulk("1-0"))),
+            ("1-*", Ok(bulk("1-1"))),
+            ("0-*", Err(ExecError::StreamIdTooSmall)),
+            ("0-0", Err(ExecError::StreamIdZero)),
+            ("5-3", Ok(bulk("5-3"))),
+            ("5-3", Err(ExecError::StreamIdTooSmall)),
+            (
+                "18446744073709551615-18446744073709551615",
+                Ok(bulk("18446744073709551615-18446744073709551615")),
+            ),
+            ("*", Err(ExecError::StreamIdExhausted)),
+            ("18446744073709551615-*", Err(ExecError::StreamIdTooSmall)),
+        ] {
+            let cmd = parse_command(&build_request("XADD", &["s", id, "f", "v"])).unwrap();
+            assert_eq!(
+                cmd.execute(server.clone(), &mut conn).await,
+                expected,
+                "{id}"
+            );
+        }
+
+        let (server, mut conn) = build_server_connection().await;
+        let before = unix_time_ms();
+        let cmd = parse_command(&build_request("XADD", &["s", "*", "f", "v"])).unwrap();
+        cmd.execute(server.clone(), &mut conn).await.unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        let RespData::BulkString(Some(id)) = resp else {
+            panic!("expected an ID");
+        };
+        let id = StreamId::parse(&id, 0).unwrap();
+        assert!(id.ms >= before && id.ms <= unix_time_ms());
+        let Some((Value::Stream(stream), _)) = server.lock().await.db.get(b"s".as_ref()).cloned()
+        else {
+            panic!("expected a stream");
+        };
+        assert_eq!(stream.len(), 2);
+        assert_eq!(stream.last_id(), id);
+    }
+
+    #[tokio::test]
+    async fn execute_xadd_should_trim_and_honor_nomkstream() {
+        let (server, mut conn) = build_server_connection().await;
+        let cmd =
+            parse_command(&build_request("XADD", &["s", "NOMKSTREAM", "*", "f", "v"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        assert_eq!(resp, RespData::Null);
+        assert!(server.lock().await.db.is_empty());
+
+        for seq in 1..=5 {
+            let id = format!("1-{seq}");
+            let cmd = parse_command(&build_request("XADD", &["s", "MAXLEN", "3", &id, "f", "v"]))
+                .unwrap();
+            cmd.execute(server.clone(), &mut conn).await.unwrap();
+        }
+        let Some((Value::Stream(stream), _)) = server.lock().await.db.get(b"s".as_ref()).cloned()
+        else {
+            panic!("expected a stream");
+        };
+        assert_eq!(stream.len(), 3);
+        assert_eq!(stream.first_id(), StreamId::new(1, 3));
+
+        server.lock().await.db.insert(
+            Bytes::from_owner("string"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        let cmd = parse_command(&build_request("XADD", &["string", "*", "f", "v"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream_with_group, stream_entry_reply,
+        xrange::parse_range_bound,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::{Stream, StreamId},
+    utils::unix_time_ms,
+};
+
+/// How many pending entries are scanned for each entry to claim.
+const ATTEMPTS_FACTOR: usize = 10;
+
+#[derive(Debug, PartialEq)]
+pub struct XAutoClaim {
+    key: Bytes,
+    group: Bytes,
+    consumer: Bytes,
+    /// Only claim entries pending for at least this many milliseconds.
+    min_idle: u64,
+    start: StreamId,
+    count: usize,
+    /// Reply the IDs only, without counting a delivery.
+    justid: bool,
+}
+
+impl Parse for XAutoClaim {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 5)?;
+        let min_idle: i64 = lexical_core::parse(&args[3])?;
+        let start = parse_range_bound(&args[4], true)?.ok_or_else(|| {
+            ParseError::InvalidArgument(String::from_utf8_lossy(&args[4]).into_owned())
+        })?;
+
+        let mut count = 100;
+        let mut justid = false;
+        let mut index = 5;
+        while index < args.len() {
+            let option = str::from_utf8(&args[index])?.to_uppercase();
+            match option.as_str() {
+                "COUNT" => {
+                    check_length_ge(args, index + 2)?;
+                    count = lexical_core::parse(&args[index + 1])?;
+                    if count == 0 || count > usize::MAX / ATTEMPTS_FACTOR {
+                        return Err(ParseError::InvalidArgument("COUNT must be > 0".to_string()));
+                    }
+                    index += 2;
+                }
+                "JUSTID" => {
+                    justid = true;
+                    index += 1;
+                }
+                _ => return Err(ParseError::InvalidArgument(option)),
+            }
+        }
+
+        Ok(XAutoClaim {
+            key: args[0].clone(),
+            group: args[1].clone(),
+            consumer: args[2].clone(),
+            min_idle: min_idle.max(0) as u64,
+            start,
+            count,
+            justid,
+        })
+    }
+}
+
+impl XAutoClaim {
+    /// Claim the idle entries pending from the start ID, scanning a bounded number of them.
+    /// Returns the ID to continue the scan from, 0-0 once done, the IDs claimed and the IDs
+    /// dropped as deleted from the stream.
+    fn claim(&self, stream: &mut Stream, now_ms: u64) -> (StreamId, Vec<StreamId>, Vec<StreamId>) {
+        // The group was checked by the caller
+        let group = stream.group_mut(&self.group).unwrap();
+        group.see_consumer(&self.consumer, now_ms);
+        let mut attempts = self.count * ATTEMPTS_FACTOR;
+        let mut scanned = group
+            .pending()
+            .range(self.start..)
+            .map(|(id, _)| *id)
+            .take(attempts + 1)
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let (mut claimed, mut deleted) = (Vec::new(), Vec::new());
+        while attempts > 0 && claimed.len() < self.count {
+            let Some(id) = scanned.next() else {
+                break;
+            };
+            attempts -= 1;
+            let exists = stream.contains(id);
+            let group = stream.group_mut(&self.group).unwrap();
+            if !exists {
+                group.ack(id);
+                deleted.push(id);
+                continue;
+            }
+            let entry = &group.pending()[&id];
+            if now_ms.saturating_sub(entry.delivery_time) < self.min_idle {
+                continue;
+            }
+            let delivery_count = entry.delivery_count + !self.justid as u64;
+            group.claim(id, &self.consumer, now_ms, delivery_count);
+            group.see_consumer(&self.consumer, now_ms).active_time = Some(now_ms);
+            claimed.push(id);
+        }
+        (scanned.next().unwrap_or(StreamId::MIN), claimed, deleted)
+    }
+}
+
+impl ExecuteCommand for XAutoClaim {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let stream =
+            get_stream_with_group(db, stats, &self.key, &self.group, ExecError::NoKeyOrGroup)?;
+        let (next, claimed, deleted) = self.claim(stream, unix_time_ms());
+        let id_reply = |id: StreamId| RespData::BulkString(Some(Bytes::from(id.to_string())));
+        let claimed = claimed
+            .into_iter()
+            .map(|id| {
+                if self.justid {
+                    id_reply(id)
+                } else {
+                    // Only existing entries are claimed
+                    stream_entry_reply(stream.get(id).unwrap())
+                }
+            })
+            .collect();
+        Ok(RespData::Array(vec![
+            id_reply(next),
+            RespData::Array(claimed),
+            RespData::Array(deleted.into_iter().map(id_reply).collect()),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::XAutoClaim;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{ConsumerGroup, Stream, StreamId},
+        utils::unix_time_ms,
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    fn ids(ids: &[&str]) -> RespData {
+        RespData::Array(ids.iter().map(|id| bulk(id)).collect())
+    }
+
+    #[test]
+    fn parse_xautoclaim_should_read_options() {
+        let cmd = parse_command(&build_request(
+            "XAUTOCLAIM",
+            &["s", "g", "c", "10", "(1-1", "COUNT", "5", "JUSTID"],
+        ))
+        .expect("parse xautoclaim");
+        assert_eq!(
+            cmd,
+            Command::XAutoClaim(XAutoClaim {
+                key: Bytes::from_owner("s"),
+                group: Bytes::from_owner("g"),
+                consumer: Bytes::from_owner("c"),
+                min_idle: 10,
+                start: StreamId::new(1, 2),
+                count: 5,
+                justid: true,
+            })
+        );
+
+        for args in [
+            &["s", "g", "c", "10"][..],
+            &["s", "g", "c", "10", "0", "COUNT", "0"],
+            &["s", "g", "c", "10", "0", "COUNT"],
+            &["s", "g", "c", "10", "x"],
+            &["s", "g", "c", "10", "0", "FORCE"],
+        ] {
+            assert!(
+                parse_command(&build_request("XAUTOCLAIM", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xautoclaim_should_scan_pending_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for seq in 1..=5 {
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+            );
+        }
+        let group = Bytes::from_owner("g");
+        stream.create_group(group.clone(), ConsumerGroup::new(StreamId::MIN, None));
+        let now_ms = unix_time_ms();
+        stream.read_group(
+            &group,
+            &Bytes::from_owner("alice"),
+            4,
+            false,
+            now_ms - 10_000,
+        );
+        stream.read_group(&group, &Bytes::from_owner("alice"), 1, false, now_ms);
+        stream.remove(StreamId::new(1, 2));
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        for (args, expected) in [
+            (
+                &["s", "g", "bob", "5000", "0", "COUNT", "2", "JUSTID"][..],
+                vec![bulk("1-4"), ids(&["1-1", "1-3"]), ids(&["1-2"])],
+            ),
+            (
+                &["s", "g", "bob", "5000", "1-4", "JUSTID"],
+                vec![bulk("0-0"), ids(&["1-4"]), ids(&[])],
+            ),
+            (
+                &["s", "g", "bob", "0", "(1-4", "JUSTID"],
+                vec![bulk("0-0"), ids(&["1-5"]), ids(&[])],
+            ),
+        ] {
+            let cmd = parse_command(&build_request("XAUTOCLAIM", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await;
+            assert_eq!(resp, Ok(RespData::Array(expected)), "{args:?}");
+        }
+
+        let cmd = parse_command(&build_request(
+            "XAUTOCLAIM",
+            &["s", "g", "carol", "0", "-", "COUNT", "1"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await;
+        assert_eq!(
+            resp,
+            Ok(RespData::Array(vec![
+                bulk("1-3"),
+                RespData::Array(vec![RespData::Array(vec![
+                    bulk("1-1"),
+                    RespData::Array(vec![bulk("f"), bulk("v")]),
+                ])]),
+                ids(&[]),
+            ]))
+        );
+
+        let server = server.lock().await;
+        let Some((Value::Stream(stream), _)) = server.db.get(b"s".as_ref()) else {
+            panic!("expected a stream");
+        };
+        let group = stream.group(b"g").unwrap();
+        // JUSTID does not count deliveries
+        assert_eq!(group.pending()[&StreamId::new(1, 3)].delivery_count, 1);
+        assert_eq!(group.pending()[&StreamId::new(1, 1)].delivery_count, 2);
+        assert_eq!(group.pending()[&StreamId::new(1, 1)].consumer, "carol");
+    }
+}
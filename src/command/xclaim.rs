@@ -0,0 +1,331 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream_with_group, parse_stream_id, stream_entry_reply,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::{Stream, StreamId},
+    utils::unix_time_ms,
+};
+
+/// When the claimed entries count as delivered.
+#[derive(Debug, PartialEq)]
+enum DeliveryTime {
+    /// `IDLE ms`, this many milliseconds ago.
+    Idle(u64),
+    /// `TIME unix-time-milliseconds`.
+    At(u64),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct XClaim {
+    key: Bytes,
+    group: Bytes,
+    consumer: Bytes,
+    /// Only claim entries pending for at least this many milliseconds.
+    min_idle: u64,
+    ids: Vec<StreamId>,
+    /// Now by default.
+    delivery_time: Option<DeliveryTime>,
+    /// Set the delivery count, which is otherwise incremented.
+    retry_count: Option<u64>,
+    /// Make existing entries pending even if they were not.
+    force: bool,
+    /// Reply the IDs only, without counting a delivery.
+    justid: bool,
+    /// Move the last ID of the group forward to this one.
+    last_id: Option<StreamId>,
+}
+
+/// Parse a non-negative integer option, clamping negative values to 0 like Redis does.
+fn parse_clamped(arg: &Bytes) -> ParseResult<u64> {
+    let value: i64 = lexical_core::parse(arg)?;
+    Ok(value.max(0) as u64)
+}
+
+impl Parse for XClaim {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 5)?;
+        // The IDs go on up to the first option
+        let ids: Vec<_> = args[4..]
+            .iter()
+            .map_while(|arg| StreamId::parse(arg, 0))
+            .collect();
+        let mut claim = XClaim {
+            key: args[0].clone(),
+            group: args[1].clone(),
+            consumer: args[2].clone(),
+            min_idle: parse_clamped(&args[3])?,
+            ids,
+            delivery_time: None,
+            retry_count: None,
+            force: false,
+            justid: false,
+            last_id: None,
+        };
+
+        let mut index = 4 + claim.ids.len();
+        while index < args.len() {
+            let option = str::from_utf8(&args[index])?.to_uppercase();
+            match option.as_str() {
+                "FORCE" => claim.force = true,
+                "JUSTID" => claim.justid = true,
+                "IDLE" | "TIME" | "RETRYCOUNT" | "LASTID" => {
+                    check_length_ge(args, index + 2)?;
+                    let value = &args[index + 1];
+                    match option.as_str() {
+                        "IDLE" => {
+                            claim.delivery_time = Some(DeliveryTime::Idle(parse_clamped(value)?))
+                        }
+                        "TIME" => {
+                            claim.delivery_time = Some(DeliveryTime::At(parse_clamped(value)?))
+                        }
+                        "RETRYCOUNT" => claim.retry_count = Some(parse_clamped(value)?),
+                        _ => claim.last_id = Some(parse_stream_id(value, 0)?),
+                    }
+                    index += 1;
+                }
+                _ => return Err(ParseError::InvalidArgument(option)),
+            }
+            index += 1;
+        }
+        Ok(claim)
+    }
+}
+
+impl XClaim {
+    /// Claim the entries for the consumer, returning the IDs claimed.
+    fn claim(&self, stream: &mut Stream, now_ms: u64) -> Vec<StreamId> {
+        let delivery_time = match self.delivery_time {
+            Some(DeliveryTime::Idle(idle)) => now_ms.saturating_sub(idle),
+            Some(DeliveryTime::At(time)) => time.min(now_ms),
+            None => now_ms,
+        };
+        // The group was checked by the caller
+        let group = stream.group_mut(&self.group).unwrap();
+        if let Some(last_id) = self.last_id {
+            group.advance_last_id(last_id);
+        }
+        group.see_consumer(&self.consumer, now_ms);
+
+        let mut claimed = Vec::new();
+        for &id in &self.ids {
+            let exists = stream.contains(id);
+            let group = stream.group_mut(&self.group).unwrap();
+            // Entries deleted from the stream can not be claimed, and are not pending anymore
+            if !exists {
+                group.ack(id);
+                continue;
+            }
+            let (idle, delivery_count) = match group.pending().get(&id) {
+                Some(entry) => (
+                    now_ms.saturating_sub(entry.delivery_time),
+                    entry.delivery_count,
+                ),
+                None if self.force => (0, 1),
+                None => continue,
+            };
+            if idle < self.min_idle {
+                continue;
+            }
+            let delivery_count = match self.retry_count {
+                Some(retry_count) => retry_count,
+                None if self.justid => delivery_count,
+                None => delivery_count + 1,
+            };
+            group.claim(id, &self.consumer, delivery_time, delivery_count);
+            group.see_consumer(&self.consumer, now_ms).active_time = Some(now_ms);
+            claimed.push(id);
+        }
+        claimed
+    }
+}
+
+impl ExecuteCommand for XClaim {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let stream =
+            get_stream_with_group(db, stats, &self.key, &self.group, ExecError::NoKeyOrGroup)?;
+        let claimed = self.claim(stream, unix_time_ms());
+        let reply = claimed
+            .into_iter()
+            .map(|id| {
+                if self.justid {
+                    RespData::BulkString(Some(Bytes::from(id.to_string())))
+                } else {
+                    // Only existing entries are claimed
+                    stream_entry_reply(stream.get(id).unwrap())
+                }
+            })
+            .collect();
+        Ok(RespData::Array(reply))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{DeliveryTime, XClaim};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{ConsumerGroup, Stream, StreamId},
+        utils::unix_time_ms,
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    #[test]
+    fn parse_xclaim_should_read_ids_and_options() {
+        let cmd = parse_command(&build_request(
+            "XCLAIM",
+            &[
+                "s",
+                "g",
+                "c",
+                "100",
+                "1-1",
+                "2",
+                "IDLE",
+                "5",
+                "RETRYCOUNT",
+                "3",
+                "force",
+                "JUSTID",
+                "LASTID",
+                "9",
+            ],
+        ))
+        .expect("parse xclaim");
+        assert_eq!(
+            cmd,
+            Command::XClaim(XClaim {
+                key: Bytes::from_owner("s"),
+                group: Bytes::from_owner("g"),
+                consumer: Bytes::from_owner("c"),
+                min_idle: 100,
+                ids: vec![StreamId::new(1, 1), StreamId::new(2, 0)],
+                delivery_time: Some(DeliveryTime::Idle(5)),
+                retry_count: Some(3),
+                force: true,
+                justid: true,
+                last_id: Some(StreamId::new(9, 0)),
+            })
+        );
+
+        for args in [
+            &["s", "g", "c", "0"][..],
+            &["s", "g", "c", "x", "1"],
+            &["s", "g", "c", "0", "1", "IDLE"],
+            &["s", "g", "c", "0", "1", "LASTID", "x"],
+            &["s", "g", "c", "0", "1", "UNKNOWN"],
+        ] {
+            assert!(
+                parse_command(&build_request("XCLAIM", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xclaim_should_transfer_idle_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for seq in 1..=4 {
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+            );
+        }
+        let group = Bytes::from_owner("g");
+        stream.create_group(group.clone(), ConsumerGroup::new(StreamId::MIN, None));
+        let now_ms = unix_time_ms();
+        stream.read_group(
+            &group,
+            &Bytes::from_owner("alice"),
+            2,
+            false,
+            now_ms - 10_000,
+        );
+        stream.read_group(&group, &Bytes::from_owner("alice"), 1, false, now_ms);
+        stream.remove(StreamId::new(1, 2));
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        // 1-2 was deleted, 1-3 is not idle enough and 1-4 was never delivered
+        let cmd = parse_command(&build_request(
+            "XCLAIM",
+            &["s", "g", "bob", "5000", "1-1", "1-2", "1-3", "1-4"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await;
+        assert_eq!(
+            resp,
+            Ok(RespData::Array(vec![RespData::Array(vec![
+                bulk("1-1"),
+                RespData::Array(vec![bulk("f"), bulk("v")]),
+            ])]))
+        );
+        let cmd = parse_command(&build_request(
+            "XCLAIM",
+            &[
+                "s", "g", "carol", "0", "1-3", "1-4", "FORCE", "JUSTID", "LASTID", "1-9",
+            ],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await;
+        assert_eq!(resp, Ok(RespData::Array(vec![bulk("1-3"), bulk("1-4")])));
+        let cmd = parse_command(&build_request("XCLAIM", &["s", "x", "bob", "0", "1-1"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await;
+        assert_eq!(
+            resp,
+            Err(ExecError::NoKeyOrGroup("s".to_string(), "x".to_string()))
+        );
+
+        let server = server.lock().await;
+        let Some((Value::Stream(stream), _)) = server.db.get(b"s".as_ref()) else {
+            panic!("expected a stream");
+        };
+        let group = stream.group(b"g").unwrap();
+        assert_eq!(group.last_id(), StreamId::new(1, 9));
+        let pending: Vec<_> = group
+            .pending()
+            .iter()
+            .map(|(id, entry)| (id.seq, entry.consumer.clone(), entry.delivery_count))
+            .collect();
+        assert_eq!(
+            pending,
+            vec![
+                (1, Bytes::from_owner("bob"), 2),
+                (3, Bytes::from_owner("carol"), 1),
+                (4, Bytes::from_owner("carol"), 1),
+            ]
+        );
+        assert!(group.consumer(b"alice").unwrap().pending().is_empty());
+        assert!(group.consumer(b"bob").unwrap().active_time.is_some());
+    }
+}
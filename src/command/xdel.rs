@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_stream,
+        parse_stream_id,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::StreamId,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct XDel {
+    key: Bytes,
+    ids: Vec<StreamId>,
+}
+
+impl Parse for XDel {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        Ok(XDel {
+            key: args[0].clone(),
+            ids: args[1..]
+                .iter()
+                .map(|arg| parse_stream_id(arg, 0))
+                .collect::<ParseResult<_>>()?,
+        })
+    }
+}
+
+impl ExecuteCommand for XDel {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let Some(stream) = get_stream(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(0));
+        };
+        let removed = self.ids.iter().filter(|id| stream.remove(**id)).count();
+        Ok(RespData::Integer(removed as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::XDel;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{Stream, StreamId},
+    };
+
+    #[test]
+    fn parse_xdel_should_read_ids() {
+        let cmd = parse_command(&build_request("XDEL", &["s", "1-2", "3"])).expect("parse xdel");
+        assert_eq!(
+            cmd,
+            Command::XDel(XDel {
+                key: Bytes::from_owner("s"),
+                ids: vec![StreamId::new(1, 2), StreamId::new(3, 0)],
+            })
+        );
+        assert!(parse_command(&build_request("XDEL", &["s", "+"])).is_err());
+        assert!(parse_command(&build_request("XDEL", &["s"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_xdel_should_count_deleted_entries_and_keep_the_stream() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for seq in 1..=3 {
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+            );
+        }
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        for (args, expected) in [
+            (&["s", "1-1", "1-1", "1-9"][..], 1),
+            (&["s", "1-2", "1-3"], 2),
+            (&["missing", "1-1"], 0),
+        ] {
+            let cmd = parse_command(&build_request("XDEL", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.expect("xdel");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+
+        let Some((Value::Stream(stream), _)) = server.lock().await.db.get(b"s".as_ref()).cloned()
+        else {
+            panic!("expected a stream");
+        };
+        assert!(stream.is_empty());
+        assert_eq!(stream.max_deleted_id(), StreamId::new(1, 3));
+    }
+}
@@ -0,0 +1,358 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_or_insert_stream, get_stream, parse_stream_id,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::{ConsumerGroup, StreamId},
+    utils::unix_time_ms,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum XGroup {
+    Create {
+        key: Bytes,
+        group: Bytes,
+        /// `None` for `$`, the last ID of the stream.
+        id: Option<StreamId>,
+        mkstream: bool,
+        entries_read: Option<u64>,
+    },
+    SetId {
+        key: Bytes,
+        group: Bytes,
+        id: Option<StreamId>,
+        entries_read: Option<u64>,
+    },
+    Destroy {
+        key: Bytes,
+        group: Bytes,
+    },
+    CreateConsumer {
+        key: Bytes,
+        group: Bytes,
+        consumer: Bytes,
+    },
+    DelConsumer {
+        key: Bytes,
+        group: Bytes,
+        consumer: Bytes,
+    },
+}
+
+/// Parse the ID a group is set to, `$` or an ID.
+fn parse_group_id(arg: &Bytes) -> ParseResult<Option<StreamId>> {
+    match arg.as_ref() {
+        b"$" => Ok(None),
+        _ => parse_stream_id(arg, 0).map(Some),
+    }
+}
+
+/// Parse the `[MKSTREAM] [ENTRIESREAD entries-read]` options, returning whether `MKSTREAM` was
+/// given when it is allowed.
+fn parse_group_options(args: &[Bytes], allow_mkstream: bool) -> ParseResult<(bool, Option<u64>)> {
+    let mut mkstream = false;
+    let mut entries_read = None;
+    let mut index = 0;
+    while index < args.len() {
+        let option = str::from_utf8(&args[index])?.to_uppercase();
+        match option.as_str() {
+            "MKSTREAM" if allow_mkstream => {
+                mkstream = true;
+                index += 1;
+            }
+            "ENTRIESREAD" => {
+                check_length_ge(args, index + 2)?;
+                let value: i64 = lexical_core::parse(&args[index + 1])?;
+                if value < -1 {
+                    return Err(ParseError::InvalidArgument(
+                        "value for ENTRIESREAD must be positive or -1".to_string(),
+                    ));
+                }
+                entries_read = u64::try_from(value).ok();
+                index += 2;
+            }
+            _ => return Err(ParseError::InvalidArgument(option)),
+        }
+    }
+    Ok((mkstream, entries_read))
+}
+
+impl Parse for XGroup {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 3)?;
+        let (key, group) = (args[1].clone(), args[2].clone());
+        let subcommand = str::from_utf8(&args[0])?;
+        match subcommand.to_uppercase().as_str() {
+            "CREATE" => {
+                check_length_ge(args, 4)?;
+                let (mkstream, entries_read) = parse_group_options(&args[4..], true)?;
+                Ok(XGroup::Create {
+                    key,
+                    group,
+                    id: parse_group_id(&args[3])?,
+                    mkstream,
+                    entries_read,
+                })
+            }
+            "SETID" => {
+                check_length_ge(args, 4)?;
+                let (_, entries_read) = parse_group_options(&args[4..], false)?;
+                Ok(XGroup::SetId {
+                    key,
+                    group,
+                    id: parse_group_id(&args[3])?,
+                    entries_read,
+                })
+            }
+            "DESTROY" => {
+                check_length_eq(args, 3)?;
+                Ok(XGroup::Destroy { key, group })
+            }
+            "CREATECONSUMER" => {
+                check_length_eq(args, 4)?;
+                Ok(XGroup::CreateConsumer {
+                    key,
+                    group,
+                    consumer: args[3].clone(),
+                })
+            }
+            "DELCONSUMER" => {
+                check_length_eq(args, 4)?;
+                Ok(XGroup::DelConsumer {
+                    key,
+                    group,
+                    consumer: args[3].clone(),
+                })
+            }
+            _ => Err(ParseError::InvalidArgument(subcommand.to_string())),
+        }
+    }
+}
+
+impl XGroup {
+    fn key(&self) -> &Bytes {
+        match self {
+            XGroup::Create { key, .. }
+            | XGroup::SetId { key, .. }
+            | XGroup::Destroy { key, .. }
+            | XGroup::CreateConsumer { key, .. }
+            | XGroup::DelConsumer { key, .. } => key,
+        }
+    }
+}
+
+impl ExecuteCommand for XGroup {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let key = self.key();
+        let stream = match self {
+            XGroup::Create { mkstream: true, .. } => get_or_insert_stream(db, stats, key)?,
+            _ => get_stream(db, stats, key)?.ok_or(ExecError::XGroupNoKey)?,
+        };
+        let no_group = |group: &Bytes| {
+            ExecError::NoGroup(
+                String::from_utf8_lossy(key).into_owned(),
+                String::from_utf8_lossy(group).into_owned(),
+            )
+        };
+
+        match self {
+            XGroup::Create {
+                group,
+                id,
+                entries_read,
+                ..
+            } => {
+                let id = id.unwrap_or(stream.last_id());
+                if !stream.create_group(group.clone(), ConsumerGroup::new(id, *entries_read)) {
+                    return Err(ExecError::BusyGroup);
+                }
+                Ok(RespData::SimpleString("OK".to_string()))
+            }
+            XGroup::SetId {
+                group,
+                id,
+                entries_read,
+                ..
+            } => {
+                let id = id.unwrap_or(stream.last_id());
+                let Some(group) = stream.group_mut(group) else {
+                    return Err(no_group(group));
+                };
+                group.set_last_id(id, *entries_read);
+                Ok(RespData::SimpleString("OK".to_string()))
+            }
+            XGroup::Destroy { group, .. } => {
+                let destroyed = stream.destroy_group(group);
+                // Clients blocked on the group get an error
+                if destroyed {
+                    server.serve_blocked_clients(key);
+                }
+                Ok(RespData::Integer(destroyed as i64))
+            }
+            XGroup::CreateConsumer {
+                group, consumer, ..
+            } => {
+                let Some(group) = stream.group_mut(group) else {
+                    return Err(no_group(group));
+                };
+                let created = group.create_consumer(consumer, unix_time_ms());
+                Ok(RespData::Integer(created as i64))
+            }
+            XGroup::DelConsumer {
+                group, consumer, ..
+            } => {
+                let Some(group) = stream.group_mut(group) else {
+                    return Err(no_group(group));
+                };
+                let pending = group.delete_consumer(consumer).unwrap_or(0);
+                Ok(RespData::Integer(pending as i64))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::XGroup;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{Stream, StreamId},
+    };
+
+    #[test]
+    fn parse_xgroup_should_read_subcommands() {
+        let cmd = parse_command(&build_request(
+            "XGROUP",
+            &["create", "s", "g", "$", "MKSTREAM", "ENTRIESREAD", "3"],
+        ))
+        .expect("parse xgroup create");
+        assert_eq!(
+            cmd,
+            Command::XGroup(XGroup::Create {
+                key: Bytes::from_owner("s"),
+                group: Bytes::from_owner("g"),
+                id: None,
+                mkstream: true,
+                entries_read: Some(3),
+            })
+        );
+        let cmd = parse_command(&build_request(
+            "XGROUP",
+            &["SETID", "s", "g", "5", "ENTRIESREAD", "-1"],
+        ))
+        .expect("parse xgroup setid");
+        assert_eq!(
+            cmd,
+            Command::XGroup(XGroup::SetId {
+                key: Bytes::from_owner("s"),
+                group: Bytes::from_owner("g"),
+                id: Some(StreamId::new(5, 0)),
+                entries_read: None,
+            })
+        );
+
+        for args in [
+            &["CREATE", "s", "g"][..],
+            &["CREATE", "s", "g", "+"],
+            &["CREATE", "s", "g", "0", "ENTRIESREAD", "-2"],
+            &["SETID", "s", "g", "0", "MKSTREAM"],
+            &["DESTROY", "s", "g", "c"],
+            &["CREATECONSUMER", "s", "g"],
+            &["HELP", "s", "g"],
+        ] {
+            assert!(
+                parse_command(&build_request("XGROUP", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xgroup_should_manage_groups_and_consumers() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        stream.add(
+            StreamId::new(1, 1),
+            &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+        );
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        let ok = || Ok(RespData::SimpleString("OK".to_string()));
+        for (args, expected) in [
+            (
+                &["CREATE", "missing", "g", "$"][..],
+                Err(ExecError::XGroupNoKey),
+            ),
+            (&["CREATE", "new", "g", "$", "MKSTREAM"], ok()),
+            (&["CREATE", "s", "g", "$"], ok()),
+            (&["CREATE", "s", "g", "0"], Err(ExecError::BusyGroup)),
+            (&["CREATE", "s", "h", "0", "ENTRIESREAD", "0"], ok()),
+            (&["SETID", "s", "h", "$"], ok()),
+            (
+                &["SETID", "s", "x", "0"],
+                Err(ExecError::NoGroup("s".to_string(), "x".to_string())),
+            ),
+            (
+                &["CREATECONSUMER", "s", "g", "alice"],
+                Ok(RespData::Integer(1)),
+            ),
+            (
+                &["CREATECONSUMER", "s", "g", "alice"],
+                Ok(RespData::Integer(0)),
+            ),
+            (
+                &["DELCONSUMER", "s", "g", "alice"],
+                Ok(RespData::Integer(0)),
+            ),
+            (&["DELCONSUMER", "s", "g", "bob"], Ok(RespData::Integer(0))),
+            (&["DESTROY", "s", "g"], Ok(RespData::Integer(1))),
+            (&["DESTROY", "s", "g"], Ok(RespData::Integer(0))),
+            (&["DESTROY", "missing", "g"], Err(ExecError::XGroupNoKey)),
+        ] {
+            let cmd = parse_command(&build_request("XGROUP", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await;
+            assert_eq!(resp, expected, "{args:?}");
+        }
+
+        let server = server.lock().await;
+        let Some((Value::Stream(stream), _)) = server.db.get(b"s".as_ref()) else {
+            panic!("expected a stream");
+        };
+        let group = stream.group(b"h").unwrap();
+        assert_eq!(group.last_id(), StreamId::new(1, 1));
+        assert_eq!(group.entries_read(), None);
+        assert!(stream.group(b"g").is_none());
+        assert!(matches!(
+            server.db.get(b"new".as_ref()),
+            Some((Value::Stream(_), _))
+        ));
+    }
+}
@@ -0,0 +1,444 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream, get_stream_with_group, stream_entries_reply, stream_entry_reply,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::{ConsumerGroup, Stream, StreamId},
+    utils::unix_time_ms,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum XInfo {
+    Stream {
+        key: Bytes,
+        /// With `FULL`, how many entries and pending entries to list, 0 meaning all of them.
+        full: Option<usize>,
+    },
+    Groups {
+        key: Bytes,
+    },
+    Consumers {
+        key: Bytes,
+        group: Bytes,
+    },
+}
+
+impl Parse for XInfo {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        let key = args[1].clone();
+        let subcommand = str::from_utf8(&args[0])?;
+        match subcommand.to_uppercase().as_str() {
+            "STREAM" => {
+                let full = match &args[2..] {
+                    [] => None,
+                    [full] if full.eq_ignore_ascii_case(b"FULL") => Some(10),
+                    [full, option, count]
+                        if full.eq_ignore_ascii_case(b"FULL")
+                            && option.eq_ignore_ascii_case(b"COUNT") =>
+                    {
+                        let count: i64 = lexical_core::parse(count)?;
+                        Some(count.max(0) as usize)
+                    }
+                    [option, ..] => {
+                        return Err(ParseError::InvalidArgument(
+                            str::from_utf8(option)?.to_string(),
+                        ));
+                    }
+                };
+                Ok(XInfo::Stream { key, full })
+            }
+            "GROUPS" => {
+                check_length_eq(args, 2)?;
+                Ok(XInfo::Groups { key })
+            }
+            "CONSUMERS" => {
+                check_length_eq(args, 3)?;
+                Ok(XInfo::Consumers {
+                    key,
+                    group: args[2].clone(),
+                })
+            }
+            _ => Err(ParseError::InvalidArgument(subcommand.to_string())),
+        }
+    }
+}
+
+fn map_reply<'a>(fields: impl IntoIterator<Item = (&'a str, RespData)>) -> RespData {
+    RespData::Map(
+        fields
+            .into_iter()
+            .map(|(name, value)| (Bytes::copy_from_slice(name.as_bytes()), value))
+            .collect(),
+    )
+}
+
+fn id_reply(id: StreamId) -> RespData {
+    RespData::BulkString(Some(Bytes::from(id.to_string())))
+}
+
+fn optional_integer_reply(value: Option<u64>) -> RespData {
+    value.map_or(RespData::Null, |value| RespData::Integer(value as i64))
+}
+
+/// The information shared by both forms of `XINFO STREAM`.
+fn stream_fields(stream: &Stream) -> [(&'static str, RespData); 6] {
+    [
+        ("length", RespData::Integer(stream.len() as i64)),
+        (
+            "radix-tree-keys",
+            RespData::Integer(stream.nodes().len() as i64),
+        ),
+        ("last-generated-id", id_reply(stream.last_id())),
+        ("max-deleted-entry-id", id_reply(stream.max_deleted_id())),
+        (
+            "entries-added",
+            RespData::Integer(stream.entries_added() as i64),
+        ),
+        ("recorded-first-entry-id", id_reply(stream.first_id())),
+    ]
+}
+
+/// Reply the stream with its entries and the full state of its groups, listing up to `count`
+/// entries and pending entries of each kind.
+fn full_stream_reply(stream: &Stream, count: usize) -> RespData {
+    let count = if count == 0 { usize::MAX } else { count };
+    let groups = stream
+        .groups()
+        .map(|(name, group)| {
+            let pending = group
+                .pending()
+                .iter()
+                .take(count)
+                .map(|(id, entry)| {
+                    RespData::Array(vec![
+                        id_reply(*id),
+                        RespData::BulkString(Some(entry.consumer.clone())),
+                        RespData::Integer(entry.delivery_time as i64),
+                        RespData::Integer(entry.delivery_count as i64),
+                    ])
+                })
+                .collect();
+            let consumers = group
+                .consumers()
+                .iter()
+                .map(|(name, consumer)| {
+                    let pending = consumer
+                        .pending()
+                        .iter()
+                        .take(count)
+                        .map(|id| {
+                            let entry = &group.pending()[id];
+                            RespData::Array(vec![
+                                id_reply(*id),
+                                RespData::Integer(entry.delivery_time as i64),
+                                RespData::Integer(entry.delivery_count as i64),
+                            ])
+                        })
+                        .collect();
+                    map_reply([
+                        ("name", RespData::BulkString(Some(name.clone()))),
+                        ("seen-time", RespData::Integer(consumer.seen_time as i64)),
+                        (
+                            "active-time",
+                            RespData::Integer(consumer.active_time.map_or(-1, |time| time as i64)),
+                        ),
+                        (
+                            "pel-count",
+                            RespData::Integer(consumer.pending().len() as i64),
+                        ),
+                        ("pending", RespData::Array(pending)),
+                    ])
+                })
+                .collect();
+            map_reply([
+                ("name", RespData::BulkString(Some(name.clone()))),
+                ("last-delivered-id", id_reply(group.last_id())),
+                ("entries-read", optional_integer_reply(group.entries_read())),
+                ("lag", optional_integer_reply(stream.lag(group))),
+                ("pel-count", RespData::Integer(group.pending().len() as i64)),
+                ("pending", RespData::Array(pending)),
+                ("consumers", RespData::Array(consumers)),
+            ])
+        })
+        .collect();
+
+    map_reply(stream_fields(stream).into_iter().chain([
+        (
+            "entries",
+            stream_entries_reply(stream.range(StreamId::MIN, StreamId::MAX).take(count)),
+        ),
+        ("groups", RespData::Array(groups)),
+    ]))
+}
+
+fn group_reply(stream: &Stream, name: &Bytes, group: &ConsumerGroup) -> RespData {
+    map_reply([
+        ("name", RespData::BulkString(Some(name.clone()))),
+        (
+            "consumers",
+            RespData::Integer(group.consumers().len() as i64),
+        ),
+        ("pending", RespData::Integer(group.pending().len() as i64)),
+        ("last-delivered-id", id_reply(group.last_id())),
+        ("entries-read", optional_integer_reply(group.entries_read())),
+        ("lag", optional_integer_reply(stream.lag(group))),
+    ])
+}
+
+impl ExecuteCommand for XInfo {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        match self {
+            XInfo::Stream { key, full } => {
+                let stream = get_stream(db, stats, key)?.ok_or(ExecError::NoSuchKey)?;
+                if let Some(count) = full {
+                    return Ok(full_stream_reply(stream, *count));
+                }
+                let entry_reply =
+                    |entry: Option<_>| entry.map_or(RespData::Null, stream_entry_reply);
+                Ok(map_reply(stream_fields(stream).into_iter().chain([
+                    ("groups", RespData::Integer(stream.groups().len() as i64)),
+                    ("first-entry", entry_reply(stream.first_entry())),
+                    ("last-entry", entry_reply(stream.last_entry())),
+                ])))
+            }
+            XInfo::Groups { key } => {
+                let stream = get_stream(db, stats, key)?.ok_or(ExecError::NoSuchKey)?;
+                Ok(RespData::Array(
+                    stream
+                        .groups()
+                        .map(|(name, group)| group_reply(stream, name, group))
+                        .collect(),
+                ))
+            }
+            XInfo::Consumers { key, group } => {
+                get_stream(db, stats, key)?.ok_or(ExecError::NoSuchKey)?;
+                let stream = get_stream_with_group(db, stats, key, group, ExecError::NoGroup)?;
+                let now_ms = unix_time_ms();
+                // The group was checked just above
+                let consumers = stream
+                    .group(group)
+                    .unwrap()
+                    .consumers()
+                    .iter()
+                    .map(|(name, consumer)| {
+                        let inactive = consumer
+                            .active_time
+                            .map_or(-1, |time| now_ms.saturating_sub(time) as i64);
+                        map_reply([
+                            ("name", RespData::BulkString(Some(name.clone()))),
+                            (
+                                "pending",
+                                RespData::Integer(consumer.pending().len() as i64),
+                            ),
+                            (
+                                "idle",
+                                RespData::Integer(now_ms.saturating_sub(consumer.seen_time) as i64),
+                            ),
+                            ("inactive", RespData::Integer(inactive)),
+                        ])
+                    })
+                    .collect();
+                Ok(RespData::Array(consumers))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+
+    use super::XInfo;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{ConsumerGroup, Stream, StreamId},
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    fn entry(id: &str) -> RespData {
+        RespData::Array(vec![bulk(id), RespData::Array(vec![bulk("f"), bulk("v")])])
+    }
+
+    fn fields(resp: RespData) -> HashMap<Bytes, RespData> {
+        match resp {
+            RespData::Map(fields) => fields.into_iter().collect(),
+            resp => panic!("expected a map, got {resp:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_xinfo_should_read_subcommands() {
+        for (args, expected) in [
+            (
+                &["STREAM", "s"][..],
+                XInfo::Stream {
+                    key: Bytes::from_owner("s"),
+                    full: None,
+                },
+            ),
+            (
+                &["stream", "s", "full"],
+                XInfo::Stream {
+                    key: Bytes::from_owner("s"),
+                    full: Some(10),
+                },
+            ),
+            (
+                &["STREAM", "s", "FULL", "COUNT", "0"],
+                XInfo::Stream {
+                    key: Bytes::from_owner("s"),
+                    full: Some(0),
+                },
+            ),
+            (
+                &["CONSUMERS", "s", "g"],
+                XInfo::Consumers {
+                    key: Bytes::from_owner("s"),
+                    group: Bytes::from_owner("g"),
+                },
+            ),
+        ] {
+            let cmd = parse_command(&build_request("XINFO", args)).expect("parse xinfo");
+            assert_eq!(cmd, Command::XInfo(expected), "{args:?}");
+        }
+
+        for args in [
+            &["STREAM"][..],
+            &["STREAM", "s", "COUNT", "1"],
+            &["GROUPS", "s", "g"],
+            &["CONSUMERS", "s"],
+            &["HELP", "s"],
+        ] {
+            assert!(
+                parse_command(&build_request("XINFO", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xinfo_should_describe_stream_groups_and_consumers() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for seq in 1..=3 {
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+            );
+        }
+        let group = Bytes::from_owner("g");
+        stream.create_group(group.clone(), ConsumerGroup::new(StreamId::MIN, Some(0)));
+        stream.read_group(&group, &Bytes::from_owner("alice"), 2, false, 1000);
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        let cmd = parse_command(&build_request("XINFO", &["STREAM", "s"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.unwrap();
+        let RespData::Map(map) = &resp else {
+            panic!("expected a map, got {resp:?}");
+        };
+        assert_eq!(
+            map.iter()
+                .map(|(name, _)| name.as_ref())
+                .take(3)
+                .collect::<Vec<_>>(),
+            [b"length".as_ref(), b"radix-tree-keys", b"last-generated-id"]
+        );
+        let info = fields(resp);
+        assert_eq!(info[b"length".as_ref()], RespData::Integer(3));
+        assert_eq!(info[b"groups".as_ref()], RespData::Integer(1));
+        assert_eq!(info[b"last-generated-id".as_ref()], bulk("1-3"));
+        assert_eq!(info[b"first-entry".as_ref()], entry("1-1"));
+        assert_eq!(info[b"last-entry".as_ref()], entry("1-3"));
+
+        let cmd = parse_command(&build_request("XINFO", &["GROUPS", "s"])).unwrap();
+        let RespData::Array(mut groups) = cmd.execute(server.clone(), &mut conn).await.unwrap()
+        else {
+            panic!("expected groups");
+        };
+        let info = fields(groups.remove(0));
+        assert_eq!(info[b"name".as_ref()], bulk("g"));
+        assert_eq!(info[b"consumers".as_ref()], RespData::Integer(1));
+        assert_eq!(info[b"pending".as_ref()], RespData::Integer(2));
+        assert_eq!(info[b"last-delivered-id".as_ref()], bulk("1-2"));
+        assert_eq!(info[b"entries-read".as_ref()], RespData::Integer(2));
+        assert_eq!(info[b"lag".as_ref()], RespData::Integer(1));
+
+        let cmd = parse_command(&build_request("XINFO", &["CONSUMERS", "s", "g"])).unwrap();
+        let RespData::Array(mut consumers) = cmd.execute(server.clone(), &mut conn).await.unwrap()
+        else {
+            panic!("expected consumers");
+        };
+        let info = fields(consumers.remove(0));
+        assert_eq!(info[b"name".as_ref()], bulk("alice"));
+        assert_eq!(info[b"pending".as_ref()], RespData::Integer(2));
+
+        let cmd = parse_command(&build_request(
+            "XINFO",
+            &["STREAM", "s", "FULL", "COUNT", "1"],
+        ))
+        .unwrap();
+        let mut info = fields(cmd.execute(server.clone(), &mut conn).await.unwrap());
+        assert_eq!(
+            info[b"entries".as_ref()],
+            RespData::Array(vec![entry("1-1")])
+        );
+        let Some(RespData::Array(mut groups)) = info.remove(b"groups".as_ref()) else {
+            panic!("expected groups");
+        };
+        let group = fields(groups.remove(0));
+        assert_eq!(group[b"pel-count".as_ref()], RespData::Integer(2));
+        assert_eq!(
+            group[b"pending".as_ref()],
+            RespData::Array(vec![RespData::Array(vec![
+                bulk("1-1"),
+                bulk("alice"),
+                RespData::Integer(1000),
+                RespData::Integer(1),
+            ])])
+        );
+
+        for (args, expected) in [
+            (&["STREAM", "missing"][..], ExecError::NoSuchKey),
+            (&["CONSUMERS", "missing", "g"], ExecError::NoSuchKey),
+            (
+                &["CONSUMERS", "s", "x"],
+                ExecError::NoGroup("s".to_string(), "x".to_string()),
+            ),
+        ] {
+            let cmd = parse_command(&build_request("XINFO", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await;
+            assert_eq!(resp, Err(expected), "{args:?}");
+        }
+    }
+}
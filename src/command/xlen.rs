@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_stream},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct XLen {
+    key: Bytes,
+}
+
+impl Parse for XLen {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(XLen {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for XLen {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let len = get_stream(db, stats, &self.key)?.map_or(0, |stream| stream.len());
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::XLen;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{Stream, StreamId},
+    };
+
+    #[test]
+    fn parse_xlen_should_read_key() {
+        let cmd = parse_command(&build_request("XLEN", &["s"])).expect("parse xlen");
+        assert_eq!(
+            cmd,
+            Command::XLen(XLen {
+                key: Bytes::from_owner("s")
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_xlen_should_count_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        stream.add(
+            StreamId::new(1, 1),
+            &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+        );
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+        server.lock().await.db.insert(
+            Bytes::from_owner("string"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        for (key, expected) in [
+            ("s", Ok(1)),
+            ("missing", Ok(0)),
+            ("string", Err(ExecError::WrongType)),
+        ] {
+            let cmd = parse_command(&build_request("XLEN", &[key])).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await;
+            assert_eq!(resp, expected.map(RespData::Integer), "{key}");
+        }
+    }
+}
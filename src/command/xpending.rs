@@ -0,0 +1,321 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream_with_group,
+        xrange::parse_range_bound,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::{ConsumerGroup, StreamId},
+    utils::unix_time_ms,
+};
+
+/// The pending entries to list, instead of summarizing them.
+#[derive(Debug, PartialEq)]
+struct PendingRange {
+    /// List the entries pending for at least this many milliseconds.
+    min_idle: u64,
+    start: StreamId,
+    end: StreamId,
+    count: usize,
+    consumer: Option<Bytes>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct XPending {
+    key: Bytes,
+    group: Bytes,
+    range: Option<PendingRange>,
+}
+
+impl Parse for XPending {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        let (key, group) = (args[0].clone(), args[1].clone());
+        if args.len() == 2 {
+            return Ok(XPending {
+                key,
+                group,
+                range: None,
+            });
+        }
+
+        let (min_idle, args) = match &args[2..] {
+            [option, min_idle, rest @ ..] if option.eq_ignore_ascii_case(b"IDLE") => {
+                let min_idle: i64 = lexical_core::parse(min_idle)?;
+                (min_idle.max(0) as u64, rest)
+            }
+            rest => (0, rest),
+        };
+        // The consumer is optional
+        if args.len() != 4 {
+            check_length_eq(args, 3)?;
+        }
+        let invalid =
+            |arg: &Bytes| ParseError::InvalidArgument(String::from_utf8_lossy(arg).into_owned());
+        let start = parse_range_bound(&args[0], true)?.ok_or_else(|| invalid(&args[0]))?;
+        let end = parse_range_bound(&args[1], false)?.ok_or_else(|| invalid(&args[1]))?;
+        let count: i64 = lexical_core::parse(&args[2])?;
+
+        Ok(XPending {
+            key,
+            group,
+            range: Some(PendingRange {
+                min_idle,
+                start,
+                end,
+                count: count.max(0) as usize,
+                consumer: args.get(3).cloned(),
+            }),
+        })
+    }
+}
+
+/// Reply the count of pending entries, their smallest and greatest IDs, and how many are pending
+/// for each consumer.
+fn summary_reply(group: &ConsumerGroup) -> RespData {
+    let pending = group.pending();
+    let (Some((first, _)), Some((last, _))) = (pending.first_key_value(), pending.last_key_value())
+    else {
+        return RespData::Array(vec![
+            RespData::Integer(0),
+            RespData::Null,
+            RespData::Null,
+            RespData::NullArray,
+        ]);
+    };
+    let consumers = group
+        .consumers()
+        .iter()
+        .filter(|(_, consumer)| !consumer.pending().is_empty())
+        .map(|(name, consumer)| {
+            RespData::Array(vec![
+                RespData::BulkString(Some(name.clone())),
+                RespData::BulkString(Some(Bytes::from(consumer.pending().len().to_string()))),
+            ])
+        })
+        .collect();
+    RespData::Array(vec![
+        RespData::Integer(pending.len() as i64),
+        RespData::BulkString(Some(Bytes::from(first.to_string()))),
+        RespData::BulkString(Some(Bytes::from(last.to_string()))),
+        RespData::Array(consumers),
+    ])
+}
+
+impl ExecuteCommand for XPending {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let stream =
+            get_stream_with_group(db, stats, &self.key, &self.group, ExecError::NoKeyOrGroup)?;
+        // The group was checked just above
+        let group = stream.group(&self.group).unwrap();
+        let Some(range) = &self.range else {
+            return Ok(summary_reply(group));
+        };
+        if range.start > range.end {
+            return Ok(RespData::Array(vec![]));
+        }
+
+        let now_ms = unix_time_ms();
+        let ids: Box<dyn Iterator<Item = &StreamId>> = match &range.consumer {
+            Some(consumer) => match group.consumer(consumer) {
+                Some(consumer) => Box::new(consumer.pending().range(range.start..=range.end)),
+                None => return Ok(RespData::Array(vec![])),
+            },
+            None => Box::new(
+                group
+                    .pending()
+                    .range(range.start..=range.end)
+                    .map(|(id, _)| id),
+            ),
+        };
+        let entries = ids
+            .map(|id| (id, &group.pending()[id]))
+            .filter(|(_, entry)| now_ms.saturating_sub(entry.delivery_time) >= range.min_idle)
+            .take(range.count)
+            .map(|(id, entry)| {
+                RespData::Array(vec![
+                    RespData::BulkString(Some(Bytes::from(id.to_string()))),
+                    RespData::BulkString(Some(entry.consumer.clone())),
+                    RespData::Integer(now_ms.saturating_sub(entry.delivery_time) as i64),
+                    RespData::Integer(entry.delivery_count as i64),
+                ])
+            })
+            .collect();
+        Ok(RespData::Array(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{PendingRange, XPending};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{ConsumerGroup, Stream, StreamId},
+        utils::unix_time_ms,
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    #[test]
+    fn parse_xpending_should_read_summary_or_range() {
+        let cmd = parse_command(&build_request(
+            "XPENDING",
+            &["s", "g", "IDLE", "100", "(1-0", "+", "10", "c"],
+        ))
+        .expect("parse xpending");
+        assert_eq!(
+            cmd,
+            Command::XPending(XPending {
+                key: Bytes::from_owner("s"),
+                group: Bytes::from_owner("g"),
+                range: Some(PendingRange {
+                    min_idle: 100,
+                    start: StreamId::new(1, 1),
+                    end: StreamId::MAX,
+                    count: 10,
+                    consumer: Some(Bytes::from_owner("c")),
+                }),
+            })
+        );
+        let cmd = parse_command(&build_request("XPENDING", &["s", "g"])).expect("parse xpending");
+        assert_eq!(
+            cmd,
+            Command::XPending(XPending {
+                key: Bytes::from_owner("s"),
+                group: Bytes::from_owner("g"),
+                range: None,
+            })
+        );
+
+        for args in [
+            &["s"][..],
+            &["s", "g", "-", "+"],
+            &["s", "g", "IDLE", "10", "-", "+"],
+            &["s", "g", "-", "+", "10", "c", "extra"],
+            &["s", "g", "x", "+", "10"],
+        ] {
+            assert!(
+                parse_command(&build_request("XPENDING", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xpending_should_summarize_and_list_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for seq in 1..=3 {
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+            );
+        }
+        let group = Bytes::from_owner("g");
+        stream.create_group(group.clone(), ConsumerGroup::new(StreamId::MIN, None));
+        stream.create_group(Bytes::from_owner("empty"), ConsumerGroup::default());
+        let now_ms = unix_time_ms();
+        stream.read_group(
+            &group,
+            &Bytes::from_owner("alice"),
+            2,
+            false,
+            now_ms - 10_000,
+        );
+        stream.read_group(&group, &Bytes::from_owner("bob"), 1, false, now_ms);
+        stream.read_group(&group, &Bytes::from_owner("carol"), 1, false, now_ms);
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        let cmd = parse_command(&build_request("XPENDING", &["s", "g"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await;
+        assert_eq!(
+            resp,
+            Ok(RespData::Array(vec![
+                RespData::Integer(3),
+                bulk("1-1"),
+                bulk("1-3"),
+                RespData::Array(vec![
+                    RespData::Array(vec![bulk("alice"), bulk("2")]),
+                    RespData::Array(vec![bulk("bob"), bulk("1")]),
+                ]),
+            ]))
+        );
+        let cmd = parse_command(&build_request("XPENDING", &["s", "empty"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await;
+        assert_eq!(
+            resp,
+            Ok(RespData::Array(vec![
+                RespData::Integer(0),
+                RespData::Null,
+                RespData::Null,
+                RespData::NullArray,
+            ]))
+        );
+
+        for (args, expected) in [
+            (&["s", "g", "-", "+", "10"][..], vec!["1-1", "1-2", "1-3"]),
+            (&["s", "g", "-", "+", "2"], vec!["1-1", "1-2"]),
+            (&["s", "g", "(1-1", "+", "10", "alice"], vec!["1-2"]),
+            (
+                &["s", "g", "IDLE", "5000", "-", "+", "10"],
+                vec!["1-1", "1-2"],
+            ),
+            (&["s", "g", "-", "+", "10", "carol"], vec![]),
+            (&["s", "g", "2", "1", "10"], vec![]),
+        ] {
+            let cmd = parse_command(&build_request("XPENDING", args)).unwrap();
+            let Ok(RespData::Array(entries)) = cmd.execute(server.clone(), &mut conn).await else {
+                panic!("expected entries for {args:?}");
+            };
+            let ids: Vec<_> = entries
+                .iter()
+                .map(|entry| match entry {
+                    RespData::Array(fields) => match &fields[0] {
+                        RespData::BulkString(Some(id)) => id.clone(),
+                        _ => panic!("expected an ID"),
+                    },
+                    _ => panic!("expected an entry"),
+                })
+                .collect();
+            assert_eq!(ids, expected, "{args:?}");
+        }
+
+        let cmd = parse_command(&build_request("XPENDING", &["s", "missing"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await;
+        assert_eq!(
+            resp,
+            Err(ExecError::NoKeyOrGroup(
+                "s".to_string(),
+                "missing".to_string()
+            ))
+        );
+    }
+}
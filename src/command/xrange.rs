@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream, parse_stream_id, stream_entries_reply,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::StreamId,
+};
+
+/// `XRANGE` and `XREVRANGE`.
+#[derive(Debug, PartialEq)]
+pub struct XRange {
+    key: Bytes,
+    start: StreamId,
+    end: StreamId,
+    count: Option<usize>,
+    /// List the entries from the end, with the bounds given end first.
+    rev: bool,
+}
+
+/// Parse a bound of an ID interval: `-` or `+`, an ID, or an ID prefixed with `(` to exclude
+/// it. A bare `<ms>` covers the whole millisecond. Returns `None` for an exclusive bound which
+/// leaves nothing to include.
+pub(super) fn parse_range_bound(arg: &Bytes, is_start: bool) -> ParseResult<Option<StreamId>> {
+    let default_seq = if is_start { 0 } else { u64::MAX };
+    match arg.as_ref() {
+        b"-" => Ok(Some(StreamId::MIN)),
+        b"+" => Ok(Some(StreamId::MAX)),
+        [b'(', id @ ..] => {
+            let id = parse_stream_id(&Bytes::copy_from_slice(id), default_seq)?;
+            Ok(if is_start { id.next() } else { id.prev() })
+        }
+        _ => parse_stream_id(arg, default_seq).map(Some),
+    }
+}
+
+impl XRange {
+    pub fn parse(args: &[Bytes], rev: bool) -> ParseResult<Self> {
+        check_length_ge(args, 3)?;
+        let (start, end) = if rev {
+            (&args[2], &args[1])
+        } else {
+            (&args[1], &args[2])
+        };
+        let invalid =
+            |arg: &Bytes| ParseError::InvalidArgument(String::from_utf8_lossy(arg).into_owned());
+        let start = parse_range_bound(start, true)?.ok_or_else(|| invalid(start))?;
+        let end = parse_range_bound(end, false)?.ok_or_else(|| invalid(end))?;
+
+        let count = match &args[3..] {
+            [] => None,
+            [option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+                Some(lexical_core::parse(count)?)
+            }
+            [option, ..] => {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(option)?.to_string(),
+                ));
+            }
+        };
+
+        Ok(XRange {
+            key: args[0].clone(),
+            start,
+            end,
+            count,
+            rev,
+        })
+    }
+}
+
+impl ExecuteCommand for XRange {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let Some(stream) = get_stream(db, stats, &self.key)? else {
+            return Ok(RespData::Array(vec![]));
+        };
+        let count = self.count.unwrap_or(usize::MAX);
+        if self.rev {
+            Ok(stream_entries_reply(
+                stream.rev_range(self.start, self.end).take(count),
+            ))
+        } else {
+            Ok(stream_entries_reply(
+                stream.range(self.start, self.end).take(count),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::XRange;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{Stream, StreamId},
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    fn entry(id: &str, value: &str) -> RespData {
+        RespData::Array(vec![
+            bulk(id),
+            RespData::Array(vec![bulk("f"), bulk(value)]),
+        ])
+    }
+
+    #[test]
+    fn parse_xrange_should_read_bounds() {
+        let cmd = parse_command(&build_request("XREVRANGE", &["s", "+", "(5", "COUNT", "2"]))
+            .expect("parse xrevrange");
+        assert_eq!(
+            cmd,
+            Command::XRange(XRange {
+                key: Bytes::from_owner("s"),
+                start: StreamId::new(5, 1),
+                end: StreamId::MAX,
+                count: Some(2),
+                rev: true,
+            })
+        );
+        let cmd =
+            parse_command(&build_request("XRANGE", &["s", "3", "(4-0"])).expect("parse xrange");
+        assert_eq!(
+            cmd,
+            Command::XRange(XRange {
+                key: Bytes::from_owner("s"),
+                start: StreamId::new(3, 0),
+                end: StreamId::new(3, u64::MAX),
+                count: None,
+                rev: false,
+            })
+        );
+
+        for args in [
+            &["s", "-"][..],
+            &["s", "(-", "+"],
+            &["s", "-", "(0-0"],
+            &["s", "(18446744073709551615-18446744073709551615", "+"],
+            &["s", "-", "+", "COUNT"],
+            &["s", "-", "+", "LIMIT", "1"],
+        ] {
+            assert!(
+                parse_command(&build_request("XRANGE", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xrange_should_list_entries_in_both_directions() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for (ms, seq) in [(1, 0), (1, 1), (2, 0), (3, 5)] {
+            let id = StreamId::new(ms, seq);
+            stream.add(id, &[(Bytes::from_owner("f"), Bytes::from(id.to_string()))]);
+        }
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        for (command, args, expected) in [
+            (
+                "XRANGE",
+                &["s", "-", "+", "COUNT", "3"][..],
+                vec!["1-0", "1-1", "2-0"],
+            ),
+            ("XRANGE", &["s", "1", "2"], vec!["1-0", "1-1", "2-0"]),
+            ("XRANGE", &["s", "(1-0", "(3-5"], vec!["1-1", "2-0"]),
+            ("XRANGE", &["s", "3", "1"], vec![]),
+            ("XREVRANGE", &["s", "+", "1-1"], vec!["3-5", "2-0", "1-1"]),
+            ("XREVRANGE", &["s", "+", "-", "COUNT", "1"], vec!["3-5"]),
+            ("XRANGE", &["missing", "-", "+"], vec![]),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("xrange");
+            assert_eq!(
+                resp,
+                RespData::Array(expected.iter().map(|id| entry(id, id)).collect()),
+                "{command} {args:?}"
+            );
+        }
+    }
+}
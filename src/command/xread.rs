@@ -0,0 +1,430 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    blocking::wait_until_served,
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream, parse_stream_id, stream_entries_reply,
+    },
+    db::Key,
+    resp::{RespData, RespProtocol},
+    server::{Connection, Server},
+    stream::{Stream, StreamEntry, StreamId},
+};
+
+/// The ID entries are read after.
+#[derive(Debug, PartialEq)]
+enum ReadId {
+    /// `$`, the last ID of the stream when the command runs.
+    Last,
+    /// `+`, the ID before the last one, so that the last entry is read.
+    LastEntry,
+    After(StreamId),
+}
+
+impl ReadId {
+    fn resolve(&self, stream: Option<&Stream>) -> StreamId {
+        let last_id = stream.map_or(StreamId::MIN, |stream| stream.last_id());
+        match self {
+            ReadId::Last => last_id,
+            ReadId::LastEntry => last_id.prev().unwrap_or(StreamId::MIN),
+            ReadId::After(id) => *id,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct XRead {
+    /// The most entries read from each stream, 0 meaning no limit.
+    count: usize,
+    /// `Some(None)` blocks forever.
+    block: Option<Option<Duration>>,
+    keys: Vec<Bytes>,
+    ids: Vec<ReadId>,
+}
+
+/// The arguments shared by `XREAD` and `XREADGROUP`, after the group of the latter:
+/// `[COUNT count] [BLOCK milliseconds] [NOACK] STREAMS key... id...`.
+pub(super) struct ReadArgs<'a> {
+    /// The most entries read from each stream, 0 meaning no limit.
+    pub count: usize,
+    /// `Some(None)` blocks forever.
+    pub block: Option<Option<Duration>>,
+    /// Only allowed by `XREADGROUP`.
+    pub noack: bool,
+    pub keys: &'a [Bytes],
+    pub ids: &'a [Bytes],
+}
+
+impl<'a> ReadArgs<'a> {
+    pub(super) fn parse(args: &'a [Bytes], command: &str) -> ParseResult<Self> {
+        let mut count = 0;
+        let mut block = None;
+        let mut noack = false;
+        let mut index = 0;
+        loop {
+            let Some(option) = args.get(index) else {
+                return Err(ParseError::InvalidArgument(
+                    "STREAMS is required".to_string(),
+                ));
+            };
+            let option = str::from_utf8(option)?.to_uppercase();
+            match option.as_str() {
+                "STREAMS" => break,
+                "NOACK" if command == "xreadgroup" => {
+                    noack = true;
+                    index += 1;
+                }
+                "COUNT" | "BLOCK" => {
+                    check_length_ge(args, index + 2)?;
+                    let value: i64 = lexical_core::parse(&args[index + 1])?;
+                    if option == "COUNT" {
+                        // Like Redis, a negative count is no limit
+                        count = value.max(0) as usize;
+                    } else if value < 0 {
+                        return Err(ParseError::InvalidArgument(
+                            "timeout is negative".to_string(),
+                        ));
+                    } else {
+                        block = Some((value > 0).then(|| Duration::from_millis(value as u64)));
+                    }
+                    index += 2;
+                }
+                _ => return Err(ParseError::InvalidArgument(option)),
+            }
+        }
+
+        let streams = &args[index + 1..];
+        if streams.is_empty() || !streams.len().is_multiple_of(2) {
+            return Err(ParseError::InvalidArgument(format!(
+                "Unbalanced '{command}' list of streams: for each stream key an ID or '$' must \
+                 be specified"
+            )));
+        }
+        let (keys, ids) = streams.split_at(streams.len() / 2);
+        Ok(ReadArgs {
+            count,
+            block,
+            noack,
+            keys,
+            ids,
+        })
+    }
+}
+
+impl Parse for XRead {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 3)?;
+        let args = ReadArgs::parse(args, "xread")?;
+        Ok(XRead {
+            count: args.count,
+            block: args.block,
+            keys: args.keys.to_vec(),
+            ids: args
+                .ids
+                .iter()
+                .map(|id| match id.as_ref() {
+                    b"$" => Ok(ReadId::Last),
+                    b"+" => Ok(ReadId::LastEntry),
+                    _ => parse_stream_id(id, 0).map(ReadId::After),
+                })
+                .collect::<ParseResult<_>>()?,
+        })
+    }
+}
+
+/// Read up to `count` entries of `stream` after `id`, 0 meaning no limit.
+fn read_after(stream: &Stream, id: StreamId, count: usize) -> Vec<StreamEntry> {
+    let Some(start) = id.next() else {
+        return vec![];
+    };
+    let count = if count == 0 { usize::MAX } else { count };
+    stream.range(start, StreamId::MAX).take(count).collect()
+}
+
+/// Reply the entries read from each stream, as `[key, entries]` pairs or as a map in RESP3.
+pub(super) fn streams_reply(streams: Vec<(Key, RespData)>, protocol: RespProtocol) -> RespData {
+    match protocol {
+        RespProtocol::Resp2 => RespData::Array(
+            streams
+                .into_iter()
+                .map(|(key, entries)| {
+                    RespData::Array(vec![RespData::BulkString(Some(key)), entries])
+                })
+                .collect(),
+        ),
+        RespProtocol::Resp3 => RespData::Map(streams.into_iter().collect()),
+    }
+}
+
+impl ExecuteCommand for XRead {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let protocol = conn.protocol;
+        let (id, receiver) = {
+            let server = &mut *server.lock().await;
+            let (db, stats) = (&mut server.db, &mut server.stats);
+
+            let mut after = Vec::with_capacity(self.keys.len());
+            let mut streams = Vec::new();
+            for (key, id) in self.keys.iter().zip(&self.ids) {
+                let stream = get_stream(db, stats, key)?.map(|stream| &*stream);
+                let id = id.resolve(stream);
+                after.push(id);
+                let entries = stream.map_or(vec![], |stream| read_after(stream, id, self.count));
+                if !entries.is_empty() {
+                    streams.push((key.clone(), stream_entries_reply(entries)));
+                }
+            }
+            if !streams.is_empty() {
+                return Ok(streams_reply(streams, protocol));
+            }
+            if self.block.is_none() {
+                return Ok(RespData::NullArray);
+            }
+
+            // Only the stream which received entries is replied
+            let (keys, count) = (self.keys.clone(), self.count);
+            server.blocking.block(
+                self.keys.clone(),
+                Box::new(move |db, stats, key, _| {
+                    let index = keys.iter().position(|k| k == key)?;
+                    let entries =
+                        read_after(get_stream(db, stats, key).ok()??, after[index], count);
+                    if entries.is_empty() {
+                        return None;
+                    }
+                    Some(Ok(streams_reply(
+                        vec![(key.clone(), stream_entries_reply(entries))],
+                        protocol,
+                    )))
+                }),
+            )
+        };
+
+        let resp = wait_until_served(server, conn, id, receiver, self.block.flatten()).await?;
+        Ok(resp.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use bytes::Bytes;
+    use tokio::sync::Mutex;
+
+    use super::{ReadId, XRead};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_connection, build_request, build_server_connection},
+        },
+        db::Value,
+        resp::{RespData, RespProtocol},
+        server::Server,
+        stream::{Stream, StreamId},
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    fn entries(ids: &[&str]) -> RespData {
+        RespData::Array(
+            ids.iter()
+                .map(|id| {
+                    RespData::Array(vec![bulk(id), RespData::Array(vec![bulk("f"), bulk(id)])])
+                })
+                .collect(),
+        )
+    }
+
+    fn stream_reply(key: &str, ids: &[&str]) -> RespData {
+        RespData::Array(vec![bulk(key), entries(ids)])
+    }
+
+    async fn insert_stream(server: &Arc<Mutex<Server>>, key: &str, ids: &[(u64, u64)]) {
+        let mut stream = Stream::new();
+        for (ms, seq) in ids {
+            let id = StreamId::new(*ms, *seq);
+            stream.add(id, &[(Bytes::from_owner("f"), Bytes::from(id.to_string()))]);
+        }
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from(key.to_string()), (Value::Stream(stream), None));
+    }
+
+    #[test]
+    fn parse_xread_should_read_options_and_streams() {
+        let cmd = parse_command(&build_request(
+            "XREAD",
+            &[
+                "COUNT", "2", "block", "1500", "STREAMS", "a", "b", "c", "$", "+", "1",
+            ],
+        ))
+        .expect("parse xread");
+        assert_eq!(
+            cmd,
+            Command::XRead(XRead {
+                count: 2,
+                block: Some(Some(Duration::from_millis(1500))),
+                keys: vec![
+                    Bytes::from_owner("a"),
+                    Bytes::from_owner("b"),
+                    Bytes::from_owner("c")
+                ],
+                ids: vec![
+                    ReadId::Last,
+                    ReadId::LastEntry,
+                    ReadId::After(StreamId::new(1, 0))
+                ],
+            })
+        );
+        let cmd = parse_command(&build_request(
+            "XREAD",
+            &["BLOCK", "0", "STREAMS", "a", "0-1"],
+        ))
+        .expect("parse xread");
+        assert_eq!(
+            cmd,
+            Command::XRead(XRead {
+                count: 0,
+                block: Some(None),
+                keys: vec![Bytes::from_owner("a")],
+                ids: vec![ReadId::After(StreamId::new(0, 1))],
+            })
+        );
+
+        for args in [
+            &["STREAMS", "a"][..],
+            &["STREAMS", "a", "b", "0"],
+            &["COUNT", "1", "a", "0"],
+            &["BLOCK", "-1", "STREAMS", "a", "0"],
+            &["STREAMS", "a", "x"],
+            &["COUNT", "1", "BLOCK", "1"],
+        ] {
+            assert!(
+                parse_command(&build_request("XREAD", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xread_should_read_entries_after_ids() {
+        let (server, mut conn) = build_server_connection().await;
+        insert_stream(&server, "a", &[(1, 0), (1, 1), (2, 0)]).await;
+        insert_stream(&server, "b", &[(5, 0)]).await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("string"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        for (args, expected) in [
+            (
+                &["STREAMS", "a", "b", "1-0", "0"][..],
+                RespData::Array(vec![
+                    stream_reply("a", &["1-1", "2-0"]),
+                    stream_reply("b", &["5-0"]),
+                ]),
+            ),
+            (
+                &["COUNT", "1", "STREAMS", "a", "missing", "0", "0"],
+                RespData::Array(vec![stream_reply("a", &["1-0"])]),
+            ),
+            (
+                &["STREAMS", "a", "b", "+", "$"],
+                RespData::Array(vec![stream_reply("a", &["2-0"])]),
+            ),
+            (&["STREAMS", "a", "missing", "$", "+"], RespData::NullArray),
+            (
+                &["STREAMS", "a", "18446744073709551615-18446744073709551615"],
+                RespData::NullArray,
+            ),
+        ] {
+            let cmd = parse_command(&build_request("XREAD", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.expect("xread");
+            assert_eq!(resp, expected, "{args:?}");
+        }
+
+        conn.protocol = RespProtocol::Resp3;
+        let cmd = parse_command(&build_request("XREAD", &["STREAMS", "b", "0"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("xread");
+        assert_eq!(
+            resp,
+            RespData::Map(vec![(Bytes::from_owner("b"), entries(&["5-0"]))])
+        );
+
+        let cmd = parse_command(&build_request(
+            "XREAD",
+            &["STREAMS", "a", "string", "0", "0"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server, &mut conn).await;
+        assert_eq!(resp, Err(ExecError::WrongType));
+    }
+
+    #[tokio::test]
+    async fn execute_xread_should_block_until_xadd() {
+        let (server, mut conn) = build_server_connection().await;
+        insert_stream(&server, "a", &[(1, 0)]).await;
+
+        let mut blocked = Vec::new();
+        for (id, args) in [
+            (1, &["BLOCK", "0", "STREAMS", "missing", "a", "$", "$"][..]),
+            (2, &["BLOCK", "0", "STREAMS", "a", "5-0"]),
+        ] {
+            let (_, mut conn) = build_connection(id).await;
+            let blocked_server = server.clone();
+            let cmd = parse_command(&build_request("XREAD", args)).unwrap();
+            blocked.push(tokio::spawn(async move {
+                cmd.execute(blocked_server, &mut conn).await
+            }));
+        }
+        while server.lock().await.blocking.len() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // Entries up to 5-0 only serve the first client
+        for id in ["2-0", "5-0", "6-0"] {
+            let cmd = parse_command(&build_request("XADD", &["a", id, "f", id])).unwrap();
+            cmd.execute(server.clone(), &mut conn).await.expect("xadd");
+            if id == "2-0" {
+                assert_eq!(server.lock().await.blocking.len(), 1);
+            }
+        }
+        let mut replies = Vec::new();
+        for handle in blocked {
+            replies.push(handle.await.unwrap().expect("xread"));
+        }
+        assert_eq!(
+            replies,
+            vec![
+                RespData::Array(vec![stream_reply("a", &["2-0"])]),
+                RespData::Array(vec![stream_reply("a", &["6-0"])]),
+            ]
+        );
+
+        let cmd = parse_command(&build_request(
+            "XREAD",
+            &["BLOCK", "10", "STREAMS", "a", "$"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("xread");
+        assert_eq!(resp, RespData::NullArray);
+        assert!(server.lock().await.blocking.is_empty());
+    }
+}
@@ -0,0 +1,416 @@
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    blocking::wait_until_served,
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream, get_stream_with_group, parse_stream_id, stream_entries_reply,
+        stream_entry_reply,
+        xread::{ReadArgs, streams_reply},
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::{Stream, StreamId},
+    utils::unix_time_ms,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct XReadGroup {
+    group: Bytes,
+    consumer: Bytes,
+    /// The most entries read from each stream, 0 meaning no limit.
+    count: usize,
+    /// `Some(None)` blocks forever.
+    block: Option<Option<Duration>>,
+    /// Do not add the entries read to the pending entries.
+    noack: bool,
+    keys: Vec<Bytes>,
+    /// `None` for `>`, which reads the entries never delivered to the group. An ID reads the
+    /// entries pending for the consumer after it instead.
+    ids: Vec<Option<StreamId>>,
+}
+
+impl Parse for XReadGroup {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 6)?;
+        if !args[0].eq_ignore_ascii_case(b"GROUP") {
+            return Err(ParseError::InvalidArgument(
+                str::from_utf8(&args[0])?.to_string(),
+            ));
+        }
+        let read = ReadArgs::parse(&args[3..], "xreadgroup")?;
+        let ids = read
+            .ids
+            .iter()
+            .map(|id| match id.as_ref() {
+                b">" => Ok(None),
+                b"$" | b"+" => Err(ParseError::InvalidArgument(format!(
+                    "The {} ID is meaningless in the context of XREADGROUP",
+                    String::from_utf8_lossy(id)
+                ))),
+                _ => parse_stream_id(id, 0).map(Some),
+            })
+            .collect::<ParseResult<_>>()?;
+        Ok(XReadGroup {
+            group: args[1].clone(),
+            consumer: args[2].clone(),
+            count: read.count,
+            block: read.block,
+            noack: read.noack,
+            keys: read.keys.to_vec(),
+            ids,
+        })
+    }
+}
+
+/// Reply the entries pending for `consumer` after `after`, up to `count`, which count as
+/// delivered again. Entries deleted since reply with no fields.
+fn read_pending(
+    stream: &mut Stream,
+    group: &[u8],
+    consumer: &Bytes,
+    after: StreamId,
+    count: usize,
+    now_ms: u64,
+) -> RespData {
+    // The group was checked by the caller
+    let ids: Vec<_> = match after.next() {
+        Some(start) => stream
+            .group_mut(group)
+            .unwrap()
+            .see_consumer(consumer, now_ms)
+            .pending()
+            .range(start..)
+            .take(count)
+            .copied()
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut entries = Vec::with_capacity(ids.len());
+    for id in ids {
+        let Some(entry) = stream.get(id) else {
+            entries.push(RespData::Array(vec![
+                RespData::BulkString(Some(Bytes::from(id.to_string()))),
+                RespData::NullArray,
+            ]));
+            continue;
+        };
+        // The ID was just found pending for the consumer
+        let pending = stream.group_mut(group).unwrap().pending_mut(id).unwrap();
+        pending.delivery_time = now_ms;
+        pending.delivery_count += 1;
+        entries.push(stream_entry_reply(entry));
+    }
+    RespData::Array(entries)
+}
+
+impl ExecuteCommand for XReadGroup {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let protocol = conn.protocol;
+        let count = if self.count == 0 {
+            usize::MAX
+        } else {
+            self.count
+        };
+        let (id, receiver) = {
+            let server = &mut *server.lock().await;
+            let (db, stats) = (&mut server.db, &mut server.stats);
+
+            // Every group must exist before anything is read
+            for key in &self.keys {
+                get_stream_with_group(
+                    db,
+                    stats,
+                    key,
+                    &self.group,
+                    ExecError::NoKeyOrGroupInXReadGroup,
+                )?;
+            }
+            let now_ms = unix_time_ms();
+            let mut streams = Vec::new();
+            for (key, id) in self.keys.iter().zip(&self.ids) {
+                // The stream was found above
+                let stream = get_stream(db, stats, key)?.unwrap();
+                match id {
+                    None => {
+                        let entries = stream
+                            .read_group(&self.group, &self.consumer, count, self.noack, now_ms)
+                            .unwrap();
+                        if !entries.is_empty() {
+                            streams.push((key.clone(), stream_entries_reply(entries)));
+                        }
+                    }
+                    // The pending entries are replied even when there are none
+                    Some(after) => streams.push((
+                        key.clone(),
+                        read_pending(stream, &self.group, &self.consumer, *after, count, now_ms),
+                    )),
+                }
+            }
+            if !streams.is_empty() {
+                return Ok(streams_reply(streams, protocol));
+            }
+            if self.block.is_none() {
+                return Ok(RespData::NullArray);
+            }
+
+            // Only `>` blocks, as pending entries are always replied
+            let (group, consumer, noack) = (self.group.clone(), self.consumer.clone(), self.noack);
+            server.blocking.block(
+                self.keys.clone(),
+                Box::new(move |db, stats, key, _| {
+                    let stream = get_stream(db, stats, key).ok()??;
+                    if stream.group(&group).is_none() {
+                        return Some(Err(ExecError::BlockedGroupDestroyed));
+                    }
+                    let entries =
+                        stream.read_group(&group, &consumer, count, noack, unix_time_ms())?;
+                    if entries.is_empty() {
+                        return None;
+                    }
+                    Some(Ok(streams_reply(
+                        vec![(key.clone(), stream_entries_reply(entries))],
+                        protocol,
+                    )))
+                }),
+            )
+        };
+
+        let resp = wait_until_served(server, conn, id, receiver, self.block.flatten()).await?;
+        Ok(resp.unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::Bytes;
+
+    use super::XReadGroup;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_connection, build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{ConsumerGroup, Stream, StreamId},
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    fn entry(id: &str) -> RespData {
+        RespData::Array(vec![bulk(id), RespData::Array(vec![bulk("f"), bulk(id)])])
+    }
+
+    fn reply(key: &str, entries: Vec<RespData>) -> RespData {
+        RespData::Array(vec![RespData::Array(vec![
+            bulk(key),
+            RespData::Array(entries),
+        ])])
+    }
+
+    fn stream_with_group(ids: &[(u64, u64)]) -> Stream {
+        let mut stream = Stream::new();
+        for (ms, seq) in ids {
+            let id = StreamId::new(*ms, *seq);
+            stream.add(id, &[(Bytes::from_owner("f"), Bytes::from(id.to_string()))]);
+        }
+        stream.create_group(
+            Bytes::from_owner("g"),
+            ConsumerGroup::new(StreamId::MIN, Some(0)),
+        );
+        stream
+    }
+
+    #[test]
+    fn parse_xreadgroup_should_read_group_options_and_ids() {
+        let cmd = parse_command(&build_request(
+            "XREADGROUP",
+            &[
+                "GROUP", "g", "c", "COUNT", "1", "BLOCK", "10", "NOACK", "STREAMS", "a", "b", ">",
+                "1-1",
+            ],
+        ))
+        .expect("parse xreadgroup");
+        assert_eq!(
+            cmd,
+            Command::XReadGroup(XReadGroup {
+                group: Bytes::from_owner("g"),
+                consumer: Bytes::from_owner("c"),
+                count: 1,
+                block: Some(Some(Duration::from_millis(10))),
+                noack: true,
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                ids: vec![None, Some(StreamId::new(1, 1))],
+            })
+        );
+
+        for args in [
+            &["GROUP", "g", "c", "STREAMS", "a"][..],
+            &["GROUP", "g", "c", "STREAMS", "a", "$"],
+            &["GROUP", "g", "c", "STREAMS", "a", "+"],
+            &["GROUPS", "g", "c", "STREAMS", "a", ">"],
+            &["GROUP", "g", "STREAMS", "a", ">"],
+        ] {
+            assert!(
+                parse_command(&build_request("XREADGROUP", args)).is_err(),
+                "{args:?}"
+            );
+        }
+        // Only XREADGROUP takes NOACK
+        assert!(parse_command(&build_request("XREAD", &["NOACK", "STREAMS", "a", "0"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_xreadgroup_should_deliver_new_then_pending_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (
+                Value::Stream(stream_with_group(&[(1, 0), (2, 0), (3, 0)])),
+                None,
+            ),
+        );
+
+        for (args, expected) in [
+            (
+                &["GROUP", "g", "alice", "COUNT", "2", "STREAMS", "s", ">"][..],
+                Ok(reply("s", vec![entry("1-0"), entry("2-0")])),
+            ),
+            (
+                &["GROUP", "g", "bob", "NOACK", "STREAMS", "s", ">"],
+                Ok(reply("s", vec![entry("3-0")])),
+            ),
+            (
+                &["GROUP", "g", "bob", "STREAMS", "s", ">"],
+                Ok(RespData::NullArray),
+            ),
+            (
+                &["GROUP", "g", "alice", "STREAMS", "s", "0"],
+                Ok(reply("s", vec![entry("1-0"), entry("2-0")])),
+            ),
+            (
+                &["GROUP", "g", "alice", "STREAMS", "s", "1-0"],
+                Ok(reply("s", vec![entry("2-0")])),
+            ),
+            (
+                &["GROUP", "g", "bob", "STREAMS", "s", "0"],
+                Ok(reply("s", vec![])),
+            ),
+            (
+                &["GROUP", "x", "bob", "STREAMS", "s", ">"],
+                Err(ExecError::NoKeyOrGroupInXReadGroup(
+                    "s".to_string(),
+                    "x".to_string(),
+                )),
+            ),
+            (
+                &["GROUP", "g", "bob", "STREAMS", "s", "missing", ">", ">"],
+                Err(ExecError::NoKeyOrGroupInXReadGroup(
+                    "missing".to_string(),
+                    "g".to_string(),
+                )),
+            ),
+        ] {
+            let cmd = parse_command(&build_request("XREADGROUP", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await;
+            assert_eq!(resp, expected, "{args:?}");
+        }
+
+        // Deleted entries stay pending, without their fields
+        let cmd = parse_command(&build_request("XDEL", &["s", "1-0"])).unwrap();
+        cmd.execute(server.clone(), &mut conn).await.expect("xdel");
+        let cmd = parse_command(&build_request(
+            "XREADGROUP",
+            &["GROUP", "g", "alice", "STREAMS", "s", "0"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await;
+        assert_eq!(
+            resp,
+            Ok(reply(
+                "s",
+                vec![
+                    RespData::Array(vec![bulk("1-0"), RespData::NullArray]),
+                    entry("2-0")
+                ]
+            ))
+        );
+
+        let server = server.lock().await;
+        let Some((Value::Stream(stream), _)) = server.db.get(b"s".as_ref()) else {
+            panic!("expected a stream");
+        };
+        let group = stream.group(b"g").unwrap();
+        assert_eq!(group.last_id(), StreamId::new(3, 0));
+        assert_eq!(group.entries_read(), Some(3));
+        assert_eq!(group.pending().len(), 2);
+        // Read once as new, then three times as pending
+        assert_eq!(group.pending()[&StreamId::new(2, 0)].delivery_count, 4);
+        assert_eq!(group.pending()[&StreamId::new(1, 0)].delivery_count, 2);
+        assert!(group.consumer(b"bob").unwrap().pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_xreadgroup_should_block_until_new_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::Stream(stream_with_group(&[])), None),
+        );
+
+        let mut blocked = Vec::new();
+        for id in 1..=2 {
+            let (_, mut conn) = build_connection(id).await;
+            let blocked_server = server.clone();
+            let cmd = parse_command(&build_request(
+                "XREADGROUP",
+                &["GROUP", "g", "c", "BLOCK", "0", "STREAMS", "s", ">"],
+            ))
+            .unwrap();
+            blocked.push(tokio::spawn(async move {
+                cmd.execute(blocked_server, &mut conn).await
+            }));
+        }
+        while server.lock().await.blocking.len() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // The entry goes to the first client only, the second one stays blocked
+        let cmd = parse_command(&build_request("XADD", &["s", "1-0", "f", "1-0"])).unwrap();
+        cmd.execute(server.clone(), &mut conn).await.expect("xadd");
+        assert_eq!(server.lock().await.blocking.len(), 1);
+        let cmd = parse_command(&build_request("XGROUP", &["DESTROY", "s", "g"])).unwrap();
+        cmd.execute(server.clone(), &mut conn)
+            .await
+            .expect("xgroup");
+
+        let mut replies = Vec::new();
+        for handle in blocked {
+            replies.push(handle.await.unwrap());
+        }
+        assert_eq!(
+            replies,
+            vec![
+                Ok(reply("s", vec![entry("1-0")])),
+                Err(ExecError::BlockedGroupDestroyed),
+            ]
+        );
+    }
+}
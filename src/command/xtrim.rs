@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_stream, parse_stream_id,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    stream::{NODE_MAX_ENTRIES, Stream, TrimStrategy},
+};
+
+/// The `MAXLEN | MINID [= | ~] threshold [LIMIT count]` options shared by `XADD` and `XTRIM`,
+/// which may come in any order.
+#[derive(Debug, Default)]
+pub(super) struct TrimOptions {
+    strategy: Option<TrimStrategy>,
+    approx: bool,
+    limit: Option<usize>,
+}
+
+impl TrimOptions {
+    /// Parse the trimming option opening `args`. Returns how many arguments it took, 0 if
+    /// `args` opens with another option.
+    pub(super) fn parse_option(&mut self, args: &[Bytes]) -> ParseResult<usize> {
+        let option = str::from_utf8(&args[0])?.to_uppercase();
+        match option.as_str() {
+            "MAXLEN" | "MINID" => {
+                if self.strategy.is_some() {
+                    return Err(ParseError::InvalidArgument(
+                        "MAXLEN and MINID options at the same time are not compatible".to_string(),
+                    ));
+                }
+                let mut taken = 1;
+                match args.get(1).map(|arg| arg.as_ref()) {
+                    Some(b"~") => {
+                        self.approx = true;
+                        taken += 1;
+                    }
+                    Some(b"=") => taken += 1,
+                    _ => {}
+                }
+                check_length_ge(args, taken + 1)?;
+                let threshold = &args[taken];
+                self.strategy = Some(if option == "MAXLEN" {
+                    TrimStrategy::MaxLen(lexical_core::parse(threshold)?)
+                } else {
+                    TrimStrategy::MinId(parse_stream_id(threshold, 0)?)
+                });
+                Ok(taken + 1)
+            }
+            "LIMIT" => {
+                check_length_ge(args, 2)?;
+                self.limit = Some(lexical_core::parse(&args[1])?);
+                Ok(2)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    /// The trimming asked for by the options, once all of them are parsed.
+    pub(super) fn finish(self) -> ParseResult<Option<Trim>> {
+        if self.limit.is_some() && !self.approx {
+            return Err(ParseError::InvalidArgument(
+                "LIMIT cannot be used without the special ~ option".to_string(),
+            ));
+        }
+        // An approximate trim does a bounded amount of work unless told otherwise
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None if self.approx => 100 * NODE_MAX_ENTRIES,
+            None => 0,
+        };
+        Ok(self.strategy.map(|strategy| Trim {
+            strategy,
+            approx: self.approx,
+            limit,
+        }))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Trim {
+    strategy: TrimStrategy,
+    /// Only free whole nodes, as asked by `~`.
+    approx: bool,
+    /// The most entries removed, 0 meaning no limit.
+    limit: usize,
+}
+
+impl Trim {
+    /// Trim `stream`, returning how many entries were removed.
+    pub(super) fn apply(&self, stream: &mut Stream) -> usize {
+        stream.trim(self.strategy, self.approx, self.limit)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct XTrim {
+    key: Bytes,
+    trim: Trim,
+}
+
+impl Parse for XTrim {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 3)?;
+        let mut options = TrimOptions::default();
+        let mut index = 1;
+        while index < args.len() {
+            let taken = options.parse_option(&args[index..])?;
+            if taken == 0 {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(&args[index])?.to_string(),
+                ));
+            }
+            index += taken;
+        }
+        let Some(trim) = options.finish()? else {
+            return Err(ParseError::InvalidArgument(
+                "MAXLEN or MINID is required".to_string(),
+            ));
+        };
+        Ok(XTrim {
+            key: args[0].clone(),
+            trim,
+        })
+    }
+}
+
+impl ExecuteCommand for XTrim {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let removed = get_stream(db, stats, &self.key)?.map_or(0, |stream| self.trim.apply(stream));
+        Ok(RespData::Integer(removed as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{Trim, XTrim};
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        stream::{Stream, StreamId, TrimStrategy},
+    };
+
+    #[test]
+    fn parse_xtrim_should_read_strategy_and_limit() {
+        for (args, expected) in [
+            (
+                &["s", "MAXLEN", "10"][..],
+                Trim {
+                    strategy: TrimStrategy::MaxLen(10),
+                    approx: false,
+                    limit: 0,
+                },
+            ),
+            (
+                &["s", "minid", "~", "5", "LIMIT", "7"],
+                Trim {
+                    strategy: TrimStrategy::MinId(StreamId::new(5, 0)),
+                    approx: true,
+                    limit: 7,
+                },
+            ),
+            (
+                &["s", "MAXLEN", "~", "0"],
+                Trim {
+                    strategy: TrimStrategy::MaxLen(0),
+                    approx: true,
+                    limit: 10_000,
+                },
+            ),
+        ] {
+            let cmd = parse_command(&build_request("XTRIM", args)).expect("parse xtrim");
+            assert_eq!(
+                cmd,
+                Command::XTrim(XTrim {
+                    key: Bytes::from_owner("s"),
+                    trim: expected,
+                })
+            );
+        }
+
+        for args in [
+            &["s", "MAXLEN"][..],
+            &["s", "MAXLEN", "-1"],
+            &["s", "MAXLEN", "=", "1", "LIMIT", "5"],
+            &["s", "MAXLEN", "1", "MINID", "1"],
+            &["s", "LIMIT", "5", "MAXLEN", "1"],
+            &["s", "MINID", "x"],
+            &["s", "LIMIT", "5"],
+        ] {
+            assert!(
+                parse_command(&build_request("XTRIM", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_xtrim_should_remove_oldest_entries() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut stream = Stream::new();
+        for seq in 1..=5 {
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner("f"), Bytes::from_owner("v"))],
+            );
+        }
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("s"), (Value::Stream(stream), None));
+
+        for (args, expected) in [
+            (&["s", "MAXLEN", "~", "2"][..], 0),
+            (&["s", "MAXLEN", "3"], 2),
+            (&["s", "MINID", "1-5"], 2),
+            (&["s", "MAXLEN", "0"], 1),
+            (&["missing", "MAXLEN", "0"], 0),
+        ] {
+            let cmd = parse_command(&build_request("XTRIM", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.expect("xtrim");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+        // Streams are kept once empty
+        let Some((Value::Stream(stream), _)) = server.lock().await.db.get(b"s".as_ref()).cloned()
+        else {
+            panic!("expected a stream");
+        };
+        assert!(stream.is_empty());
+        assert_eq!(stream.last_id(), StreamId::new(1, 5));
+    }
+}
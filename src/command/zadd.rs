@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_or_insert_zset, get_zset, score_reply,
+    },
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+    zset::parse_score,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZAdd {
+    key: Bytes,
+    /// Only add new members.
+    nx: bool,
+    /// Only update existing members.
+    xx: bool,
+    /// Only update scores which grow.
+    gt: bool,
+    /// Only update scores which shrink.
+    lt: bool,
+    /// Count the changed members rather than the added ones.
+    ch: bool,
+    /// Increment the score of the single member instead, like `ZINCRBY`.
+    incr: bool,
+    members: Vec<(f64, Bytes)>,
+}
+
+impl Parse for ZAdd {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 3)?;
+        let mut zadd = ZAdd {
+            key: args[0].clone(),
+            nx: false,
+            xx: false,
+            gt: false,
+            lt: false,
+            ch: false,
+            incr: false,
+            members: vec![],
+        };
+
+        let mut index = 1;
+        while let Some(arg) = args.get(index) {
+            let flag = match str::from_utf8(arg).map(|arg| arg.to_uppercase()).as_deref() {
+                Ok("NX") => &mut zadd.nx,
+                Ok("XX") => &mut zadd.xx,
+                Ok("GT") => &mut zadd.gt,
+                Ok("LT") => &mut zadd.lt,
+                Ok("CH") => &mut zadd.ch,
+                Ok("INCR") => &mut zadd.incr,
+                _ => break,
+            };
+            *flag = true;
+            index += 1;
+        }
+
+        let pairs = &args[index..];
+        if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+            return Err(ParseError::ExpectPairs(args.to_vec()));
+        }
+        if zadd.nx && zadd.xx {
+            return Err(ParseError::InvalidArgument(
+                "XX and NX options at the same time are not compatible".to_string(),
+            ));
+        }
+        if [zadd.nx, zadd.gt, zadd.lt]
+            .into_iter()
+            .filter(|f| *f)
+            .count()
+            > 1
+        {
+            return Err(ParseError::InvalidArgument(
+                "GT, LT, and/or NX options at the same time are not compatible".to_string(),
+            ));
+        }
+        if zadd.incr && pairs.len() > 2 {
+            return Err(ParseError::InvalidArgument(
+                "INCR option supports a single increment-element pair".to_string(),
+            ));
+        }
+
+        for pair in pairs.chunks_exact(2) {
+            let score = parse_score(&pair[0]).ok_or_else(|| {
+                ParseError::InvalidArgument(String::from_utf8_lossy(&pair[0]).into_owned())
+            })?;
+            zadd.members.push((score, pair[1].clone()));
+        }
+        Ok(zadd)
+    }
+}
+
+impl ExecuteCommand for ZAdd {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        // XX never creates the key
+        if self.xx && get_zset(db, stats, &self.key)?.is_none() {
+            return Ok(if self.incr {
+                RespData::Null
+            } else {
+                RespData::Integer(0)
+            });
+        }
+
+        let zset = get_or_insert_zset(db, stats, &self.key)?;
+        let mut added = 0;
+        let mut changed = 0;
+        let mut incremented = None;
+        for (score, member) in &self.members {
+            match zset.score(member) {
+                None => {
+                    if self.xx {
+                        continue;
+                    }
+                    zset.insert(member.clone(), *score);
+                    added += 1;
+                    incremented = Some(*score);
+                }
+                Some(current) => {
+                    if self.nx {
+                        continue;
+                    }
+                    let new = if self.incr { current + score } else { *score };
+                    if new.is_nan() {
+                        remove_if_empty(db, &self.key);
+                        return Err(ExecError::ScoreNaN);
+                    }
+                    if (self.gt && new <= current) || (self.lt && new >= current) {
+                        continue;
+                    }
+                    if new != current {
+                        zset.insert(member.clone(), new);
+                        changed += 1;
+                    }
+                    incremented = Some(new);
+                }
+            }
+        }
+        remove_if_empty(db, &self.key);
+        server.serve_blocked_clients(&self.key);
+
+        if self.incr {
+            return Ok(
+                incremented.map_or(RespData::Null, |score| score_reply(score, conn.protocol))
+            );
+        }
+        Ok(RespData::Integer(if self.ch {
+            added + changed
+        } else {
+            added
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZAdd;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_zadd_should_read_flags_and_pairs() {
+        let cmd = parse_command(&build_request(
+            "ZADD",
+            &["z", "xx", "GT", "ch", "1", "a", "-inf", "b"],
+        ))
+        .expect("parse zadd");
+        assert_eq!(
+            cmd,
+            Command::ZAdd(ZAdd {
+                key: Bytes::from_owner("z"),
+                nx: false,
+                xx: true,
+                gt: true,
+                lt: false,
+                ch: true,
+                incr: false,
+                members: vec![
+                    (1.0, Bytes::from_owner("a")),
+                    (f64::NEG_INFINITY, Bytes::from_owner("b"))
+                ],
+            })
+        );
+
+        for args in [
+            &["z", "1"][..],
+            &["z", "NX", "XX", "1", "a"],
+            &["z", "NX", "GT", "1", "a"],
+            &["z", "GT", "LT", "1", "a"],
+            &["z", "INCR", "1", "a", "2", "b"],
+            &["z", "nan", "a"],
+            &["z", "one", "a"],
+        ] {
+            assert!(
+                parse_command(&build_request("ZADD", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_zadd_should_add_and_update_members() {
+        let (server, mut conn) = build_server_connection().await;
+
+        let cmd = parse_command(&build_request("ZADD", &["z", "1", "a", "2", "b"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+        assert_eq!(resp, RespData::Integer(2));
+
+        let cmd = parse_command(&build_request(
+            "ZADD",
+            &["z", "CH", "3", "a", "2", "b", "4", "c"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+        assert_eq!(resp, RespData::Integer(2));
+
+        assert_eq!(
+            server.lock().await.db.get(b"z".as_ref()),
+            Some(&(
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("a"), 3.0),
+                    (Bytes::from_owner("b"), 2.0),
+                    (Bytes::from_owner("c"), 4.0),
+                ])),
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_zadd_should_honor_conditions() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("a"), 5.0),
+                    (Bytes::from_owner("b"), 5.0),
+                ])),
+                None,
+            ),
+        );
+
+        for (args, expected) in [
+            (&["z", "NX", "1", "a", "1", "c"][..], 1),
+            (&["z", "XX", "CH", "1", "b", "1", "d"], 1),
+            (&["z", "GT", "CH", "4", "a", "6", "c"], 1),
+            (&["z", "LT", "CH", "9", "a", "0", "b"], 1),
+        ] {
+            let cmd = parse_command(&build_request("ZADD", args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+        assert_eq!(
+            server.lock().await.db.get(b"z".as_ref()),
+            Some(&(
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("a"), 5.0),
+                    (Bytes::from_owner("b"), 0.0),
+                    (Bytes::from_owner("c"), 6.0),
+                ])),
+                None
+            ))
+        );
+
+        let cmd = parse_command(&build_request("ZADD", &["new", "XX", "1", "a"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(!server.lock().await.db.contains_key(b"new".as_ref()));
+    }
+
+    #[tokio::test]
+    async fn execute_zadd_incr_should_reply_new_score() {
+        let (server, mut conn) = build_server_connection().await;
+
+        let cmd = parse_command(&build_request("ZADD", &["z", "INCR", "1.5", "a"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+        assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("1.5"))));
+
+        let cmd = parse_command(&build_request("ZADD", &["z", "INCR", "2", "a"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+        assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("3.5"))));
+
+        let cmd = parse_command(&build_request("ZADD", &["z", "NX", "INCR", "2", "a"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+        assert_eq!(resp, RespData::Null);
+
+        let cmd = parse_command(&build_request("ZADD", &["z", "INCR", "inf", "a"])).unwrap();
+        cmd.execute(server.clone(), &mut conn).await.expect("zadd");
+        let cmd = parse_command(&build_request("ZADD", &["z", "INCR", "-inf", "a"])).unwrap();
+        let err = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect_err("NaN");
+        assert_eq!(err, ExecError::ScoreNaN);
+
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        let cmd = parse_command(&build_request("ZADD", &["s", "1", "a"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_zset},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZCard {
+    key: Bytes,
+}
+
+impl Parse for ZCard {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 1)?;
+        Ok(ZCard {
+            key: args[0].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for ZCard {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let len = get_zset(db, stats, &self.key)?.map_or(0, |zset| zset.len());
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZCard;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_zcard_should_read_key() {
+        let cmd = parse_command(&build_request("ZCARD", &["z"])).expect("parse zcard");
+        assert_eq!(
+            cmd,
+            Command::ZCard(ZCard {
+                key: Bytes::from_owner("z"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_zcard_should_count_members() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("a"), 1.0),
+                    (Bytes::from_owner("b"), 1.0),
+                ])),
+                None,
+            ),
+        );
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        let cmd = parse_command(&build_request("ZCARD", &["z"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zcard");
+        assert_eq!(resp, RespData::Integer(2));
+
+        let cmd = parse_command(&build_request("ZCARD", &["missing"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zcard");
+        assert_eq!(resp, RespData::Integer(0));
+
+        let cmd = parse_command(&build_request("ZCARD", &["s"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
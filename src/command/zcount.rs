@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq,
+        error::{ExecResult, ParseError},
+        get_zset,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    zset::ScoreRange,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZCount {
+    key: Bytes,
+    range: ScoreRange,
+}
+
+impl Parse for ZCount {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(ZCount {
+            key: args[0].clone(),
+            range: ScoreRange::parse(&args[1], &args[2]).ok_or_else(|| {
+                ParseError::InvalidArgument("min or max is not a float".to_string())
+            })?,
+        })
+    }
+}
+
+impl ExecuteCommand for ZCount {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        // Two descents of the skiplist, whatever the number of members in the range
+        let count =
+            get_zset(db, stats, &self.key)?.map_or(0, |zset| zset.score_range(&self.range).len());
+        Ok(RespData::Integer(count as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZCount;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        zset::ScoreRange,
+    };
+
+    #[test]
+    fn parse_zcount_should_read_range() {
+        let cmd =
+            parse_command(&build_request("ZCOUNT", &["z", "(1", "+inf"])).expect("parse zcount");
+        assert_eq!(
+            cmd,
+            Command::ZCount(ZCount {
+                key: Bytes::from_owner("z"),
+                range: ScoreRange {
+                    min: 1.0,
+                    max: f64::INFINITY,
+                    min_exclusive: true,
+                    max_exclusive: false,
+                },
+            })
+        );
+        assert!(parse_command(&build_request("ZCOUNT", &["z", "[1", "2"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_zcount_should_count_members_in_range() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(
+                    (0..100)
+                        .map(|i| (Bytes::from(i.to_string()), i as f64))
+                        .collect(),
+                ),
+                None,
+            ),
+        );
+
+        for (args, expected) in [
+            (&["z", "10", "19"][..], 10),
+            (&["z", "(10", "(19"], 8),
+            (&["z", "-inf", "+inf"], 100),
+            (&["z", "50", "10"], 0),
+            (&["missing", "-inf", "+inf"], 0),
+        ] {
+            let cmd = parse_command(&build_request("ZCOUNT", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("zcount");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, Parse, ParseResult, check_length_eq,
+        error::{ExecResult, ParseError},
+        get_or_insert_zset, get_zset, score_reply,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+    zset::parse_score,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZIncrBy {
+    key: Bytes,
+    increment: f64,
+    member: Bytes,
+}
+
+impl Parse for ZIncrBy {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 3)?;
+        Ok(ZIncrBy {
+            key: args[0].clone(),
+            increment: parse_score(&args[1]).ok_or_else(|| {
+                ParseError::InvalidArgument(String::from_utf8_lossy(&args[1]).into_owned())
+            })?,
+            member: args[2].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for ZIncrBy {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let current = get_zset(db, stats, &self.key)?
+            .and_then(|zset| zset.score(&self.member))
+            .unwrap_or(0.0);
+        let score = current + self.increment;
+        if score.is_nan() {
+            return Err(ExecError::ScoreNaN);
+        }
+        get_or_insert_zset(db, stats, &self.key)?.insert(self.member.clone(), score);
+        server.serve_blocked_clients(&self.key);
+
+        Ok(score_reply(score, conn.protocol))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZIncrBy;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::{RespData, RespProtocol},
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_zincrby_should_read_increment() {
+        let cmd =
+            parse_command(&build_request("ZINCRBY", &["z", "-2.5", "m"])).expect("parse zincrby");
+        assert_eq!(
+            cmd,
+            Command::ZIncrBy(ZIncrBy {
+                key: Bytes::from_owner("z"),
+                increment: -2.5,
+                member: Bytes::from_owner("m"),
+            })
+        );
+        assert!(parse_command(&build_request("ZINCRBY", &["z", "x", "m"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_zincrby_should_create_and_increment_member() {
+        let (server, mut conn) = build_server_connection().await;
+
+        let cmd = parse_command(&build_request("ZINCRBY", &["z", "2", "m"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zincrby");
+        assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("2"))));
+
+        conn.protocol = RespProtocol::Resp3;
+        let cmd = parse_command(&build_request("ZINCRBY", &["z", "0.5", "m"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zincrby");
+        assert_eq!(resp, RespData::Double(2.5));
+        assert_eq!(
+            server.lock().await.db.get(b"z".as_ref()),
+            Some(&(
+                Value::ZSet(SortedSet::from([(Bytes::from_owner("m"), 2.5)])),
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_zincrby_should_reject_nan() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(SortedSet::from([(Bytes::from_owner("m"), f64::INFINITY)])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("ZINCRBY", &["z", "-inf", "m"])).unwrap();
+        let err = cmd.execute(server, &mut conn).await.expect_err("NaN");
+        assert_eq!(err, ExecError::ScoreNaN);
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult,
+        error::{ExecResult, ParseError},
+        parse_numkeys,
+        zsetop::lookup_inputs,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZInterCard {
+    keys: Vec<Bytes>,
+    /// Stop counting there, 0 meaning no limit.
+    limit: usize,
+}
+
+impl Parse for ZInterCard {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        let (keys, options) = parse_numkeys(args)?;
+        let limit = match options {
+            [] => 0,
+            [option, limit] if option.eq_ignore_ascii_case(b"LIMIT") => lexical_core::parse(limit)?,
+            [option, ..] => {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(option)?.to_string(),
+                ));
+            }
+        };
+        Ok(ZInterCard {
+            keys: keys.to_vec(),
+            limit,
+        })
+    }
+}
+
+impl ExecuteCommand for ZInterCard {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let Some(mut inputs) = lookup_inputs(db, stats, &self.keys)?
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(RespData::Integer(0));
+        };
+
+        // Probe the other inputs with the members of the smallest one
+        inputs.sort_by_key(|input| input.len());
+        let limit = if self.limit == 0 {
+            usize::MAX
+        } else {
+            self.limit
+        };
+        let count = inputs[0]
+            .iter()
+            .filter(|(member, _)| {
+                inputs[1..]
+                    .iter()
+                    .all(|input| input.score(member).is_some())
+            })
+            .take(limit)
+            .count();
+        Ok(RespData::Integer(count as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZInterCard;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::RespData,
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_zintercard_should_read_limit() {
+        let cmd = parse_command(&build_request("ZINTERCARD", &["2", "a", "b", "LIMIT", "3"]))
+            .expect("parse zintercard");
+        assert_eq!(
+            cmd,
+            Command::ZInterCard(ZInterCard {
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                limit: 3,
+            })
+        );
+        assert!(parse_command(&build_request("ZINTERCARD", &["1", "a", "LIMIT", "-1"])).is_err());
+        assert!(parse_command(&build_request("ZINTERCARD", &["2", "a"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_zintercard_should_count_common_members() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(
+                    ["a", "b", "c", "d"]
+                        .into_iter()
+                        .map(|member| (Bytes::from_owner(member), 1.0))
+                        .collect::<SortedSet>(),
+                ),
+                None,
+            ),
+        );
+        server.lock().await.db.insert(
+            Bytes::from_owner("set"),
+            (
+                Value::Set(Set::from([
+                    Bytes::from_owner("b"),
+                    Bytes::from_owner("c"),
+                    Bytes::from_owner("d"),
+                    Bytes::from_owner("e"),
+                ])),
+                None,
+            ),
+        );
+
+        for (args, expected) in [
+            (&["2", "z", "set"][..], 3),
+            (&["2", "z", "set", "LIMIT", "2"], 2),
+            (&["2", "z", "set", "LIMIT", "0"], 3),
+            (&["2", "z", "missing"], 0),
+        ] {
+            let cmd = parse_command(&build_request("ZINTERCARD", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("zintercard");
+            assert_eq!(resp, RespData::Integer(expected), "{args:?}");
+        }
+    }
+}
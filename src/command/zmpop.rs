@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult,
+        error::{ExecResult, ParseError},
+        get_zset, parse_numkeys, parse_zset_end, score_reply,
+    },
+    db::{Db, Key, ZSetEnd, remove_if_empty},
+    resp::{RespData, RespProtocol},
+    server::{Connection, Server, Stats},
+    zset::SortedSet,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZMPop {
+    pub(super) keys: Vec<Bytes>,
+    pub(super) end: ZSetEnd,
+    pub(super) count: usize,
+}
+
+impl Parse for ZMPop {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        let (keys, options) = parse_numkeys(args)?;
+        let Some((end, options)) = options.split_first() else {
+            return Err(ParseError::ExpectLengthGe(
+                keys.len() + 2,
+                args.len(),
+                args.to_vec(),
+            ));
+        };
+
+        let mut zmpop = ZMPop {
+            keys: keys.to_vec(),
+            end: parse_zset_end(end)?,
+            count: 1,
+        };
+
+        match options {
+            [] => {}
+            [option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+                zmpop.count = lexical_core::parse(count)?;
+                if zmpop.count == 0 {
+                    return Err(ParseError::InvalidArgument("COUNT 0".to_string()));
+                }
+            }
+            [option, ..] => {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(option)?.to_string(),
+                ));
+            }
+        }
+
+        Ok(zmpop)
+    }
+}
+
+impl ZMPop {
+    /// Pop from the first non-empty sorted set among the keys.
+    pub(super) fn pop(
+        &self,
+        db: &mut Db,
+        stats: &mut Stats,
+        protocol: RespProtocol,
+    ) -> ExecResult<Option<RespData>> {
+        for key in &self.keys {
+            if let Some(zset) = get_zset(db, stats, key)? {
+                let resp = pop_reply(zset, key, self.end, self.count, protocol);
+                remove_if_empty(db, key);
+                return Ok(Some(resp));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Pop up to `count` members, replying with the key and the popped `[member, score]` pairs.
+pub(super) fn pop_reply(
+    zset: &mut SortedSet,
+    key: &Key,
+    end: ZSetEnd,
+    count: usize,
+    protocol: RespProtocol,
+) -> RespData {
+    let members = std::iter::from_fn(|| end.pop(zset))
+        .take(count)
+        .map(|(member, score)| {
+            RespData::Array(vec![
+                RespData::BulkString(Some(member)),
+                score_reply(score, protocol),
+            ])
+        })
+        .collect();
+    RespData::Array(vec![
+        RespData::BulkString(Some(key.clone())),
+        RespData::Array(members),
+    ])
+}
+
+impl ExecuteCommand for ZMPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        Ok(self
+            .pop(db, stats, conn.protocol)?
+            .unwrap_or(RespData::NullArray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZMPop;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Value, ZSetEnd},
+        resp::RespData,
+        zset::SortedSet,
+    };
+
+    fn bulk(s: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(s.to_string())))
+    }
+
+    #[test]
+    fn parse_zmpop_should_read_keys_end_and_count() {
+        let cmd = parse_command(&build_request(
+            "ZMPOP",
+            &["2", "a", "b", "max", "COUNT", "3"],
+        ))
+        .expect("parse zmpop");
+        assert_eq!(
+            cmd,
+            Command::ZMPop(ZMPop {
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                end: ZSetEnd::Max,
+                count: 3,
+            })
+        );
+
+        for args in [
+            &["1", "a"][..],
+            &["1", "a", "LEFT"],
+            &["0", "a", "MIN"],
+            &["1", "a", "MIN", "COUNT", "0"],
+            &["1", "a", "MIN", "LIMIT", "1"],
+        ] {
+            assert!(
+                parse_command(&build_request("ZMPOP", args)).is_err(),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_zmpop_should_pop_from_first_non_empty_key() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("b"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("x"), 1.0),
+                    (Bytes::from_owner("y"), 2.0),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request(
+            "ZMPOP",
+            &["2", "a", "b", "MIN", "COUNT", "5"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zmpop");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                bulk("b"),
+                RespData::Array(vec![
+                    RespData::Array(vec![bulk("x"), bulk("1")]),
+                    RespData::Array(vec![bulk("y"), bulk("2")]),
+                ]),
+            ])
+        );
+        assert!(!server.lock().await.db.contains_key(b"b".as_ref()));
+
+        let resp = cmd.execute(server, &mut conn).await.expect("zmpop");
+        assert_eq!(resp, RespData::NullArray);
+    }
+}
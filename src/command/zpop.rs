@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_zset, score_reply, scored_members_reply,
+    },
+    db::{ZSetEnd, remove_if_empty},
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// ZPOPMIN and ZPOPMAX.
+#[derive(Debug, PartialEq)]
+pub struct ZPop {
+    key: Bytes,
+    /// Without a count a single `[member, score]` pair is replied, even on RESP3.
+    count: Option<usize>,
+    end: ZSetEnd,
+}
+
+impl ZPop {
+    pub fn parse(args: &[Bytes], end: ZSetEnd) -> ParseResult<Self> {
+        check_length_ge(args, 1)?;
+        let count = match args {
+            [_] => None,
+            [_, count] => Some(lexical_core::parse(count)?),
+            _ => {
+                return Err(ParseError::InvalidArgument(
+                    str::from_utf8(&args[2])?.to_string(),
+                ));
+            }
+        };
+        Ok(ZPop {
+            key: args[0].clone(),
+            count,
+            end,
+        })
+    }
+}
+
+impl ExecuteCommand for ZPop {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(zset) = get_zset(db, stats, &self.key)? else {
+            return Ok(RespData::Array(vec![]));
+        };
+        let popped: Vec<_> = std::iter::from_fn(|| self.end.pop(zset))
+            .take(self.count.unwrap_or(1))
+            .collect();
+        remove_if_empty(db, &self.key);
+
+        if self.count.is_some() {
+            return Ok(scored_members_reply(popped, true, conn.protocol));
+        }
+        Ok(RespData::Array(
+            popped
+                .into_iter()
+                .flat_map(|(member, score)| {
+                    [
+                        RespData::BulkString(Some(member)),
+                        score_reply(score, conn.protocol),
+                    ]
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZPop;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::{Value, ZSetEnd},
+        resp::{RespData, RespProtocol},
+        zset::SortedSet,
+    };
+
+    fn bulk(member: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(member.to_string())))
+    }
+
+    #[test]
+    fn parse_zpop_should_read_count() {
+        let cmd = parse_command(&build_request("ZPOPMAX", &["z", "2"])).expect("parse zpopmax");
+        assert_eq!(
+            cmd,
+            Command::ZPop(ZPop {
+                key: Bytes::from_owner("z"),
+                count: Some(2),
+                end: ZSetEnd::Max,
+            })
+        );
+        assert!(parse_command(&build_request("ZPOPMIN", &["z", "-1"])).is_err());
+        assert!(parse_command(&build_request("ZPOPMIN", &["z", "1", "2"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_zpop_should_pop_from_either_end() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("a"), 1.0),
+                    (Bytes::from_owner("b"), 2.0),
+                    (Bytes::from_owner("c"), 3.0),
+                    (Bytes::from_owner("d"), 4.0),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("ZPOPMIN", &["z"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zpopmin");
+        assert_eq!(resp, RespData::Array(vec![bulk("a"), bulk("1")]));
+
+        let cmd = parse_command(&build_request("ZPOPMAX", &["z", "2"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zpopmax");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![bulk("d"), bulk("4"), bulk("c"), bulk("3")])
+        );
+
+        conn.protocol = RespProtocol::Resp3;
+        let cmd = parse_command(&build_request("ZPOPMAX", &["z", "5"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zpopmax");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![RespData::Array(vec![
+                bulk("b"),
+                RespData::Double(2.0)
+            ])])
+        );
+        assert!(!server.lock().await.db.contains_key(b"z".as_ref()));
+
+        let cmd = parse_command(&build_request("ZPOPMIN", &["z"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("zpopmin");
+        assert_eq!(resp, RespData::Array(vec![]));
+    }
+}
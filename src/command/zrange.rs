@@ -0,0 +1,480 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_zset, scored_members_reply,
+    },
+    db::Value,
+    resp::RespData,
+    server::{Connection, Server},
+    utils::normalize_range,
+    zset::{LexRange, ScoreRange, SortedSet},
+};
+
+/// What the bounds of a range are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeKind {
+    Rank,
+    Score,
+    Lex,
+}
+
+#[derive(Debug, PartialEq)]
+enum RangeBy {
+    Rank(i64, i64),
+    Score(ScoreRange),
+    Lex(LexRange),
+}
+
+/// `ZRANGE`, `ZRANGESTORE`, and the older `ZREVRANGE`, `ZRANGEBYSCORE`, `ZREVRANGEBYSCORE`,
+/// `ZRANGEBYLEX` and `ZREVRANGEBYLEX` which it subsumes.
+#[derive(Debug, PartialEq)]
+pub struct ZRange {
+    /// Store the range there and reply its length, instead of replying it.
+    destination: Option<Bytes>,
+    key: Bytes,
+    range: RangeBy,
+    /// Walk the set from the highest score. Ranks then count from the end as well.
+    rev: bool,
+    /// The `offset` and `count` of `LIMIT`, a negative count meaning no limit.
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+}
+
+impl ZRange {
+    /// Parse `ZRANGE key start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count] [WITHSCORES]`,
+    /// or `ZRANGESTORE` which takes a destination first and no `WITHSCORES`.
+    pub fn parse(args: &[Bytes], store: bool) -> ParseResult<Self> {
+        let (destination, args) = if store {
+            check_length_ge(args, 4)?;
+            (Some(args[0].clone()), &args[1..])
+        } else {
+            check_length_ge(args, 3)?;
+            (None, args)
+        };
+
+        let mut kind = RangeKind::Rank;
+        let mut rev = false;
+        let mut limit = None;
+        let mut with_scores = false;
+        let mut index = 3;
+        while let Some(arg) = args.get(index) {
+            let argument = str::from_utf8(arg)?;
+            match argument.to_uppercase().as_str() {
+                "BYSCORE" if kind != RangeKind::Lex => kind = RangeKind::Score,
+                "BYLEX" if kind != RangeKind::Score => kind = RangeKind::Lex,
+                "REV" => rev = true,
+                "LIMIT" => {
+                    limit = Some(parse_limit(&args[index + 1..])?);
+                    index += 2;
+                }
+                "WITHSCORES" if !store => with_scores = true,
+                _ => return Err(ParseError::InvalidArgument(argument.to_string())),
+            }
+            index += 1;
+        }
+
+        if limit.is_some() && kind == RangeKind::Rank {
+            return Err(ParseError::InvalidArgument(
+                "LIMIT is only supported in combination with either BYSCORE or BYLEX".to_string(),
+            ));
+        }
+        Self::build(destination, args, kind, rev, limit, with_scores)
+    }
+
+    /// Parse one of the older range commands, which range by `kind` and in reverse if `rev`.
+    /// `ZREVRANGE` and `ZRANGEBYSCORE` take `WITHSCORES`, and the by score and by lex ones take
+    /// `LIMIT`.
+    pub fn parse_legacy(args: &[Bytes], kind: RangeKind, rev: bool) -> ParseResult<Self> {
+        check_length_ge(args, 3)?;
+        let mut limit = None;
+        let mut with_scores = false;
+        let mut index = 3;
+        while let Some(arg) = args.get(index) {
+            let argument = str::from_utf8(arg)?;
+            match argument.to_uppercase().as_str() {
+                "LIMIT" if kind != RangeKind::Rank => {
+                    limit = Some(parse_limit(&args[index + 1..])?);
+                    index += 2;
+                }
+                "WITHSCORES" if kind != RangeKind::Lex => with_scores = true,
+                _ => return Err(ParseError::InvalidArgument(argument.to_string())),
+            }
+            index += 1;
+        }
+        Self::build(None, args, kind, rev, limit, with_scores)
+    }
+
+    fn build(
+        destination: Option<Bytes>,
+        args: &[Bytes],
+        kind: RangeKind,
+        rev: bool,
+        limit: Option<(i64, i64)>,
+        with_scores: bool,
+    ) -> ParseResult<Self> {
+        if with_scores && kind == RangeKind::Lex {
+            return Err(ParseError::InvalidArgument(
+                "WITHSCORES not supported in combination with BYLEX".to_string(),
+            ));
+        }
+        // Reversed score and lex ranges give the upper bound first
+        let (min, max) = if rev && kind != RangeKind::Rank {
+            (&args[2], &args[1])
+        } else {
+            (&args[1], &args[2])
+        };
+        let range = match kind {
+            RangeKind::Rank => RangeBy::Rank(lexical_core::parse(min)?, lexical_core::parse(max)?),
+            RangeKind::Score => RangeBy::Score(ScoreRange::parse(min, max).ok_or_else(|| {
+                ParseError::InvalidArgument("min or max is not a float".to_string())
+            })?),
+            RangeKind::Lex => RangeBy::Lex(LexRange::parse(min, max).ok_or_else(|| {
+                ParseError::InvalidArgument("min or max not valid string range item".to_string())
+            })?),
+        };
+        Ok(ZRange {
+            destination,
+            key: args[0].clone(),
+            range,
+            rev,
+            limit,
+            with_scores,
+        })
+    }
+
+    /// The members in the range, in the order of the reply.
+    fn select(&self, zset: &SortedSet) -> Vec<(Bytes, f64)> {
+        let len = zset.len();
+        // The ranks in ascending order
+        let ranks = match &self.range {
+            RangeBy::Rank(start, stop) => match normalize_range(*start, *stop, len) {
+                None => return vec![],
+                Some((start, stop)) if self.rev => len - 1 - stop..len - start,
+                Some((start, stop)) => start..stop + 1,
+            },
+            RangeBy::Score(range) => zset.score_range(range),
+            RangeBy::Lex(range) => zset.lex_range(range),
+        };
+        // Narrow the ranks to the limit rather than skipping members one by one
+        let ranks = match self.limit {
+            None => ranks,
+            Some((offset, _)) if offset < 0 => return vec![],
+            Some((offset, count)) => {
+                let offset = (offset as usize).min(ranks.len());
+                let count = usize::try_from(count).map_or(usize::MAX, |count| count);
+                let count = count.min(ranks.len() - offset);
+                if self.rev {
+                    ranks.end - offset - count..ranks.end - offset
+                } else {
+                    ranks.start + offset..ranks.start + offset + count
+                }
+            }
+        };
+
+        let members = if self.rev {
+            zset.rev_range(ranks)
+        } else {
+            zset.range(ranks)
+        };
+        members
+            .map(|(member, score)| (member.clone(), score))
+            .collect()
+    }
+}
+
+/// Parse the `offset count` of a `LIMIT` option.
+fn parse_limit(args: &[Bytes]) -> ParseResult<(i64, i64)> {
+    check_length_ge(args, 2)?;
+    Ok((
+        lexical_core::parse(&args[0])?,
+        lexical_core::parse(&args[1])?,
+    ))
+}
+
+impl ExecuteCommand for ZRange {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let members = get_zset(db, stats, &self.key)?.map_or(vec![], |zset| self.select(zset));
+
+        let Some(destination) = &self.destination else {
+            return Ok(scored_members_reply(
+                members,
+                self.with_scores,
+                conn.protocol,
+            ));
+        };
+        let len = members.len();
+        if members.is_empty() {
+            db.remove(destination);
+        } else {
+            db.insert(
+                destination.clone(),
+                (Value::ZSet(members.into_iter().collect()), None),
+            );
+            server.serve_blocked_clients(destination);
+        }
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{RangeBy, ZRange};
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::{RespData, RespProtocol},
+        server::Server,
+        zset::{LexBound, LexRange, ScoreRange, SortedSet},
+    };
+
+    fn bulk(member: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(member.to_string())))
+    }
+
+    fn bulks(members: &[&str]) -> RespData {
+        RespData::Array(members.iter().map(|member| bulk(member)).collect())
+    }
+
+    async fn seed(server: &tokio::sync::Mutex<Server>) {
+        let zset: SortedSet = ["a", "b", "c", "d", "e"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, member)| (Bytes::from_owner(member), i as f64 + 1.0))
+            .collect();
+        server
+            .lock()
+            .await
+            .db
+            .insert(Bytes::from_owner("z"), (Value::ZSet(zset), None));
+    }
+
+    #[test]
+    fn parse_zrange_should_read_options() {
+        let cmd = parse_command(&build_request(
+            "ZRANGE",
+            &[
+                "z",
+                "(5",
+                "-inf",
+                "byscore",
+                "REV",
+                "LIMIT",
+                "1",
+                "-1",
+                "WITHSCORES",
+            ],
+        ))
+        .expect("parse zrange");
+        assert_eq!(
+            cmd,
+            Command::ZRange(ZRange {
+                destination: None,
+                key: Bytes::from_owner("z"),
+                range: RangeBy::Score(ScoreRange {
+                    min: f64::NEG_INFINITY,
+                    max: 5.0,
+                    min_exclusive: false,
+                    max_exclusive: true,
+                }),
+                rev: true,
+                limit: Some((1, -1)),
+                with_scores: true,
+            })
+        );
+
+        let cmd = parse_command(&build_request(
+            "ZRANGESTORE",
+            &["dst", "z", "[a", "+", "BYLEX"],
+        ))
+        .expect("parse zrangestore");
+        assert_eq!(
+            cmd,
+            Command::ZRange(ZRange {
+                destination: Some(Bytes::from_owner("dst")),
+                key: Bytes::from_owner("z"),
+                range: RangeBy::Lex(LexRange {
+                    min: LexBound::Inclusive(Bytes::from_owner("a")),
+                    max: LexBound::Max,
+                }),
+                rev: false,
+                limit: None,
+                with_scores: false,
+            })
+        );
+
+        let cmd = parse_command(&build_request("ZREVRANGEBYSCORE", &["z", "+inf", "1"]))
+            .expect("parse zrevrangebyscore");
+        let Command::ZRange(zrange) = cmd else {
+            panic!("not a range");
+        };
+        assert!(zrange.rev);
+        assert_eq!(
+            zrange.range,
+            RangeBy::Score(ScoreRange::parse(b"1", b"+inf").unwrap())
+        );
+
+        for (command, args) in [
+            ("ZRANGE", &["z", "0", "1", "LIMIT", "0", "1"][..]),
+            ("ZRANGE", &["z", "a", "c", "BYLEX", "WITHSCORES"]),
+            ("ZRANGE", &["z", "0", "1", "BYSCORE", "BYLEX"]),
+            ("ZRANGE", &["z", "x", "1", "BYSCORE"]),
+            ("ZRANGE", &["z", "0", "1", "BYSCORE", "LIMIT", "0"]),
+            ("ZRANGESTORE", &["dst", "z", "0", "1", "WITHSCORES"]),
+            ("ZREVRANGE", &["z", "0", "1", "LIMIT", "0", "1"]),
+            ("ZRANGEBYLEX", &["z", "-", "+", "WITHSCORES"]),
+        ] {
+            assert!(
+                parse_command(&build_request(command, args)).is_err(),
+                "{command} {args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_zrange_should_range_by_rank() {
+        let (server, mut conn) = build_server_connection().await;
+        seed(&server).await;
+
+        for (command, args, expected) in [
+            (
+                "ZRANGE",
+                &["z", "0", "-1"][..],
+                &["a", "b", "c", "d", "e"][..],
+            ),
+            ("ZRANGE", &["z", "1", "2"], &["b", "c"]),
+            ("ZRANGE", &["z", "0", "1", "REV"], &["e", "d"]),
+            ("ZREVRANGE", &["z", "-2", "-1"], &["b", "a"]),
+            ("ZRANGE", &["z", "3", "1"], &[]),
+            ("ZRANGE", &["missing", "0", "-1"], &[]),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("zrange");
+            assert_eq!(resp, bulks(expected), "{command} {args:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_zrange_should_range_by_score_and_lex_with_limit() {
+        let (server, mut conn) = build_server_connection().await;
+        seed(&server).await;
+
+        for (command, args, expected) in [
+            (
+                "ZRANGE",
+                &["z", "(1", "4", "BYSCORE"][..],
+                &["b", "c", "d"][..],
+            ),
+            (
+                "ZRANGE",
+                &["z", "4", "(1", "BYSCORE", "REV"],
+                &["d", "c", "b"],
+            ),
+            (
+                "ZRANGE",
+                &["z", "-inf", "+inf", "BYSCORE", "LIMIT", "1", "2"],
+                &["b", "c"],
+            ),
+            (
+                "ZRANGE",
+                &["z", "+inf", "-inf", "BYSCORE", "REV", "LIMIT", "1", "-1"],
+                &["d", "c", "b", "a"],
+            ),
+            ("ZRANGEBYSCORE", &["z", "2", "3", "LIMIT", "-1", "1"], &[]),
+            ("ZRANGEBYLEX", &["z", "[b", "(d"], &["b", "c"]),
+            (
+                "ZREVRANGEBYLEX",
+                &["z", "+", "(c", "LIMIT", "0", "1"],
+                &["e"],
+            ),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("zrange");
+            assert_eq!(resp, bulks(expected), "{command} {args:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_zrange_withscores_should_depend_on_protocol() {
+        let (server, mut conn) = build_server_connection().await;
+        seed(&server).await;
+
+        let cmd = parse_command(&build_request("ZRANGE", &["z", "0", "1", "WITHSCORES"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zrange");
+        assert_eq!(resp, bulks(&["a", "1", "b", "2"]));
+
+        conn.protocol = RespProtocol::Resp3;
+        let resp = cmd.execute(server, &mut conn).await.expect("zrange");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![
+                RespData::Array(vec![bulk("a"), RespData::Double(1.0)]),
+                RespData::Array(vec![bulk("b"), RespData::Double(2.0)]),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_zrangestore_should_overwrite_destination() {
+        let (server, mut conn) = build_server_connection().await;
+        seed(&server).await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("dst"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        let cmd = parse_command(&build_request(
+            "ZRANGESTORE",
+            &["dst", "z", "2", "+inf", "BYSCORE", "LIMIT", "0", "2"],
+        ))
+        .unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zrangestore");
+        assert_eq!(resp, RespData::Integer(2));
+        assert_eq!(
+            server.lock().await.db.get(b"dst".as_ref()),
+            Some(&(
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("b"), 2.0),
+                    (Bytes::from_owner("c"), 3.0),
+                ])),
+                None
+            ))
+        );
+
+        let cmd = parse_command(&build_request("ZRANGESTORE", &["dst", "z", "10", "20"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zrangestore");
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(!server.lock().await.db.contains_key(b"dst".as_ref()));
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, ParseResult, check_length_ge,
+        error::{ExecResult, ParseError},
+        get_zset, score_reply,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+/// `ZRANK` and `ZREVRANK`.
+#[derive(Debug, PartialEq)]
+pub struct ZRank {
+    key: Bytes,
+    member: Bytes,
+    /// Count ranks from the highest score.
+    rev: bool,
+    with_score: bool,
+}
+
+impl ZRank {
+    pub fn parse(args: &[Bytes], rev: bool) -> ParseResult<Self> {
+        check_length_ge(args, 2)?;
+        if args.len() > 3 {
+            return Err(ParseError::ExpectLengthEq(3, args.len(), args.to_vec()));
+        }
+        let with_score = match args.get(2) {
+            None => false,
+            Some(arg) => {
+                let argument = str::from_utf8(arg)?;
+                if !argument.eq_ignore_ascii_case("WITHSCORE") {
+                    return Err(ParseError::InvalidArgument(argument.to_string()));
+                }
+                true
+            }
+        };
+        Ok(ZRank {
+            key: args[0].clone(),
+            member: args[1].clone(),
+            rev,
+            with_score,
+        })
+    }
+}
+
+impl ExecuteCommand for ZRank {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        // A missing member is a null array when a pair would have been replied
+        let missing = if self.with_score {
+            RespData::NullArray
+        } else {
+            RespData::Null
+        };
+        let Some(zset) = get_zset(db, stats, &self.key)? else {
+            return Ok(missing);
+        };
+        let (Some(rank), Some(score)) = (zset.rank(&self.member), zset.score(&self.member)) else {
+            return Ok(missing);
+        };
+        let rank = if self.rev {
+            zset.len() - 1 - rank
+        } else {
+            rank
+        };
+
+        if self.with_score {
+            return Ok(RespData::Array(vec![
+                RespData::Integer(rank as i64),
+                score_reply(score, conn.protocol),
+            ]));
+        }
+        Ok(RespData::Integer(rank as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZRank;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_zrank_should_read_withscore() {
+        let cmd = parse_command(&build_request("ZREVRANK", &["z", "m", "withscore"]))
+            .expect("parse zrevrank");
+        assert_eq!(
+            cmd,
+            Command::ZRank(ZRank {
+                key: Bytes::from_owner("z"),
+                member: Bytes::from_owner("m"),
+                rev: true,
+                with_score: true,
+            })
+        );
+        assert!(parse_command(&build_request("ZRANK", &["z", "m", "WITHSCORES"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_zrank_should_count_from_either_end() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("a"), 1.0),
+                    (Bytes::from_owner("b"), 2.0),
+                    (Bytes::from_owner("c"), 3.0),
+                ])),
+                None,
+            ),
+        );
+
+        for (command, args, expected) in [
+            ("ZRANK", &["z", "a"][..], RespData::Integer(0)),
+            ("ZREVRANK", &["z", "a"], RespData::Integer(2)),
+            ("ZRANK", &["z", "missing"], RespData::Null),
+            ("ZRANK", &["missing", "a"], RespData::Null),
+            ("ZRANK", &["z", "missing", "WITHSCORE"], RespData::NullArray),
+            (
+                "ZRANK",
+                &["z", "c", "WITHSCORE"],
+                RespData::Array(vec![
+                    RespData::Integer(2),
+                    RespData::BulkString(Some(Bytes::from_owner("3"))),
+                ]),
+            ),
+        ] {
+            let cmd = parse_command(&build_request(command, args)).unwrap();
+            let resp = cmd.execute(server.clone(), &mut conn).await.expect("zrank");
+            assert_eq!(resp, expected, "{command} {args:?}");
+        }
+    }
+}
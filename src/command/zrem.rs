@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{ExecuteCommand, Parse, ParseResult, check_length_ge, error::ExecResult, get_zset},
+    db::remove_if_empty,
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZRem {
+    key: Bytes,
+    members: Vec<Bytes>,
+}
+
+impl Parse for ZRem {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_ge(args, 2)?;
+        Ok(ZRem {
+            key: args[0].clone(),
+            members: args[1..].to_vec(),
+        })
+    }
+}
+
+impl ExecuteCommand for ZRem {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        _conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+
+        let Some(zset) = get_zset(db, stats, &self.key)? else {
+            return Ok(RespData::Integer(0));
+        };
+        let removed = self
+            .members
+            .iter()
+            .filter(|member| zset.remove(member).is_some())
+            .count();
+        remove_if_empty(db, &self.key);
+
+        Ok(RespData::Integer(removed as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZRem;
+    use crate::{
+        command::{
+            Command, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::RespData,
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_zrem_should_read_members() {
+        let cmd = parse_command(&build_request("ZREM", &["z", "a", "b"])).expect("parse zrem");
+        assert_eq!(
+            cmd,
+            Command::ZRem(ZRem {
+                key: Bytes::from_owner("z"),
+                members: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+            })
+        );
+        assert!(parse_command(&build_request("ZREM", &["z"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_zrem_should_remove_members_and_empty_key() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("a"), 1.0),
+                    (Bytes::from_owner("b"), 2.0),
+                ])),
+                None,
+            ),
+        );
+
+        let cmd = parse_command(&build_request("ZREM", &["z", "a", "a", "missing"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zrem");
+        assert_eq!(resp, RespData::Integer(1));
+
+        let cmd = parse_command(&build_request("ZREM", &["z", "b"])).unwrap();
+        let resp = cmd.execute(server.clone(), &mut conn).await.expect("zrem");
+        assert_eq!(resp, RespData::Integer(1));
+        assert!(!server.lock().await.db.contains_key(b"z".as_ref()));
+    }
+}
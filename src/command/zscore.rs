@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecuteCommand, Parse, ParseResult, check_length_eq, error::ExecResult, get_zset,
+        score_reply,
+    },
+    resp::RespData,
+    server::{Connection, Server},
+};
+
+#[derive(Debug, PartialEq)]
+pub struct ZScore {
+    key: Bytes,
+    member: Bytes,
+}
+
+impl Parse for ZScore {
+    fn parse(args: &[Bytes]) -> ParseResult<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        check_length_eq(args, 2)?;
+        Ok(ZScore {
+            key: args[0].clone(),
+            member: args[1].clone(),
+        })
+    }
+}
+
+impl ExecuteCommand for ZScore {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let Server { db, stats, .. } = &mut *server.lock().await;
+        let score = get_zset(db, stats, &self.key)?.and_then(|zset| zset.score(&self.member));
+        Ok(score.map_or(RespData::Null, |score| score_reply(score, conn.protocol)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::ZScore;
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            test::{build_request, build_server_connection},
+        },
+        db::Value,
+        resp::{RespData, RespProtocol},
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn parse_zscore_should_read_member() {
+        let cmd = parse_command(&build_request("ZSCORE", &["z", "m"])).expect("parse zscore");
+        assert_eq!(
+            cmd,
+            Command::ZScore(ZScore {
+                key: Bytes::from_owner("z"),
+                member: Bytes::from_owner("m"),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_zscore_should_reply_score() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("z"),
+            (
+                Value::ZSet(SortedSet::from([(Bytes::from_owner("m"), 1.25)])),
+                None,
+            ),
+        );
+        server.lock().await.db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+
+        let cmd = parse_command(&build_request("ZSCORE", &["z", "m"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zscore");
+        assert_eq!(resp, RespData::BulkString(Some(Bytes::from_owner("1.25"))));
+
+        conn.protocol = RespProtocol::Resp3;
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zscore");
+        assert_eq!(resp, RespData::Double(1.25));
+
+        let cmd = parse_command(&build_request("ZSCORE", &["z", "missing"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zscore");
+        assert_eq!(resp, RespData::Null);
+
+        let cmd = parse_command(&build_request("ZSCORE", &["s", "m"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+}
@@ -0,0 +1,534 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bytes::Bytes;
+use tokio::sync::Mutex;
+
+use crate::{
+    command::{
+        ExecError, ExecuteCommand, ParseResult,
+        error::{ExecResult, ParseError},
+        parse_numkeys, scored_members_reply,
+        setop::SetOperator,
+    },
+    db::{Db, Set, Value, lookup_key},
+    resp::RespData,
+    server::{Connection, Server, Stats},
+    zset::{SortedSet, parse_score},
+};
+
+/// How the scores of a member found in several inputs combine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            // Like Redis, `inf + -inf` sums to 0 rather than NaN
+            Aggregate::Sum => Some(a + b).filter(|sum| !sum.is_nan()).unwrap_or(0.0),
+            Aggregate::Min => a.min(b),
+            Aggregate::Max => a.max(b),
+        }
+    }
+}
+
+/// `ZUNION`, `ZINTER`, `ZDIFF` and their `STORE` variants.
+#[derive(Debug, PartialEq)]
+pub struct ZSetOp {
+    operator: SetOperator,
+    /// Store the result there and reply its cardinality, instead of replying it.
+    destination: Option<Bytes>,
+    keys: Vec<Bytes>,
+    /// One multiplier per key for the scores of its members.
+    weights: Vec<f64>,
+    aggregate: Aggregate,
+    with_scores: bool,
+}
+
+impl ZSetOp {
+    /// Parse `[destination] numkeys key [key ...] [WEIGHTS weight [weight ...]]
+    /// [AGGREGATE SUM | MIN | MAX] [WITHSCORES]`. `ZDIFF` takes neither `WEIGHTS` nor
+    /// `AGGREGATE`, and the `STORE` variants take no `WITHSCORES`.
+    pub fn parse(args: &[Bytes], operator: SetOperator, store: bool) -> ParseResult<Self> {
+        let (destination, args) = match args.split_first() {
+            Some((destination, args)) if store => (Some(destination.clone()), args),
+            _ => (None, args),
+        };
+        let (keys, mut options) = parse_numkeys(args)?;
+
+        let mut zsetop = ZSetOp {
+            operator,
+            destination,
+            keys: keys.to_vec(),
+            weights: vec![1.0; keys.len()],
+            aggregate: Aggregate::Sum,
+            with_scores: false,
+        };
+        let weighted = operator != SetOperator::Diff;
+        while let Some((option, rest)) = options.split_first() {
+            let argument = str::from_utf8(option)?;
+            options = match argument.to_uppercase().as_str() {
+                "WEIGHTS" if weighted && rest.len() >= keys.len() => {
+                    for (weight, arg) in zsetop.weights.iter_mut().zip(rest) {
+                        *weight = parse_score(arg).ok_or_else(|| {
+                            ParseError::InvalidArgument("weight value is not a float".to_string())
+                        })?;
+                    }
+                    &rest[keys.len()..]
+                }
+                "AGGREGATE" if weighted && !rest.is_empty() => {
+                    let aggregate = str::from_utf8(&rest[0])?;
+                    zsetop.aggregate = match aggregate.to_uppercase().as_str() {
+                        "SUM" => Aggregate::Sum,
+                        "MIN" => Aggregate::Min,
+                        "MAX" => Aggregate::Max,
+                        _ => return Err(ParseError::InvalidArgument(aggregate.to_string())),
+                    };
+                    &rest[1..]
+                }
+                "WITHSCORES" if !store => {
+                    zsetop.with_scores = true;
+                    rest
+                }
+                _ => return Err(ParseError::InvalidArgument(argument.to_string())),
+            };
+        }
+        Ok(zsetop)
+    }
+}
+
+/// A sorted set or a plain set taking part in a sorted set operation. The members of a plain set
+/// score 1.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Input<'a> {
+    ZSet(&'a SortedSet),
+    Set(&'a Set),
+}
+
+impl<'a> Input<'a> {
+    pub(super) fn len(&self) -> usize {
+        match self {
+            Input::ZSet(zset) => zset.len(),
+            Input::Set(set) => set.len(),
+        }
+    }
+
+    pub(super) fn score(&self, member: &[u8]) -> Option<f64> {
+        match self {
+            Input::ZSet(zset) => zset.score(member),
+            Input::Set(set) => set.contains(member).then_some(1.0),
+        }
+    }
+
+    pub(super) fn iter(&self) -> Box<dyn Iterator<Item = (&'a Bytes, f64)> + 'a> {
+        match *self {
+            Input::ZSet(zset) => Box::new(zset.iter()),
+            Input::Set(set) => Box::new(set.iter().map(|member| (member, 1.0))),
+        }
+    }
+}
+
+/// Look up the sorted sets or sets of `keys`, missing keys being `None`. Fails if any key holds
+/// another type.
+pub(super) fn lookup_inputs<'a>(
+    db: &'a mut Db,
+    stats: &mut Stats,
+    keys: &[Bytes],
+) -> ExecResult<Vec<Option<Input<'a>>>> {
+    for key in keys {
+        match lookup_key(db, stats, key) {
+            None | Some((Value::ZSet(_), _)) | Some((Value::Set(_), _)) => {}
+            Some(_) => return Err(ExecError::WrongType),
+        }
+    }
+    let db = &*db;
+    Ok(keys
+        .iter()
+        .map(|key| match db.get(key) {
+            Some((Value::ZSet(zset), _)) => Some(Input::ZSet(zset)),
+            Some((Value::Set(set), _)) => Some(Input::Set(set)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Multiply a score by a weight, where `0 * inf` gives 0 as in Redis.
+fn weigh(score: f64, weight: f64) -> f64 {
+    Some(score * weight)
+        .filter(|score| !score.is_nan())
+        .unwrap_or(0.0)
+}
+
+fn combine(
+    operator: SetOperator,
+    inputs: &[Option<Input>],
+    weights: &[f64],
+    aggregate: Aggregate,
+) -> SortedSet {
+    match operator {
+        SetOperator::Union => {
+            let mut scores: HashMap<&Bytes, f64> = HashMap::new();
+            for (input, weight) in inputs.iter().zip(weights) {
+                for (member, score) in input.iter().flat_map(|input| input.iter()) {
+                    let score = weigh(score, *weight);
+                    scores
+                        .entry(member)
+                        .and_modify(|current| *current = aggregate.apply(*current, score))
+                        .or_insert(score);
+                }
+            }
+            scores
+                .into_iter()
+                .map(|(member, score)| (member.clone(), score))
+                .collect()
+        }
+        SetOperator::Inter => {
+            let Some(inputs) = inputs.iter().copied().collect::<Option<Vec<_>>>() else {
+                // A missing key is an empty set, which empties the intersection
+                return SortedSet::new();
+            };
+            // Probe the other inputs with the members of the smallest one
+            let mut inputs: Vec<_> = inputs.into_iter().zip(weights.iter().copied()).collect();
+            inputs.sort_by_key(|(input, _)| input.len());
+            let (smallest, weight) = inputs[0];
+            smallest
+                .iter()
+                .filter_map(|(member, score)| {
+                    inputs[1..]
+                        .iter()
+                        .try_fold(weigh(score, weight), |total, (input, weight)| {
+                            let score = input.score(member)?;
+                            Some(aggregate.apply(total, weigh(score, *weight)))
+                        })
+                        .map(|score| (member.clone(), score))
+                })
+                .collect()
+        }
+        SetOperator::Diff => {
+            let Some(first) = inputs[0] else {
+                return SortedSet::new();
+            };
+            first
+                .iter()
+                .filter(|(member, _)| {
+                    !inputs[1..]
+                        .iter()
+                        .flatten()
+                        .any(|input| input.score(member).is_some())
+                })
+                .map(|(member, score)| (member.clone(), score))
+                .collect()
+        }
+    }
+}
+
+impl ExecuteCommand for ZSetOp {
+    async fn execute(
+        &self,
+        server: Arc<Mutex<Server>>,
+        conn: &mut Connection,
+    ) -> ExecResult<RespData> {
+        let server = &mut *server.lock().await;
+        let (db, stats) = (&mut server.db, &mut server.stats);
+        let inputs = lookup_inputs(db, stats, &self.keys)?;
+        let result = combine(self.operator, &inputs, &self.weights, self.aggregate);
+
+        let Some(destination) = &self.destination else {
+            let members = result
+                .iter()
+                .map(|(member, score)| (member.clone(), score))
+                .collect();
+            return Ok(scored_members_reply(
+                members,
+                self.with_scores,
+                conn.protocol,
+            ));
+        };
+
+        let len = result.len();
+        // The destination is overwritten whatever it holds, and removed by an empty result
+        if result.is_empty() {
+            db.remove(destination);
+        } else {
+            db.insert(destination.clone(), (Value::ZSet(result), None));
+            server.serve_blocked_clients(destination);
+        }
+        Ok(RespData::Integer(len as i64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{Aggregate, ZSetOp};
+    use crate::{
+        command::{
+            Command, ExecError, ExecuteCommand, parse_command,
+            setop::SetOperator,
+            test::{build_request, build_server_connection},
+        },
+        db::{Set, Value},
+        resp::{RespData, RespProtocol},
+        server::Server,
+        zset::SortedSet,
+    };
+
+    fn zset(members: &[(&str, f64)]) -> SortedSet {
+        members
+            .iter()
+            .map(|(member, score)| (Bytes::from(member.to_string()), *score))
+            .collect()
+    }
+
+    fn bulk(member: &str) -> RespData {
+        RespData::BulkString(Some(Bytes::from(member.to_string())))
+    }
+
+    async fn seed(server: &tokio::sync::Mutex<Server>) {
+        let db = &mut server.lock().await.db;
+        db.insert(
+            Bytes::from_owner("z1"),
+            (
+                Value::ZSet(zset(&[("a", 1.0), ("b", 2.0), ("c", 3.0)])),
+                None,
+            ),
+        );
+        db.insert(
+            Bytes::from_owner("z2"),
+            (
+                Value::ZSet(zset(&[("b", 10.0), ("c", 20.0), ("d", 30.0)])),
+                None,
+            ),
+        );
+        db.insert(
+            Bytes::from_owner("set"),
+            (
+                Value::Set(Set::from([Bytes::from_owner("c"), Bytes::from_owner("d")])),
+                None,
+            ),
+        );
+        db.insert(
+            Bytes::from_owner("s"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+    }
+
+    #[test]
+    fn parse_zsetop_should_read_options() {
+        let cmd = parse_command(&build_request(
+            "ZUNIONSTORE",
+            &[
+                "dst",
+                "2",
+                "a",
+                "b",
+                "WEIGHTS",
+                "2",
+                "-1.5",
+                "aggregate",
+                "max",
+            ],
+        ))
+        .expect("parse zunionstore");
+        assert_eq!(
+            cmd,
+            Command::ZSetOp(ZSetOp {
+                operator: SetOperator::Union,
+                destination: Some(Bytes::from_owner("dst")),
+                keys: vec![Bytes::from_owner("a"), Bytes::from_owner("b")],
+                weights: vec![2.0, -1.5],
+                aggregate: Aggregate::Max,
+                with_scores: false,
+            })
+        );
+
+        let cmd =
+            parse_command(&build_request("ZDIFF", &["1", "a", "WITHSCORES"])).expect("parse zdiff");
+        assert_eq!(
+            cmd,
+            Command::ZSetOp(ZSetOp {
+                operator: SetOperator::Diff,
+                destination: None,
+                keys: vec![Bytes::from_owner("a")],
+                weights: vec![1.0],
+                aggregate: Aggregate::Sum,
+                with_scores: true,
+            })
+        );
+
+        for (command, args) in [
+            ("ZUNION", &["0", "a"][..]),
+            ("ZUNION", &["3", "a", "b"]),
+            ("ZUNION", &["2", "a", "b", "WEIGHTS", "1"]),
+            ("ZINTER", &["1", "a", "WEIGHTS", "x"]),
+            ("ZINTER", &["1", "a", "AGGREGATE", "AVG"]),
+            ("ZDIFF", &["1", "a", "WEIGHTS", "1"]),
+            ("ZINTERSTORE", &["dst", "1", "a", "WITHSCORES"]),
+        ] {
+            assert!(
+                parse_command(&build_request(command, args)).is_err(),
+                "{command} {args:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_zunion_should_aggregate_weighted_scores() {
+        let (server, mut conn) = build_server_connection().await;
+        seed(&server).await;
+
+        for (args, expected) in [
+            (
+                &["2", "z1", "z2", "WITHSCORES"][..],
+                &["a", "1", "b", "12", "c", "23", "d", "30"][..],
+            ),
+            (
+                &[
+                    "2",
+                    "z1",
+                    "z2",
+                    "WEIGHTS",
+                    "10",
+                    "1",
+                    "AGGREGATE",
+                    "MIN",
+                    "WITHSCORES",
+                ],
+                &["a", "10", "b", "10", "c", "20", "d", "30"],
+            ),
+            (
+                &[
+                    "3",
+                    "z1",
+                    "set",
+                    "missing",
+                    "AGGREGATE",
+                    "MAX",
+                    "WITHSCORES",
+                ],
+                &["a", "1", "d", "1", "b", "2", "c", "3"],
+            ),
+        ] {
+            let cmd = parse_command(&build_request("ZUNION", args)).unwrap();
+            let resp = cmd
+                .execute(server.clone(), &mut conn)
+                .await
+                .expect("zunion");
+            assert_eq!(
+                resp,
+                RespData::Array(expected.iter().map(|s| bulk(s)).collect()),
+                "{args:?}"
+            );
+        }
+
+        let cmd = parse_command(&build_request("ZUNION", &["2", "z1", "s"])).unwrap();
+        let err = cmd
+            .execute(server, &mut conn)
+            .await
+            .expect_err("wrong type");
+        assert_eq!(err, ExecError::WrongType);
+    }
+
+    #[tokio::test]
+    async fn execute_zinter_and_zdiff_should_combine_members() {
+        let (server, mut conn) = build_server_connection().await;
+        seed(&server).await;
+        conn.protocol = RespProtocol::Resp3;
+
+        let cmd = parse_command(&build_request(
+            "ZINTER",
+            &[
+                "3",
+                "z1",
+                "z2",
+                "set",
+                "WEIGHTS",
+                "1",
+                "0.5",
+                "100",
+                "WITHSCORES",
+            ],
+        ))
+        .unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zinter");
+        assert_eq!(
+            resp,
+            RespData::Array(vec![RespData::Array(vec![
+                bulk("c"),
+                RespData::Double(113.0)
+            ])])
+        );
+
+        let cmd = parse_command(&build_request("ZINTER", &["2", "z1", "missing"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zinter");
+        assert_eq!(resp, RespData::Array(vec![]));
+
+        let cmd = parse_command(&build_request("ZDIFF", &["3", "z1", "set", "missing"])).unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("zdiff");
+        assert_eq!(resp, RespData::Array(vec![bulk("a"), bulk("b")]));
+    }
+
+    #[tokio::test]
+    async fn execute_zsetop_store_should_overwrite_destination() {
+        let (server, mut conn) = build_server_connection().await;
+        seed(&server).await;
+
+        let cmd = parse_command(&build_request("ZINTERSTORE", &["s", "2", "z1", "z2"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zinterstore");
+        assert_eq!(resp, RespData::Integer(2));
+        assert_eq!(
+            server.lock().await.db.get(b"s".as_ref()),
+            Some(&(Value::ZSet(zset(&[("b", 12.0), ("c", 23.0)])), None))
+        );
+
+        let cmd = parse_command(&build_request("ZDIFFSTORE", &["s", "2", "z1", "z1"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zdiffstore");
+        assert_eq!(resp, RespData::Integer(0));
+        assert!(!server.lock().await.db.contains_key(b"s".as_ref()));
+    }
+
+    #[tokio::test]
+    async fn execute_zunion_should_not_produce_nan() {
+        let (server, mut conn) = build_server_connection().await;
+        server.lock().await.db.insert(
+            Bytes::from_owner("pos"),
+            (Value::ZSet(zset(&[("m", f64::INFINITY)])), None),
+        );
+        server.lock().await.db.insert(
+            Bytes::from_owner("neg"),
+            (Value::ZSet(zset(&[("m", f64::NEG_INFINITY)])), None),
+        );
+
+        let cmd =
+            parse_command(&build_request("ZUNION", &["2", "pos", "neg", "WITHSCORES"])).unwrap();
+        let resp = cmd
+            .execute(server.clone(), &mut conn)
+            .await
+            .expect("zunion");
+        assert_eq!(resp, RespData::Array(vec![bulk("m"), bulk("0")]));
+
+        let cmd = parse_command(&build_request(
+            "ZUNION",
+            &["1", "pos", "WEIGHTS", "0", "WITHSCORES"],
+        ))
+        .unwrap();
+        let resp = cmd.execute(server, &mut conn).await.expect("zunion");
+        assert_eq!(resp, RespData::Array(vec![bulk("m"), bulk("0")]));
+    }
+}
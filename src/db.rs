@@ -0,0 +1,364 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque, hash_map};
+
+use bytes::Bytes;
+use rand::seq::IndexedRandom;
+
+use crate::{
+    server::Stats,
+    stream::Stream,
+    utils::{BytesInStr, unix_time_ms},
+    zset::SortedSet,
+};
+
+pub type Key = Bytes;
+pub type List = VecDeque<Bytes>;
+pub type Set = HashSet<Bytes>;
+/// A value and its expire time in Unix time milliseconds.
+pub type DbItem = (Value, Option<u64>);
+pub type Db = HashMap<Key, DbItem>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(Bytes),
+    List(List),
+    Hash(Hash),
+    Set(Set),
+    ZSet(SortedSet),
+    Stream(Stream),
+}
+
+impl Value {
+    /// Whether the value is a collection holding no element. Such keys are removed, as Redis
+    /// never keeps empty collections. Streams are the exception, as they keep their last ID.
+    pub fn is_empty_collection(&self) -> bool {
+        match self {
+            Value::String(_) => false,
+            Value::List(list) => list.is_empty(),
+            Value::Hash(hash) => hash.is_empty(),
+            Value::Set(set) => set.is_empty(),
+            Value::ZSet(zset) => zset.is_empty(),
+            Value::Stream(_) => false,
+        }
+    }
+
+    /// The name reported by `TYPE` and used in logs.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::List(_) => "list",
+            Value::Hash(_) => "hash",
+            Value::Set(_) => "set",
+            Value::ZSet(_) => "zset",
+            Value::Stream(_) => "stream",
+        }
+    }
+}
+
+/// A hash whose fields may expire individually, as set by `HEXPIRE` and friends.
+///
+/// Expired fields are removed by [`lookup_key`] before any command sees the hash, so the
+/// accessors below never filter them out themselves.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hash {
+    fields: HashMap<Bytes, Bytes>,
+    /// Expire times of the volatile fields, in Unix time milliseconds.
+    expires: HashMap<Bytes, u64>,
+    /// The volatile fields ordered by expire time, to find the expired ones without a scan.
+    expire_order: BTreeSet<(u64, Bytes)>,
+}
+
+impl Hash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get(&self, field: &[u8]) -> Option<&Bytes> {
+        self.fields.get(field)
+    }
+
+    pub fn contains_key(&self, field: &[u8]) -> bool {
+        self.fields.contains_key(field)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Bytes, Bytes> {
+        self.fields.iter()
+    }
+
+    pub fn keys(&self) -> hash_map::Keys<'_, Bytes, Bytes> {
+        self.fields.keys()
+    }
+
+    pub fn values(&self) -> hash_map::Values<'_, Bytes, Bytes> {
+        self.fields.values()
+    }
+
+    /// Set a field, discarding its expire time as `HSET` does. Returns the previous value.
+    pub fn insert(&mut self, field: Bytes, value: Bytes) -> Option<Bytes> {
+        self.persist(&field);
+        self.fields.insert(field, value)
+    }
+
+    /// Set a field, keeping its expire time as `HINCRBY` does. Returns the previous value.
+    pub fn insert_keep_ttl(&mut self, field: Bytes, value: Bytes) -> Option<Bytes> {
+        self.fields.insert(field, value)
+    }
+
+    pub fn remove(&mut self, field: &[u8]) -> Option<Bytes> {
+        self.persist(field);
+        self.fields.remove(field)
+    }
+
+    /// The expire time of `field` in Unix time milliseconds, if it is volatile.
+    pub fn expire_time(&self, field: &[u8]) -> Option<u64> {
+        self.expires.get(field).copied()
+    }
+
+    /// Make an existing `field` expire at `when`, in Unix time milliseconds.
+    pub fn set_expire_time(&mut self, field: &Bytes, when: u64) {
+        debug_assert!(self.fields.contains_key(field));
+        if let Some(old) = self.expires.insert(field.clone(), when) {
+            self.expire_order.remove(&(old, field.clone()));
+        }
+        self.expire_order.insert((when, field.clone()));
+    }
+
+    /// Remove the expire time of `field`. Returns whether it had one.
+    pub fn persist(&mut self, field: &[u8]) -> bool {
+        match self.expires.remove_entry(field) {
+            Some((field, when)) => self.expire_order.remove(&(when, field)),
+            None => false,
+        }
+    }
+
+    /// Whether any field has an expire time.
+    pub fn has_volatile_fields(&self) -> bool {
+        !self.expires.is_empty()
+    }
+
+    /// Remove the fields whose expire time is not after `now`. Returns how many were removed.
+    pub fn remove_expired(&mut self, now: u64) -> usize {
+        self.remove_expired_up_to(now, usize::MAX)
+    }
+
+    /// Remove at most `limit` of the fields whose expire time is not after `now`, the earliest
+    /// first. Returns how many were removed.
+    pub fn remove_expired_up_to(&mut self, now: u64, limit: usize) -> usize {
+        let mut removed = 0;
+        while removed < limit
+            && let Some((when, _)) = self.expire_order.first()
+            && *when <= now
+        {
+            // The set was just checked to be non-empty
+            let (_, field) = self.expire_order.pop_first().unwrap();
+            self.expires.remove(&field);
+            self.fields.remove(&field);
+            removed += 1;
+        }
+        removed
+    }
+}
+
+impl<const N: usize> From<[(Bytes, Bytes); N]> for Hash {
+    fn from(fields: [(Bytes, Bytes); N]) -> Self {
+        fields.into_iter().collect()
+    }
+}
+
+impl FromIterator<(Bytes, Bytes)> for Hash {
+    fn from_iter<T: IntoIterator<Item = (Bytes, Bytes)>>(iter: T) -> Self {
+        Hash {
+            fields: iter.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The largest size of a string value, the default `proto-max-bulk-len` of Redis.
+pub const STRING_MAX_LEN: usize = 512 * 1024 * 1024;
+
+/// The largest expire time a hash field accepts, in Unix time milliseconds.
+pub const FIELD_EXPIRE_TIME_MAX: u64 = (1 << 48) - 1;
+
+/// An expire time given relative to now or as a Unix time, both in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expiration {
+    In(u64),
+    At(u64),
+}
+
+impl Expiration {
+    /// The expire time in Unix time milliseconds, `None` when it overflows the signed 64 bits
+    /// Redis keeps it in.
+    pub fn unix_time_ms(&self, now: u64) -> Option<u64> {
+        match self {
+            Expiration::In(ms) => now.checked_add(*ms),
+            Expiration::At(ms) => Some(*ms),
+        }
+        .filter(|when| *when <= i64::MAX as u64)
+    }
+}
+
+/// The `NX | XX | GT | LT` condition of the expire commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpireCondition {
+    Nx,
+    Xx,
+    Gt,
+    Lt,
+}
+
+impl ExpireCondition {
+    /// Whether the expire time may change from `current` to `new`. A missing expire time counts
+    /// as an infinite one for `GT` and `LT`.
+    pub fn allows(&self, current: Option<u64>, new: u64) -> bool {
+        match (self, current) {
+            (ExpireCondition::Nx, current) => current.is_none(),
+            (ExpireCondition::Xx, current) => current.is_some(),
+            (ExpireCondition::Gt, current) => current.is_some_and(|current| new > current),
+            (ExpireCondition::Lt, current) => current.is_none_or(|current| new < current),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListEnd {
+    Left,
+    Right,
+}
+
+impl ListEnd {
+    pub fn push(&self, list: &mut List, element: Bytes) {
+        match self {
+            ListEnd::Left => list.push_front(element),
+            ListEnd::Right => list.push_back(element),
+        }
+    }
+
+    pub fn pop(&self, list: &mut List) -> Option<Bytes> {
+        match self {
+            ListEnd::Left => list.pop_front(),
+            ListEnd::Right => list.pop_back(),
+        }
+    }
+}
+
+/// The end of a sorted set that `ZPOPMIN`, `ZPOPMAX` and their blocking variants pop from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZSetEnd {
+    Min,
+    Max,
+}
+
+impl ZSetEnd {
+    pub fn pop(&self, zset: &mut SortedSet) -> Option<(Bytes, f64)> {
+        match self {
+            ZSetEnd::Min => zset.pop_first(),
+            ZSetEnd::Max => zset.pop_last(),
+        }
+    }
+}
+
+/// Keys the active expiry samples at random: the keys given an expire time, or the hashes given
+/// fields with an expire time. Keys which later lost them or were deleted stay until sampled.
+#[derive(Debug, Default)]
+pub struct VolatileKeys {
+    keys: Vec<Key>,
+    positions: HashMap<Key, usize>,
+}
+
+impl VolatileKeys {
+    pub fn insert(&mut self, key: Key) {
+        if let hash_map::Entry::Vacant(entry) = self.positions.entry(key) {
+            self.keys.push(entry.key().clone());
+            entry.insert(self.keys.len() - 1);
+        }
+    }
+
+    pub fn remove(&mut self, key: &[u8]) {
+        let Some(position) = self.positions.remove(key) else {
+            return;
+        };
+        self.keys.swap_remove(position);
+        if let Some(moved) = self.keys.get(position) {
+            self.positions.insert(moved.clone(), position);
+        }
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Up to `amount` distinct keys picked at random.
+    pub fn sample(&self, amount: usize) -> Vec<Key> {
+        self.keys
+            .choose_multiple(&mut rand::rng(), amount)
+            .cloned()
+            .collect()
+    }
+}
+
+impl FromIterator<Key> for VolatileKeys {
+    fn from_iter<T: IntoIterator<Item = Key>>(iter: T) -> Self {
+        let mut keys = VolatileKeys::default();
+        for key in iter {
+            keys.insert(key);
+        }
+        keys
+    }
+}
+
+/// Look up `key`, lazily removing it first if its expire time has passed, which counts in
+/// `stats` as an expired key. The expired fields of a hash are removed as well, along with the
+/// hash if no field is left; as in Redis, that hash is deleted rather than counted as expired,
+/// since the key itself had no expire time.
+pub fn lookup_key<'a>(db: &'a mut Db, stats: &mut Stats, key: &[u8]) -> Option<&'a mut DbItem> {
+    let now = unix_time_ms();
+    if db
+        .get(key)
+        .is_some_and(|(_, expire_time)| expire_time.is_some_and(|t| t <= now))
+    {
+        // The key exists, as it was just checked
+        let (value, _) = db.remove(key).unwrap();
+        stats.expired_keys += 1;
+        tracing::info!(
+            "Remove Key: {}, Type: {}",
+            BytesInStr::from_bytes(key),
+            value.type_name()
+        );
+        return None;
+    }
+    if let Some((Value::Hash(hash), _)) = db.get_mut(key)
+        && hash.remove_expired(now) > 0
+        && hash.is_empty()
+    {
+        db.remove(key);
+        return None;
+    }
+    db.get_mut(key)
+}
+
+/// Remove `key` if it holds an empty collection, after elements have been taken out of it.
+pub fn remove_if_empty(db: &mut Db, key: &[u8]) {
+    if db
+        .get(key)
+        .is_some_and(|(value, _)| value.is_empty_collection())
+    {
+        db.remove(key);
+    }
+}
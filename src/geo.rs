@@ -0,0 +1,444 @@
+//! Geohashes, which index coordinates as sorted set scores the way Redis does.
+//!
+//! A position is encoded as a 52 bits geohash: the longitude and latitude are each scaled to 26
+//! bits, then interleaved, the longitude taking the odd bits. Nearby positions share a prefix of
+//! their hash, so the positions within an area are found by a few score ranges: the cell of the
+//! searched point at a precision matching the searched size, and its eight neighbours.
+
+use crate::zset::ScoreRange;
+
+const LONG_MIN: f64 = -180.0;
+const LONG_MAX: f64 = 180.0;
+/// The latitudes beyond which the Web Mercator projection is not defined.
+const LAT_MIN: f64 = -85.05112878;
+const LAT_MAX: f64 = 85.05112878;
+/// The latitudes of the standard geohash strings.
+const STANDARD_LAT_MIN: f64 = -90.0;
+const STANDARD_LAT_MAX: f64 = 90.0;
+
+/// The number of bits of each coordinate in a hash.
+const STEP_MAX: u8 = 26;
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+/// Half the circumference of the earth in the Web Mercator projection.
+const MERCATOR_MAX: f64 = 20037726.37;
+
+const BASE32_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+const GEOHASH_STRING_LEN: usize = 11;
+
+/// Whether coordinates can be indexed.
+pub fn is_valid(longitude: f64, latitude: f64) -> bool {
+    (LONG_MIN..=LONG_MAX).contains(&longitude) && (LAT_MIN..=LAT_MAX).contains(&latitude)
+}
+
+/// The 52 bits hash of valid coordinates, as stored in the score of a member.
+pub fn encode(longitude: f64, latitude: f64) -> u64 {
+    Hash::encode(longitude, latitude, STEP_MAX, (LAT_MIN, LAT_MAX)).bits
+}
+
+/// The coordinates at the center of the cell of a 52 bits hash.
+pub fn decode(bits: u64) -> (f64, f64) {
+    let area = Hash {
+        bits,
+        step: STEP_MAX,
+    }
+    .area((LAT_MIN, LAT_MAX));
+    let longitude = ((area.longitude.0 + area.longitude.1) / 2.0).clamp(LONG_MIN, LONG_MAX);
+    let latitude = ((area.latitude.0 + area.latitude.1) / 2.0).clamp(LAT_MIN, LAT_MAX);
+    (longitude, latitude)
+}
+
+/// The standard 11 characters geohash of the position stored as a 52 bits hash, as `GEOHASH`
+/// replies. Standard geohashes span latitudes from -90 to 90, so the position is encoded again.
+pub fn to_geohash_string(bits: u64) -> String {
+    let (longitude, latitude) = decode(bits);
+    let bits = Hash::encode(
+        longitude,
+        latitude,
+        STEP_MAX,
+        (STANDARD_LAT_MIN, STANDARD_LAT_MAX),
+    )
+    .bits;
+    (0..GEOHASH_STRING_LEN)
+        .map(|i| {
+            // The 52 bits fill 10 characters and 2 bits, the last character is padded with 0
+            let index = if i == GEOHASH_STRING_LEN - 1 {
+                0
+            } else {
+                (bits >> (52 - (i + 1) * 5)) & 0x1F
+            };
+            BASE32_ALPHABET[index as usize] as char
+        })
+        .collect()
+}
+
+/// The great circle distance in meters between two positions, with the haversine formula.
+pub fn distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (longitude1, latitude1) = from;
+    let (longitude2, latitude2) = to;
+    let v = ((longitude2.to_radians() - longitude1.to_radians()) / 2.0).sin();
+    // Positions on the same meridian only differ by their latitude
+    if v == 0.0 {
+        return latitude_distance(latitude1, latitude2);
+    }
+    let latitude1 = latitude1.to_radians();
+    let latitude2 = latitude2.to_radians();
+    let u = ((latitude2 - latitude1) / 2.0).sin();
+    let a = u * u + latitude1.cos() * latitude2.cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+fn latitude_distance(latitude1: f64, latitude2: f64) -> f64 {
+    EARTH_RADIUS_IN_METERS * (latitude2.to_radians() - latitude1.to_radians()).abs()
+}
+
+/// The number of meters in a distance unit: `m`, `km`, `ft` or `mi`, case insensitive.
+pub fn unit_in_meters(unit: &[u8]) -> Option<f64> {
+    match unit.to_ascii_lowercase().as_slice() {
+        b"m" => Some(1.0),
+        b"km" => Some(1000.0),
+        b"ft" => Some(0.3048),
+        b"mi" => Some(1609.34),
+        _ => None,
+    }
+}
+
+/// An area searched around a point, in meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Radius(f64),
+    Box { width: f64, height: f64 },
+}
+
+impl Shape {
+    /// The distance from `center` to `point` if the point is within the shape centered there.
+    pub fn distance_within(&self, center: (f64, f64), point: (f64, f64)) -> Option<f64> {
+        match *self {
+            Shape::Radius(radius) => Some(distance(center, point)).filter(|&d| d <= radius),
+            Shape::Box { width, height } => {
+                // The distance along the latitude is the cheaper to check
+                if latitude_distance(point.1, center.1) > height / 2.0
+                    || distance((point.0, point.1), (center.0, point.1)) > width / 2.0
+                {
+                    return None;
+                }
+                Some(distance(center, point))
+            }
+        }
+    }
+
+    /// The bounds of the shape centered at `center`, as the minimum and maximum longitudes and
+    /// latitudes.
+    fn bounding_box(&self, center: (f64, f64)) -> Area {
+        let (longitude, latitude) = center;
+        let (width, height) = match *self {
+            Shape::Radius(radius) => (radius, radius),
+            Shape::Box { width, height } => (width / 2.0, height / 2.0),
+        };
+        let latitude_delta = (height / EARTH_RADIUS_IN_METERS).to_degrees();
+        let longitude_delta = |latitude: f64| {
+            (width / EARTH_RADIUS_IN_METERS / latitude.to_radians().cos()).to_degrees()
+        };
+        // The side nearer to the pole is the wider in degrees
+        let longitude_delta = if latitude < 0.0 {
+            longitude_delta(latitude - latitude_delta)
+        } else {
+            longitude_delta(latitude + latitude_delta)
+        };
+        Area {
+            longitude: (longitude - longitude_delta, longitude + longitude_delta),
+            latitude: (latitude - latitude_delta, latitude + latitude_delta),
+        }
+    }
+
+    /// The distance from the center to the farthest point of the shape.
+    fn radius(&self) -> f64 {
+        match *self {
+            Shape::Radius(radius) => radius,
+            Shape::Box { width, height } => (width / 2.0).hypot(height / 2.0),
+        }
+    }
+}
+
+/// The score ranges holding every position within `shape` centered at `center`, along with
+/// positions outside of it. They are the cell of the center and the cells around it, at the
+/// finest precision where they still cover the shape.
+pub fn search_ranges(center: (f64, f64), shape: &Shape) -> Vec<ScoreRange> {
+    let lat_range = (LAT_MIN, LAT_MAX);
+    let (longitude, latitude) = center;
+    let bounds = shape.bounding_box(center);
+
+    let mut step = estimate_step(shape.radius(), latitude);
+    let mut hash = Hash::encode(longitude, latitude, step, lat_range);
+    // The estimate may be too fine when the shape is close to the border of the cell
+    let too_fine = hash.neighbour(0, 1).area(lat_range).latitude.1 < bounds.latitude.1
+        || hash.neighbour(0, -1).area(lat_range).latitude.0 > bounds.latitude.0
+        || hash.neighbour(1, 0).area(lat_range).longitude.1 < bounds.longitude.1
+        || hash.neighbour(-1, 0).area(lat_range).longitude.0 > bounds.longitude.0;
+    if step > 1 && too_fine {
+        step -= 1;
+        hash = Hash::encode(longitude, latitude, step, lat_range);
+    }
+
+    // Skip the neighbours on the sides the shape does not reach
+    let area = hash.area(lat_range);
+    let (mut west, mut east, mut south, mut north) = (true, true, true, true);
+    if step >= 2 {
+        south = area.latitude.0 >= bounds.latitude.0;
+        north = area.latitude.1 <= bounds.latitude.1;
+        west = area.longitude.0 >= bounds.longitude.0;
+        east = area.longitude.1 <= bounds.longitude.1;
+    }
+    let cells = [
+        (0, 0, true),
+        (0, 1, north),
+        (0, -1, south),
+        (1, 0, east),
+        (-1, 0, west),
+        (1, 1, north && east),
+        (-1, 1, north && west),
+        (1, -1, south && east),
+        (-1, -1, south && west),
+    ];
+
+    let mut ranges: Vec<ScoreRange> = vec![];
+    let shift = 2 * (STEP_MAX - step) as u32;
+    for (x, y, _) in cells.into_iter().filter(|(_, _, searched)| *searched) {
+        let cell = hash.neighbour(x, y);
+        let range = ScoreRange {
+            min: (cell.bits << shift) as f64,
+            max: ((cell.bits + 1) << shift) as f64,
+            min_exclusive: false,
+            max_exclusive: true,
+        };
+        // Neighbours wrap around when the cells are large, then some are the same
+        if ranges.last() != Some(&range) {
+            ranges.push(range);
+        }
+    }
+    ranges
+}
+
+/// The precision of the cells to search for a radius, which they should be larger than.
+fn estimate_step(radius: f64, latitude: f64) -> u8 {
+    if radius == 0.0 {
+        return STEP_MAX;
+    }
+    let mut step: i32 = 1;
+    let mut range = radius;
+    while range < MERCATOR_MAX {
+        range *= 2.0;
+        step += 1;
+    }
+    // Make sure the radius is included in most cases
+    step -= 2;
+    // The cells get narrower towards the poles
+    if !(-66.0..=66.0).contains(&latitude) {
+        step -= 1;
+        if !(-80.0..=80.0).contains(&latitude) {
+            step -= 1;
+        }
+    }
+    step.clamp(1, STEP_MAX as i32) as u8
+}
+
+/// Longitudes and latitudes from a minimum to a maximum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Area {
+    longitude: (f64, f64),
+    latitude: (f64, f64),
+}
+
+/// A geohash of `step` bits per coordinate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hash {
+    bits: u64,
+    step: u8,
+}
+
+impl Hash {
+    fn encode(longitude: f64, latitude: f64, step: u8, lat_range: (f64, f64)) -> Self {
+        let scale = (1u64 << step) as f64;
+        let latitude = (latitude - lat_range.0) / (lat_range.1 - lat_range.0) * scale;
+        let longitude = (longitude - LONG_MIN) / (LONG_MAX - LONG_MIN) * scale;
+        Self {
+            bits: interleave(latitude as u32, longitude as u32),
+            step,
+        }
+    }
+
+    fn area(&self, lat_range: (f64, f64)) -> Area {
+        let scale = (1u64 << self.step) as f64;
+        let latitude = squash(self.bits) as f64;
+        let longitude = squash(self.bits >> 1) as f64;
+        let lat_scale = lat_range.1 - lat_range.0;
+        let long_scale = LONG_MAX - LONG_MIN;
+        Area {
+            longitude: (
+                LONG_MIN + longitude / scale * long_scale,
+                LONG_MIN + (longitude + 1.0) / scale * long_scale,
+            ),
+            latitude: (
+                lat_range.0 + latitude / scale * lat_scale,
+                lat_range.0 + (latitude + 1.0) / scale * lat_scale,
+            ),
+        }
+    }
+
+    /// The cell `x` cells to the east and `y` cells to the north, wrapping around.
+    fn neighbour(&self, x: i8, y: i8) -> Self {
+        let bits = Self::move_bits(self.bits, self.step, x, 1);
+        let bits = Self::move_bits(bits, self.step, y, 0);
+        Self {
+            bits,
+            step: self.step,
+        }
+    }
+
+    /// Add `delta` to the coordinate taking the bits from `offset`, the longitude being at the
+    /// odd bits. The carries cross the bits of the other coordinate, which are set for the time
+    /// of the addition.
+    fn move_bits(bits: u64, step: u8, delta: i8, offset: u32) -> u64 {
+        if delta == 0 {
+            return bits;
+        }
+        let mask = 0x5555_5555_5555_5555u64 << offset;
+        let moved = bits & mask;
+        let other = bits & !mask;
+        let filler = !mask >> (64 - step as u32 * 2);
+        let moved = if delta > 0 {
+            moved.wrapping_add(filler + 1)
+        } else {
+            (moved | filler).wrapping_sub(filler + 1)
+        };
+        (moved & (mask >> (64 - step as u32 * 2))) | other
+    }
+}
+
+/// Interleave the bits of two coordinates, `x` taking the even bits.
+fn interleave(x: u32, y: u32) -> u64 {
+    spread(x) | (spread(y) << 1)
+}
+
+/// Spread the bits of a value to the even bits.
+fn spread(value: u32) -> u64 {
+    let mut x = value as u64;
+    x = (x | x << 16) & 0x0000_FFFF_0000_FFFF;
+    x = (x | x << 8) & 0x00FF_00FF_00FF_00FF;
+    x = (x | x << 4) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | x << 2) & 0x3333_3333_3333_3333;
+    (x | x << 1) & 0x5555_5555_5555_5555
+}
+
+/// Gather the even bits of a value, the reverse of `spread`.
+fn squash(value: u64) -> u32 {
+    let mut x = value & 0x5555_5555_5555_5555;
+    x = (x | x >> 1) & 0x3333_3333_3333_3333;
+    x = (x | x >> 2) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | x >> 4) & 0x00FF_00FF_00FF_00FF;
+    x = (x | x >> 8) & 0x0000_FFFF_0000_FFFF;
+    (x | x >> 16) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Hash, Shape, decode, distance, encode, is_valid, search_ranges, to_geohash_string,
+    };
+
+    const PALERMO: (f64, f64) = (13.361389, 38.115556);
+    const CATANIA: (f64, f64) = (15.087269, 37.502669);
+
+    #[test]
+    fn encode_should_match_redis_scores() {
+        assert_eq!(encode(PALERMO.0, PALERMO.1), 3479099956230698);
+        assert_eq!(encode(CATANIA.0, CATANIA.1), 3479447370796909);
+
+        let (longitude, latitude) = decode(3479099956230698);
+        assert!((longitude - 13.361389338970184).abs() < 1e-12);
+        assert!((latitude - 38.1155563954963).abs() < 1e-12);
+
+        assert!(is_valid(-180.0, 85.05112878));
+        assert!(!is_valid(180.1, 0.0));
+        assert!(!is_valid(0.0, -85.06));
+    }
+
+    #[test]
+    fn to_geohash_string_should_match_redis() {
+        assert_eq!(
+            to_geohash_string(encode(PALERMO.0, PALERMO.1)),
+            "sqc8b49rny0"
+        );
+        assert_eq!(
+            to_geohash_string(encode(CATANIA.0, CATANIA.1)),
+            "sqdtr74hyu0"
+        );
+    }
+
+    #[test]
+    fn distance_should_use_haversine() {
+        let distance = distance(
+            decode(encode(PALERMO.0, PALERMO.1)),
+            decode(encode(CATANIA.0, CATANIA.1)),
+        );
+        assert!((distance - 166274.1516).abs() < 1e-4, "{distance}");
+        assert_eq!(super::distance((1.0, 0.0), (1.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn neighbour_should_wrap_around() {
+        let hash = Hash {
+            bits: 0b0000,
+            step: 2,
+        };
+        // West of the first cell is the last one along the longitude, in the odd bits
+        assert_eq!(hash.neighbour(-1, 0).bits, 0b1010);
+        assert_eq!(hash.neighbour(0, -1).bits, 0b0101);
+        assert_eq!(hash.neighbour(1, 1).bits, 0b0011);
+        assert_eq!(hash.neighbour(1, 1).neighbour(-1, -1), hash);
+    }
+
+    #[test]
+    fn search_ranges_should_cover_shape() {
+        let center = (15.0, 37.0);
+        for shape in [
+            Shape::Radius(200_000.0),
+            Shape::Box {
+                width: 400_000.0,
+                height: 400_000.0,
+            },
+            Shape::Radius(10.0),
+        ] {
+            let ranges = search_ranges(center, &shape);
+            assert!(!ranges.is_empty() && ranges.len() <= 9, "{shape:?}");
+            for point in [PALERMO, CATANIA, center] {
+                let point = decode(encode(point.0, point.1));
+                if shape.distance_within(center, point).is_none() {
+                    continue;
+                }
+                let score = encode(point.0, point.1) as f64;
+                assert!(
+                    ranges
+                        .iter()
+                        .any(|range| !range.is_below(score) && !range.is_above(score)),
+                    "{shape:?} {point:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn distance_within_should_check_box_sides() {
+        let shape = Shape::Box {
+            width: 200_000.0,
+            height: 100_000.0,
+        };
+        let center = (15.0, 37.0);
+        assert!(shape.distance_within(center, (15.9, 37.0)).is_some());
+        assert!(shape.distance_within(center, (15.0, 37.9)).is_none());
+        assert!(shape.distance_within(center, (16.2, 37.0)).is_none());
+        assert_eq!(
+            Shape::Radius(1.0).distance_within(center, center),
+            Some(0.0)
+        );
+    }
+}
@@ -0,0 +1,485 @@
+//! HyperLogLog, kept in the string layout of Redis so that the values can be exchanged with it.
+//!
+//! The string starts with a header: the magic `HYLL`, the encoding, three unused bytes, then the
+//! cached cardinality as a little endian `u64`, whose most significant bit marks it as stale. The
+//! 16384 registers of 6 bits follow, either packed (dense), or run length encoded (sparse) while
+//! most of them are still zero. A sparse value is promoted to dense once a register exceeds what
+//! the sparse opcodes can hold, or once it grows past `SPARSE_MAX_BYTES`.
+
+use bytes::Bytes;
+
+const MAGIC: &[u8; 4] = b"HYLL";
+const HEADER_SIZE: usize = 16;
+/// Where the cached cardinality starts in the header.
+const CARD_OFFSET: usize = 8;
+/// The bit of the last cardinality byte telling the cached cardinality is stale.
+const CARD_INVALID: u8 = 0x80;
+
+const ENCODING_DENSE: u8 = 0;
+const ENCODING_SPARSE: u8 = 1;
+
+/// The number of bits of the hash addressing a register.
+const P: u32 = 14;
+/// The number of bits of the hash left to count the run of zeros in.
+const Q: u32 = 64 - P;
+const REGISTERS: usize = 1 << P;
+const REGISTER_BITS: usize = 6;
+const REGISTER_MAX: u8 = (1 << REGISTER_BITS) - 1;
+const DENSE_SIZE: usize = HEADER_SIZE + (REGISTERS * REGISTER_BITS).div_ceil(8);
+
+/// `00xxxxxx`: a run of up to 64 zero registers.
+const SPARSE_ZERO_MAX_LEN: usize = 64;
+/// `01xxxxxx yyyyyyyy`: a run of up to 16384 zero registers.
+const SPARSE_XZERO: u8 = 0x40;
+const SPARSE_XZERO_MAX_LEN: usize = 16384;
+/// `1vvvvvxx`: a run of up to 4 registers set to a value up to 32.
+const SPARSE_VAL: u8 = 0x80;
+const SPARSE_VAL_MAX_VALUE: u8 = 32;
+const SPARSE_VAL_MAX_LEN: usize = 4;
+/// The default of `hll-sparse-max-bytes`, the largest sparse value, header included.
+const SPARSE_MAX_BYTES: usize = 3000;
+
+const HASH_SEED: u64 = 0xadc83b19;
+/// 0.5 / ln(2), the limit of the bias correction for an infinite number of registers.
+const ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The string is not a HyperLogLog.
+    Invalid,
+    /// The header is valid, but the sparse registers are not.
+    Corrupted,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+    /// Whether the registers must stay dense. A sparse HyperLogLog may still be promoted when
+    /// encoded.
+    dense: bool,
+    /// The cached cardinality, as found in the header.
+    card: [u8; 8],
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+            dense: false,
+            card: [0; 8],
+        }
+    }
+}
+
+impl HyperLogLog {
+    /// Decode a HyperLogLog from its string value.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let card = read_header(bytes)?;
+        let data = &bytes[HEADER_SIZE..];
+        if bytes[MAGIC.len()] == ENCODING_DENSE {
+            Ok(Self {
+                registers: (0..REGISTERS)
+                    .map(|index| dense_register(data, index))
+                    .collect(),
+                dense: true,
+                card,
+            })
+        } else {
+            Ok(Self {
+                registers: decode_sparse(data).ok_or(Error::Corrupted)?,
+                dense: false,
+                card,
+            })
+        }
+    }
+
+    /// Encode the HyperLogLog as a string value, sparse if it still can be.
+    pub fn to_bytes(&self) -> Bytes {
+        let sparse = if self.dense {
+            None
+        } else {
+            encode_sparse(&self.registers)
+        };
+        let mut bytes = Vec::with_capacity(DENSE_SIZE);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(if sparse.is_some() {
+            ENCODING_SPARSE
+        } else {
+            ENCODING_DENSE
+        });
+        bytes.extend_from_slice(&[0; 3]);
+        bytes.extend_from_slice(&self.card);
+        match sparse {
+            Some(data) => bytes.extend_from_slice(&data),
+            None => {
+                bytes.resize(DENSE_SIZE, 0);
+                let data = &mut bytes[HEADER_SIZE..];
+                for (index, &value) in self.registers.iter().enumerate() {
+                    set_dense_register(data, index, value);
+                }
+            }
+        }
+        Bytes::from(bytes)
+    }
+
+    /// Add an element, returning whether a register changed, that is whether the estimated
+    /// cardinality may have.
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let (index, count) = pattern(element);
+        if self.registers[index] >= count {
+            return false;
+        }
+        self.registers[index] = count;
+        self.invalidate_cache();
+        true
+    }
+
+    /// Merge the registers of another HyperLogLog in, so that this one estimates the cardinality
+    /// of their union.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (register, &value) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(value);
+        }
+        self.dense |= other.dense;
+        self.invalidate_cache();
+    }
+
+    /// The cached cardinality, unless stale.
+    #[cfg(test)]
+    pub fn cached_count(&self) -> Option<u64> {
+        cached_count(self.card)
+    }
+
+    pub fn invalidate_cache(&mut self) {
+        self.card[7] |= CARD_INVALID;
+    }
+
+    /// Estimate the cardinality, with the estimator of Otmar Ertl in "New cardinality estimation
+    /// algorithms for HyperLogLog sketches", as Redis does.
+    pub fn count(&self) -> u64 {
+        let mut histogram = [0u32; Q as usize + 2];
+        for &value in &self.registers {
+            histogram[value as usize] += 1;
+        }
+
+        let m = REGISTERS as f64;
+        let mut z = m * tau((m - histogram[Q as usize + 1] as f64) / m);
+        for &count in histogram[1..=Q as usize].iter().rev() {
+            z += count as f64;
+            z *= 0.5;
+        }
+        z += m * sigma(histogram[0] as f64 / m);
+        (ALPHA_INF * m * m / z).round() as u64
+    }
+}
+
+/// The cached cardinality of an encoded HyperLogLog, unless stale, read from its header without
+/// decoding the registers.
+pub fn read_cached_count(bytes: &[u8]) -> Result<Option<u64>, Error> {
+    read_header(bytes).map(cached_count)
+}
+
+/// Store a freshly computed cardinality in the header of an encoded HyperLogLog.
+pub fn write_cached_count(bytes: &mut [u8], count: u64) {
+    bytes[CARD_OFFSET..HEADER_SIZE].copy_from_slice(&count.to_le_bytes());
+}
+
+/// Check the header of an encoded HyperLogLog, returning its cached cardinality.
+fn read_header(bytes: &[u8]) -> Result<[u8; 8], Error> {
+    if bytes.len() < HEADER_SIZE || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(Error::Invalid);
+    }
+    match bytes[MAGIC.len()] {
+        ENCODING_DENSE if bytes.len() != DENSE_SIZE => Err(Error::Invalid),
+        ENCODING_DENSE | ENCODING_SPARSE => Ok(bytes[CARD_OFFSET..HEADER_SIZE].try_into().unwrap()),
+        _ => Err(Error::Invalid),
+    }
+}
+
+fn cached_count(card: [u8; 8]) -> Option<u64> {
+    (card[7] & CARD_INVALID == 0).then(|| u64::from_le_bytes(card))
+}
+
+/// The register an element falls in, and the length of the run of zeros ending its hash, plus
+/// one.
+fn pattern(element: &[u8]) -> (usize, u8) {
+    let hash = murmur_hash64a(element, HASH_SEED);
+    let index = hash as usize & (REGISTERS - 1);
+    // The extra bit caps the count to Q + 1
+    let hash = (hash >> P) | (1 << Q);
+    (index, hash.trailing_zeros() as u8 + 1)
+}
+
+/// MurmurHash64A, reading the blocks as little endian whatever the platform, as Redis does.
+fn murmur_hash64a(key: &[u8], seed: u64) -> u64 {
+    const M: u64 = 0xc6a4a7935bd1e995;
+    const R: u32 = 47;
+
+    let mut h = seed ^ (key.len() as u64).wrapping_mul(M);
+    let mut chunks = key.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut k = u64::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h ^= k;
+        h = h.wrapping_mul(M);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, &byte) in tail.iter().enumerate() {
+            h ^= (byte as u64) << (i * 8);
+        }
+        h = h.wrapping_mul(M);
+    }
+    h ^= h >> R;
+    h = h.wrapping_mul(M);
+    h ^= h >> R;
+    h
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let previous = z;
+        z += x * y;
+        y += y;
+        if z == previous {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let previous = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if z == previous {
+            return z / 3.0;
+        }
+    }
+}
+
+/// Registers are packed from the least significant bit, so one may straddle two bytes.
+fn dense_register(data: &[u8], index: usize) -> u8 {
+    let byte = index * REGISTER_BITS / 8;
+    let shift = index * REGISTER_BITS % 8;
+    // The byte after the last register is the terminator of the string in Redis
+    let next = data.get(byte + 1).copied().unwrap_or(0) as u16;
+    let bits = (data[byte] as u16 | next << 8) >> shift;
+    bits as u8 & REGISTER_MAX
+}
+
+fn set_dense_register(data: &mut [u8], index: usize, value: u8) {
+    let byte = index * REGISTER_BITS / 8;
+    let shift = index * REGISTER_BITS % 8;
+    let mask = (REGISTER_MAX as u16) << shift;
+    let bits = (value as u16) << shift;
+    data[byte] = (data[byte] & !mask as u8) | bits as u8;
+    if let Some(next) = data.get_mut(byte + 1) {
+        *next = (*next & !(mask >> 8) as u8) | (bits >> 8) as u8;
+    }
+}
+
+/// Decode sparse registers, which must cover all the registers exactly.
+fn decode_sparse(data: &[u8]) -> Option<Vec<u8>> {
+    let mut registers = Vec::with_capacity(REGISTERS);
+    let mut opcodes = data.iter();
+    while let Some(&opcode) = opcodes.next() {
+        let (value, len) = if opcode & SPARSE_VAL != 0 {
+            ((opcode >> 2 & 0x1F) + 1, (opcode & 0x03) as usize + 1)
+        } else if opcode & SPARSE_XZERO != 0 {
+            let low = *opcodes.next()?;
+            (0, (((opcode & 0x3F) as usize) << 8 | low as usize) + 1)
+        } else {
+            (0, (opcode & 0x3F) as usize + 1)
+        };
+        if registers.len() + len > REGISTERS {
+            return None;
+        }
+        registers.resize(registers.len() + len, value);
+    }
+    (registers.len() == REGISTERS).then_some(registers)
+}
+
+/// Encode registers as sparse, unless one is too large for it or the result would exceed
+/// `SPARSE_MAX_BYTES`.
+fn encode_sparse(registers: &[u8]) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut index = 0;
+    while index < registers.len() {
+        let value = registers[index];
+        let run = registers[index..]
+            .iter()
+            .take_while(|&&register| register == value)
+            .count();
+        index += run;
+
+        let mut left = run;
+        while left > 0 {
+            let len = if value != 0 {
+                if value > SPARSE_VAL_MAX_VALUE {
+                    return None;
+                }
+                let len = left.min(SPARSE_VAL_MAX_LEN);
+                data.push(SPARSE_VAL | (value - 1) << 2 | (len - 1) as u8);
+                len
+            } else if left > SPARSE_ZERO_MAX_LEN {
+                let len = left.min(SPARSE_XZERO_MAX_LEN);
+                data.push(SPARSE_XZERO | ((len - 1) >> 8) as u8);
+                data.push((len - 1) as u8);
+                len
+            } else {
+                data.push((left - 1) as u8);
+                left
+            };
+            left -= len;
+        }
+        if HEADER_SIZE + data.len() > SPARSE_MAX_BYTES {
+            return None;
+        }
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DENSE_SIZE, Error, HEADER_SIZE, HyperLogLog, REGISTERS, dense_register, murmur_hash64a,
+        read_cached_count, set_dense_register, write_cached_count,
+    };
+
+    #[test]
+    fn new_hyperloglog_should_encode_as_single_xzero() {
+        let hll = HyperLogLog::default();
+        let bytes = hll.to_bytes();
+        assert_eq!(
+            bytes.as_ref(),
+            b"HYLL\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f\xff"
+        );
+        assert_eq!(HyperLogLog::from_bytes(&bytes), Ok(hll));
+        assert_eq!(HyperLogLog::default().cached_count(), Some(0));
+        assert_eq!(HyperLogLog::default().count(), 0);
+    }
+
+    #[test]
+    fn murmur_hash64a_should_hash_every_byte() {
+        assert_eq!(murmur_hash64a(b"", 0), 0);
+        // Every tail length, and a full block
+        let hashes: Vec<_> = (0..=9)
+            .map(|len| murmur_hash64a(&b"abcdefghi"[..len], 0xadc83b19))
+            .collect();
+        for (i, hash) in hashes.iter().enumerate() {
+            assert!(!hashes[..i].contains(hash), "{i}");
+        }
+    }
+
+    #[test]
+    fn dense_registers_should_round_trip() {
+        let mut data = vec![0; DENSE_SIZE - HEADER_SIZE];
+        for index in 0..REGISTERS {
+            set_dense_register(&mut data, index, (index % 64) as u8);
+        }
+        for index in 0..REGISTERS {
+            assert_eq!(dense_register(&data, index), (index % 64) as u8, "{index}");
+        }
+        set_dense_register(&mut data, 1, 0);
+        assert_eq!(&data[..3], &[0b0000_0000, 0b0010_0000, 0b0000_1100]);
+    }
+
+    #[test]
+    fn add_should_update_registers_and_cache() {
+        let mut hll = HyperLogLog::default();
+        assert!(hll.add(b"a"));
+        assert!(!hll.add(b"a"));
+        assert_eq!(hll.cached_count(), None);
+        assert_eq!(hll.count(), 1);
+
+        let mut bytes = hll.to_bytes().to_vec();
+        // A single register set takes a VAL opcode between two zero runs
+        assert_eq!(bytes[4], 1);
+        assert!(bytes.len() <= HEADER_SIZE + 5);
+        write_cached_count(&mut bytes, 1);
+        assert_eq!(
+            HyperLogLog::from_bytes(&bytes).unwrap().cached_count(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn count_should_be_within_standard_error() {
+        let mut hll = HyperLogLog::default();
+        let mut added = 0;
+        for n in [10, 100, 1000, 100_000] {
+            for i in added..n {
+                hll.add(format!("element:{i}").as_bytes());
+            }
+            added = n;
+            let count = hll.count() as f64;
+            let error = (count - n as f64).abs() / n as f64;
+            assert!(error < 0.03, "{n}: {count}");
+        }
+
+        // Too many registers for the sparse encoding by then
+        let bytes = hll.to_bytes();
+        assert_eq!(bytes.len(), DENSE_SIZE);
+        assert_eq!(bytes[4], 0);
+        let decoded = HyperLogLog::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.count(), hll.count());
+    }
+
+    #[test]
+    fn merge_should_take_register_maximums() {
+        let mut a = HyperLogLog::default();
+        let mut b = HyperLogLog::default();
+        for i in 0..100 {
+            a.add(format!("a{i}").as_bytes());
+            b.add(format!("b{i}").as_bytes());
+        }
+        a.merge(&b);
+        let count = a.count() as f64;
+        assert!((count - 200.0).abs() < 6.0, "{count}");
+        assert_eq!(a.cached_count(), None);
+    }
+
+    #[test]
+    fn from_bytes_should_reject_invalid_values() {
+        for bytes in [
+            &b"HYLL"[..],
+            b"XYLL\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f\xff",
+            b"HYLL\x02\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f\xff",
+            b"HYLL\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f\xff",
+        ] {
+            assert_eq!(
+                HyperLogLog::from_bytes(bytes),
+                Err(Error::Invalid),
+                "{bytes:?}"
+            );
+            assert_eq!(read_cached_count(bytes), Err(Error::Invalid), "{bytes:?}");
+        }
+        for bytes in [
+            // Short of a register, then past the last one, then a truncated XZERO
+            &b"HYLL\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f\xfe"[..],
+            b"HYLL\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f\xff\x00",
+            b"HYLL\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x7f",
+        ] {
+            assert_eq!(
+                HyperLogLog::from_bytes(bytes),
+                Err(Error::Corrupted),
+                "{bytes:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,442 @@
+//! Listpacks, the compact encoding Redis stores small collections and stream nodes in.
+//!
+//! A listpack is a single buffer: a header holding its total size and element count, the
+//! elements, then a terminator byte. Every element is its encoding and data followed by their
+//! length written backwards, which lets the elements be walked in both directions. Strings
+//! holding a canonical integer are stored as integers, the way Redis does.
+
+/// The total size as a `u32`, then the element count as a `u16`.
+const HEADER_SIZE: usize = 6;
+const TERMINATOR: u8 = 0xFF;
+/// The element count of the header saturates there, after which elements must be counted.
+const COUNT_UNKNOWN: u16 = u16::MAX;
+
+const ENC_INT16: u8 = 0xF1;
+const ENC_INT24: u8 = 0xF2;
+const ENC_INT32: u8 = 0xF3;
+const ENC_INT64: u8 = 0xF4;
+const ENC_STR32: u8 = 0xF0;
+
+/// An element read from a listpack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element<'a> {
+    Int(i64),
+    Str(&'a [u8]),
+}
+
+impl Element<'_> {
+    /// The element as a string, integers being formatted in decimal.
+    pub fn to_vec(self) -> Vec<u8> {
+        match self {
+            Element::Int(value) => value.to_string().into_bytes(),
+            Element::Str(string) => string.to_vec(),
+        }
+    }
+
+    /// The element as an integer, if it is one.
+    pub fn as_int(self) -> Option<i64> {
+        match self {
+            Element::Int(value) => Some(value),
+            Element::Str(_) => None,
+        }
+    }
+
+    /// Whether the element equals `string`, as it would once formatted.
+    pub fn eq_bytes(self, string: &[u8]) -> bool {
+        match self {
+            Element::Int(value) => parse_canonical_int(string) == Some(value),
+            Element::Str(element) => element == string,
+        }
+    }
+}
+
+/// Parse `string` if it is the canonical decimal form of an integer, so that formatting the
+/// integer back gives the same string.
+fn parse_canonical_int(string: &[u8]) -> Option<i64> {
+    let digits = string.strip_prefix(b"-").unwrap_or(string);
+    // No sign but a leading minus, and no leading zero, nor "-0"
+    if digits.is_empty()
+        || digits.len() > 19
+        || !digits.iter().all(u8::is_ascii_digit)
+        || (digits[0] == b'0' && string.len() > 1)
+    {
+        return None;
+    }
+    str::from_utf8(string).ok()?.parse().ok()
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Listpack {
+    buf: Vec<u8>,
+}
+
+impl Listpack {
+    pub fn new() -> Self {
+        let mut buf = Vec::with_capacity(HEADER_SIZE + 1);
+        buf.extend_from_slice(&((HEADER_SIZE + 1) as u32).to_le_bytes());
+        buf.extend_from_slice(&0u16.to_le_bytes());
+        buf.push(TERMINATOR);
+        Listpack { buf }
+    }
+
+    /// Take a serialized listpack, checking that every element can be read. Returns `None` if
+    /// it is corrupted.
+    pub fn from_vec(buf: Vec<u8>) -> Option<Self> {
+        if buf.len() <= HEADER_SIZE
+            || u32::from_le_bytes(buf[..4].try_into().unwrap()) as usize != buf.len()
+            || buf.last() != Some(&TERMINATOR)
+        {
+            return None;
+        }
+        let listpack = Listpack { buf };
+
+        let mut count = 0;
+        let mut position = HEADER_SIZE;
+        while listpack.buf[position] != TERMINATOR {
+            let size = listpack.encoded_size(position)?;
+            let mut backlen = Vec::with_capacity(5);
+            push_backlen(&mut backlen, size);
+            let backlen_start = position + size;
+            let end = backlen_start + backlen.len();
+            if listpack.buf.get(backlen_start..end)? != backlen.as_slice() {
+                return None;
+            }
+            position = end;
+            count += 1;
+            if position >= listpack.buf.len() {
+                return None;
+            }
+        }
+        let header_count = listpack.header_count();
+        if position != listpack.buf.len() - 1
+            || (header_count != COUNT_UNKNOWN && header_count as usize != count)
+        {
+            return None;
+        }
+        Some(listpack)
+    }
+
+    /// The serialized listpack.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// The size of the serialized listpack in bytes.
+    pub fn size(&self) -> usize {
+        self.buf.len()
+    }
+
+    fn header_count(&self) -> u16 {
+        u16::from_le_bytes(self.buf[4..6].try_into().unwrap())
+    }
+
+    fn update_header(&mut self, count_delta: isize) {
+        let size = self.buf.len() as u32;
+        self.buf[..4].copy_from_slice(&size.to_le_bytes());
+        let count = self.header_count();
+        if count != COUNT_UNKNOWN {
+            let count = (count as isize + count_delta).clamp(0, COUNT_UNKNOWN as isize) as u16;
+            self.buf[4..6].copy_from_slice(&count.to_le_bytes());
+        }
+    }
+
+    /// The position of the first element, which is the terminator when the listpack is empty.
+    pub fn first(&self) -> usize {
+        HEADER_SIZE
+    }
+
+    /// The position past the last element, where elements are appended.
+    pub fn end(&self) -> usize {
+        self.buf.len() - 1
+    }
+
+    /// Read the element at `position`. Returns it with the position of the next element, or
+    /// `None` at the end.
+    pub fn get(&self, position: usize) -> Option<(Element<'_>, usize)> {
+        let size = self.encoded_size(position)?;
+        let encoding = self.buf[position];
+        let data = &self.buf[position..position + size];
+        let element = if encoding & 0x80 == 0 {
+            Element::Int(encoding as i64)
+        } else if encoding & 0xC0 == 0x80 {
+            Element::Str(&data[1..])
+        } else if encoding & 0xE0 == 0xC0 {
+            let value = ((encoding as i64 & 0x1F) << 8) | data[1] as i64;
+            Element::Int(sign_extend(value, 13))
+        } else if encoding & 0xF0 == 0xE0 {
+            Element::Str(&data[2..])
+        } else {
+            match encoding {
+                ENC_STR32 => Element::Str(&data[5..]),
+                _ => {
+                    let mut bytes = [0; 8];
+                    bytes[..size - 1].copy_from_slice(&data[1..]);
+                    Element::Int(sign_extend(
+                        i64::from_le_bytes(bytes),
+                        (size as u32 - 1) * 8,
+                    ))
+                }
+            }
+        };
+        Some((element, position + size + backlen_size(size)))
+    }
+
+    /// The position of the element after the one at `position`, without decoding it.
+    pub fn skip(&self, position: usize) -> Option<usize> {
+        let size = self.encoded_size(position)?;
+        Some(position + size + backlen_size(size))
+    }
+
+    /// The position of the element before `position`, `None` at the first element.
+    pub fn prev(&self, position: usize) -> Option<usize> {
+        if position <= HEADER_SIZE {
+            return None;
+        }
+        let mut size = 0;
+        let mut shift = 0;
+        let mut backlen_start = position;
+        loop {
+            backlen_start -= 1;
+            let byte = self.buf[backlen_start];
+            size |= ((byte & 0x7F) as usize) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        Some(backlen_start - size)
+    }
+
+    /// The size of the encoding and data of the element at `position`, `None` at the end or if
+    /// the element runs past it.
+    fn encoded_size(&self, position: usize) -> Option<usize> {
+        let encoding = *self.buf.get(position)?;
+        let byte = |offset: usize| self.buf.get(position + offset).map(|b| *b as usize);
+        let size = if encoding & 0x80 == 0 {
+            1
+        } else if encoding & 0xC0 == 0x80 {
+            1 + (encoding & 0x3F) as usize
+        } else if encoding & 0xE0 == 0xC0 {
+            2
+        } else if encoding & 0xF0 == 0xE0 {
+            2 + (((encoding & 0x0F) as usize) << 8 | byte(1)?)
+        } else {
+            match encoding {
+                ENC_INT16 => 3,
+                ENC_INT24 => 4,
+                ENC_INT32 => 5,
+                ENC_INT64 => 9,
+                ENC_STR32 => {
+                    let length = self.buf.get(position + 1..position + 5)?;
+                    5 + u32::from_le_bytes(length.try_into().unwrap()) as usize
+                }
+                _ => return None,
+            }
+        };
+        // The terminator always follows the element
+        (position + size < self.buf.len()).then_some(size)
+    }
+
+    /// Append a string, stored as an integer if it is the canonical form of one.
+    pub fn push(&mut self, string: &[u8]) {
+        self.buf.pop();
+        match parse_canonical_int(string) {
+            Some(value) => push_int(&mut self.buf, value),
+            None => push_str(&mut self.buf, string),
+        }
+        self.buf.push(TERMINATOR);
+        self.update_header(1);
+    }
+
+    /// Append an integer.
+    pub fn push_int(&mut self, value: i64) {
+        self.buf.pop();
+        push_int(&mut self.buf, value);
+        self.buf.push(TERMINATOR);
+        self.update_header(1);
+    }
+
+    /// Replace the element at `position` by an integer. The elements after it may move.
+    pub fn replace_int(&mut self, position: usize, value: i64) {
+        let next = self.skip(position).expect("no element to replace");
+        let mut element = Vec::with_capacity(10);
+        push_int(&mut element, value);
+        self.buf.splice(position..next, element);
+        self.update_header(0);
+    }
+}
+
+impl Default for Listpack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Listpack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        let mut position = self.first();
+        while let Some((element, next)) = self.get(position) {
+            list.entry(&element);
+            position = next;
+        }
+        list.finish()
+    }
+}
+
+fn sign_extend(value: i64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    (value << shift) >> shift
+}
+
+fn backlen_size(size: usize) -> usize {
+    if size < 128 {
+        1
+    } else if size < 16383 {
+        2
+    } else if size < 2097151 {
+        3
+    } else if size < 268435455 {
+        4
+    } else {
+        5
+    }
+}
+
+/// Append the size of an element written backwards: the last byte holds the lowest 7 bits, and
+/// every byte but the first one has its high bit set.
+fn push_backlen(buf: &mut Vec<u8>, size: usize) {
+    let len = backlen_size(size);
+    for i in 0..len {
+        let bits = ((size >> (7 * (len - 1 - i))) & 0x7F) as u8;
+        buf.push(if i == 0 { bits } else { bits | 0x80 });
+    }
+}
+
+/// Append a string element.
+fn push_str(buf: &mut Vec<u8>, string: &[u8]) {
+    let start = buf.len();
+    let len = string.len();
+    if len < 64 {
+        buf.push(0x80 | len as u8);
+    } else if len < 4096 {
+        buf.push(0xE0 | (len >> 8) as u8);
+        buf.push(len as u8);
+    } else {
+        buf.push(ENC_STR32);
+        buf.extend_from_slice(&(len as u32).to_le_bytes());
+    }
+    buf.extend_from_slice(string);
+    push_backlen(buf, buf.len() - start);
+}
+
+/// Append an integer element in the smallest encoding holding it.
+fn push_int(buf: &mut Vec<u8>, value: i64) {
+    let start = buf.len();
+    match value {
+        0..=127 => buf.push(value as u8),
+        -4096..=4095 => {
+            let value = value as u64 & 0x1FFF;
+            buf.extend_from_slice(&[0xC0 | (value >> 8) as u8, value as u8]);
+        }
+        _ => {
+            let (encoding, size) = match value {
+                -32768..=32767 => (ENC_INT16, 2),
+                -8388608..=8388607 => (ENC_INT24, 3),
+                -2147483648..=2147483647 => (ENC_INT32, 4),
+                _ => (ENC_INT64, 8),
+            };
+            buf.push(encoding);
+            buf.extend_from_slice(&value.to_le_bytes()[..size]);
+        }
+    }
+    push_backlen(buf, buf.len() - start);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Element, Listpack};
+
+    #[test]
+    fn listpack_should_read_elements_back_in_both_directions() {
+        let long = vec![b'x'; 5000];
+        let medium = vec![b'y'; 300];
+        let strings: [&[u8]; 12] = [
+            b"",
+            b"a",
+            b"007",
+            b"-0",
+            b"12",
+            b"-100",
+            b"30000",
+            b"-8000000",
+            b"2147483647",
+            b"-9223372036854775808",
+            &medium,
+            &long,
+        ];
+        let mut listpack = Listpack::new();
+        for string in strings {
+            listpack.push(string);
+        }
+        listpack.push_int(i64::MAX);
+
+        let mut positions = Vec::new();
+        let mut position = listpack.first();
+        while let Some((_, next)) = listpack.get(position) {
+            positions.push(position);
+            position = next;
+        }
+        assert_eq!(position, listpack.end());
+        assert_eq!(positions.len(), strings.len() + 1);
+        for (string, &position) in strings.iter().zip(&positions) {
+            let (element, _) = listpack.get(position).unwrap();
+            assert_eq!(element.to_vec(), *string);
+            assert!(element.eq_bytes(string));
+        }
+        assert_eq!(listpack.get(positions[1]).unwrap().0, Element::Str(b"a"));
+        assert_eq!(listpack.get(positions[4]).unwrap().0, Element::Int(12));
+        assert_eq!(listpack.get(positions[2]).unwrap().0, Element::Str(b"007"));
+
+        let mut backwards = Vec::new();
+        let mut position = listpack.end();
+        while let Some(prev) = listpack.prev(position) {
+            backwards.push(prev);
+            position = prev;
+        }
+        backwards.reverse();
+        assert_eq!(backwards, positions);
+
+        let parsed = Listpack::from_vec(listpack.as_bytes().to_vec()).expect("valid listpack");
+        assert_eq!(parsed, listpack);
+    }
+
+    #[test]
+    fn replace_int_should_resize_the_element() {
+        let mut listpack = Listpack::new();
+        listpack.push_int(127);
+        listpack.push(b"after");
+        listpack.replace_int(listpack.first(), 128);
+        let (element, next) = listpack.get(listpack.first()).unwrap();
+        assert_eq!(element, Element::Int(128));
+        assert_eq!(listpack.get(next).unwrap().0, Element::Str(b"after"));
+        assert!(Listpack::from_vec(listpack.as_bytes().to_vec()).is_some());
+    }
+
+    #[test]
+    fn from_vec_should_reject_corrupted_listpacks() {
+        let mut listpack = Listpack::new();
+        listpack.push(b"hello");
+        let bytes = listpack.as_bytes().to_vec();
+
+        let mut truncated = bytes[..bytes.len() - 1].to_vec();
+        truncated[0] -= 1;
+        assert!(Listpack::from_vec(truncated).is_none());
+        let mut wrong_backlen = bytes.clone();
+        wrong_backlen[bytes.len() - 2] = 1;
+        assert!(Listpack::from_vec(wrong_backlen).is_none());
+        let mut wrong_count = bytes;
+        wrong_count[4] = 2;
+        assert!(Listpack::from_vec(wrong_count).is_none());
+    }
+}
@@ -5,12 +5,22 @@ use std::{fs, net::SocketAddr, path::PathBuf, str::FromStr, sync::Arc};
 use clap::Parser;
 use tokio::{net::TcpListener, sync::Mutex};
 
-use crate::server::{Connection, Server, handle_connection};
+use crate::server::{
+    Connection, DEFAULT_HZ, MAX_HZ, MIN_HZ, Server, handle_connection, server_cron,
+};
 
+mod blocking;
 mod command;
+mod db;
+mod geo;
+mod hyperloglog;
+mod listpack;
+mod rdb;
 mod resp;
 pub mod server;
+mod stream;
 mod utils;
+mod zset;
 
 #[derive(Debug, Parser)]
 #[command(version, about, long_about=None)]
@@ -26,6 +36,10 @@ struct Args {
 
     #[arg(short, long, default_value_t = 6379)]
     port: u16,
+
+    /// How many times per second background jobs such as the active expiry run
+    #[arg(long, default_value_t = DEFAULT_HZ)]
+    hz: u64,
 }
 
 #[tokio::main]
@@ -53,7 +67,18 @@ async fn main() {
     rdb_filename.push(&args.dbfilename);
 
     // init server
-    let server = Arc::new(Mutex::new(Server::new(server_addr, rdb_filename)));
+    let mut server = Server::new(server_addr, rdb_filename);
+    server.hz = args.hz.clamp(MIN_HZ, MAX_HZ);
+    // A file this server cannot read must not keep it from starting, so it starts empty
+    if let Err(err) = server.load_rdb() {
+        tracing::error!(
+            "Failed to load {}, starting with an empty keyspace: {}",
+            server.rdb_file.display(),
+            err
+        );
+    }
+    let server = Arc::new(Mutex::new(server));
+    tokio::spawn(server_cron(server.clone()));
     loop {
         match listener.accept().await {
             Ok((stream, addr)) => {
@@ -1,67 +1,988 @@
-use crate::utils::{ascii_to_number, number_to_ascii};
+//! Reading and writing RDB files, the snapshot format of Redis.
+//!
+//! Values are written in the plain encodings which every Redis version loads, except streams
+//! which only have a listpack encoding, the one they are kept in. Files are checksummed with
+//! CRC-64/Jones like Redis does, and a zero checksum is never verified.
 
-const VERSION_LENGTH: usize = 4;
+use std::collections::BTreeMap;
 
-struct RdbFile {
-    rdb_version: u8,
-    redis_version: String,
+use bytes::{BufMut, Bytes, BytesMut};
+use thiserror::Error;
+
+use crate::{
+    db::{Db, Hash, List, Set, Value},
+    listpack::Listpack,
+    server::REDIS_VERSION,
+    stream::{Consumer, ConsumerGroup, PendingEntry, Stream, StreamId},
+    utils::unix_time_ms,
+    zset::SortedSet,
+};
+
+const MAGIC: &[u8] = b"REDIS";
+const RDB_VERSION: u32 = 12;
+
+// Value types
+const TYPE_STRING: u8 = 0;
+const TYPE_LIST: u8 = 1;
+const TYPE_SET: u8 = 2;
+const TYPE_HASH: u8 = 4;
+/// A sorted set with binary scores.
+const TYPE_ZSET_2: u8 = 5;
+/// A stream without the first ID, max deleted ID and entries added.
+const TYPE_STREAM_LISTPACKS: u8 = 15;
+const TYPE_STREAM_LISTPACKS_2: u8 = 19;
+/// A stream whose consumers have an active time.
+const TYPE_STREAM_LISTPACKS_3: u8 = 21;
+/// A set of integers in an intset.
+const TYPE_SET_INTSET: u8 = 11;
+/// A hash in a listpack of alternating fields and values.
+const TYPE_HASH_LISTPACK: u8 = 16;
+/// A sorted set in a listpack of alternating members and scores.
+const TYPE_ZSET_LISTPACK: u8 = 17;
+/// A list of nodes which are either a listpack or a single plain element.
+const TYPE_LIST_QUICKLIST_2: u8 = 18;
+const TYPE_SET_LISTPACK: u8 = 20;
+/// A hash whose fields may have an expire time.
+const TYPE_HASH_METADATA: u8 = 24;
+/// A hash in a listpack of field, value and expire time triplets.
+const TYPE_HASH_LISTPACK_EX: u8 = 25;
+
+// Quicklist node containers
+const QUICKLIST_NODE_PLAIN: u64 = 1;
+const QUICKLIST_NODE_PACKED: u64 = 2;
+
+// Opcodes
+const OPCODE_SLOT_INFO: u8 = 0xF4;
+const OPCODE_FUNCTION2: u8 = 0xF5;
+const OPCODE_IDLE: u8 = 0xF8;
+const OPCODE_FREQ: u8 = 0xF9;
+const OPCODE_AUX: u8 = 0xFA;
+const OPCODE_RESIZEDB: u8 = 0xFB;
+const OPCODE_EXPIRETIME_MS: u8 = 0xFC;
+const OPCODE_EXPIRETIME: u8 = 0xFD;
+const OPCODE_SELECTDB: u8 = 0xFE;
+const OPCODE_EOF: u8 = 0xFF;
+
+// Special string encodings
+const ENC_INT8: u8 = 0;
+const ENC_INT16: u8 = 1;
+const ENC_INT32: u8 = 2;
+const ENC_LZF: u8 = 3;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Not an RDB file")]
+    InvalidMagic,
+
+    #[error("Unsupported RDB version: {}", .0)]
+    UnsupportedVersion(u32),
+
+    #[error("Unexpected end of the RDB file")]
+    UnexpectedEof,
+
+    #[error("Unsupported value type: {}", .0)]
+    UnsupportedType(u8),
+
+    #[error("Unsupported opcode: {:#x}", .0)]
+    UnsupportedOpcode(u8),
+
+    #[error("Invalid string encoding: {}", .0)]
+    InvalidEncoding(u8),
+
+    #[error("Corrupted LZF compressed string")]
+    InvalidLzf,
+
+    #[error("Sorted set score is not a number")]
+    InvalidScore,
+
+    #[error("Corrupted stream")]
+    InvalidStream,
+
+    #[error("Corrupted listpack")]
+    InvalidListpack,
+
+    #[error("Corrupted intset")]
+    InvalidIntset,
+
+    #[error("Wrong checksum: expected {:#x}, computed {:#x}", .0, .1)]
+    ChecksumMismatch(u64, u64),
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+// ======================================== Dump ========================================
+/// Serialize the keyspace into an RDB file. Keys whose expire time has passed are left out.
+pub fn dump(db: &Db) -> Vec<u8> {
+    let now_ms = unix_time_ms();
+
+    let mut content = BytesMut::new();
+    content.put_slice(MAGIC);
+    content.put_slice(format!("{RDB_VERSION:04}").as_bytes());
+    put_aux(&mut content, "redis-ver", REDIS_VERSION);
+    put_aux(&mut content, "redis-bits", "64");
+    put_aux(&mut content, "ctime", &(now_ms / 1000).to_string());
+
+    content.put_u8(OPCODE_SELECTDB);
+    put_length(&mut content, 0);
+    content.put_u8(OPCODE_RESIZEDB);
+    put_length(&mut content, db.len() as u64);
+    put_length(
+        &mut content,
+        db.values()
+            .filter(|(_, expire_time)| expire_time.is_some())
+            .count() as u64,
+    );
+
+    for (key, (value, expire_time)) in db.iter() {
+        if let Some(expire_time) = expire_time {
+            if *expire_time <= now_ms {
+                continue;
+            }
+            content.put_u8(OPCODE_EXPIRETIME_MS);
+            content.put_u64_le(*expire_time);
+        }
+        match value {
+            Value::String(string) => {
+                content.put_u8(TYPE_STRING);
+                put_string(&mut content, key);
+                put_string(&mut content, string);
+            }
+            Value::List(list) => {
+                content.put_u8(TYPE_LIST);
+                put_string(&mut content, key);
+                put_length(&mut content, list.len() as u64);
+                for element in list {
+                    put_string(&mut content, element);
+                }
+            }
+            Value::Set(set) => {
+                content.put_u8(TYPE_SET);
+                put_string(&mut content, key);
+                put_length(&mut content, set.len() as u64);
+                for member in set {
+                    put_string(&mut content, member);
+                }
+            }
+            Value::Hash(hash) => put_hash(&mut content, key, hash),
+            Value::ZSet(zset) => {
+                content.put_u8(TYPE_ZSET_2);
+                put_string(&mut content, key);
+                put_length(&mut content, zset.len() as u64);
+                // Highest scores first, like Redis, so that loading inserts at the head
+                for (member, score) in zset.rev_range(0..zset.len()) {
+                    put_string(&mut content, member);
+                    content.put_f64_le(score);
+                }
+            }
+            Value::Stream(stream) => put_stream(&mut content, key, stream),
+        }
+    }
+
+    content.put_u8(OPCODE_EOF);
+    let checksum = crc64(&content);
+    content.put_u64_le(checksum);
+    content.to_vec()
+}
+
+/// A hash with volatile fields saves the smallest expire time, then every field prefixed by its
+/// expire time relative to it, plus one so that zero means no expire time.
+fn put_hash(content: &mut BytesMut, key: &[u8], hash: &Hash) {
+    let min_expire_time = hash
+        .keys()
+        .filter_map(|field| hash.expire_time(field))
+        .min();
+
+    match min_expire_time {
+        None => {
+            content.put_u8(TYPE_HASH);
+            put_string(content, key);
+        }
+        Some(min_expire_time) => {
+            content.put_u8(TYPE_HASH_METADATA);
+            put_string(content, key);
+            content.put_u64_le(min_expire_time);
+        }
+    }
+    put_length(content, hash.len() as u64);
+    for (field, value) in hash.iter() {
+        if let Some(min_expire_time) = min_expire_time {
+            let ttl = hash
+                .expire_time(field)
+                .map_or(0, |expire_time| expire_time - min_expire_time + 1);
+            put_length(content, ttl);
+        }
+        put_string(content, field);
+        put_string(content, value);
+    }
 }
 
-impl RdbFile {
-    pub fn load(bytes_content: &[u8]) -> RdbFile {
-        // head "REDIS0012"
-        // &bytes_content[..5]; // "REDIS"
-        let rdb_version = ascii_to_number(&bytes_content[5..9]) as u8; // version number
+/// A stream saves its nodes keyed by their big-endian master ID, then its metadata, then its
+/// consumer groups. A group saves its pending entries, then its consumers with the IDs of the
+/// entries pending for them. Unknown counts and times are saved as -1.
+fn put_stream(content: &mut BytesMut, key: &[u8], stream: &Stream) {
+    content.put_u8(TYPE_STREAM_LISTPACKS_3);
+    put_string(content, key);
+    put_length(content, stream.nodes().len() as u64);
+    for (master_id, listpack) in stream.nodes() {
+        put_string(content, &master_id.to_be_bytes());
+        put_string(content, listpack.as_bytes());
+    }
+    put_length(content, stream.len() as u64);
+    for id in [stream.last_id(), stream.first_id(), stream.max_deleted_id()] {
+        put_length(content, id.ms);
+        put_length(content, id.seq);
+    }
+    put_length(content, stream.entries_added());
+
+    put_length(content, stream.groups().len() as u64);
+    for (name, group) in stream.groups() {
+        put_string(content, name);
+        put_length(content, group.last_id().ms);
+        put_length(content, group.last_id().seq);
+        put_length(content, group.entries_read().unwrap_or(u64::MAX));
+        put_length(content, group.pending().len() as u64);
+        for (id, entry) in group.pending() {
+            content.put_slice(&id.to_be_bytes());
+            content.put_u64_le(entry.delivery_time);
+            put_length(content, entry.delivery_count);
+        }
+        put_length(content, group.consumers().len() as u64);
+        for (name, consumer) in group.consumers() {
+            put_string(content, name);
+            content.put_u64_le(consumer.seen_time);
+            content.put_u64_le(consumer.active_time.unwrap_or(u64::MAX));
+            put_length(content, consumer.pending().len() as u64);
+            for id in consumer.pending() {
+                content.put_slice(&id.to_be_bytes());
+            }
+        }
+    }
+}
+
+fn put_aux(content: &mut BytesMut, name: &str, value: &str) {
+    content.put_u8(OPCODE_AUX);
+    put_string(content, name.as_bytes());
+    put_string(content, value.as_bytes());
+}
+
+fn put_length(content: &mut BytesMut, length: u64) {
+    if length < 1 << 6 {
+        content.put_u8(length as u8);
+    } else if length < 1 << 14 {
+        content.put_u16(0x4000 | length as u16);
+    } else if length <= u32::MAX as u64 {
+        content.put_u8(0x80);
+        content.put_u32(length as u32);
+    } else {
+        content.put_u8(0x81);
+        content.put_u64(length);
+    }
+}
+
+fn put_string(content: &mut BytesMut, string: &[u8]) {
+    put_length(content, string.len() as u64);
+    content.put_slice(string);
+}
+
+// ======================================== Load ========================================
+/// A length, or the special encoding of a string.
+enum Length {
+    Plain(u64),
+    Encoded(u8),
+}
+
+struct Reader<'a> {
+    content: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(n).ok_or(Error::UnexpectedEof)?;
+        let bytes = self
+            .content
+            .get(self.position..end)
+            .ok_or(Error::UnexpectedEof)?;
+        self.position += n;
+        Ok(bytes)
+    }
 
-        let mut rdb = RdbFile {
-            rdb_version,
-            redis_version: String::new(),
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32_le(&mut self) -> Result<u32> {
+        // `take` returns exactly the requested number of bytes
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64_le(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn length_or_encoding(&mut self) -> Result<Length> {
+        let first = self.u8()?;
+        let length = match first >> 6 {
+            0 => (first & 0x3F) as u64,
+            1 => ((first & 0x3F) as u64) << 8 | self.u8()? as u64,
+            2 if first == 0x80 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            2 if first == 0x81 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            2 => return Err(Error::InvalidEncoding(first)),
+            _ => return Ok(Length::Encoded(first & 0x3F)),
+        };
+        Ok(Length::Plain(length))
+    }
+
+    fn length(&mut self) -> Result<u64> {
+        match self.length_or_encoding()? {
+            Length::Plain(length) => Ok(length),
+            Length::Encoded(encoding) => Err(Error::InvalidEncoding(encoding)),
+        }
+    }
+
+    fn string(&mut self) -> Result<Bytes> {
+        let string = match self.length_or_encoding()? {
+            Length::Plain(length) => Bytes::copy_from_slice(self.take(length as usize)?),
+            Length::Encoded(ENC_INT8) => Bytes::from((self.u8()? as i8).to_string()),
+            Length::Encoded(ENC_INT16) => {
+                let bytes = self.take(2)?.try_into().unwrap();
+                Bytes::from(i16::from_le_bytes(bytes).to_string())
+            }
+            Length::Encoded(ENC_INT32) => Bytes::from((self.u32_le()? as i32).to_string()),
+            Length::Encoded(ENC_LZF) => {
+                let compressed_length = self.length()? as usize;
+                let length = self.length()? as usize;
+                Bytes::from(lzf_decompress(self.take(compressed_length)?, length)?)
+            }
+            Length::Encoded(encoding) => return Err(Error::InvalidEncoding(encoding)),
         };
+        Ok(string)
+    }
+}
 
-        // metadata
-        // &bytes_content[9..10]; // 0xFA
-        let mut index = 10;
-        while bytes_content[index] != 0xFE {
-            let attr_name_length = bytes_content[index] as usize;
-            let attr_name = str::from_utf8(&bytes_content[index + 1..index + 1 + attr_name_length])
-                .expect("Invalid UTF-8")
-                .to_lowercase();
-            index += 1 + attr_name_length;
+/// Deserialize an RDB file into a keyspace. Keys and hash fields whose expire time has passed
+/// are left out.
+pub fn load(content: &[u8]) -> Result<Db> {
+    let now_ms = unix_time_ms();
+    let mut reader = Reader {
+        content,
+        position: 0,
+    };
 
-            let attr_value_length = bytes_content[index] as usize;
-            let attr_value =
-                str::from_utf8(&bytes_content[index + 1..index + 1 + attr_value_length])
-                    .expect("Invalid UTF-8")
-                    .to_string();
-            index += 1 + attr_value_length;
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(Error::InvalidMagic);
+    }
+    let version = str::from_utf8(reader.take(4)?)
+        .ok()
+        .and_then(|version| version.parse().ok())
+        .ok_or(Error::InvalidMagic)?;
+    if version > RDB_VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
 
-            match attr_name.as_str() {
-                "redis-ver" => rdb.redis_version = attr_value,
-                _ => println!("attr_name: {}\nattr_value:{}\n\n", attr_name, attr_value),
+    let mut db = Db::new();
+    let mut expire_time_ms = None;
+    loop {
+        match reader.u8()? {
+            OPCODE_AUX => {
+                reader.string()?;
+                reader.string()?;
+            }
+            OPCODE_RESIZEDB => {
+                db.reserve(reader.length()? as usize);
+                reader.length()?;
+            }
+            OPCODE_SELECTDB => {
+                reader.length()?;
+            }
+            OPCODE_SLOT_INFO => {
+                for _ in 0..3 {
+                    reader.length()?;
+                }
+            }
+            OPCODE_FUNCTION2 => {
+                reader.string()?;
+            }
+            OPCODE_IDLE => {
+                reader.length()?;
+            }
+            OPCODE_FREQ => {
+                reader.u8()?;
+            }
+            OPCODE_EXPIRETIME_MS => expire_time_ms = Some(reader.u64_le()?),
+            OPCODE_EXPIRETIME => expire_time_ms = Some(reader.u32_le()? as u64 * 1000),
+            OPCODE_EOF => break,
+            opcode @ 0xF0.. => return Err(Error::UnsupportedOpcode(opcode)),
+            value_type => {
+                let key = reader.string()?;
+                let value = load_value(&mut reader, value_type, now_ms)?;
+                let expire_time = expire_time_ms.take();
+                if expire_time.is_some_and(|ms| ms <= now_ms) {
+                    continue;
+                }
+                if !value.is_empty_collection() {
+                    db.insert(key, (value, expire_time));
+                }
             }
         }
+    }
 
-        rdb
+    // Files older than version 5 carry no checksum
+    if version >= 5 {
+        let checksum_end = reader.position;
+        let expected = reader.u64_le()?;
+        let computed = crc64(&content[..checksum_end]);
+        if expected != 0 && expected != computed {
+            return Err(Error::ChecksumMismatch(expected, computed));
+        }
     }
 
-    pub fn dump(&self) -> Vec<u8> {
-        // head
-        let mut content = b"REDIS".to_vec();
-        let mut version = number_to_ascii(self.rdb_version as usize);
-        let pad_len = VERSION_LENGTH - version.len();
-        for _ in 0..pad_len {
-            version.insert(0, b'0');
+    Ok(db)
+}
+
+fn load_value(reader: &mut Reader, value_type: u8, now_ms: u64) -> Result<Value> {
+    let value = match value_type {
+        TYPE_STRING => Value::String(reader.string()?),
+        TYPE_LIST => {
+            let len = reader.length()? as usize;
+            // Every element takes at least one byte, which bounds a corrupted length
+            let mut list = List::with_capacity(len.min(reader.content.len()));
+            for _ in 0..len {
+                list.push_back(reader.string()?);
+            }
+            Value::List(list)
         }
-        content.extend(version);
+        TYPE_SET => {
+            let len = reader.length()? as usize;
+            let mut set = Set::with_capacity(len.min(reader.content.len()));
+            for _ in 0..len {
+                set.insert(reader.string()?);
+            }
+            Value::Set(set)
+        }
+        TYPE_HASH => {
+            let len = reader.length()?;
+            let mut hash = Hash::new();
+            for _ in 0..len {
+                let field = reader.string()?;
+                hash.insert(field, reader.string()?);
+            }
+            Value::Hash(hash)
+        }
+        TYPE_ZSET_2 => {
+            let len = reader.length()?;
+            let mut zset = SortedSet::new();
+            for _ in 0..len {
+                let member = reader.string()?;
+                let score = f64::from_bits(reader.u64_le()?);
+                if score.is_nan() {
+                    return Err(Error::InvalidScore);
+                }
+                zset.insert(member, score);
+            }
+            Value::ZSet(zset)
+        }
+        TYPE_HASH_METADATA => {
+            let min_expire_time = reader.u64_le()?;
+            let len = reader.length()?;
+            let mut hash = Hash::new();
+            for _ in 0..len {
+                let ttl = reader.length()?;
+                let field = reader.string()?;
+                let value = reader.string()?;
+                if ttl == 0 {
+                    hash.insert(field, value);
+                    continue;
+                }
+                let expire_time = (ttl - 1).saturating_add(min_expire_time);
+                if expire_time > now_ms {
+                    hash.insert(field.clone(), value);
+                    hash.set_expire_time(&field, expire_time);
+                }
+            }
+            Value::Hash(hash)
+        }
+        TYPE_STREAM_LISTPACKS | TYPE_STREAM_LISTPACKS_2 | TYPE_STREAM_LISTPACKS_3 => {
+            Value::Stream(load_stream(reader, value_type)?)
+        }
+        TYPE_SET_INTSET => Value::Set(load_intset(&reader.string()?)?.collect()),
+        TYPE_SET_LISTPACK => Value::Set(load_listpack(reader)?.into_iter().collect()),
+        TYPE_HASH_LISTPACK => {
+            let elements = load_listpack(reader)?;
+            let (pairs, []) = elements.as_chunks::<2>() else {
+                return Err(Error::InvalidListpack);
+            };
+            Value::Hash(
+                pairs
+                    .iter()
+                    .map(|[field, value]| (field.clone(), value.clone()))
+                    .collect(),
+            )
+        }
+        TYPE_HASH_LISTPACK_EX => {
+            // The smallest expire time is found again from the fields
+            reader.u64_le()?;
+            let elements = load_listpack(reader)?;
+            let (triplets, []) = elements.as_chunks::<3>() else {
+                return Err(Error::InvalidListpack);
+            };
+            let mut hash = Hash::new();
+            for [field, value, expire_time] in triplets {
+                let expire_time: u64 =
+                    lexical_core::parse(expire_time).map_err(|_| Error::InvalidListpack)?;
+                if expire_time == 0 {
+                    hash.insert(field.clone(), value.clone());
+                } else if expire_time > now_ms {
+                    hash.insert(field.clone(), value.clone());
+                    hash.set_expire_time(field, expire_time);
+                }
+            }
+            Value::Hash(hash)
+        }
+        TYPE_ZSET_LISTPACK => {
+            let elements = load_listpack(reader)?;
+            let (pairs, []) = elements.as_chunks::<2>() else {
+                return Err(Error::InvalidListpack);
+            };
+            let mut zset = SortedSet::new();
+            for [member, score] in pairs {
+                let score: f64 = str::from_utf8(score)
+                    .ok()
+                    .and_then(|score| score.parse().ok())
+                    .filter(|score: &f64| !score.is_nan())
+                    .ok_or(Error::InvalidScore)?;
+                zset.insert(member.clone(), score);
+            }
+            Value::ZSet(zset)
+        }
+        TYPE_LIST_QUICKLIST_2 => {
+            let mut list = List::new();
+            for _ in 0..reader.length()? {
+                match reader.length()? {
+                    QUICKLIST_NODE_PLAIN => list.push_back(reader.string()?),
+                    QUICKLIST_NODE_PACKED => list.extend(load_listpack(reader)?),
+                    _ => return Err(Error::InvalidListpack),
+                }
+            }
+            Value::List(list)
+        }
+        value_type => return Err(Error::UnsupportedType(value_type)),
+    };
+    Ok(value)
+}
+
+/// Read a listpack stored as a string, returning its elements with integers formatted back.
+fn load_listpack(reader: &mut Reader) -> Result<Vec<Bytes>> {
+    let listpack = Listpack::from_vec(reader.string()?.into()).ok_or(Error::InvalidListpack)?;
+    let mut elements = vec![];
+    let mut position = listpack.first();
+    while let Some((element, next)) = listpack.get(position) {
+        elements.push(Bytes::from(element.to_vec()));
+        position = next;
+    }
+    Ok(elements)
+}
+
+/// Decode an intset: its integer size in bytes and length, then the sorted integers, all little
+/// endian.
+fn load_intset(intset: &[u8]) -> Result<impl Iterator<Item = Bytes>> {
+    let header = intset.get(..8).ok_or(Error::InvalidIntset)?;
+    let size = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+    let len = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+    if ![2, 4, 8].contains(&size) || intset.len() != 8 + size * len {
+        return Err(Error::InvalidIntset);
+    }
+    Ok(intset[8..].chunks_exact(size).map(|chunk| {
+        let mut bytes = [0; 8];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        let shift = 64 - chunk.len() as u32 * 8;
+        Bytes::from(((i64::from_le_bytes(bytes) << shift) >> shift).to_string())
+    }))
+}
+
+fn load_stream(reader: &mut Reader, value_type: u8) -> Result<Stream> {
+    let mut nodes = BTreeMap::new();
+    for _ in 0..reader.length()? {
+        let master_id = StreamId::from_be_bytes(&reader.string()?).ok_or(Error::InvalidStream)?;
+        let listpack = Listpack::from_vec(reader.string()?.into()).ok_or(Error::InvalidStream)?;
+        nodes.insert(master_id, listpack);
+    }
+    let len = reader.length()?;
+    let last_id = StreamId::new(reader.length()?, reader.length()?);
+    let (max_deleted_id, entries_added) = if value_type == TYPE_STREAM_LISTPACKS {
+        (StreamId::MIN, None)
+    } else {
+        // The first ID is found again from the nodes
+        reader.length()?;
+        reader.length()?;
+        let max_deleted_id = StreamId::new(reader.length()?, reader.length()?);
+        (max_deleted_id, Some(reader.length()?))
+    };
+    let mut stream = Stream::from_nodes(nodes, last_id, max_deleted_id, entries_added)
+        .filter(|stream| stream.len() as u64 == len)
+        .ok_or(Error::InvalidStream)?;
+
+    for _ in 0..reader.length()? {
+        let name = reader.string()?;
+        let last_id = StreamId::new(reader.length()?, reader.length()?);
+        let entries_read = if value_type == TYPE_STREAM_LISTPACKS {
+            stream.estimate_entries_read(last_id)
+        } else {
+            Some(reader.length()?).filter(|read| *read != u64::MAX)
+        };
+
+        // The pending entries get their consumer from the consumer listing them
+        let mut unowned = BTreeMap::new();
+        for _ in 0..reader.length()? {
+            let id = StreamId::from_be_bytes(reader.take(16)?).ok_or(Error::InvalidStream)?;
+            unowned.insert(id, (reader.u64_le()?, reader.length()?));
+        }
+        let mut pending = BTreeMap::new();
+        let mut consumers = BTreeMap::new();
+        for _ in 0..reader.length()? {
+            let name = reader.string()?;
+            let seen_time = reader.u64_le()?;
+            // Older files do not tell apart seeing a consumer and its activity
+            let active_time = if value_type == TYPE_STREAM_LISTPACKS_3 {
+                Some(reader.u64_le()?).filter(|time| *time != u64::MAX)
+            } else {
+                Some(seen_time)
+            };
+            for _ in 0..reader.length()? {
+                let id = StreamId::from_be_bytes(reader.take(16)?).ok_or(Error::InvalidStream)?;
+                let (delivery_time, delivery_count) =
+                    unowned.remove(&id).ok_or(Error::InvalidStream)?;
+                let entry = PendingEntry {
+                    consumer: name.clone(),
+                    delivery_time,
+                    delivery_count,
+                };
+                pending.insert(id, entry);
+            }
+            if consumers
+                .insert(name, Consumer::new(seen_time, active_time))
+                .is_some()
+            {
+                return Err(Error::InvalidStream);
+            }
+        }
+        if !unowned.is_empty() {
+            return Err(Error::InvalidStream);
+        }
+
+        let group = ConsumerGroup::from_parts(last_id, entries_read, pending, consumers)
+            .ok_or(Error::InvalidStream)?;
+        if !stream.create_group(name, group) {
+            return Err(Error::InvalidStream);
+        }
+    }
+    Ok(stream)
+}
+
+fn lzf_decompress(input: &[u8], length: usize) -> Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(length);
+    let mut i = 0;
+    while i < input.len() {
+        let control = input[i] as usize;
+        i += 1;
+
+        if control < 1 << 5 {
+            // A run of `control + 1` literal bytes
+            let literal = input.get(i..i + control + 1).ok_or(Error::InvalidLzf)?;
+            output.extend_from_slice(literal);
+            i += control + 1;
+            continue;
+        }
+
+        // A back reference of `run + 2` bytes
+        let mut run = control >> 5;
+        if run == 7 {
+            run += *input.get(i).ok_or(Error::InvalidLzf)? as usize;
+            i += 1;
+        }
+        let offset = ((control & 0x1F) << 8 | *input.get(i).ok_or(Error::InvalidLzf)? as usize) + 1;
+        i += 1;
+        let start = output.len().checked_sub(offset).ok_or(Error::InvalidLzf)?;
+        // The reference may overlap the bytes it produces, so copy byte by byte
+        for k in start..start + run + 2 {
+            output.push(output[k]);
+        }
+    }
+
+    if output.len() != length {
+        return Err(Error::InvalidLzf);
+    }
+    Ok(output)
+}
+
+// ======================================== Checksum ========================================
+/// The reflected polynomial of CRC-64/Jones.
+const CRC64_POLY: u64 = 0x95AC_9329_AC4B_C9B5;
+
+const CRC64_TABLE: [u64; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC64_POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc64(content: &[u8]) -> u64 {
+    content.iter().fold(0, |crc, byte| {
+        CRC64_TABLE[((crc ^ *byte as u64) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{Error, crc64, dump, load, lzf_decompress};
+    use crate::{
+        db::{Db, Hash, Value},
+        stream::{ConsumerGroup, Stream, StreamId},
+        utils::unix_time_ms,
+        zset::SortedSet,
+    };
+
+    #[test]
+    fn crc64_should_match_redis_check_value() {
+        assert_eq!(crc64(b"123456789"), 0xE9C6_D914_C4B8_D9CA);
+    }
+
+    #[test]
+    fn lzf_decompress_should_expand_literals_and_back_references() {
+        let compressed = [0x02, b'a', b'b', b'c', 0x80, 0x02];
+        assert_eq!(lzf_decompress(&compressed, 9).unwrap(), b"abcabcabc");
+        assert_eq!(lzf_decompress(&compressed, 8), Err(Error::InvalidLzf));
+        assert_eq!(lzf_decompress(&[0x80, 0x02], 6), Err(Error::InvalidLzf));
+    }
+
+    #[test]
+    fn load_should_read_file_written_by_redis() {
+        // `SET foo bar`, `SET num 123` and `SET tmp x PX ...` saved with checksums disabled
+        let mut content = b"REDIS0011\xFA\x09redis-ver\x057.2.0\xFA\x0Aredis-bits\xC0\x40".to_vec();
+        content.extend(b"\xFE\x00\xFB\x03\x01");
+        content.extend(b"\x00\x03foo\x03bar");
+        content.extend(b"\x00\x03num\xC0\x7B");
+        content.push(0xFC);
+        content.extend((unix_time_ms() + 60_000).to_le_bytes());
+        content.extend(b"\x00\x03tmp\x01x");
+        content.push(0xFC);
+        content.extend(1_000u64.to_le_bytes());
+        content.extend(b"\x00\x04gone\x01x");
+        content.extend(b"\xFF\x00\x00\x00\x00\x00\x00\x00\x00");
+
+        let db = load(&content).expect("load rdb");
+        assert_eq!(db.len(), 3);
+        assert_eq!(
+            db.get(b"foo".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("bar")), None))
+        );
+        assert_eq!(
+            db.get(b"num".as_ref()),
+            Some(&(Value::String(Bytes::from_owner("123")), None))
+        );
+        let (_, expire_time) = db.get(b"tmp".as_ref()).unwrap();
+        let ttl = expire_time.unwrap() - unix_time_ms();
+        assert!(ttl > 59_000 && ttl <= 60_000);
+    }
+
+    #[test]
+    fn load_should_read_compact_encodings() {
+        // The values as Redis 7.4 encodes them, with checksums disabled
+        let when = unix_time_ms() + 60_000;
+        // `RPUSH list a b 1` plus a large element, which Redis keeps in a plain node
+        let mut content = b"REDIS0012\xFE\x00".to_vec();
+        content.extend(b"\x12\x04list\x02\x02\x0F");
+        content.extend(b"\x0F\x00\x00\x00\x03\x00\x81a\x02\x81b\x02\x01\x01\xFF");
+        content.extend(b"\x01\x03big");
+        // `HSET hash f v n 7`
+        content.extend(b"\x10\x04hash\x12");
+        content.extend(b"\x12\x00\x00\x00\x04\x00\x81f\x02\x81v\x02\x81n\x02\x07\x01\xFF");
+        // `SADD ints 1 2 3` and `SADD set a b`
+        content.extend(b"\x0B\x04ints\x0E");
+        content.extend(b"\x02\x00\x00\x00\x03\x00\x00\x00\x01\x00\x02\x00\x03\x00");
+        content.extend(b"\x14\x03set\x0D");
+        content.extend(b"\x0D\x00\x00\x00\x02\x00\x81a\x02\x81b\x02\xFF");
+        // `ZADD zset 1.5 a 2 b`
+        content.extend(b"\x11\x04zset\x14");
+        content.extend(b"\x14\x00\x00\x00\x04\x00\x81a\x02\x831.5\x04\x81b\x02\x02\x01\xFF");
+        // `HSETEX volatile PXAT <when> FIELDS 1 f v`, `HSET volatile g w`, and a field `x`
+        // which expired at 1000
+        content.extend(b"\x19\x08volatile");
+        content.extend(1000u64.to_le_bytes());
+        content.extend(b"\x28\x28\x00\x00\x00\x09\x00\x81f\x02\x81v\x02\xF4");
+        content.extend(when.to_le_bytes());
+        content.extend(b"\x09\x81g\x02\x81w\x02\x00\x01\x81x\x02\x81y\x02\xC3\xE8\x02\xFF");
+        content.extend(b"\xFF\x00\x00\x00\x00\x00\x00\x00\x00");
+
+        let db = load(&content).expect("load rdb");
+        assert_eq!(
+            db[b"list".as_ref()].0,
+            Value::List(
+                ["a", "b", "1", "big"]
+                    .map(Bytes::from_owner)
+                    .into_iter()
+                    .collect()
+            )
+        );
+        assert_eq!(
+            db[b"hash".as_ref()].0,
+            Value::Hash(Hash::from([
+                (Bytes::from_owner("f"), Bytes::from_owner("v")),
+                (Bytes::from_owner("n"), Bytes::from_owner("7")),
+            ]))
+        );
+        assert_eq!(
+            db[b"ints".as_ref()].0,
+            Value::Set(["1", "2", "3"].map(Bytes::from_owner).into_iter().collect())
+        );
+        assert_eq!(
+            db[b"set".as_ref()].0,
+            Value::Set(["a", "b"].map(Bytes::from_owner).into_iter().collect())
+        );
+        assert_eq!(
+            db[b"zset".as_ref()].0,
+            Value::ZSet(SortedSet::from([
+                (Bytes::from_owner("a"), 1.5),
+                (Bytes::from_owner("b"), 2.0),
+            ]))
+        );
+        let mut volatile = Hash::from([
+            (Bytes::from_owner("f"), Bytes::from_owner("v")),
+            (Bytes::from_owner("g"), Bytes::from_owner("w")),
+        ]);
+        volatile.set_expire_time(&Bytes::from_owner("f"), when);
+        assert_eq!(db[b"volatile".as_ref()].0, Value::Hash(volatile));
+    }
+
+    #[test]
+    fn dump_then_load_should_keep_values_and_expire_times() {
+        let now_ms = unix_time_ms();
+        let mut hash: Hash = (0..100)
+            .map(|i| (Bytes::from(format!("field{i}")), Bytes::from(i.to_string())))
+            .collect();
+        hash.set_expire_time(&Bytes::from_owner("field1"), now_ms + 10_000);
+        hash.set_expire_time(&Bytes::from_owner("field2"), now_ms + 20_000);
+
+        let mut db = Db::new();
+        db.insert(
+            Bytes::from_owner("string"),
+            (
+                Value::String(Bytes::from(vec![b'x'; 20_000])),
+                Some(unix_time_ms() + 60_000),
+            ),
+        );
+        db.insert(
+            Bytes::from_owner("list"),
+            (
+                Value::List([Bytes::from_owner("a"), Bytes::from_owner("b")].into()),
+                None,
+            ),
+        );
+        db.insert(
+            Bytes::from_owner("plain"),
+            (
+                Value::Hash(Hash::from([(
+                    Bytes::from_owner("f"),
+                    Bytes::from_owner("v"),
+                )])),
+                None,
+            ),
+        );
+        db.insert(
+            Bytes::from_owner("volatile"),
+            (Value::Hash(hash.clone()), None),
+        );
+        db.insert(
+            Bytes::from_owner("set"),
+            (
+                Value::Set(["a", "b", "1"].map(Bytes::from_owner).into()),
+                None,
+            ),
+        );
+        db.insert(
+            Bytes::from_owner("zset"),
+            (
+                Value::ZSet(SortedSet::from([
+                    (Bytes::from_owner("low"), -1.5),
+                    (Bytes::from_owner("high"), f64::INFINITY),
+                ])),
+                None,
+            ),
+        );
+        let mut stream = Stream::new();
+        for seq in 0..150 {
+            let field = if seq % 7 == 0 { "odd" } else { "field" };
+            stream.add(
+                StreamId::new(1, seq),
+                &[(Bytes::from_owner(field), Bytes::from(seq.to_string()))],
+            );
+        }
+        stream.remove(StreamId::new(1, 3));
+        let group = Bytes::from_owner("group");
+        stream.create_group(group.clone(), ConsumerGroup::new(StreamId::MIN, None));
+        stream.create_group(
+            Bytes::from_owner("idle"),
+            ConsumerGroup::new(StreamId::new(1, 100), Some(100)),
+        );
+        stream.read_group(&group, &Bytes::from_owner("alice"), 10, false, now_ms);
+        stream.read_group(&group, &Bytes::from_owner("bob"), 5, false, now_ms);
+        stream.group_mut(b"group").unwrap().ack(StreamId::new(1, 2));
+        stream
+            .group_mut(b"idle")
+            .unwrap()
+            .create_consumer(&Bytes::from_owner("carol"), now_ms);
+        db.insert(Bytes::from_owner("stream"), (Value::Stream(stream), None));
+        db.insert(
+            Bytes::from_owner("expired"),
+            (
+                Value::String(Bytes::from_owner("x")),
+                Some(unix_time_ms() - 1),
+            ),
+        );
+
+        let loaded = load(&dump(&db)).expect("load dumped rdb");
+        db.remove(b"expired".as_ref());
+        assert_eq!(loaded.len(), db.len());
+        for key in [
+            b"list".as_ref(),
+            b"plain",
+            b"volatile",
+            b"set",
+            b"zset",
+            b"stream",
+        ] {
+            assert!(db.contains_key(key), "{key:?} missing from the fixture");
+            assert_eq!(loaded.get(key), db.get(key));
+        }
+        let (value, expire_time) = loaded.get(b"string".as_ref()).unwrap();
+        assert_eq!(value, &db[b"string".as_ref()].0);
+        assert!(expire_time.unwrap() > unix_time_ms() + 59_000);
+    }
 
-        // metadata
-        content.push(0xFA);
-        // redis-ver
-        content.push(0x09);
-        content.extend(b"redis-ver");
-        content.push(self.redis_version.len() as u8);
-        content.extend(self.redis_version.as_bytes());
+    #[test]
+    fn load_should_reject_corrupted_files() {
+        let mut content = dump(&Db::new());
+        let last = content.len() - 1;
+        content[last] ^= 1;
+        assert!(matches!(load(&content), Err(Error::ChecksumMismatch(_, _))));
 
-        content
+        assert_eq!(load(b"RUBBISH00"), Err(Error::InvalidMagic));
+        assert_eq!(load(b"REDIS0099"), Err(Error::UnsupportedVersion(99)));
+        assert_eq!(load(b"REDIS0012\xFE"), Err(Error::UnexpectedEof));
     }
 }
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use thiserror::Error;
 
@@ -12,19 +10,44 @@ const MAX_NUMBER_STR_LEN: usize = lexical_core::BUFFER_SIZE;
 #[derive(Debug, PartialEq)]
 pub enum RespData {
     Null,
+    /// A missing array, which RESP2 tells apart from a null bulk string.
+    NullArray,
     Boolean(bool),
     Integer(i64),
     Double(f64),
-    // BigNumber(),
+    /// Decimal digits with an optional sign, too large for `Integer`.
+    BigNumber(String),
     SimpleString(String), //todo 如何采用 &str
     SimpleError(String),  //todo 如何采用 &str
     BulkString(Option<Bytes>),
     BulkError(Bytes),
-    // VerbatimString(),
+    /// A three bytes format such as "txt" or "mkd", and the content.
+    VerbatimString(String, Bytes),
     Array(Vec<RespData>),
-    Map(HashMap<Bytes, RespData>),
-    // Set(HashSet<RespValue<'a>>),
-    // Push(Vec<RespData>),
+    /// Key value pairs, in the order they are replied.
+    Map(Vec<(Bytes, RespData)>),
+    Set(Vec<RespData>),
+    Push(Vec<RespData>),
+    /// Auxiliary attributes followed by the reply they describe.
+    Attribute(Vec<(Bytes, RespData)>, Box<RespData>),
+}
+
+/// The protocol a connection speaks, negotiated through `HELLO`. RESP2 is the default until a
+/// client asks for RESP3.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RespProtocol {
+    #[default]
+    Resp2,
+    Resp3,
+}
+
+impl RespProtocol {
+    pub fn version(&self) -> i64 {
+        match self {
+            RespProtocol::Resp2 => 2,
+            RespProtocol::Resp3 => 3,
+        }
+    }
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -55,6 +78,12 @@ pub enum ParseError {
     #[error("Expected boolean, but got \"{:?}\".", .0)]
     ExpectedBoolean(Bytes),
 
+    #[error("Expected a big number, but got \"{:?}\".", .0)]
+    ExpectedBigNumber(Bytes),
+
+    #[error("Expected a verbatim string with a three bytes format, but got \"{:?}\".", .0)]
+    ExpectedVerbatimFormat(Bytes),
+
     #[error("Expected a bulk string, but got '{}'.", .0)]
     ExpectedBulkString(u8),
 
@@ -67,6 +96,9 @@ pub enum ParseError {
     #[error("Expected map key to be bytes-compatible string.")]
     ExpectedMapKeyBytes,
 
+    #[error("unbalanced quotes in request")]
+    UnbalancedQuotes,
+
     #[error("Unknown RESP type prefix: '{}'", .0)]
     UnknownTypePrefix(u8),
 }
@@ -181,6 +213,32 @@ pub fn parse_bulk_string(buffer: &mut BytesMut) -> Result<Option<Bytes>> {
     }
 }
 
+pub fn parse_big_number(buffer: &mut BytesMut) -> Result<String> {
+    let (data, _) = get_bytes_until_next_sep_pos(buffer)?;
+    let digits = data
+        .strip_prefix(b"-")
+        .or(data.strip_prefix(b"+"))
+        .unwrap_or(&data);
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return Err(ParseError::ExpectedBigNumber(Bytes::from_owner(data)));
+    }
+    Ok(str::from_utf8(&data)?.to_string())
+}
+
+pub fn parse_verbatim_string(buffer: &mut BytesMut) -> Result<(String, Bytes)> {
+    let (data, _) = get_bytes_until_next_sep_pos(buffer)?;
+    let s_len = lexical_core::parse(&data)?;
+    let mut data = split_to(buffer, s_len)?;
+    expect_sep(buffer)?;
+
+    if data.len() < 4 || data[3] != b':' {
+        return Err(ParseError::ExpectedVerbatimFormat(Bytes::from_owner(data)));
+    }
+    let format = str::from_utf8(&data[..3])?.to_string();
+    data.advance(4);
+    Ok((format, Bytes::from_owner(data)))
+}
+
 pub fn parse_bulk_error(buffer: &mut BytesMut) -> Result<Bytes> {
     let (data, _) = get_bytes_until_next_sep_pos(buffer)?;
     let s_len = lexical_core::parse(&data)?;
@@ -202,11 +260,11 @@ pub fn parse_array(buffer: &mut BytesMut) -> Result<Vec<RespData>> {
     Ok(array)
 }
 
-pub fn parse_map(buffer: &mut BytesMut) -> Result<HashMap<Bytes, RespData>> {
+pub fn parse_map(buffer: &mut BytesMut) -> Result<Vec<(Bytes, RespData)>> {
     let (data, _) = get_bytes_until_next_sep_pos(buffer)?;
     let length = lexical_core::parse(&data)?;
 
-    let mut map = HashMap::with_capacity(length);
+    let mut map: Vec<(Bytes, RespData)> = Vec::with_capacity(length);
     for _ in 0..length {
         let key = parse_resp(buffer)?;
         let value = parse_resp(buffer)?;
@@ -215,7 +273,11 @@ pub fn parse_map(buffer: &mut BytesMut) -> Result<HashMap<Bytes, RespData>> {
             RespData::SimpleString(key) => Bytes::from_owner(key),
             _ => return Err(ParseError::ExpectedMapKeyBytes),
         };
-        map.insert(key, value);
+        // A duplicate key keeps its first position and takes the last value.
+        match map.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => map.push((key, value)),
+        }
     }
 
     Ok(map)
@@ -235,8 +297,26 @@ pub fn parse_resp(buffer: &mut BytesMut) -> Result<RespData> {
         b'-' => Ok(RespData::SimpleError(parse_simple_error(buffer)?)),
         b'$' => Ok(RespData::BulkString(parse_bulk_string(buffer)?)),
         b'!' => Ok(RespData::BulkError(parse_bulk_error(buffer)?)),
+        b'*' if buffer.starts_with(b"-1\r\n") => {
+            buffer.advance(2 + SEP_LEN);
+            Ok(RespData::NullArray)
+        }
         b'*' => Ok(RespData::Array(parse_array(buffer)?)),
         b'%' => Ok(RespData::Map(parse_map(buffer)?)),
+        b'(' => Ok(RespData::BigNumber(parse_big_number(buffer)?)),
+        b'=' => {
+            let (format, data) = parse_verbatim_string(buffer)?;
+            Ok(RespData::VerbatimString(format, data))
+        }
+        // Sets and pushes share the aggregate layout of arrays.
+        b'~' => Ok(RespData::Set(parse_array(buffer)?)),
+        b'>' => Ok(RespData::Push(parse_array(buffer)?)),
+        // Attributes share the layout of maps, and precede the reply they describe.
+        b'|' => {
+            let attributes = parse_map(buffer)?;
+            let data = parse_resp(buffer)?;
+            Ok(RespData::Attribute(attributes, Box::new(data)))
+        }
         t => Err(ParseError::UnknownTypePrefix(t)),
     }
 }
@@ -248,6 +328,27 @@ pub struct ClientRequest {
 }
 
 pub fn parse_client_request(buffer: &mut BytesMut) -> Result<ClientRequest> {
+    loop {
+        check_length(buffer, 1)?;
+        let array = if buffer[0] == b'*' {
+            parse_multibulk_request(buffer)?
+        } else {
+            parse_inline_request(buffer)?
+        };
+
+        // Blank inline lines carry no command and are silently skipped.
+        if array.is_empty() {
+            continue;
+        }
+
+        return Ok(ClientRequest {
+            command: str::from_utf8(&array[0])?.to_string(),
+            args: array[1..].to_vec(),
+        });
+    }
+}
+
+fn parse_multibulk_request(buffer: &mut BytesMut) -> Result<Vec<Bytes>> {
     expect_array(buffer)?;
     let (data, _) = get_bytes_until_next_sep_pos(buffer)?;
     let length = lexical_core::parse(&data)?;
@@ -265,10 +366,116 @@ pub fn parse_client_request(buffer: &mut BytesMut) -> Result<ClientRequest> {
         array.push(data);
     }
 
-    Ok(ClientRequest {
-        command: str::from_utf8(&array[0])?.to_string(),
-        args: array[1..].to_vec(),
-    })
+    Ok(array)
+}
+
+/// Inline commands are a single line of space separated arguments ended by "\r\n" or "\n",
+/// as typed by hand through `telnet` or `nc`.
+fn parse_inline_request(buffer: &mut BytesMut) -> Result<Vec<Bytes>> {
+    let pos = buffer
+        .iter()
+        .position(|c| *c == b'\n')
+        .ok_or(ParseError::Eof(Bytes::copy_from_slice(buffer)))?;
+    let mut line = buffer.split_to(pos);
+    buffer.advance(1);
+    if line.last() == Some(&b'\r') {
+        line.truncate(line.len() - 1);
+    }
+
+    split_inline_args(&line)
+}
+
+/// Split an inline line into arguments the way `sdssplitargs` in Redis does: arguments may be
+/// wrapped in double quotes supporting "\xHH" and C-like escapes, or in single quotes where only
+/// "\'" is an escape. A closing quote must be followed by a space or the end of the line.
+fn split_inline_args(line: &[u8]) -> Result<Vec<Bytes>> {
+    let mut args = Vec::new();
+    let mut i = 0;
+
+    loop {
+        while i < line.len() && line[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i == line.len() {
+            return Ok(args);
+        }
+
+        let mut current = Vec::new();
+        let mut in_double_quotes = false;
+        let mut in_single_quotes = false;
+        loop {
+            let c = line.get(i).copied();
+            if in_double_quotes {
+                match c {
+                    None => return Err(ParseError::UnbalancedQuotes),
+                    Some(b'\\')
+                        if i + 3 < line.len()
+                            && line[i + 1] == b'x'
+                            && line[i + 2].is_ascii_hexdigit()
+                            && line[i + 3].is_ascii_hexdigit() =>
+                    {
+                        current
+                            .push(hex_digit_to_u8(line[i + 2]) * 16 + hex_digit_to_u8(line[i + 3]));
+                        i += 3;
+                    }
+                    Some(b'\\') if i + 1 < line.len() => {
+                        i += 1;
+                        current.push(match line[i] {
+                            b'n' => b'\n',
+                            b'r' => b'\r',
+                            b't' => b'\t',
+                            b'b' => 0x08,
+                            b'a' => 0x07,
+                            c => c,
+                        });
+                    }
+                    Some(b'"') => {
+                        if line.get(i + 1).is_some_and(|c| !c.is_ascii_whitespace()) {
+                            return Err(ParseError::UnbalancedQuotes);
+                        }
+                        i += 1;
+                        break;
+                    }
+                    Some(c) => current.push(c),
+                }
+            } else if in_single_quotes {
+                match c {
+                    None => return Err(ParseError::UnbalancedQuotes),
+                    Some(b'\\') if line.get(i + 1) == Some(&b'\'') => {
+                        current.push(b'\'');
+                        i += 1;
+                    }
+                    Some(b'\'') => {
+                        if line.get(i + 1).is_some_and(|c| !c.is_ascii_whitespace()) {
+                            return Err(ParseError::UnbalancedQuotes);
+                        }
+                        i += 1;
+                        break;
+                    }
+                    Some(c) => current.push(c),
+                }
+            } else {
+                match c {
+                    None | Some(b' ' | b'\n' | b'\r' | b'\t' | b'\0') => break,
+                    Some(b'"') => in_double_quotes = true,
+                    Some(b'\'') => in_single_quotes = true,
+                    Some(c) => current.push(c),
+                }
+            }
+            i += 1;
+        }
+
+        args.push(Bytes::from(current));
+    }
+}
+
+#[inline]
+fn hex_digit_to_u8(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => c - b'A' + 10,
+    }
 }
 
 // ======================================== Serialize ========================================
@@ -298,7 +505,7 @@ pub fn serialize_integer(buffer: &mut BytesMut, integer: i64) {
 }
 
 pub fn serialize_double(buffer: &mut BytesMut, double: f64) {
-    buffer.put_u8(b':');
+    buffer.put_u8(b',');
     lexical_write(double, buffer);
     buffer.put(SEP_STR.as_bytes());
 }
@@ -332,6 +539,23 @@ pub fn serialize_bulk_string(buffer: &mut BytesMut, bytes: &Option<Bytes>) {
     buffer.put(SEP_STR.as_bytes());
 }
 
+pub fn serialize_big_number(buffer: &mut BytesMut, number: &str) {
+    buffer.put_u8(b'(');
+    buffer.put(number.as_bytes());
+    buffer.put(SEP_STR.as_bytes());
+}
+
+pub fn serialize_verbatim_string(buffer: &mut BytesMut, format: &str, bytes: &Bytes) {
+    buffer.put_u8(b'=');
+    lexical_write(format.len() + 1 + bytes.len(), buffer);
+    buffer.put(SEP_STR.as_bytes());
+
+    buffer.put(format.as_bytes());
+    buffer.put_u8(b':');
+    buffer.put(bytes.clone());
+    buffer.put(SEP_STR.as_bytes());
+}
+
 pub fn serialize_bulk_error(buffer: &mut BytesMut, bytes: &Bytes) {
     buffer.put_u8(b'!');
     lexical_write(bytes.len(), buffer);
@@ -341,56 +565,147 @@ pub fn serialize_bulk_error(buffer: &mut BytesMut, bytes: &Bytes) {
     buffer.put(SEP_STR.as_bytes());
 }
 
-pub fn serialize_array(buffer: &mut BytesMut, array: &[RespData]) {
-    buffer.put_u8(b'*');
-    lexical_write(array.len(), buffer);
+pub fn serialize_array(buffer: &mut BytesMut, array: &[RespData], protocol: RespProtocol) {
+    serialize_aggregate(buffer, b'*', array, protocol);
+}
+
+/// Sets and pushes are sent as plain arrays to RESP2 connections.
+pub fn serialize_set(buffer: &mut BytesMut, set: &[RespData], protocol: RespProtocol) {
+    let prefix = match protocol {
+        RespProtocol::Resp2 => b'*',
+        RespProtocol::Resp3 => b'~',
+    };
+    serialize_aggregate(buffer, prefix, set, protocol);
+}
+
+pub fn serialize_push(buffer: &mut BytesMut, push: &[RespData], protocol: RespProtocol) {
+    let prefix = match protocol {
+        RespProtocol::Resp2 => b'*',
+        RespProtocol::Resp3 => b'>',
+    };
+    serialize_aggregate(buffer, prefix, push, protocol);
+}
+
+#[inline]
+fn serialize_aggregate(
+    buffer: &mut BytesMut,
+    prefix: u8,
+    items: &[RespData],
+    protocol: RespProtocol,
+) {
+    buffer.put_u8(prefix);
+    lexical_write(items.len(), buffer);
     buffer.put(SEP_STR.as_bytes());
 
-    for resp in array {
-        serialize_resp(buffer, resp);
+    for resp in items {
+        serialize_resp(buffer, resp, protocol);
     }
 }
 
-pub fn serialize_map(buffer: &mut BytesMut, map: &HashMap<Bytes, RespData>) {
-    buffer.put_u8(b'%');
-    lexical_write(map.len(), buffer);
+pub fn serialize_null_array(buffer: &mut BytesMut) {
+    buffer.put_u8(b'*');
+    lexical_write(-1, buffer);
     buffer.put(SEP_STR.as_bytes());
+}
+
+pub fn serialize_map(buffer: &mut BytesMut, map: &[(Bytes, RespData)], protocol: RespProtocol) {
+    match protocol {
+        RespProtocol::Resp2 => {
+            // RESP2 has no map type, send a flat array of key value pairs instead.
+            buffer.put_u8(b'*');
+            lexical_write(map.len() * 2, buffer);
+            buffer.put(SEP_STR.as_bytes());
+            serialize_map_entries(buffer, map, protocol);
+        }
+        RespProtocol::Resp3 => {
+            buffer.put_u8(b'%');
+            lexical_write(map.len(), buffer);
+            buffer.put(SEP_STR.as_bytes());
+            serialize_map_entries(buffer, map, protocol);
+        }
+    }
+}
 
-    let mut entries: Vec<_> = map.iter().collect();
-    entries.sort_by(|(k1, _), (k2, _)| k1.as_ref().cmp(k2.as_ref()));
+/// RESP2 has no attribute type, so only the reply itself is sent.
+pub fn serialize_attribute(
+    buffer: &mut BytesMut,
+    attributes: &[(Bytes, RespData)],
+    data: &RespData,
+    protocol: RespProtocol,
+) {
+    if protocol == RespProtocol::Resp3 {
+        buffer.put_u8(b'|');
+        lexical_write(attributes.len(), buffer);
+        buffer.put(SEP_STR.as_bytes());
+        serialize_map_entries(buffer, attributes, protocol);
+    }
+    serialize_resp(buffer, data, protocol);
+}
 
-    for (key, value) in entries {
+#[inline]
+fn serialize_map_entries(buffer: &mut BytesMut, map: &[(Bytes, RespData)], protocol: RespProtocol) {
+    for (key, value) in map {
         serialize_bulk_string(buffer, &Some(key.clone()));
-        serialize_resp(buffer, value);
+        serialize_resp(buffer, value, protocol);
     }
 }
 
-pub fn serialize_resp(buffer: &mut BytesMut, resp: &RespData) {
-    match resp {
-        RespData::Null => serialize_null(buffer),
-        RespData::Boolean(boolean) => serialize_boolean(buffer, *boolean),
-        RespData::Integer(integer) => serialize_integer(buffer, *integer),
-        RespData::Double(double) => serialize_double(buffer, *double),
-        RespData::SimpleString(s) => serialize_simple_string(buffer, s),
-        RespData::SimpleError(s) => serialize_simple_error(buffer, s),
-        RespData::BulkString(bytes) => serialize_bulk_string(buffer, bytes),
-        RespData::BulkError(bytes) => serialize_bulk_error(buffer, bytes),
-        RespData::Array(array) => serialize_array(buffer, array),
-        RespData::Map(map) => serialize_map(buffer, map),
+/// Serialize `resp` for a connection speaking `protocol`. RESP3-only types are downgraded to
+/// their RESP2 forms for RESP2 connections.
+pub fn serialize_resp(buffer: &mut BytesMut, resp: &RespData, protocol: RespProtocol) {
+    match (resp, protocol) {
+        (RespData::Null, RespProtocol::Resp2) => serialize_bulk_string(buffer, &None),
+        (RespData::Null, RespProtocol::Resp3) => serialize_null(buffer),
+        (RespData::NullArray, RespProtocol::Resp2) => serialize_null_array(buffer),
+        (RespData::NullArray, RespProtocol::Resp3) => serialize_null(buffer),
+        (RespData::Boolean(boolean), RespProtocol::Resp2) => {
+            serialize_integer(buffer, *boolean as i64)
+        }
+        (RespData::Boolean(boolean), RespProtocol::Resp3) => serialize_boolean(buffer, *boolean),
+        (RespData::Integer(integer), _) => serialize_integer(buffer, *integer),
+        (RespData::Double(double), RespProtocol::Resp2) => {
+            let mut double_buffer = BytesMut::new();
+            lexical_write(*double, &mut double_buffer);
+            serialize_bulk_string(buffer, &Some(double_buffer.freeze()));
+        }
+        (RespData::Double(double), RespProtocol::Resp3) => serialize_double(buffer, *double),
+        (RespData::BigNumber(number), RespProtocol::Resp2) => {
+            serialize_bulk_string(buffer, &Some(Bytes::copy_from_slice(number.as_bytes())))
+        }
+        (RespData::BigNumber(number), RespProtocol::Resp3) => serialize_big_number(buffer, number),
+        (RespData::SimpleString(s), _) => serialize_simple_string(buffer, s),
+        (RespData::SimpleError(s), _) => serialize_simple_error(buffer, s),
+        (RespData::BulkString(bytes), _) => serialize_bulk_string(buffer, bytes),
+        (RespData::BulkError(bytes), RespProtocol::Resp2) => {
+            serialize_simple_error(buffer, &String::from_utf8_lossy(bytes))
+        }
+        (RespData::BulkError(bytes), RespProtocol::Resp3) => serialize_bulk_error(buffer, bytes),
+        (RespData::VerbatimString(_, bytes), RespProtocol::Resp2) => {
+            serialize_bulk_string(buffer, &Some(bytes.clone()))
+        }
+        (RespData::VerbatimString(format, bytes), RespProtocol::Resp3) => {
+            serialize_verbatim_string(buffer, format, bytes)
+        }
+        (RespData::Array(array), _) => serialize_array(buffer, array, protocol),
+        (RespData::Map(map), _) => serialize_map(buffer, map, protocol),
+        (RespData::Set(set), _) => serialize_set(buffer, set, protocol),
+        (RespData::Push(push), _) => serialize_push(buffer, push, protocol),
+        (RespData::Attribute(attributes, data), _) => {
+            serialize_attribute(buffer, attributes, data, protocol)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
 
     use bytes::{Bytes, BytesMut};
 
     use crate::resp::ClientRequest;
 
     use super::{
-        ParseError, RespData, parse_client_request, parse_resp, serialize_bulk_string,
-        serialize_resp,
+        ParseError, RespData, RespProtocol, parse_client_request, parse_resp,
+        serialize_bulk_string, serialize_resp,
     };
 
     fn parse_resp_from_str(input: &str) -> RespData {
@@ -486,7 +801,7 @@ mod tests {
     #[test]
     fn parse_resp_should_parse_map() {
         let resp = parse_resp_from_str("%2\r\n+first\r\n:1\r\n+second\r\n$5\r\nhello\r\n");
-        let expected = HashMap::from([
+        let expected = Vec::from([
             (Bytes::from_owner("first"), RespData::Integer(1)),
             (
                 Bytes::from_owner("second"),
@@ -508,7 +823,7 @@ mod tests {
         let resp = parse_resp_from_str("%2\r\n+key\r\n:1\r\n+key\r\n:2\r\n");
         assert_eq!(
             resp,
-            RespData::Map(HashMap::from([(
+            RespData::Map(Vec::from([(
                 Bytes::from_owner("key"),
                 RespData::Integer(2),
             )]))
@@ -551,10 +866,89 @@ mod tests {
     }
 
     #[test]
-    fn parse_client_request_should_reject_non_array_prefix() {
+    fn parse_client_request_should_parse_inline_command() {
+        let mut buffer = BytesMut::from("SET  key value\r\nPING\n");
+        let request = parse_client_request(&mut buffer).expect("inline request should parse");
+        assert_eq!(
+            request,
+            ClientRequest {
+                command: "SET".to_string(),
+                args: vec![Bytes::from_owner("key"), Bytes::from_owner("value")]
+            }
+        );
+
+        let request = parse_client_request(&mut buffer).expect("lf ended request should parse");
+        assert_eq!(
+            request,
+            ClientRequest {
+                command: "PING".to_string(),
+                args: vec![]
+            }
+        );
+        assert!(buffer.is_empty(), "buffer should be fully consumed");
+    }
+
+    #[test]
+    fn parse_client_request_should_treat_non_array_prefix_as_inline() {
         let mut buffer = BytesMut::from("$3\r\nGET\r\n");
+        let request = parse_client_request(&mut buffer).expect("inline request should parse");
+        assert_eq!(
+            request,
+            ClientRequest {
+                command: "$3".to_string(),
+                args: vec![]
+            }
+        );
+        assert_eq!(buffer.as_ref(), b"GET\r\n");
+    }
+
+    #[test]
+    fn parse_client_request_should_skip_blank_inline_lines() {
+        let mut buffer = BytesMut::from("\r\n   \nPING\r\n");
+        let request = parse_client_request(&mut buffer).expect("inline request should parse");
+        assert_eq!(request.command, "PING");
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn parse_client_request_should_return_eof_for_partial_inline_line() {
+        let mut buffer = BytesMut::from("SET key va");
         let result = parse_client_request(&mut buffer);
-        assert_eq!(result, Err(ParseError::ExpectedArray(b'$')));
+        assert!(matches!(result, Err(ParseError::Eof(_))));
+    }
+
+    #[test]
+    fn parse_client_request_should_unquote_inline_arguments() {
+        let mut buffer = BytesMut::from("SET \"hello world\" \"a\\x41\\n\\\"\" 'it\\'s' \"\"\r\n");
+        let request = parse_client_request(&mut buffer).expect("inline request should parse");
+        assert_eq!(
+            request,
+            ClientRequest {
+                command: "SET".to_string(),
+                args: vec![
+                    Bytes::from_owner("hello world"),
+                    Bytes::from_owner("aA\n\""),
+                    Bytes::from_owner("it's"),
+                    Bytes::from_owner(""),
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn parse_client_request_should_reject_unbalanced_quotes() {
+        for line in [
+            "SET \"key value\r\n",
+            "SET 'key\r\n",
+            "SET \"key\"value\r\n",
+        ] {
+            let mut buffer = BytesMut::from(line);
+            let result = parse_client_request(&mut buffer);
+            assert!(
+                matches!(result, Err(ParseError::UnbalancedQuotes)),
+                "{line:?} should be rejected"
+            );
+        }
     }
 
     #[test]
@@ -606,6 +1000,7 @@ mod tests {
                 RespData::BulkString(Some(Bytes::from_owner("hello"))),
                 RespData::Integer(7),
             ]),
+            RespProtocol::Resp3,
         );
         assert_eq!(buffer.as_ref(), b"*3\r\n+OK\r\n$5\r\nhello\r\n:7\r\n");
     }
@@ -615,17 +1010,18 @@ mod tests {
         let mut buffer = BytesMut::new();
         serialize_resp(
             &mut buffer,
-            &RespData::Map(HashMap::from([
-                (Bytes::from_owner("first"), RespData::Integer(1)),
+            &RespData::Map(Vec::from([
                 (
                     Bytes::from_owner("second"),
                     RespData::BulkString(Some(Bytes::from_owner("hello"))),
                 ),
+                (Bytes::from_owner("first"), RespData::Integer(1)),
             ])),
+            RespProtocol::Resp3,
         );
         assert_eq!(
             buffer.as_ref(),
-            b"%2\r\n$5\r\nfirst\r\n:1\r\n$6\r\nsecond\r\n$5\r\nhello\r\n"
+            b"%2\r\n$6\r\nsecond\r\n$5\r\nhello\r\n$5\r\nfirst\r\n:1\r\n"
         );
     }
 
@@ -640,15 +1036,168 @@ mod tests {
             RespData::BulkString(Some(Bytes::from_owner("hello"))),
             RespData::BulkString(None),
             RespData::BulkError(Bytes::from_owner("error")),
-            RespData::Map(HashMap::from([(
+            RespData::Map(Vec::from([(
                 Bytes::from_owner("k"),
                 RespData::SimpleString("v".to_string()),
             )])),
         ]);
-        serialize_resp(&mut buffer, &expected);
+        serialize_resp(&mut buffer, &expected, RespProtocol::Resp3);
+
+        let actual = parse_resp(&mut buffer).expect("resp should parse back");
+        assert_eq!(actual, expected);
+        assert!(buffer.is_empty(), "all bytes should be consumed");
+    }
+
+    #[test]
+    fn serialize_resp_should_write_null_array_per_protocol() {
+        for (protocol, expected) in [
+            (RespProtocol::Resp2, "*-1\r\n"),
+            (RespProtocol::Resp3, "_\r\n"),
+        ] {
+            let mut buffer = BytesMut::new();
+            serialize_resp(&mut buffer, &RespData::NullArray, protocol);
+            assert_eq!(buffer.as_ref(), expected.as_bytes(), "{protocol:?}");
+        }
+        assert_eq!(parse_resp_from_str("*-1\r\n"), RespData::NullArray);
+    }
+
+    #[test]
+    fn serialize_resp_should_write_resp3_double() {
+        let mut buffer = BytesMut::new();
+        serialize_resp(&mut buffer, &RespData::Double(1.5), RespProtocol::Resp3);
+        assert_eq!(buffer.as_ref(), b",1.5\r\n");
+    }
+
+    #[test]
+    fn serialize_resp_should_downgrade_resp3_types_for_resp2() {
+        let mut buffer = BytesMut::new();
+        serialize_resp(
+            &mut buffer,
+            &RespData::Array(vec![
+                RespData::Null,
+                RespData::NullArray,
+                RespData::Boolean(true),
+                RespData::Boolean(false),
+                RespData::Double(1.5),
+                RespData::BulkError(Bytes::from_owner("ERR oops")),
+                RespData::Map(Vec::from([(
+                    Bytes::from_owner("k"),
+                    RespData::Map(Vec::from([(Bytes::from_owner("inner"), RespData::Null)])),
+                )])),
+            ]),
+            RespProtocol::Resp2,
+        );
+        assert_eq!(
+            buffer.as_ref(),
+            b"*7\r\n$-1\r\n*-1\r\n:1\r\n:0\r\n$3\r\n1.5\r\n-ERR oops\r\n\
+*2\r\n$1\r\nk\r\n*2\r\n$5\r\ninner\r\n$-1\r\n"
+        );
+    }
+
+    #[test]
+    fn parse_resp_should_parse_resp3_only_types() {
+        assert_eq!(
+            parse_resp_from_str("(3492890328409238509324850943850943825024385\r\n"),
+            RespData::BigNumber("3492890328409238509324850943850943825024385".to_string())
+        );
+        assert_eq!(
+            parse_resp_from_str("(-12\r\n"),
+            RespData::BigNumber("-12".to_string())
+        );
+        assert_eq!(
+            parse_resp_from_str("=15\r\ntxt:Some string\r\n"),
+            RespData::VerbatimString("txt".to_string(), Bytes::from_owner("Some string"))
+        );
+        assert_eq!(
+            parse_resp_from_str("~2\r\n+a\r\n:1\r\n"),
+            RespData::Set(vec![
+                RespData::SimpleString("a".to_string()),
+                RespData::Integer(1)
+            ])
+        );
+        assert_eq!(
+            parse_resp_from_str(">2\r\n$10\r\ninvalidate\r\n*1\r\n$3\r\nkey\r\n"),
+            RespData::Push(vec![
+                RespData::BulkString(Some(Bytes::from_owner("invalidate"))),
+                RespData::Array(vec![RespData::BulkString(Some(Bytes::from_owner("key")))])
+            ])
+        );
+        assert_eq!(
+            parse_resp_from_str("|1\r\n+ttl\r\n:3600\r\n$5\r\nhello\r\n"),
+            RespData::Attribute(
+                Vec::from([(Bytes::from_owner("ttl"), RespData::Integer(3600))]),
+                Box::new(RespData::BulkString(Some(Bytes::from_owner("hello"))))
+            )
+        );
+    }
+
+    #[test]
+    fn parse_resp_should_reject_invalid_big_number() {
+        let mut buffer = BytesMut::from("(12a\r\n");
+        let result = parse_resp(&mut buffer);
+        assert!(matches!(result, Err(ParseError::ExpectedBigNumber(_))));
+    }
+
+    #[test]
+    fn parse_resp_should_reject_verbatim_string_without_format() {
+        let mut buffer = BytesMut::from("=2\r\nab\r\n");
+        let result = parse_resp(&mut buffer);
+        assert!(matches!(result, Err(ParseError::ExpectedVerbatimFormat(_))));
+    }
+
+    #[test]
+    fn parse_resp_should_report_eof_for_partial_push() {
+        let mut buffer = BytesMut::from(">2\r\n+message\r\n");
+        let result = parse_resp(&mut buffer);
+        assert!(matches!(result, Err(ParseError::Eof(_))));
+    }
+
+    #[test]
+    fn serialize_and_parse_resp3_only_types_should_roundtrip() {
+        let expected = RespData::Push(vec![
+            RespData::BigNumber("-123456789012345678901234567890".to_string()),
+            RespData::VerbatimString("mkd".to_string(), Bytes::from_owner("# title\r\n")),
+            RespData::Set(vec![RespData::Integer(1), RespData::Double(2.5)]),
+            RespData::Attribute(
+                Vec::from([(
+                    Bytes::from_owner("key-popularity"),
+                    RespData::Array(vec![RespData::Double(0.1923)]),
+                )]),
+                Box::new(RespData::Array(vec![RespData::Integer(2039123)])),
+            ),
+        ]);
+        let mut buffer = BytesMut::new();
+        serialize_resp(&mut buffer, &expected, RespProtocol::Resp3);
+        assert!(
+            buffer.starts_with(
+                b">4\r\n(-123456789012345678901234567890\r\n=13\r\nmkd:# title\r\n\r\n"
+            )
+        );
 
         let actual = parse_resp(&mut buffer).expect("resp should parse back");
         assert_eq!(actual, expected);
         assert!(buffer.is_empty(), "all bytes should be consumed");
     }
+
+    #[test]
+    fn serialize_resp_should_downgrade_resp3_only_types_for_resp2() {
+        let mut buffer = BytesMut::new();
+        serialize_resp(
+            &mut buffer,
+            &RespData::Push(vec![
+                RespData::BigNumber("12".to_string()),
+                RespData::VerbatimString("txt".to_string(), Bytes::from_owner("hi")),
+                RespData::Set(vec![RespData::Integer(1)]),
+                RespData::Attribute(
+                    Vec::from([(Bytes::from_owner("a"), RespData::Integer(1))]),
+                    Box::new(RespData::Integer(2)),
+                ),
+            ]),
+            RespProtocol::Resp2,
+        );
+        assert_eq!(
+            buffer.as_ref(),
+            b"*4\r\n$2\r\n12\r\n$2\r\nhi\r\n*1\r\n:1\r\n:2\r\n"
+        );
+    }
 }
@@ -1,32 +1,75 @@
-use std::{collections::HashMap, net::SocketAddr, path::PathBuf, sync::Arc};
+use std::{
+    fs, io,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use bytes::{Buf, Bytes, BytesMut};
+use bytes::{Buf, BytesMut};
 use thiserror::Error;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::TcpStream,
     sync::Mutex,
-    time::Instant,
 };
 
 use crate::{
+    blocking::BlockingClients,
     command::{self, ExecuteCommand, parse_command},
-    resp::{self, RespData, parse_client_request, serialize_resp, serialize_simple_error},
-    utils::BytesInStr,
+    rdb,
+    resp::{
+        self, RespData, RespProtocol, parse_client_request, serialize_resp, serialize_simple_error,
+    },
+    utils::{BytesInStr, unix_time_ms},
 };
 
 const BUFFER_INITIAL_SIZE: usize = 128;
 
-pub type Key = Bytes;
-pub type Value = Bytes;
-pub type DbItem = (Value, Option<Instant>);
-pub type Db = HashMap<Key, DbItem>;
+/// How many times per second [`server_cron`] runs its background jobs by default, and the bounds
+/// the `hz` setting is clamped to.
+pub const DEFAULT_HZ: u64 = 10;
+pub const MIN_HZ: u64 = 1;
+pub const MAX_HZ: u64 = 500;
+
+/// Keys sampled at a time by the active expiry.
+const ACTIVE_EXPIRE_KEYS_PER_LOOP: usize = 20;
+/// The share of CPU time the active expiry may take, in percent.
+const ACTIVE_EXPIRE_TIME_PERC: u64 = 25;
+/// The active expiry samples again while more than this percentage of a sample was expired.
+const ACTIVE_EXPIRE_ACCEPTABLE_STALE_PERC: usize = 25;
+/// Hash fields removed at most by a sample of the active expiry of hash fields.
+const ACTIVE_EXPIRE_FIELDS_PER_LOOP: usize = 200;
+
+pub const REDIS_VERSION: &str = "7.4.0";
+
+pub use crate::db::{Db, DbItem, Key, Value, VolatileKeys};
 
 pub struct Server {
     pub addr: SocketAddr,
     pub rdb_file: PathBuf,
     pub db: Db,
     pub conn_num: u64,
+    pub blocking: BlockingClients,
+    /// Keys of the hashes which had fields with an expire time, for the active expiry of hash
+    /// fields. Keys that no longer hold such a hash are dropped once sampled.
+    pub volatile_hashes: VolatileKeys,
+    /// Keys given an expire time, for the active expiry of keys. Keys deleted or persisted since
+    /// are dropped once sampled.
+    pub volatile_keys: VolatileKeys,
+    /// How many times per second the background jobs run.
+    pub hz: u64,
+    pub stats: Stats,
+}
+
+/// Statistics of the server, named as in the stats section of `INFO`.
+#[derive(Debug, Default)]
+pub struct Stats {
+    /// Keys removed as their expire time passed, lazily on access or by the active expiry.
+    pub expired_keys: u64,
+    /// An estimate of the percentage of keys with an expire time which are expired but not
+    /// removed yet, as a moving average over the active expiry cycles.
+    pub expired_stale_perc: f64,
 }
 
 impl Server {
@@ -34,10 +77,155 @@ impl Server {
         Self {
             addr,
             rdb_file: rdb_filename,
-            db: HashMap::new(),
+            db: Db::new(),
             conn_num: 0,
+            blocking: BlockingClients::default(),
+            volatile_hashes: VolatileKeys::default(),
+            volatile_keys: VolatileKeys::default(),
+            hz: DEFAULT_HZ,
+            stats: Stats::default(),
         }
     }
+
+    /// Load the keyspace from the RDB file, if it exists.
+    pub fn load_rdb(&mut self) -> Result<(), rdb::Error> {
+        let content = match fs::read(&self.rdb_file) {
+            Ok(content) => content,
+            Err(err) => {
+                tracing::info!(
+                    "No RDB file loaded from {}: {}",
+                    self.rdb_file.display(),
+                    err
+                );
+                return Ok(());
+            }
+        };
+        self.db = rdb::load(&content)?;
+        self.volatile_hashes = self
+            .db
+            .iter()
+            .filter(
+                |(_, (value, _))| matches!(value, Value::Hash(hash) if hash.has_volatile_fields()),
+            )
+            .map(|(key, _)| key.clone())
+            .collect();
+        self.volatile_keys = VolatileKeys::default();
+        for (key, (_, expire_time)) in self.db.iter() {
+            if expire_time.is_some() {
+                self.volatile_keys.insert(key.clone());
+            }
+        }
+        tracing::info!(
+            "Loaded {} keys from {}",
+            self.db.len(),
+            self.rdb_file.display()
+        );
+        Ok(())
+    }
+
+    /// Save the keyspace to the RDB file. The file is written aside then renamed, so that a
+    /// failed save never leaves a truncated file behind.
+    pub fn save_rdb(&self) -> io::Result<()> {
+        let temp_file = self
+            .rdb_file
+            .with_file_name(format!("temp-{}.rdb", std::process::id()));
+        fs::write(&temp_file, rdb::dump(&self.db))?;
+        fs::rename(&temp_file, &self.rdb_file)
+    }
+
+    /// Remove expired keys, sampling keys with an expire time at random as Redis does: each
+    /// sample deletes its expired keys, and another one is taken while more than
+    /// [`ACTIVE_EXPIRE_ACCEPTABLE_STALE_PERC`] percent of it was expired, until the cycle runs out
+    /// of its share of CPU time.
+    pub fn active_expire_keys(&mut self) {
+        let start = Instant::now();
+        let time_limit =
+            Duration::from_micros(1_000_000 * ACTIVE_EXPIRE_TIME_PERC / 100 / self.hz.max(1));
+        let (mut total_sampled, mut total_expired) = (0, 0);
+
+        while !self.volatile_keys.is_empty() && start.elapsed() < time_limit {
+            let now = unix_time_ms();
+            let (mut sampled, mut expired) = (0, 0);
+            for key in self.volatile_keys.sample(ACTIVE_EXPIRE_KEYS_PER_LOOP) {
+                match self.db.get(&key) {
+                    Some((_, Some(when))) => {
+                        sampled += 1;
+                        if *when <= now {
+                            self.db.remove(&key);
+                            self.volatile_keys.remove(&key);
+                            expired += 1;
+                        }
+                    }
+                    // Deleted or persisted since it was given an expire time
+                    _ => self.volatile_keys.remove(&key),
+                }
+            }
+            total_sampled += sampled;
+            total_expired += expired;
+            if sampled > 0 && expired * 100 <= sampled * ACTIVE_EXPIRE_ACCEPTABLE_STALE_PERC {
+                break;
+            }
+        }
+
+        self.stats.expired_keys += total_expired as u64;
+        let current_perc = if total_sampled > 0 {
+            total_expired as f64 * 100.0 / total_sampled as f64
+        } else {
+            0.0
+        };
+        self.stats.expired_stale_perc = current_perc * 0.05 + self.stats.expired_stale_perc * 0.95;
+    }
+
+    /// Remove expired hash fields, and the hashes left without fields, sampling the hashes with
+    /// volatile fields as [`Server::active_expire_keys`] samples keys. A sample removes at most
+    /// [`ACTIVE_EXPIRE_FIELDS_PER_LOOP`] fields, and another one is taken while it hit that limit
+    /// or more than [`ACTIVE_EXPIRE_ACCEPTABLE_STALE_PERC`] percent of its hashes had expired
+    /// fields, until the cycle runs out of its share of CPU time.
+    pub fn active_expire_hash_fields(&mut self) {
+        let start = Instant::now();
+        let time_limit =
+            Duration::from_micros(1_000_000 * ACTIVE_EXPIRE_TIME_PERC / 100 / self.hz.max(1));
+
+        while !self.volatile_hashes.is_empty() && start.elapsed() < time_limit {
+            let now = unix_time_ms();
+            let (mut sampled, mut stale) = (0, 0);
+            let mut fields_left = ACTIVE_EXPIRE_FIELDS_PER_LOOP;
+            for key in self.volatile_hashes.sample(ACTIVE_EXPIRE_KEYS_PER_LOOP) {
+                let Some((Value::Hash(hash), _)) = self.db.get_mut(&key) else {
+                    self.volatile_hashes.remove(&key);
+                    continue;
+                };
+                sampled += 1;
+                let removed = hash.remove_expired_up_to(now, fields_left);
+                fields_left -= removed;
+                if removed > 0 {
+                    stale += 1;
+                }
+                if hash.is_empty() {
+                    self.db.remove(&key);
+                    self.volatile_hashes.remove(&key);
+                } else if !hash.has_volatile_fields() {
+                    self.volatile_hashes.remove(&key);
+                }
+                if fields_left == 0 {
+                    break;
+                }
+            }
+            if fields_left > 0
+                && sampled > 0
+                && stale * 100 <= sampled * ACTIVE_EXPIRE_ACCEPTABLE_STALE_PERC
+            {
+                break;
+            }
+        }
+    }
+
+    /// Serve the clients blocked on `key`, after elements were added to it.
+    pub fn serve_blocked_clients(&mut self, key: &Key) {
+        self.blocking.signal_key_as_ready(key);
+        self.blocking
+            .serve_ready_keys(&mut self.db, &mut self.stats);
+    }
 }
 
 pub struct Connection {
@@ -47,6 +235,7 @@ pub struct Connection {
     pub name: String,
     pub lib_name: String,
     pub lib_ver: String,
+    pub protocol: RespProtocol,
 }
 
 impl Connection {
@@ -58,6 +247,7 @@ impl Connection {
             name: String::new(),
             lib_name: String::new(),
             lib_ver: String::new(),
+            protocol: RespProtocol::default(),
         }
     }
 }
@@ -65,16 +255,39 @@ impl Connection {
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 enum Error {
-    #[error("Parse RESP failed: {}", .0)]
+    #[error("ERR Protocol error: {}", .0)]
     RespParseError(#[from] resp::ParseError),
 
     #[error("Parse redis command failed: {}", .0)]
-    CommandParseError(#[from] command::ParseError),
+    CommandParseError(command::ParseError),
 
-    #[error("Execute command failed: {}", .0)]
+    /// Execution errors are already formatted as Redis error replies.
+    #[error("{}", .0)]
     CommandExecError(#[from] command::ExecError),
 }
 
+impl From<command::ParseError> for Error {
+    fn from(err: command::ParseError) -> Self {
+        match err {
+            command::ParseError::Rejected(err) => Error::CommandExecError(err),
+            err => Error::CommandParseError(err),
+        }
+    }
+}
+
+/// Run the periodic background jobs of the server, such as the active expiry, until the process
+/// exits.
+pub async fn server_cron(server: Arc<Mutex<Server>>) {
+    let hz = server.lock().await.hz;
+    let mut interval = tokio::time::interval(Duration::from_millis(1000 / hz));
+    loop {
+        interval.tick().await;
+        let mut server = server.lock().await;
+        server.active_expire_keys();
+        server.active_expire_hash_fields();
+    }
+}
+
 pub async fn handle_connection(server: Arc<Mutex<Server>>, mut conn: Connection) {
     let mut input_buffer = BytesMut::with_capacity(BUFFER_INITIAL_SIZE);
     let mut output_buffer = BytesMut::with_capacity(BUFFER_INITIAL_SIZE);
@@ -84,28 +297,242 @@ pub async fn handle_connection(server: Arc<Mutex<Server>>, mut conn: Connection)
     {
         tracing::info!("Command: {}", BytesInStr::from_bytes(&input_buffer));
 
-        // Stream-friendly parse: parse on a snapshot and consume input only after a full frame.
-        let mut parsing_buffer = input_buffer.clone();
+        let keep_open =
+            process_input_buffer(&server, &mut conn, &mut input_buffer, &mut output_buffer).await;
+
+        if let Err(err) = conn.stream.write_all_buf(&mut output_buffer).await {
+            tracing::error!("Failed to send result to client: {}", err);
+        }
+        if !keep_open {
+            break;
+        }
+    }
+
+    server.lock().await.conn_num -= 1;
+}
+
+/// Execute every complete frame held in `input_buffer` in order and append the replies to
+/// `output_buffer`. A trailing partial frame is kept in `input_buffer` until more bytes arrive.
+///
+/// Returns `false` after a protocol error, whose reply is the last one: the rest of the input is
+/// discarded as where the next frame starts is unknown, and the connection must be closed.
+async fn process_input_buffer(
+    server: &Arc<Mutex<Server>>,
+    conn: &mut Connection,
+    input_buffer: &mut BytesMut,
+    output_buffer: &mut BytesMut,
+) -> bool {
+    // Stream-friendly parse: parse on a snapshot and consume input only after full frames.
+    let mut parsing_buffer = input_buffer.clone();
+    let mut unconsumed = parsing_buffer.len();
+
+    while !parsing_buffer.is_empty() {
         let result: Result<RespData, Error> = async {
             let request = parse_client_request(&mut parsing_buffer)?;
             let command = parse_command(&request)?;
-            let resp = command.execute(server.clone(), &mut conn).await?;
+            let resp = command.execute(server.clone(), conn).await?;
             Ok(resp)
         }
         .await;
 
         match result {
-            Err(Error::RespParseError(resp::ParseError::Eof(_))) => continue,
-            Ok(resp) => serialize_resp(&mut output_buffer, &resp),
-            Err(err) => serialize_simple_error(&mut output_buffer, err.to_string().as_str()),
+            Err(Error::RespParseError(resp::ParseError::Eof(_))) => break,
+            Err(err @ Error::RespParseError(_)) => {
+                serialize_simple_error(output_buffer, err.to_string().as_str());
+                input_buffer.clear();
+                return false;
+            }
+            Ok(resp) => serialize_resp(output_buffer, &resp, conn.protocol),
+            Err(err) => serialize_simple_error(output_buffer, err.to_string().as_str()),
         }
+        unconsumed = parsing_buffer.len();
+    }
 
-        let consumed = input_buffer.len() - parsing_buffer.len();
-        input_buffer.advance(consumed);
-        if let Err(err) = conn.stream.write_all_buf(&mut output_buffer).await {
-            tracing::error!("Failed to send result to client: {}", err);
+    input_buffer.advance(input_buffer.len() - unconsumed);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Bytes, BytesMut};
+
+    use super::{ACTIVE_EXPIRE_FIELDS_PER_LOOP, process_input_buffer};
+    use crate::{
+        command::test::build_server_connection,
+        db::{Hash, Value},
+        utils::unix_time_ms,
+    };
+
+    #[tokio::test]
+    async fn process_input_buffer_should_answer_every_pipelined_command() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut input = BytesMut::from(
+            "*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nECHO\r\n$2\r\nhi\r\n*1\r\n$4\r\nPING\r\n",
+        );
+        let mut output = BytesMut::new();
+
+        assert!(process_input_buffer(&server, &mut conn, &mut input, &mut output).await);
+
+        assert_eq!(output.as_ref(), b"+PONG\r\n$2\r\nhi\r\n+PONG\r\n");
+        assert!(input.is_empty(), "all frames should be consumed");
+    }
+
+    #[tokio::test]
+    async fn process_input_buffer_should_keep_trailing_partial_frame() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut input = BytesMut::from("*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nECHO\r\n$5\r\nwo");
+        let mut output = BytesMut::new();
+
+        assert!(process_input_buffer(&server, &mut conn, &mut input, &mut output).await);
+        assert_eq!(output.as_ref(), b"+PONG\r\n");
+        assert_eq!(input.as_ref(), b"*2\r\n$4\r\nECHO\r\n$5\r\nwo");
+
+        output.clear();
+        input.extend_from_slice(b"rld\r\n*1\r\n$4\r\nPI");
+        assert!(process_input_buffer(&server, &mut conn, &mut input, &mut output).await);
+        assert_eq!(output.as_ref(), b"$5\r\nworld\r\n");
+        assert_eq!(input.as_ref(), b"*1\r\n$4\r\nPI");
+    }
+
+    #[tokio::test]
+    async fn process_input_buffer_should_reply_errors_in_order() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut input = BytesMut::from("*2\r\n$4\r\nPING\r\n$1\r\nx\r\n*1\r\n$4\r\nPING\r\n");
+        let mut output = BytesMut::new();
+
+        assert!(process_input_buffer(&server, &mut conn, &mut input, &mut output).await);
+
+        assert!(output.starts_with(b"-Parse redis command failed"));
+        assert!(output.ends_with(b"\r\n+PONG\r\n"));
+        assert!(input.is_empty());
+    }
+
+    #[tokio::test]
+    async fn process_input_buffer_should_reply_rejected_argument_as_is() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut input = BytesMut::from("*3\r\n$6\r\nINCRBY\r\n$1\r\nk\r\n$3\r\nabc\r\n");
+        let mut output = BytesMut::new();
+
+        assert!(process_input_buffer(&server, &mut conn, &mut input, &mut output).await);
+        assert_eq!(
+            output.as_ref(),
+            b"-ERR value is not an integer or out of range\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn process_input_buffer_should_reply_unbalanced_quotes() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut input = BytesMut::from("SET k \"v\r\n");
+        let mut output = BytesMut::new();
+
+        assert!(!process_input_buffer(&server, &mut conn, &mut input, &mut output).await);
+        assert_eq!(
+            output.as_ref(),
+            b"-ERR Protocol error: unbalanced quotes in request\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn process_input_buffer_should_stop_at_protocol_error() {
+        let (server, mut conn) = build_server_connection().await;
+        let mut input =
+            BytesMut::from("*1\r\n$4\r\nPING\r\n*2\r\n$3\r\nGET\r\n$10\r\nab\r\nSET x pwned\r\n");
+        let mut output = BytesMut::new();
+
+        let keep_open = process_input_buffer(&server, &mut conn, &mut input, &mut output).await;
+
+        assert!(!keep_open);
+        assert!(output.starts_with(b"+PONG\r\n-ERR Protocol error: "));
+        assert!(input.is_empty());
+        assert!(server.lock().await.db.is_empty());
+    }
+
+    #[tokio::test]
+    async fn active_expire_keys_should_remove_expired_keys() {
+        let (server, _conn) = build_server_connection().await;
+        let mut server = server.lock().await;
+
+        let now = unix_time_ms();
+        for i in 0..110 {
+            let key = Bytes::from(format!("key{i}"));
+            let expire_time = if i < 100 { now - 1 } else { now + 60_000 };
+            server.db.insert(
+                key.clone(),
+                (Value::String(Bytes::from_owner("v")), Some(expire_time)),
+            );
+            server.volatile_keys.insert(key);
         }
+        server.db.insert(
+            Bytes::from_owner("persistent"),
+            (Value::String(Bytes::from_owner("v")), None),
+        );
+        server.volatile_keys.insert(Bytes::from_owner("persistent"));
+        server.volatile_keys.insert(Bytes::from_owner("missing"));
+
+        // A sample holds at least 10 expired keys until all of them fit in one
+        server.active_expire_keys();
+
+        assert_eq!(server.db.len(), 11);
+        assert_eq!(server.volatile_keys.len(), 10);
+        assert_eq!(server.stats.expired_keys, 100);
+        assert!(server.stats.expired_stale_perc > 0.0);
     }
 
-    server.lock().await.conn_num -= 1;
+    #[tokio::test]
+    async fn active_expire_hash_fields_should_remove_expired_fields_and_empty_hashes() {
+        let (server, _conn) = build_server_connection().await;
+        let mut server = server.lock().await;
+
+        let mut partly = Hash::from([
+            (Bytes::from_owner("old"), Bytes::from_owner("1")),
+            (Bytes::from_owner("new"), Bytes::from_owner("2")),
+        ]);
+        partly.set_expire_time(&Bytes::from_owner("old"), 1);
+        partly.set_expire_time(&Bytes::from_owner("new"), unix_time_ms() + 60_000);
+        let mut fully = Hash::from([(Bytes::from_owner("old"), Bytes::from_owner("1"))]);
+        fully.set_expire_time(&Bytes::from_owner("old"), 1);
+        for (key, hash) in [("partly", partly), ("fully", fully)] {
+            server
+                .db
+                .insert(Bytes::from_owner(key), (Value::Hash(hash), None));
+            server.volatile_hashes.insert(Bytes::from_owner(key));
+        }
+        server.volatile_hashes.insert(Bytes::from_owner("missing"));
+
+        server.active_expire_hash_fields();
+
+        assert_eq!(server.db.len(), 1);
+        let Some((Value::Hash(hash), _)) = server.db.get(b"partly".as_ref()) else {
+            panic!("expected a hash");
+        };
+        assert_eq!(hash.len(), 1);
+        assert!(hash.contains_key(b"new"));
+        assert_eq!(server.volatile_hashes.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn active_expire_hash_fields_should_sample_again_past_field_limit() {
+        let (server, _conn) = build_server_connection().await;
+        let mut server = server.lock().await;
+
+        let mut hash = Hash::from([(Bytes::from_owner("live"), Bytes::from_owner("v"))]);
+        for i in 0..ACTIVE_EXPIRE_FIELDS_PER_LOOP * 3 {
+            let field = Bytes::from(format!("field{i}"));
+            hash.insert(field.clone(), Bytes::from_owner("v"));
+            hash.set_expire_time(&field, 1);
+        }
+        server
+            .db
+            .insert(Bytes::from_owner("h"), (Value::Hash(hash), None));
+        server.volatile_hashes.insert(Bytes::from_owner("h"));
+
+        server.active_expire_hash_fields();
+
+        let Some((Value::Hash(hash), _)) = server.db.get(b"h".as_ref()) else {
+            panic!("expected a hash");
+        };
+        assert_eq!(hash.len(), 1);
+        assert!(server.volatile_hashes.is_empty());
+    }
 }
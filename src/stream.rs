@@ -0,0 +1,1123 @@
+//! The stream type, an append-only log of field-value entries identified by increasing IDs.
+//!
+//! Entries are stored like Redis does: in listpack nodes of up to [`NODE_MAX_ENTRIES`] entries,
+//! keyed by the ID of their first entry, the master ID. A node starts with a master entry
+//! holding its live and deleted entry counts and the fields of its first entry. Every entry then
+//! stores its ID relative to the master ID, and only its values when it has the same fields as
+//! the master entry, which is the common case:
+//!
+//! ```text
+//! master: count | deleted | num-fields | field... | 0
+//! entry:  flags | ms-diff | seq-diff | [num-fields | field value...] or [value...] | lp-count
+//! ```
+//!
+//! Deleted entries are only flagged, and a node is freed once all its entries are deleted.
+//!
+//! A stream also keeps its consumer groups, which track the entries delivered to each of their
+//! consumers until they are acknowledged.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, btree_map},
+    fmt,
+};
+
+use bytes::Bytes;
+
+use crate::listpack::{Element, Listpack};
+
+/// The most entries a node holds, deleted ones included, like `stream-node-max-entries`.
+pub const NODE_MAX_ENTRIES: usize = 100;
+/// The size in bytes past which a node takes no more entries, like `stream-node-max-bytes`.
+const NODE_MAX_BYTES: usize = 4096;
+
+const FLAG_DELETED: i64 = 1;
+const FLAG_SAME_FIELDS: i64 = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl StreamId {
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    pub const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    pub fn new(ms: u64, seq: u64) -> Self {
+        StreamId { ms, seq }
+    }
+
+    /// Parse `<ms>-<seq>`, or `<ms>` alone which takes `default_seq`.
+    pub fn parse(arg: &[u8], default_seq: u64) -> Option<StreamId> {
+        let arg = str::from_utf8(arg).ok()?;
+        let (ms, seq) = match arg.split_once('-') {
+            Some((ms, seq)) => (ms, seq.parse().ok()?),
+            None => (arg, default_seq),
+        };
+        Some(StreamId::new(ms.parse().ok()?, seq))
+    }
+
+    /// The smallest ID greater than this one, `None` for the maximum ID.
+    pub fn next(&self) -> Option<StreamId> {
+        match (self.ms, self.seq) {
+            (u64::MAX, u64::MAX) => None,
+            (ms, u64::MAX) => Some(StreamId::new(ms + 1, 0)),
+            (ms, seq) => Some(StreamId::new(ms, seq + 1)),
+        }
+    }
+
+    /// The greatest ID smaller than this one, `None` for the minimum ID.
+    pub fn prev(&self) -> Option<StreamId> {
+        match (self.ms, self.seq) {
+            (0, 0) => None,
+            (ms, 0) => Some(StreamId::new(ms - 1, u64::MAX)),
+            (ms, seq) => Some(StreamId::new(ms, seq - 1)),
+        }
+    }
+
+    /// The big-endian form keying the nodes in RDB files, which sorts like the IDs.
+    pub fn to_be_bytes(self) -> [u8; 16] {
+        ((self.ms as u128) << 64 | self.seq as u128).to_be_bytes()
+    }
+
+    pub fn from_be_bytes(bytes: &[u8]) -> Option<StreamId> {
+        let id = u128::from_be_bytes(bytes.try_into().ok()?);
+        Some(StreamId::new((id >> 64) as u64, id as u64))
+    }
+}
+
+impl fmt::Display for StreamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+/// An entry with its field-value pairs in insertion order.
+pub type StreamEntry = (StreamId, Vec<(Bytes, Bytes)>);
+
+/// How `XADD` and `XTRIM` trim a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrimStrategy {
+    /// Keep at most this many entries.
+    MaxLen(usize),
+    /// Remove the entries with a smaller ID.
+    MinId(StreamId),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stream {
+    nodes: BTreeMap<StreamId, Listpack>,
+    len: usize,
+    last_id: StreamId,
+    /// The ID of the first entry, `0-0` when the stream is empty.
+    first_id: StreamId,
+    /// The greatest ID deleted by `XDEL`.
+    max_deleted_id: StreamId,
+    /// How many entries were ever added.
+    entries_added: u64,
+    groups: BTreeMap<Bytes, ConsumerGroup>,
+}
+
+impl Stream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    pub fn first_id(&self) -> StreamId {
+        self.first_id
+    }
+
+    pub fn max_deleted_id(&self) -> StreamId {
+        self.max_deleted_id
+    }
+
+    pub fn entries_added(&self) -> u64 {
+        self.entries_added
+    }
+
+    /// The nodes keyed by their master ID, as saved in RDB files.
+    pub fn nodes(&self) -> btree_map::Iter<'_, StreamId, Listpack> {
+        self.nodes.iter()
+    }
+
+    /// Rebuild a stream from the nodes and metadata saved in an RDB file. Returns `None` if a
+    /// node is corrupted.
+    pub fn from_nodes(
+        nodes: BTreeMap<StreamId, Listpack>,
+        last_id: StreamId,
+        max_deleted_id: StreamId,
+        entries_added: Option<u64>,
+    ) -> Option<Stream> {
+        let mut len = 0;
+        let mut prev_id = None;
+        for (master_id, listpack) in &nodes {
+            let node = Node::new(*master_id, listpack)?;
+            let (mut live, mut deleted) = (0, 0);
+            let mut position = node.entries_position;
+            while position != listpack.end() {
+                let (entry, next) = node.read_entry(position)?;
+                if prev_id.is_some_and(|prev_id| entry.id <= prev_id) || entry.id > last_id {
+                    return None;
+                }
+                prev_id = Some(entry.id);
+                if entry.is_deleted() {
+                    deleted += 1;
+                } else {
+                    live += 1;
+                }
+                position = next;
+            }
+            if live != node.count || deleted != node.deleted || live == 0 {
+                return None;
+            }
+            len += live;
+        }
+
+        let mut stream = Stream {
+            nodes,
+            len,
+            last_id,
+            first_id: StreamId::MIN,
+            max_deleted_id,
+            entries_added: entries_added.unwrap_or(len as u64),
+            groups: BTreeMap::new(),
+        };
+        stream.update_first_id();
+        Some(stream)
+    }
+
+    /// The ID `XADD *` gives the next entry at Unix time `now_ms`: the time itself, or the last
+    /// ID plus one if the clock went backwards. `None` once the IDs are exhausted.
+    pub fn next_id(&self, now_ms: u64) -> Option<StreamId> {
+        if now_ms > self.last_id.ms {
+            Some(StreamId::new(now_ms, 0))
+        } else {
+            self.last_id.next()
+        }
+    }
+
+    /// The ID `XADD <ms>-*` gives the next entry, `None` if it would not be greater than the
+    /// last ID.
+    pub fn next_id_at(&self, ms: u64) -> Option<StreamId> {
+        if ms > self.last_id.ms {
+            Some(StreamId::new(ms, 0))
+        } else if ms == self.last_id.ms {
+            self.last_id
+                .seq
+                .checked_add(1)
+                .map(|seq| StreamId::new(ms, seq))
+        } else {
+            None
+        }
+    }
+
+    /// Append an entry, whose ID must be greater than the last ID.
+    pub fn add(&mut self, id: StreamId, fields: &[(Bytes, Bytes)]) {
+        debug_assert!(id > self.last_id);
+
+        // Append to the last node while it has room, comparing the fields with its master entry
+        let last = self
+            .nodes
+            .last_key_value()
+            .and_then(|(master_id, listpack)| {
+                let node = Node::new(*master_id, listpack).unwrap();
+                let fits = listpack.size() < NODE_MAX_BYTES
+                    && node.count + node.deleted < NODE_MAX_ENTRIES;
+                let same_fields = node.master_fields.len() == fields.len()
+                    && node
+                        .master_fields
+                        .iter()
+                        .zip(fields)
+                        .all(|(master_field, (field, _))| master_field.eq_bytes(field));
+                fits.then_some((*master_id, node.count, same_fields))
+            });
+        let (master_id, count, same_fields) = last.unwrap_or_else(|| {
+            let mut listpack = Listpack::new();
+            listpack.push_int(0);
+            listpack.push_int(0);
+            listpack.push_int(fields.len() as i64);
+            for (field, _) in fields {
+                listpack.push(field);
+            }
+            listpack.push_int(0);
+            self.nodes.insert(id, listpack);
+            (id, 0, true)
+        });
+        // The node was either found or inserted just above
+        let listpack = self.nodes.get_mut(&master_id).unwrap();
+
+        listpack.push_int(if same_fields { FLAG_SAME_FIELDS } else { 0 });
+        listpack.push_int(id.ms.wrapping_sub(master_id.ms) as i64);
+        listpack.push_int(id.seq.wrapping_sub(master_id.seq) as i64);
+        if same_fields {
+            for (_, value) in fields {
+                listpack.push(value);
+            }
+            listpack.push_int(fields.len() as i64 + 3);
+        } else {
+            listpack.push_int(fields.len() as i64);
+            for (field, value) in fields {
+                listpack.push(field);
+                listpack.push(value);
+            }
+            listpack.push_int(fields.len() as i64 * 2 + 4);
+        }
+        listpack.replace_int(listpack.first(), count as i64 + 1);
+
+        if self.len == 0 {
+            self.first_id = id;
+        }
+        self.len += 1;
+        self.last_id = id;
+        self.entries_added += 1;
+    }
+
+    /// The entries with an ID within `start..=end`, in increasing order.
+    pub fn range(&self, start: StreamId, end: StreamId) -> Range<'_> {
+        Range {
+            nodes: Box::new(self.nodes_within(start, end)),
+            node: None,
+            pending: Vec::new().into_iter(),
+            start,
+            end,
+            rev: false,
+        }
+    }
+
+    /// The entries with an ID within `start..=end`, in decreasing order.
+    pub fn rev_range(&self, start: StreamId, end: StreamId) -> Range<'_> {
+        Range {
+            nodes: Box::new(self.nodes_within(start, end).rev()),
+            node: None,
+            pending: Vec::new().into_iter(),
+            start,
+            end,
+            rev: true,
+        }
+    }
+
+    /// The nodes which may hold entries within `start..=end`.
+    fn nodes_within(
+        &self,
+        start: StreamId,
+        end: StreamId,
+    ) -> btree_map::Range<'_, StreamId, Listpack> {
+        // The node holding `start` may begin before it
+        let first = self
+            .nodes
+            .range(..=start)
+            .next_back()
+            .map_or(start, |(master_id, _)| *master_id);
+        if first <= end {
+            self.nodes.range(first..=end)
+        } else {
+            self.nodes.range(StreamId::MAX..StreamId::MAX)
+        }
+    }
+
+    pub fn first_entry(&self) -> Option<StreamEntry> {
+        self.range(StreamId::MIN, StreamId::MAX).next()
+    }
+
+    pub fn last_entry(&self) -> Option<StreamEntry> {
+        self.rev_range(StreamId::MIN, StreamId::MAX).next()
+    }
+
+    /// Delete the entry `id`. Returns whether it existed.
+    pub fn remove(&mut self, id: StreamId) -> bool {
+        let Some((&master_id, listpack)) = self.nodes.range_mut(..=id).next_back() else {
+            return false;
+        };
+        let node = Node::new(master_id, &*listpack).unwrap();
+        let Some(entry) = node
+            .entries()
+            .find(|entry| entry.id == id && !entry.is_deleted())
+        else {
+            return false;
+        };
+
+        let count = node.count - 1;
+        mark_deleted(listpack, &[entry], count);
+        if count == 0 {
+            self.nodes.remove(&master_id);
+        }
+
+        self.len -= 1;
+        self.max_deleted_id = self.max_deleted_id.max(id);
+        if id == self.first_id {
+            self.update_first_id();
+        }
+        true
+    }
+
+    /// Trim the stream by `strategy`. An approximate trim only frees whole nodes, which is much
+    /// cheaper, and may keep a few more entries than asked. A `limit` other than 0 caps the
+    /// entries removed. Returns how many entries were removed.
+    pub fn trim(&mut self, strategy: TrimStrategy, approx: bool, limit: usize) -> usize {
+        let mut removed = 0;
+        while let Some(mut first) = self.nodes.first_entry() {
+            if let TrimStrategy::MaxLen(max_len) = strategy
+                && self.len <= max_len
+            {
+                break;
+            }
+
+            let node = Node::new(*first.key(), first.get()).unwrap();
+            let count = node.count;
+            if limit != 0 && removed + count > limit {
+                break;
+            }
+            let entries: Vec<_> = node.entries().collect();
+            let remove_node = match strategy {
+                TrimStrategy::MaxLen(max_len) => self.len - count >= max_len,
+                TrimStrategy::MinId(min_id) => entries.last().is_some_and(|last| last.id < min_id),
+            };
+            if remove_node {
+                first.remove();
+                self.len -= count;
+                removed += count;
+                continue;
+            }
+            if approx {
+                break;
+            }
+
+            // Only part of the first node goes, so flag its entries deleted
+            let mut deleted = Vec::new();
+            for entry in entries.into_iter().filter(|entry| !entry.is_deleted()) {
+                let done = match strategy {
+                    TrimStrategy::MaxLen(max_len) => self.len - deleted.len() <= max_len,
+                    TrimStrategy::MinId(min_id) => entry.id >= min_id,
+                };
+                if done {
+                    break;
+                }
+                deleted.push(entry);
+            }
+            mark_deleted(first.get_mut(), &deleted, count - deleted.len());
+            self.len -= deleted.len();
+            removed += deleted.len();
+            break;
+        }
+
+        if removed > 0 {
+            self.update_first_id();
+        }
+        removed
+    }
+
+    fn update_first_id(&mut self) {
+        self.first_id = self
+            .first_entry()
+            .map_or(StreamId::MIN, |(first_id, _)| first_id);
+    }
+
+    /// The entry `id`, `None` if it does not exist or was deleted.
+    pub fn get(&self, id: StreamId) -> Option<StreamEntry> {
+        self.range(id, id).next()
+    }
+
+    pub fn contains(&self, id: StreamId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// The consumer groups by name.
+    pub fn groups(&self) -> btree_map::Iter<'_, Bytes, ConsumerGroup> {
+        self.groups.iter()
+    }
+
+    pub fn group(&self, name: &[u8]) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    pub fn group_mut(&mut self, name: &[u8]) -> Option<&mut ConsumerGroup> {
+        self.groups.get_mut(name)
+    }
+
+    /// Add a consumer group. Returns `false`, leaving the stream unchanged, if a group with this
+    /// name exists.
+    pub fn create_group(&mut self, name: Bytes, group: ConsumerGroup) -> bool {
+        match self.groups.entry(name) {
+            btree_map::Entry::Occupied(_) => false,
+            btree_map::Entry::Vacant(vacant) => {
+                vacant.insert(group);
+                true
+            }
+        }
+    }
+
+    pub fn destroy_group(&mut self, name: &[u8]) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    /// Deliver up to `count` entries never delivered to the group `name` to its `consumer`,
+    /// which become pending unless `noack`. Returns `None` if the group does not exist.
+    pub fn read_group(
+        &mut self,
+        name: &[u8],
+        consumer: &Bytes,
+        count: usize,
+        noack: bool,
+        now_ms: u64,
+    ) -> Option<Vec<StreamEntry>> {
+        let group = self.groups.get(name)?;
+        let entries: Vec<_> = match group.last_id.next() {
+            Some(start) => self.range(start, StreamId::MAX).take(count).collect(),
+            None => Vec::new(),
+        };
+
+        // Count the reads while no deleted entry lies ahead, which would make the count drift
+        let mut entries_read = group.entries_read;
+        for (id, _) in &entries {
+            entries_read = match entries_read {
+                Some(read) if !self.has_tombstones_from(*id) => Some(read + 1),
+                _ if self.entries_added > 0 => self.estimate_entries_read(*id),
+                _ => entries_read,
+            };
+        }
+
+        // The group was found above
+        let group = self.groups.get_mut(name).unwrap();
+        let seen = group.see_consumer(consumer, now_ms);
+        if let Some((last_id, _)) = entries.last() {
+            seen.active_time = Some(now_ms);
+            group.last_id = *last_id;
+            group.entries_read = entries_read;
+        }
+        if !noack {
+            for (id, _) in &entries {
+                group.claim(*id, consumer, now_ms, 1);
+            }
+        }
+        Some(entries)
+    }
+
+    /// Whether entries from `start` on may have been deleted.
+    fn has_tombstones_from(&self, start: StreamId) -> bool {
+        self.len > 0
+            && self.max_deleted_id != StreamId::MIN
+            && self.first_id <= self.max_deleted_id
+            && start <= self.max_deleted_id
+    }
+
+    /// How many entries were added up to `id` included, when it can be told without counting
+    /// them.
+    pub fn estimate_entries_read(&self, id: StreamId) -> Option<u64> {
+        if self.entries_added == 0 {
+            return Some(0);
+        }
+        if id >= self.last_id {
+            // The count of a future ID is unknown
+            return (id == self.last_id).then_some(self.entries_added);
+        }
+        if self.is_empty() {
+            return Some(self.entries_added);
+        }
+        // Without deletions past the first entry, the entries are contiguous
+        if self.max_deleted_id == StreamId::MIN || self.max_deleted_id < self.first_id {
+            if id < self.first_id {
+                return Some(self.entries_added - self.len as u64);
+            } else if id == self.first_id {
+                return Some(self.entries_added - self.len as u64 + 1);
+            }
+        }
+        None
+    }
+
+    /// How many entries the group has yet to read, when it can be told.
+    pub fn lag(&self, group: &ConsumerGroup) -> Option<u64> {
+        if self.entries_added == 0 {
+            return Some(0);
+        }
+        let entries_read = match group.entries_read {
+            Some(read) if !self.has_tombstones_from(group.last_id) => read,
+            _ => self.estimate_entries_read(group.last_id)?,
+        };
+        Some(self.entries_added.saturating_sub(entries_read))
+    }
+}
+
+/// An entry delivered to a consumer of a group and not acknowledged yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingEntry {
+    pub consumer: Bytes,
+    /// The Unix time in milliseconds of the last delivery.
+    pub delivery_time: u64,
+    pub delivery_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consumer {
+    /// The Unix time in milliseconds of the last attempted interaction.
+    pub seen_time: u64,
+    /// The Unix time in milliseconds of the last successful interaction, `None` before any.
+    pub active_time: Option<u64>,
+    /// The IDs of the entries pending for this consumer.
+    pending: BTreeSet<StreamId>,
+}
+
+impl Consumer {
+    pub fn new(seen_time: u64, active_time: Option<u64>) -> Self {
+        Consumer {
+            seen_time,
+            active_time,
+            pending: BTreeSet::new(),
+        }
+    }
+
+    pub fn pending(&self) -> &BTreeSet<StreamId> {
+        &self.pending
+    }
+}
+
+/// A consumer group, whose pending entries list the entries delivered to its consumers which
+/// are not acknowledged yet. Every pending entry is also listed by the consumer owning it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConsumerGroup {
+    /// The ID of the last entry delivered to the group.
+    last_id: StreamId,
+    /// How many entries the group read, `None` when unknown.
+    entries_read: Option<u64>,
+    pending: BTreeMap<StreamId, PendingEntry>,
+    consumers: BTreeMap<Bytes, Consumer>,
+}
+
+impl ConsumerGroup {
+    pub fn new(last_id: StreamId, entries_read: Option<u64>) -> Self {
+        ConsumerGroup {
+            last_id,
+            entries_read,
+            ..Default::default()
+        }
+    }
+
+    /// Rebuild a group saved in an RDB file, listing the pending entries of each consumer.
+    /// Returns `None` if an entry is pending for a missing consumer.
+    pub fn from_parts(
+        last_id: StreamId,
+        entries_read: Option<u64>,
+        pending: BTreeMap<StreamId, PendingEntry>,
+        mut consumers: BTreeMap<Bytes, Consumer>,
+    ) -> Option<Self> {
+        for (id, entry) in &pending {
+            consumers.get_mut(&entry.consumer)?.pending.insert(*id);
+        }
+        Some(ConsumerGroup {
+            last_id,
+            entries_read,
+            pending,
+            consumers,
+        })
+    }
+
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    pub fn entries_read(&self) -> Option<u64> {
+        self.entries_read
+    }
+
+    pub fn set_last_id(&mut self, last_id: StreamId, entries_read: Option<u64>) {
+        self.last_id = last_id;
+        self.entries_read = entries_read;
+    }
+
+    /// Move the last ID forward to `last_id`, keeping the read count.
+    pub fn advance_last_id(&mut self, last_id: StreamId) {
+        self.last_id = self.last_id.max(last_id);
+    }
+
+    /// The pending entries list, by ID.
+    pub fn pending(&self) -> &BTreeMap<StreamId, PendingEntry> {
+        &self.pending
+    }
+
+    pub fn pending_mut(&mut self, id: StreamId) -> Option<&mut PendingEntry> {
+        self.pending.get_mut(&id)
+    }
+
+    pub fn consumers(&self) -> &BTreeMap<Bytes, Consumer> {
+        &self.consumers
+    }
+
+    pub fn consumer(&self, name: &[u8]) -> Option<&Consumer> {
+        self.consumers.get(name)
+    }
+
+    /// The consumer `name`, created if missing, seen at `now_ms`.
+    pub fn see_consumer(&mut self, name: &Bytes, now_ms: u64) -> &mut Consumer {
+        let consumer = self
+            .consumers
+            .entry(name.clone())
+            .or_insert_with(|| Consumer::new(now_ms, None));
+        consumer.seen_time = now_ms;
+        consumer
+    }
+
+    /// Add the consumer `name`. Returns `false` if it exists.
+    pub fn create_consumer(&mut self, name: &Bytes, now_ms: u64) -> bool {
+        if self.consumers.contains_key(name) {
+            return false;
+        }
+        self.consumers
+            .insert(name.clone(), Consumer::new(now_ms, None));
+        true
+    }
+
+    /// Remove the consumer `name` along with its pending entries. Returns how many entries were
+    /// pending for it, `None` if it does not exist.
+    pub fn delete_consumer(&mut self, name: &[u8]) -> Option<usize> {
+        let consumer = self.consumers.remove(name)?;
+        for id in &consumer.pending {
+            self.pending.remove(id);
+        }
+        Some(consumer.pending.len())
+    }
+
+    /// Make entry `id` pending for `consumer`, which must exist, taking it from any consumer
+    /// it was pending for.
+    pub fn claim(
+        &mut self,
+        id: StreamId,
+        consumer: &Bytes,
+        delivery_time: u64,
+        delivery_count: u64,
+    ) {
+        let entry = PendingEntry {
+            consumer: consumer.clone(),
+            delivery_time,
+            delivery_count,
+        };
+        if let Some(previous) = self.pending.insert(id, entry)
+            && let Some(owner) = self.consumers.get_mut(&previous.consumer)
+        {
+            owner.pending.remove(&id);
+        }
+        self.consumers
+            .get_mut(consumer)
+            .expect("the consumer exists")
+            .pending
+            .insert(id);
+    }
+
+    /// Remove entry `id` from the pending entries. Returns whether it was pending.
+    pub fn ack(&mut self, id: StreamId) -> bool {
+        let Some(entry) = self.pending.remove(&id) else {
+            return false;
+        };
+        if let Some(owner) = self.consumers.get_mut(&entry.consumer) {
+            owner.pending.remove(&id);
+        }
+        true
+    }
+}
+
+/// Flag `entries` of a node as deleted, then update the counts of its master entry with `count`
+/// entries left. The entries are given in order.
+fn mark_deleted(listpack: &mut Listpack, entries: &[RawEntry], count: usize) {
+    if entries.is_empty() {
+        return;
+    }
+    // The flags keep their size, so the positions after them hold. The counts are rewritten
+    // last, as they may change size and move every entry.
+    for entry in entries {
+        listpack.replace_int(entry.flags_position, entry.flags | FLAG_DELETED);
+    }
+    let node = Node::new(StreamId::MIN, listpack).unwrap();
+    let deleted = node.deleted + entries.len();
+    let deleted_position = listpack.skip(listpack.first()).unwrap();
+    listpack.replace_int(deleted_position, deleted as i64);
+    listpack.replace_int(listpack.first(), count as i64);
+}
+
+/// A node, with its master entry decoded.
+struct Node<'a> {
+    master_id: StreamId,
+    listpack: &'a Listpack,
+    count: usize,
+    deleted: usize,
+    master_fields: Vec<Element<'a>>,
+    /// The position of the first entry, past the master entry.
+    entries_position: usize,
+}
+
+impl<'a> Node<'a> {
+    /// Decode the master entry, `None` if it is corrupted.
+    fn new(master_id: StreamId, listpack: &'a Listpack) -> Option<Self> {
+        let mut position = listpack.first();
+        let mut next_int = || {
+            let (element, next) = listpack.get(position)?;
+            position = next;
+            usize::try_from(element.as_int()?).ok()
+        };
+        let count = next_int()?;
+        let deleted = next_int()?;
+        let num_fields = next_int()?;
+
+        let mut master_fields = Vec::with_capacity(num_fields.min(listpack.size()));
+        for _ in 0..num_fields {
+            let (field, next) = listpack.get(position)?;
+            master_fields.push(field);
+            position = next;
+        }
+        let (terminator, entries_position) = listpack.get(position)?;
+        if terminator != Element::Int(0) {
+            return None;
+        }
+
+        Some(Node {
+            master_id,
+            listpack,
+            count,
+            deleted,
+            master_fields,
+            entries_position,
+        })
+    }
+
+    /// The entries in order, deleted ones included.
+    fn entries(&self) -> impl Iterator<Item = RawEntry> + '_ {
+        let mut position = self.entries_position;
+        std::iter::from_fn(move || {
+            let (entry, next) = self.read_entry(position)?;
+            position = next;
+            Some(entry)
+        })
+    }
+
+    /// Read the entry at `position`. Returns it with the position of the next entry.
+    fn read_entry(&self, position: usize) -> Option<(RawEntry, usize)> {
+        let listpack = self.listpack;
+        let mut position_now = position;
+        let mut next_int = || {
+            let (element, next) = listpack.get(position_now)?;
+            position_now = next;
+            element.as_int()
+        };
+        let flags = next_int()?;
+        let ms = self.master_id.ms.wrapping_add(next_int()? as u64);
+        let seq = self.master_id.seq.wrapping_add(next_int()? as u64);
+        let same_fields = flags & FLAG_SAME_FIELDS != 0;
+        let (num_fields, lp_count) = if same_fields {
+            let num_fields = self.master_fields.len();
+            (num_fields, num_fields as i64 + 3)
+        } else {
+            let num_fields = usize::try_from(next_int()?).ok()?;
+            (num_fields, num_fields as i64 * 2 + 4)
+        };
+        let fields_position = position_now;
+
+        let elements = if same_fields {
+            num_fields
+        } else {
+            num_fields.checked_mul(2)?
+        };
+        for _ in 0..elements {
+            position_now = listpack.skip(position_now)?;
+        }
+        let (count, next) = listpack.get(position_now)?;
+        if count.as_int()? != lp_count {
+            return None;
+        }
+
+        Some((
+            RawEntry {
+                id: StreamId::new(ms, seq),
+                flags,
+                flags_position: position,
+                fields_position,
+                num_fields,
+            },
+            next,
+        ))
+    }
+
+    /// Decode the field-value pairs of `entry`.
+    fn fields(&self, entry: &RawEntry) -> Vec<(Bytes, Bytes)> {
+        let mut position = entry.fields_position;
+        let mut next = || {
+            // The entry was fully read already
+            let (element, next) = self.listpack.get(position).unwrap();
+            position = next;
+            Bytes::from(element.to_vec())
+        };
+        if entry.flags & FLAG_SAME_FIELDS != 0 {
+            self.master_fields
+                .iter()
+                .map(|field| (Bytes::from(field.to_vec()), next()))
+                .collect()
+        } else {
+            (0..entry.num_fields).map(|_| (next(), next())).collect()
+        }
+    }
+}
+
+/// An entry as laid out in a node, with its fields left encoded.
+#[derive(Debug, Clone, Copy)]
+struct RawEntry {
+    id: StreamId,
+    flags: i64,
+    flags_position: usize,
+    fields_position: usize,
+    num_fields: usize,
+}
+
+impl RawEntry {
+    fn is_deleted(&self) -> bool {
+        self.flags & FLAG_DELETED != 0
+    }
+}
+
+/// An iterator over the entries of a stream within an ID range, built by [`Stream::range`] and
+/// [`Stream::rev_range`].
+pub struct Range<'a> {
+    nodes: Box<dyn Iterator<Item = (&'a StreamId, &'a Listpack)> + 'a>,
+    node: Option<Node<'a>>,
+    /// The live entries of the current node within the range, in iteration order.
+    pending: std::vec::IntoIter<RawEntry>,
+    start: StreamId,
+    end: StreamId,
+    rev: bool,
+}
+
+impl Iterator for Range<'_> {
+    type Item = StreamEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.pending.next() {
+                // The node is set along with its pending entries
+                let node = self.node.as_ref().unwrap();
+                return Some((entry.id, node.fields(&entry)));
+            }
+
+            let (master_id, listpack) = self.nodes.next()?;
+            let node = Node::new(*master_id, listpack)?;
+            let (start, end) = (self.start, self.end);
+            let mut entries: Vec<_> = node
+                .entries()
+                .filter(|entry| !entry.is_deleted() && (start..=end).contains(&entry.id))
+                .collect();
+            if self.rev {
+                entries.reverse();
+            }
+            self.pending = entries.into_iter();
+            self.node = Some(node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{ConsumerGroup, NODE_MAX_ENTRIES, Stream, StreamId, TrimStrategy};
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(Bytes, Bytes)> {
+        pairs
+            .iter()
+            .map(|(field, value)| {
+                (
+                    Bytes::from(field.to_string()),
+                    Bytes::from(value.to_string()),
+                )
+            })
+            .collect()
+    }
+
+    fn ids(entries: impl Iterator<Item = (StreamId, Vec<(Bytes, Bytes)>)>) -> Vec<u64> {
+        entries.map(|(id, _)| id.seq).collect()
+    }
+
+    /// A stream of `len` entries with IDs `1-0` to `1-<len - 1>`.
+    fn build_stream(len: u64) -> Stream {
+        let mut stream = Stream::new();
+        for seq in 0..len {
+            let value = seq.to_string();
+            stream.add(StreamId::new(1, seq), &fields(&[("n", &value)]));
+        }
+        stream
+    }
+
+    #[test]
+    fn stream_id_should_parse_and_step() {
+        assert_eq!(StreamId::parse(b"5-3", 0), Some(StreamId::new(5, 3)));
+        assert_eq!(
+            StreamId::parse(b"5", u64::MAX),
+            Some(StreamId::new(5, u64::MAX))
+        );
+        for invalid in [&b"5-"[..], b"-3", b"a-1", b"1-2-3", b""] {
+            assert_eq!(StreamId::parse(invalid, 0), None);
+        }
+        assert_eq!(StreamId::new(1, u64::MAX).next(), Some(StreamId::new(2, 0)));
+        assert_eq!(StreamId::MAX.next(), None);
+        assert_eq!(StreamId::new(2, 0).prev(), Some(StreamId::new(1, u64::MAX)));
+        assert_eq!(StreamId::MIN.prev(), None);
+        let id = StreamId::new(7, 9);
+        assert_eq!(StreamId::from_be_bytes(&id.to_be_bytes()), Some(id));
+    }
+
+    #[test]
+    fn stream_should_keep_fields_across_nodes() {
+        let mut stream = Stream::new();
+        stream.add(StreamId::new(1, 1), &fields(&[("a", "1"), ("b", "2")]));
+        stream.add(StreamId::new(1, 2), &fields(&[("a", "3"), ("b", "4")]));
+        stream.add(StreamId::new(2, 0), &fields(&[("other", "-12"), ("", "")]));
+        for seq in 0..2 * NODE_MAX_ENTRIES as u64 {
+            stream.add(StreamId::new(3, seq), &fields(&[("a", "x")]));
+        }
+        assert_eq!(stream.len(), 3 + 2 * NODE_MAX_ENTRIES);
+        assert_eq!(stream.nodes().count(), 3);
+
+        let entries: Vec<_> = stream.range(StreamId::MIN, StreamId::new(2, 0)).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (StreamId::new(1, 1), fields(&[("a", "1"), ("b", "2")])),
+                (StreamId::new(1, 2), fields(&[("a", "3"), ("b", "4")])),
+                (StreamId::new(2, 0), fields(&[("other", "-12"), ("", "")])),
+            ]
+        );
+        let last: Vec<_> = stream
+            .rev_range(StreamId::new(1, 2), StreamId::MAX)
+            .take(2)
+            .collect();
+        assert_eq!(last[0].0, StreamId::new(3, 2 * NODE_MAX_ENTRIES as u64 - 1));
+        assert_eq!(last[1].0, StreamId::new(3, 2 * NODE_MAX_ENTRIES as u64 - 2));
+        assert_eq!(
+            stream
+                .range(StreamId::new(2, 1), StreamId::new(2, 5))
+                .count(),
+            0
+        );
+        assert_eq!(stream.range(StreamId::new(4, 0), StreamId::MAX).count(), 0);
+        assert_eq!(
+            stream.rev_range(StreamId::MIN, StreamId::new(1, 0)).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn remove_should_flag_entries_and_free_empty_nodes() {
+        let mut stream = build_stream(NODE_MAX_ENTRIES as u64 + 2);
+        assert!(stream.remove(StreamId::new(1, 0)));
+        assert!(!stream.remove(StreamId::new(1, 0)));
+        assert!(!stream.remove(StreamId::new(0, 5)));
+        assert_eq!(stream.first_id(), StreamId::new(1, 1));
+        assert_eq!(stream.max_deleted_id(), StreamId::new(1, 0));
+
+        assert!(stream.remove(StreamId::new(1, NODE_MAX_ENTRIES as u64 + 1)));
+        assert!(stream.remove(StreamId::new(1, NODE_MAX_ENTRIES as u64)));
+        assert_eq!(stream.nodes().count(), 1);
+        assert_eq!(stream.len(), NODE_MAX_ENTRIES - 1);
+        assert_eq!(
+            stream.last_id(),
+            StreamId::new(1, NODE_MAX_ENTRIES as u64 + 1)
+        );
+        assert_eq!(
+            ids(stream.range(StreamId::MIN, StreamId::new(1, 3))),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn trim_should_remove_whole_nodes_when_approximate() {
+        let len = 3 * NODE_MAX_ENTRIES as u64;
+        let mut stream = build_stream(len);
+        assert_eq!(stream.trim(TrimStrategy::MaxLen(150), true, 0), 100);
+        assert_eq!(stream.len(), 200);
+        assert_eq!(stream.first_id(), StreamId::new(1, 100));
+
+        assert_eq!(stream.trim(TrimStrategy::MaxLen(150), false, 0), 50);
+        assert_eq!(stream.len(), 150);
+        assert_eq!(stream.first_id(), StreamId::new(1, 150));
+
+        let mut stream = build_stream(len);
+        assert_eq!(
+            stream.trim(TrimStrategy::MinId(StreamId::new(1, 250)), true, 0),
+            200
+        );
+        assert_eq!(
+            stream.trim(TrimStrategy::MinId(StreamId::new(1, 250)), false, 0),
+            50
+        );
+        assert_eq!(stream.first_id(), StreamId::new(1, 250));
+        assert_eq!(stream.trim(TrimStrategy::MaxLen(0), true, 20), 0);
+        assert_eq!(stream.trim(TrimStrategy::MaxLen(0), false, 0), 50);
+        assert!(stream.is_empty());
+        assert_eq!(stream.first_id(), StreamId::MIN);
+        assert_eq!(stream.last_id(), StreamId::new(1, len - 1));
+    }
+
+    #[test]
+    fn from_nodes_should_rebuild_the_stream() {
+        let mut stream = build_stream(250);
+        stream.remove(StreamId::new(1, 0));
+        let nodes = stream
+            .nodes()
+            .map(|(master_id, listpack)| (*master_id, listpack.clone()))
+            .collect();
+        let rebuilt = Stream::from_nodes(
+            nodes,
+            stream.last_id(),
+            stream.max_deleted_id(),
+            Some(stream.entries_added()),
+        );
+        assert_eq!(rebuilt.as_ref(), Some(&stream));
+
+        let nodes = stream
+            .nodes()
+            .map(|(master_id, listpack)| (*master_id, listpack.clone()))
+            .collect();
+        assert!(Stream::from_nodes(nodes, StreamId::new(1, 100), StreamId::MIN, None).is_none());
+    }
+
+    #[test]
+    fn read_group_should_track_reads_and_lag() {
+        let mut stream = build_stream(10);
+        let (name, consumer) = (Bytes::from_owner("g"), Bytes::from_owner("c"));
+        stream.create_group(name.clone(), ConsumerGroup::new(StreamId::MIN, None));
+        assert_eq!(stream.lag(stream.group(&name).unwrap()), Some(10));
+
+        let entries = stream.read_group(&name, &consumer, 3, false, 0).unwrap();
+        assert_eq!(ids(entries.into_iter()), [0, 1, 2]);
+        let group = stream.group(&name).unwrap();
+        assert_eq!(group.entries_read(), Some(3));
+        assert_eq!(stream.lag(group), Some(7));
+
+        // A deletion ahead of the group makes its lag unknown, until it reads the last entry
+        stream.remove(StreamId::new(1, 5));
+        assert_eq!(stream.lag(stream.group(&name).unwrap()), None);
+        let entries = stream
+            .read_group(&name, &consumer, usize::MAX, false, 0)
+            .unwrap();
+        assert_eq!(ids(entries.into_iter()), [3, 4, 6, 7, 8, 9]);
+        let group = stream.group(&name).unwrap();
+        assert_eq!(group.entries_read(), Some(10));
+        assert_eq!(stream.lag(group), Some(0));
+
+        let group = stream.group_mut(&name).unwrap();
+        assert_eq!(group.pending().len(), 9);
+        assert!(group.ack(StreamId::new(1, 0)));
+        assert!(!group.ack(StreamId::new(1, 0)));
+        assert_eq!(group.consumer(&consumer).unwrap().pending().len(), 8);
+        assert_eq!(group.delete_consumer(&consumer), Some(8));
+        assert!(group.pending().is_empty());
+    }
+}
@@ -1,4 +1,7 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use bytes::Bytes;
 
@@ -15,6 +18,128 @@ pub fn config_logger() {
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set global subscriber");
 }
 
+/// The current Unix time in milliseconds, the clock of expire times that must outlive the process.
+pub fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Resolve a possibly negative `index` counted from the end, as Redis does for list and string
+/// offsets. Returns `None` when the index falls outside `0..len`.
+pub fn normalize_index(index: i64, len: usize) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+/// Resolve an inclusive `start..=stop` range with possibly negative bounds against `len`,
+/// clamping it the way `LRANGE` and `GETRANGE` do. Returns `None` when the range is empty.
+pub fn normalize_range(start: i64, stop: i64, len: usize) -> Option<(usize, usize)> {
+    let len = len as i64;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start
+    };
+    let stop = if stop < 0 {
+        len + stop
+    } else {
+        stop.min(len - 1)
+    };
+    (start <= stop && start < len).then_some((start as usize, stop as usize))
+}
+
+/// Format a float the way Redis replies it: the shortest digits that parse back to the same value,
+/// laid out as C's `%.17g` would. Exponents below -4 or from 17 up use the scientific notation
+/// `1.5e+300`, and integral values otherwise drop the fractional part.
+pub fn format_float(f: f64) -> String {
+    if f.is_infinite() {
+        return if f > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    let scientific = format!("{f:e}");
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    if (-4..17).contains(&exponent) {
+        return f.to_string();
+    }
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exponent.unsigned_abs())
+}
+
+/// Glob-style matching used by `MATCH` options: `*`, `?`, `[abc]`, `[^abc]`, `[a-z]`, and `\`
+/// to escape the next byte.
+pub fn glob_match(pattern: &[u8], string: &[u8]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // The pattern past the last star, and where in the string that star stops matching. Only the
+    // last star ever needs to match more, which keeps the matching linear in both lengths.
+    let mut star = None;
+    while s < string.len() {
+        if pattern.get(p) == Some(&b'*') {
+            p += 1;
+            star = Some((p, s));
+            continue;
+        }
+        if let Some(len) = match_token(&pattern[p..], string[s]) {
+            p += len;
+            s += 1;
+            continue;
+        }
+        let Some((star_p, star_s)) = star else {
+            return false;
+        };
+        p = star_p;
+        s = star_s + 1;
+        star = Some((star_p, s));
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Match the token opening `pattern`, other than a star, against the byte `c`. Returns the length
+/// of the token if it matches.
+fn match_token(pattern: &[u8], c: u8) -> Option<usize> {
+    match pattern {
+        [] => None,
+        [b'?', ..] => Some(1),
+        [b'[', class @ ..] => {
+            let (negate, mut rest) = match class.split_first() {
+                Some((b'^', rest)) => (true, rest),
+                _ => (false, class),
+            };
+            let mut matched = false;
+            loop {
+                match rest {
+                    // An unclosed class matches up to the end of the pattern
+                    [] => break,
+                    [b']', tail @ ..] => {
+                        rest = tail;
+                        break;
+                    }
+                    [b'\\', escaped, tail @ ..] => {
+                        matched |= *escaped == c;
+                        rest = tail;
+                    }
+                    [start, b'-', end, tail @ ..] if *end != b']' => {
+                        let (start, end) = if start <= end {
+                            (start, end)
+                        } else {
+                            (end, start)
+                        };
+                        matched |= (*start..=*end).contains(&c);
+                        rest = tail;
+                    }
+                    [other, tail @ ..] => {
+                        matched |= *other == c;
+                        rest = tail;
+                    }
+                }
+            }
+            (matched != negate).then_some(pattern.len() - rest.len())
+        }
+        [b'\\', escaped, ..] => (*escaped == c).then_some(2),
+        [other, ..] => (*other == c).then_some(1),
+    }
+}
+
 #[derive(Debug)]
 pub enum BytesInStr<'a> {
     Str(&'a str),
@@ -71,3 +196,60 @@ impl<'a> Display for BytesInStr<'a> {
 //         Ok(())
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::{format_float, glob_match};
+
+    #[test]
+    fn format_float_should_match_percent_17g_layout() {
+        for (f, expected) in [
+            (0.0, "0"),
+            (3.0, "3"),
+            (-2.5, "-2.5"),
+            (0.1, "0.1"),
+            (0.0001, "0.0001"),
+            (1e16, "10000000000000000"),
+            (1e17, "1e+17"),
+            (1e300, "1e+300"),
+            (-1.5e300, "-1.5e+300"),
+            (0.00001, "1e-05"),
+            (1.25e-300, "1.25e-300"),
+            (f64::MIN_POSITIVE, "2.2250738585072014e-308"),
+            (f64::INFINITY, "inf"),
+            (f64::NEG_INFINITY, "-inf"),
+        ] {
+            assert_eq!(format_float(f), expected, "{f:e}");
+        }
+    }
+    #[test]
+    fn glob_match_should_backtrack_to_last_star() {
+        for (pattern, string, expected) in [
+            ("*", "", true),
+            ("", "a", false),
+            ("a*b*c", "abbbc", true),
+            ("a*b*c", "abcb", false),
+            ("*ab", "aab", true),
+            ("h?llo*", "hello world", true),
+            ("h[^e]llo", "hello", false),
+            ("h[a-e]llo", "hello", true),
+            ("\\*x", "*x", true),
+            ("\\*x", "ax", false),
+            ("[", "a", false),
+        ] {
+            assert_eq!(
+                glob_match(pattern.as_bytes(), string.as_bytes()),
+                expected,
+                "{pattern} {string}"
+            );
+        }
+    }
+
+    #[test]
+    fn glob_match_should_not_blow_up_on_many_stars() {
+        let pattern = "*a".repeat(30) + "*b";
+        let string = "a".repeat(100);
+        assert!(!glob_match(pattern.as_bytes(), string.as_bytes()));
+        assert!(glob_match(pattern.as_bytes(), (string + "b").as_bytes()));
+    }
+}
@@ -0,0 +1,653 @@
+//! The sorted set type, indexed by a skiplist with spans as in Redis.
+//!
+//! Members are ordered by score, then lexicographically. Every link of the skiplist records how
+//! many nodes it skips, so the rank of a member and the member at a rank are both found in
+//! O(log n), and a score or lex range is located with two descents.
+
+use std::{collections::HashMap, fmt, ops::Range};
+
+use bytes::Bytes;
+use rand::Rng;
+
+const MAX_LEVEL: usize = 32;
+/// The probability for a node to reach the next level.
+const LEVEL_PROBABILITY: f64 = 0.25;
+
+/// The index of the header node, which holds no member.
+const HEAD: usize = 0;
+/// The index standing for a missing node.
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct Level {
+    forward: usize,
+    /// How many nodes the link to `forward` skips, `forward` included.
+    span: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    member: Bytes,
+    score: f64,
+    backward: usize,
+    levels: Vec<Level>,
+}
+
+impl Node {
+    fn new(member: Bytes, score: f64, level: usize) -> Self {
+        Node {
+            member,
+            score,
+            backward: NIL,
+            levels: vec![
+                Level {
+                    forward: NIL,
+                    span: 0
+                };
+                level
+            ],
+        }
+    }
+
+    /// Whether the node is ordered before the member `member` scored `score`.
+    fn is_before(&self, score: f64, member: &[u8]) -> bool {
+        self.score < score || (self.score == score && self.member.as_ref() < member)
+    }
+}
+
+/// The nodes live in an arena and link to each other by index, with the slots of the deleted
+/// nodes kept for reuse.
+#[derive(Clone)]
+struct SkipList {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    tail: usize,
+    len: usize,
+    level: usize,
+}
+
+impl SkipList {
+    fn new() -> Self {
+        SkipList {
+            nodes: vec![Node::new(Bytes::new(), 0.0, MAX_LEVEL)],
+            free: Vec::new(),
+            tail: NIL,
+            len: 0,
+            level: 1,
+        }
+    }
+
+    fn random_level() -> usize {
+        let mut rng = rand::rng();
+        let mut level = 1;
+        while level < MAX_LEVEL && rng.random_bool(LEVEL_PROBABILITY) {
+            level += 1;
+        }
+        level
+    }
+
+    /// Find the last node for which `is_before` holds, along with its rank. Ranks start at 1,
+    /// the header having rank 0. `is_before` must hold for a prefix of the nodes.
+    fn last_before(&self, is_before: impl Fn(&Node) -> bool) -> (usize, usize) {
+        let mut x = HEAD;
+        let mut rank = 0;
+        for i in (0..self.level).rev() {
+            loop {
+                let Level { forward, span } = self.nodes[x].levels[i];
+                if forward == NIL || !is_before(&self.nodes[forward]) {
+                    break;
+                }
+                rank += span;
+                x = forward;
+            }
+        }
+        (x, rank)
+    }
+
+    /// For every level, the last node before the member `member` scored `score` along with its
+    /// rank.
+    fn predecessors(&self, score: f64, member: &[u8]) -> ([usize; MAX_LEVEL], [usize; MAX_LEVEL]) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut ranks = [0; MAX_LEVEL];
+        let mut x = HEAD;
+        let mut rank = 0;
+        for i in (0..self.level).rev() {
+            loop {
+                let Level { forward, span } = self.nodes[x].levels[i];
+                if forward == NIL || !self.nodes[forward].is_before(score, member) {
+                    break;
+                }
+                rank += span;
+                x = forward;
+            }
+            update[i] = x;
+            ranks[i] = rank;
+        }
+        (update, ranks)
+    }
+
+    /// Insert a member which is not in the list yet.
+    fn insert(&mut self, member: Bytes, score: f64) {
+        let (mut update, mut ranks) = self.predecessors(score, &member);
+
+        let level = Self::random_level();
+        if level > self.level {
+            for i in self.level..level {
+                update[i] = HEAD;
+                ranks[i] = 0;
+                self.nodes[HEAD].levels[i].span = self.len;
+            }
+            self.level = level;
+        }
+
+        let node = Node::new(member, score, level);
+        let x = match self.free.pop() {
+            Some(x) => {
+                self.nodes[x] = node;
+                x
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+
+        for i in 0..level {
+            let previous = self.nodes[update[i]].levels[i];
+            let skipped = ranks[0] - ranks[i];
+            self.nodes[x].levels[i] = Level {
+                forward: previous.forward,
+                span: previous.span - skipped,
+            };
+            self.nodes[update[i]].levels[i] = Level {
+                forward: x,
+                span: skipped + 1,
+            };
+        }
+        // The new node is skipped by the untouched levels above it
+        for (i, &previous) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[previous].levels[i].span += 1;
+        }
+
+        self.nodes[x].backward = if update[0] == HEAD { NIL } else { update[0] };
+        match self.nodes[x].levels[0].forward {
+            NIL => self.tail = x,
+            forward => self.nodes[forward].backward = x,
+        }
+        self.len += 1;
+    }
+
+    /// Remove the member `member` scored `score`. Returns whether it was found.
+    fn remove(&mut self, score: f64, member: &[u8]) -> bool {
+        let (update, _) = self.predecessors(score, member);
+        let x = self.nodes[update[0]].levels[0].forward;
+        if x == NIL || self.nodes[x].score != score || self.nodes[x].member.as_ref() != member {
+            return false;
+        }
+
+        for (i, &previous) in update.iter().enumerate().take(self.level) {
+            if self.nodes[previous].levels[i].forward == x {
+                let Level { forward, span } = self.nodes[x].levels[i];
+                self.nodes[previous].levels[i] = Level {
+                    forward,
+                    span: self.nodes[previous].levels[i].span + span - 1,
+                };
+            } else {
+                self.nodes[previous].levels[i].span -= 1;
+            }
+        }
+
+        let backward = self.nodes[x].backward;
+        match self.nodes[x].levels[0].forward {
+            NIL => self.tail = backward,
+            forward => self.nodes[forward].backward = backward,
+        }
+        while self.level > 1 && self.nodes[HEAD].levels[self.level - 1].forward == NIL {
+            self.level -= 1;
+        }
+        self.len -= 1;
+
+        // Release the member now rather than when the slot is reused
+        self.nodes[x].member = Bytes::new();
+        self.free.push(x);
+        true
+    }
+
+    /// The node at `rank`, which starts at 1.
+    fn node_at(&self, rank: usize) -> usize {
+        if rank == 0 || rank > self.len {
+            return NIL;
+        }
+        let mut x = HEAD;
+        let mut traversed = 0;
+        for i in (0..self.level).rev() {
+            loop {
+                let Level { forward, span } = self.nodes[x].levels[i];
+                if forward == NIL || traversed + span > rank {
+                    break;
+                }
+                traversed += span;
+                x = forward;
+            }
+            if traversed == rank {
+                return x;
+            }
+        }
+        NIL
+    }
+}
+
+/// A sorted set: a map from members to scores, plus the skiplist ordering them.
+#[derive(Clone)]
+pub struct SortedSet {
+    scores: HashMap<Bytes, f64>,
+    list: SkipList,
+}
+
+impl SortedSet {
+    pub fn new() -> Self {
+        SortedSet {
+            scores: HashMap::new(),
+            list: SkipList::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    pub fn contains(&self, member: &[u8]) -> bool {
+        self.scores.contains_key(member)
+    }
+
+    /// Add `member` or update its score, which must not be NaN. Returns the previous score.
+    pub fn insert(&mut self, member: Bytes, score: f64) -> Option<f64> {
+        debug_assert!(!score.is_nan());
+        let previous = self.scores.insert(member.clone(), score);
+        match previous {
+            Some(previous) if previous == score => {}
+            Some(previous) => {
+                self.list.remove(previous, &member);
+                self.list.insert(member, score);
+            }
+            None => self.list.insert(member, score),
+        }
+        previous
+    }
+
+    /// Remove `member`. Returns its score.
+    pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+        let score = self.scores.remove(member)?;
+        self.list.remove(score, member);
+        Some(score)
+    }
+
+    /// The rank of `member` in ascending order, starting at 0.
+    pub fn rank(&self, member: &[u8]) -> Option<usize> {
+        let score = self.score(member)?;
+        let (_, rank) = self
+            .list
+            .last_before(|node| node.is_before(score, member) || node.member.as_ref() == member);
+        Some(rank - 1)
+    }
+
+    /// The member at `rank` in ascending order, starting at 0, with its score.
+    pub fn get(&self, rank: usize) -> Option<(&Bytes, f64)> {
+        match self.list.node_at(rank + 1) {
+            NIL => None,
+            x => Some((&self.list.nodes[x].member, self.list.nodes[x].score)),
+        }
+    }
+
+    /// Iterate over all the members in ascending order.
+    pub fn iter(&self) -> Iter<'_> {
+        self.range(0..self.len())
+    }
+
+    /// Iterate over the members whose ranks are in `ranks`, in ascending order.
+    pub fn range(&self, ranks: Range<usize>) -> Iter<'_> {
+        let ranks = ranks.start..ranks.end.min(self.len());
+        Iter {
+            list: &self.list,
+            node: self.list.node_at(ranks.start + 1),
+            remaining: ranks.len(),
+            reverse: false,
+        }
+    }
+
+    /// Iterate over the members whose ranks are in `ranks`, in descending order.
+    pub fn rev_range(&self, ranks: Range<usize>) -> Iter<'_> {
+        let ranks = ranks.start..ranks.end.min(self.len());
+        Iter {
+            list: &self.list,
+            node: self.list.node_at(ranks.end),
+            remaining: ranks.len(),
+            reverse: true,
+        }
+    }
+
+    /// The ranks of the members whose scores are in `range`.
+    pub fn score_range(&self, range: &ScoreRange) -> Range<usize> {
+        let (_, start) = self.list.last_before(|node| range.is_below(node.score));
+        let (_, end) = self.list.last_before(|node| !range.is_above(node.score));
+        start..end.max(start)
+    }
+
+    /// The ranks of the members in the lexicographical `range`. The members are expected to
+    /// share one score, as only then are they ordered lexicographically.
+    pub fn lex_range(&self, range: &LexRange) -> Range<usize> {
+        let (_, start) = self.list.last_before(|node| range.is_below(&node.member));
+        let (_, end) = self.list.last_before(|node| !range.is_above(&node.member));
+        start..end.max(start)
+    }
+
+    /// Remove and return the member with the lowest score.
+    pub fn pop_first(&mut self) -> Option<(Bytes, f64)> {
+        let (member, score) = self.get(0).map(|(member, score)| (member.clone(), score))?;
+        self.remove(&member);
+        Some((member, score))
+    }
+
+    /// Remove and return the member with the highest score.
+    pub fn pop_last(&mut self) -> Option<(Bytes, f64)> {
+        let tail = self.list.nodes.get(self.list.tail)?;
+        let (member, score) = (tail.member.clone(), tail.score);
+        self.remove(&member);
+        Some((member, score))
+    }
+}
+
+impl Default for SortedSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for SortedSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.scores == other.scores
+    }
+}
+
+impl fmt::Debug for SortedSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> From<[(Bytes, f64); N]> for SortedSet {
+    fn from(members: [(Bytes, f64); N]) -> Self {
+        members.into_iter().collect()
+    }
+}
+
+impl FromIterator<(Bytes, f64)> for SortedSet {
+    fn from_iter<T: IntoIterator<Item = (Bytes, f64)>>(iter: T) -> Self {
+        let mut zset = SortedSet::new();
+        for (member, score) in iter {
+            zset.insert(member, score);
+        }
+        zset
+    }
+}
+
+/// An iterator over a rank range of a sorted set, yielding members with their scores.
+pub struct Iter<'a> {
+    list: &'a SkipList,
+    node: usize,
+    remaining: usize,
+    reverse: bool,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a Bytes, f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 || self.node == NIL {
+            return None;
+        }
+        let node = &self.list.nodes[self.node];
+        self.node = if self.reverse {
+            node.backward
+        } else {
+            node.levels[0].forward
+        };
+        self.remaining -= 1;
+        Some((&node.member, node.score))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// A score interval, as given to `ZRANGE BYSCORE` and `ZCOUNT`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreRange {
+    pub min: f64,
+    pub max: f64,
+    pub min_exclusive: bool,
+    pub max_exclusive: bool,
+}
+
+impl ScoreRange {
+    /// Parse bounds such as `1.5`, `(1.5` for an exclusive bound, `-inf` or `+inf`. Returns
+    /// `None` if a bound is not a number.
+    pub fn parse(min: &[u8], max: &[u8]) -> Option<Self> {
+        let (min, min_exclusive) = Self::parse_bound(min)?;
+        let (max, max_exclusive) = Self::parse_bound(max)?;
+        Some(ScoreRange {
+            min,
+            max,
+            min_exclusive,
+            max_exclusive,
+        })
+    }
+
+    fn parse_bound(bound: &[u8]) -> Option<(f64, bool)> {
+        let (bound, exclusive) = match bound.strip_prefix(b"(") {
+            Some(bound) => (bound, true),
+            None => (bound, false),
+        };
+        let value = parse_score(bound)?;
+        Some((value, exclusive))
+    }
+
+    /// Whether `score` is below the interval.
+    pub fn is_below(&self, score: f64) -> bool {
+        if self.min_exclusive {
+            score <= self.min
+        } else {
+            score < self.min
+        }
+    }
+
+    /// Whether `score` is above the interval.
+    pub fn is_above(&self, score: f64) -> bool {
+        if self.max_exclusive {
+            score >= self.max
+        } else {
+            score > self.max
+        }
+    }
+}
+
+/// Parse a score the way Redis does, accepting `inf` spelled in any case and refusing `NaN`.
+pub fn parse_score(score: &[u8]) -> Option<f64> {
+    let score: f64 = std::str::from_utf8(score).ok()?.parse().ok()?;
+    (!score.is_nan()).then_some(score)
+}
+
+/// A bound of a lexicographical interval.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexBound {
+    /// `-`, below every member.
+    Min,
+    /// `+`, above every member.
+    Max,
+    /// `[member`
+    Inclusive(Bytes),
+    /// `(member`
+    Exclusive(Bytes),
+}
+
+impl LexBound {
+    /// Parse `-`, `+`, `[member` or `(member`.
+    pub fn parse(bound: &Bytes) -> Option<Self> {
+        match bound.first() {
+            Some(b'-') if bound.len() == 1 => Some(LexBound::Min),
+            Some(b'+') if bound.len() == 1 => Some(LexBound::Max),
+            Some(b'[') => Some(LexBound::Inclusive(bound.slice(1..))),
+            Some(b'(') => Some(LexBound::Exclusive(bound.slice(1..))),
+            _ => None,
+        }
+    }
+}
+
+/// A lexicographical interval, as given to `ZRANGE BYLEX`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexRange {
+    pub min: LexBound,
+    pub max: LexBound,
+}
+
+impl LexRange {
+    /// Parse the two bounds. Returns `None` if one is malformed.
+    pub fn parse(min: &Bytes, max: &Bytes) -> Option<Self> {
+        Some(LexRange {
+            min: LexBound::parse(min)?,
+            max: LexBound::parse(max)?,
+        })
+    }
+
+    /// Whether `member` is below the interval.
+    pub fn is_below(&self, member: &[u8]) -> bool {
+        match &self.min {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(min) => member < min.as_ref(),
+            LexBound::Exclusive(min) => member <= min.as_ref(),
+        }
+    }
+
+    /// Whether `member` is above the interval.
+    pub fn is_above(&self, member: &[u8]) -> bool {
+        match &self.max {
+            LexBound::Min => true,
+            LexBound::Max => false,
+            LexBound::Inclusive(max) => member > max.as_ref(),
+            LexBound::Exclusive(max) => member >= max.as_ref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{LexBound, LexRange, ScoreRange, SortedSet, parse_score};
+
+    fn members(iter: super::Iter<'_>) -> Vec<&str> {
+        iter.map(|(member, _)| str::from_utf8(member).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sorted_set_should_order_by_score_then_member() {
+        let zset = SortedSet::from([
+            (Bytes::from_owner("c"), 2.0),
+            (Bytes::from_owner("b"), 1.0),
+            (Bytes::from_owner("a"), 2.0),
+            (Bytes::from_owner("d"), -1.0),
+        ]);
+        assert_eq!(members(zset.iter()), ["d", "b", "a", "c"]);
+        assert_eq!(members(zset.rev_range(0..4)), ["c", "a", "b", "d"]);
+        assert_eq!(members(zset.range(1..3)), ["b", "a"]);
+        assert_eq!(members(zset.rev_range(1..3)), ["a", "b"]);
+        assert_eq!(zset.rank(b"a"), Some(2));
+        assert_eq!(zset.rank(b"missing"), None);
+        assert_eq!(zset.get(3), Some((&Bytes::from_owner("c"), 2.0)));
+        assert_eq!(zset.get(4), None);
+    }
+
+    #[test]
+    fn sorted_set_should_keep_ranks_through_updates_and_removals() {
+        let mut zset: SortedSet = (0..1000)
+            .map(|i| (Bytes::from(format!("m{i:04}")), i as f64))
+            .collect();
+        for i in (0..1000).step_by(3) {
+            assert_eq!(zset.remove(format!("m{i:04}").as_bytes()), Some(i as f64));
+        }
+        assert_eq!(zset.insert(Bytes::from_owner("m0001"), 5000.0), Some(1.0));
+        assert_eq!(zset.len(), 666);
+
+        let expected: Vec<_> = (0..1000)
+            .filter(|i| i % 3 != 0 && *i != 1)
+            .map(|i| format!("m{i:04}"))
+            .chain(["m0001".to_string()])
+            .collect();
+        assert_eq!(members(zset.iter()), expected);
+        for (rank, member) in expected.iter().enumerate() {
+            assert_eq!(zset.rank(member.as_bytes()), Some(rank));
+            assert_eq!(zset.get(rank).unwrap().0, member.as_bytes());
+        }
+
+        assert_eq!(zset.pop_first(), Some((Bytes::from_owner("m0002"), 2.0)));
+        assert_eq!(zset.pop_last(), Some((Bytes::from_owner("m0001"), 5000.0)));
+        assert_eq!(zset.len(), 664);
+    }
+
+    #[test]
+    fn score_range_should_honor_exclusive_and_infinite_bounds() {
+        let zset: SortedSet = (1..=5)
+            .map(|i| (Bytes::from(i.to_string()), i as f64))
+            .collect();
+        let ranks = |min: &str, max: &str| {
+            zset.score_range(&ScoreRange::parse(min.as_bytes(), max.as_bytes()).unwrap())
+        };
+        assert_eq!(ranks("2", "4"), 1..4);
+        assert_eq!(ranks("(2", "(4"), 2..3);
+        assert_eq!(ranks("-inf", "+inf"), 0..5);
+        assert_eq!(ranks("(5", "+inf"), 5..5);
+        assert_eq!(ranks("4", "2"), 3..3);
+        assert!(ScoreRange::parse(b"nan", b"1").is_none());
+        assert!(ScoreRange::parse(b"x", b"1").is_none());
+        assert_eq!(parse_score(b"-INF"), Some(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn lex_range_should_honor_bounds() {
+        let zset: SortedSet = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|member| (Bytes::from_owner(member), 0.0))
+            .collect();
+        let ranks = |min: &str, max: &str| {
+            zset.lex_range(
+                &LexRange::parse(
+                    &Bytes::copy_from_slice(min.as_bytes()),
+                    &Bytes::copy_from_slice(max.as_bytes()),
+                )
+                .unwrap(),
+            )
+        };
+        assert_eq!(ranks("-", "+"), 0..4);
+        assert_eq!(ranks("[b", "(d"), 1..3);
+        assert_eq!(ranks("(b", "[d"), 2..4);
+        assert_eq!(ranks("+", "-"), 4..4);
+        assert_eq!(
+            LexBound::parse(&Bytes::from_owner("-x")),
+            None,
+            "`-` must stand alone"
+        );
+    }
+}